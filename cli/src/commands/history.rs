@@ -0,0 +1,384 @@
+//! History command - export the desktop app's transcription history
+//!
+//! Reads the EZ Flow history database directly (read-only). The JSON output
+//! uses the same format as the app's export, so it can be imported there.
+
+use super::Context;
+use anyhow::{bail, Context as AnyhowContext, Result};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use colored::Colorize;
+use ez_flow_history::export::{
+    self as formats, ExportedEntry, HistoryExport, HistoryRecord, RevisionRecord,
+};
+use ez_flow_history::schema::{
+    is_encrypted, ENTRY_COLUMNS, ENTRY_COLUMNS_VERSION, REVISION_COLUMNS,
+};
+use ez_flow_history::search::SearchQuery;
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, OpenFlags};
+use std::path::{Path, PathBuf};
+
+/// Export format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Lossless JSON, importable in the app
+    Json,
+    /// Comma-separated values
+    Csv,
+    /// Markdown journal grouped by day
+    Markdown,
+}
+
+/// Which entries to export
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    /// Start of the date range (RFC 3339 or YYYY-MM-DD)
+    pub from: Option<String>,
+    /// End of the date range (a bare date includes the whole day)
+    pub to: Option<String>,
+    /// Search query
+    pub query: Option<String>,
+}
+
+/// Export history entries to a file, or stdout when no output is given
+pub fn export(
+    format: ExportFormat,
+    filter: &ExportFilter,
+    output: Option<&Path>,
+    ctx: &Context,
+) -> Result<()> {
+    let path = database_path()?;
+    let conn = open_database(&path)?;
+
+    let entries = find_entries(&conn, filter)?;
+    let content = match format {
+        ExportFormat::Json => to_json(&conn, &entries)?,
+        ExportFormat::Csv => formats::to_csv(&entries),
+        ExportFormat::Markdown => formats::to_markdown(&entries),
+    };
+
+    match output {
+        Some(output) => {
+            std::fs::write(output, content)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+
+            if ctx.json_output {
+                let result = serde_json::json!({
+                    "exported": entries.len(),
+                    "path": output,
+                });
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                println!(
+                    "{} Exported {} entries to {}",
+                    "✓".green(),
+                    entries.len(),
+                    output.display()
+                );
+            }
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// Location of the desktop app's history database
+fn database_path() -> Result<PathBuf> {
+    let dirs = directories::ProjectDirs::from("com", "ezflow", "EZ Flow")
+        .context("Could not determine data directory")?;
+    Ok(dirs.data_dir().join("history.db"))
+}
+
+fn open_database(path: &Path) -> Result<Connection> {
+    if !path.exists() {
+        bail!("History database not found at {}", path.display());
+    }
+    if is_encrypted(path).with_context(|| format!("Failed to read {}", path.display()))? {
+        bail!("History database is encrypted; export it from the EZ Flow app instead");
+    }
+
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < ENTRY_COLUMNS_VERSION {
+        bail!("History database is from an older EZ Flow version; open the app once to upgrade it");
+    }
+
+    Ok(conn)
+}
+
+/// Entries matching the filter, oldest first
+fn find_entries(conn: &Connection, filter: &ExportFilter) -> Result<Vec<HistoryRecord>> {
+    let mut values = Vec::new();
+    let conditions = range_conditions(filter, &mut values);
+
+    let search = SearchQuery::parse(filter.query.as_deref().unwrap_or_default());
+    if search.is_empty() {
+        return query_entries(conn, "FROM history h", &conditions, values);
+    }
+
+    // Full-text search first, then substring matching like the app
+    let mut fts_values = Vec::new();
+    let mut fts_conditions = Vec::new();
+    let mut from = "FROM history h";
+    if let Some(fts) = search.fts_match() {
+        from = "FROM history h JOIN history_fts fts ON h.id = fts.rowid";
+        fts_conditions.push("history_fts MATCH ?".to_string());
+        fts_values.push(Value::Text(fts));
+    } else if let Some(excluded) = search.fts_excluded() {
+        fts_conditions.push(
+            "h.id NOT IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)".to_string(),
+        );
+        fts_values.push(Value::Text(excluded));
+    }
+    fts_conditions.extend(conditions.iter().cloned());
+    fts_values.extend(values.iter().cloned());
+    let entries = query_entries(conn, from, &fts_conditions, fts_values)?;
+    if !entries.is_empty() {
+        return Ok(entries);
+    }
+
+    let mut like_values = Vec::new();
+    let mut like_conditions = Vec::new();
+    for (terms, condition) in [
+        (&search.include, "LOWER(h.text) LIKE ?"),
+        (&search.exclude, "LOWER(h.text) NOT LIKE ?"),
+    ] {
+        for term in terms {
+            like_conditions.push(condition.to_string());
+            like_values.push(Value::Text(format!("%{}%", term.text.to_lowercase())));
+        }
+    }
+    like_conditions.extend(conditions);
+    like_values.extend(values);
+    query_entries(conn, "FROM history h", &like_conditions, like_values)
+}
+
+fn range_conditions(filter: &ExportFilter, values: &mut Vec<Value>) -> Vec<String> {
+    let mut conditions = Vec::new();
+
+    if let Some(from) = &filter.from {
+        conditions.push("julianday(h.timestamp) >= julianday(?)".to_string());
+        values.push(Value::Text(from.clone()));
+    }
+    if let Some(to) = &filter.to {
+        if NaiveDate::parse_from_str(to, "%Y-%m-%d").is_ok() {
+            conditions.push("julianday(h.timestamp) < julianday(?, '+1 day')".to_string());
+        } else {
+            conditions.push("julianday(h.timestamp) <= julianday(?)".to_string());
+        }
+        values.push(Value::Text(to.clone()));
+    }
+
+    conditions
+}
+
+fn query_entries(
+    conn: &Connection,
+    from: &str,
+    conditions: &[String],
+    values: Vec<Value>,
+) -> Result<Vec<HistoryRecord>> {
+    let mut sql = format!("SELECT {} {}", ENTRY_COLUMNS, from);
+    if !conditions.is_empty() {
+        sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+    }
+    sql.push_str(" ORDER BY h.timestamp ASC");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let entries = stmt
+        .query_map(params_from_iter(values), HistoryRecord::from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(entries)
+}
+
+/// JSON export with each entry's revisions, importable in the app
+fn to_json(conn: &Connection, records: &[HistoryRecord]) -> Result<String> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM history_revisions WHERE history_id = ?1 ORDER BY created_at ASC",
+        REVISION_COLUMNS
+    ))?;
+
+    let mut entries = Vec::with_capacity(records.len());
+    for record in records {
+        let revisions = stmt
+            .query_map([record.id], RevisionRecord::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        entries.push(ExportedEntry {
+            entry: record.clone(),
+            revisions,
+        });
+    }
+
+    Ok(HistoryExport::new(entries).to_json()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                text TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                model_id TEXT NOT NULL,
+                language TEXT,
+                gpu_used INTEGER DEFAULT 0,
+                audio_path TEXT,
+                source TEXT,
+                source_app TEXT,
+                source_window TEXT,
+                streaming_mode TEXT,
+                transcription_ms INTEGER,
+                injection_ms INTEGER,
+                voice_commands TEXT,
+                pinned INTEGER NOT NULL DEFAULT 0,
+                original_text TEXT,
+                translated INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE history_revisions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                history_id INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                model_id TEXT NOT NULL,
+                language TEXT,
+                gpu_used INTEGER DEFAULT 0,
+                options TEXT,
+                created_at TEXT NOT NULL
+            );
+            CREATE VIRTUAL TABLE history_fts USING fts5(
+                text, content='history', content_rowid='id'
+            );
+            INSERT INTO history (
+                text, timestamp, duration_ms, model_id, source_app, voice_commands, pinned,
+                original_text
+            )
+            VALUES
+                ('Meeting notes, part one', '2024-01-01T09:00:00+00:00', 1000, 'base', 'obsidian',
+                 '[{"type":"insert_text","value":"."}]', 1, 'Meeting notes part one'),
+                ('Shopping list', '2024-01-02T09:00:00+00:00', 1000, 'base', NULL, NULL, 0, NULL),
+                ('More meeting notes', '2024-01-03T09:00:00+00:00', 1000, 'base', NULL, NULL, 0,
+                 NULL);
+            INSERT INTO history_revisions (history_id, text, model_id, created_at)
+            VALUES (2, 'Shopping lists', 'small', '2024-01-04T09:00:00+00:00');
+            INSERT INTO history_fts (rowid, text) SELECT id, text FROM history;
+            "#,
+        )
+        .unwrap();
+        conn
+    }
+
+    fn texts(entries: &[HistoryRecord]) -> Vec<&str> {
+        entries.iter().map(|e| e.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_find_entries_oldest_first() {
+        let conn = test_db();
+        let entries = find_entries(&conn, &ExportFilter::default()).unwrap();
+        assert_eq!(
+            texts(&entries),
+            vec![
+                "Meeting notes, part one",
+                "Shopping list",
+                "More meeting notes"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_entries_with_query_and_range() {
+        let conn = test_db();
+
+        let filter = ExportFilter {
+            query: Some("meeting".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(find_entries(&conn, &filter).unwrap().len(), 2);
+
+        let filter = ExportFilter {
+            from: Some("2024-01-02".to_string()),
+            to: Some("2024-01-02".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            texts(&find_entries(&conn, &filter).unwrap()),
+            vec!["Shopping list"]
+        );
+
+        // Substring fallback when full-text search finds nothing
+        let filter = ExportFilter {
+            query: Some("opping".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            texts(&find_entries(&conn, &filter).unwrap()),
+            vec!["Shopping list"]
+        );
+    }
+
+    #[test]
+    fn test_find_entries_with_phrases_and_exclusions() {
+        let conn = test_db();
+        let search = |query: &str| {
+            let filter = ExportFilter {
+                query: Some(query.to_string()),
+                ..Default::default()
+            };
+            find_entries(&conn, &filter).unwrap()
+        };
+
+        assert_eq!(
+            texts(&search("\"meeting notes\" -more")),
+            vec!["Meeting notes, part one"]
+        );
+        assert_eq!(
+            texts(&search("meeting NOT part")),
+            vec!["More meeting notes"]
+        );
+        // Only exclusions: everything except the excluded entries
+        assert_eq!(texts(&search("-meeting")), vec!["Shopping list"]);
+        // Substring fallback honours exclusions too
+        assert_eq!(texts(&search("opping -list")), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_json_matches_app_format() {
+        let conn = test_db();
+        let entries = find_entries(&conn, &ExportFilter::default()).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json(&conn, &entries).unwrap()).unwrap();
+
+        assert_eq!(json["version"], formats::EXPORT_VERSION);
+        let first = &json["entries"][0];
+        assert_eq!(first["source_app"], "obsidian");
+        assert_eq!(first["voice_commands"][0]["type"], "insert_text");
+        assert_eq!(first["pinned"], true);
+        assert_eq!(first["original_text"], "Meeting notes part one");
+        assert_eq!(json["entries"][1]["pinned"], false);
+        assert!(first.get("revisions").is_none());
+        assert_eq!(json["entries"][1]["revisions"][0]["text"], "Shopping lists");
+    }
+
+    #[test]
+    fn test_csv_and_markdown() {
+        let conn = test_db();
+        let entries = find_entries(&conn, &ExportFilter::default()).unwrap();
+
+        let csv = formats::to_csv(&entries);
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.contains("\"Meeting notes, part one\""));
+
+        let markdown = formats::to_markdown(&entries);
+        assert_eq!(markdown.matches("\n### ").count(), 3);
+        assert!(markdown.contains(" · obsidian\n\nMeeting notes, part one\n"));
+    }
+}
@@ -1,5 +1,6 @@
 //! CLI command implementations
 
+pub mod history;
 pub mod models;
 pub mod record;
 pub mod transcribe;
@@ -60,6 +60,12 @@ enum Commands {
         #[command(subcommand)]
         action: ModelsAction,
     },
+
+    /// Work with the desktop app's transcription history
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
 }
 
 #[derive(Subcommand)]
@@ -86,6 +92,32 @@ enum ModelsAction {
     },
 }
 
+#[derive(Subcommand)]
+enum HistoryAction {
+    /// Export history entries
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "json")]
+        format: commands::history::ExportFormat,
+
+        /// Only entries on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Only entries on or before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Only entries matching this search query (words, "phrases", -excluded)
+        #[arg(long)]
+        query: Option<String>,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 fn init_logging(verbose: bool) {
     let filter = if verbose {
         EnvFilter::from_default_env()
@@ -133,6 +165,18 @@ fn run() -> anyhow::Result<()> {
                 commands::models::info(&name, &ctx)?;
             }
         },
+        Commands::History { action } => match action {
+            HistoryAction::Export {
+                format,
+                from,
+                to,
+                query,
+                output,
+            } => {
+                let filter = commands::history::ExportFilter { from, to, query };
+                commands::history::export(format, &filter, output.as_deref(), &ctx)?;
+            }
+        },
     }
 
     Ok(())
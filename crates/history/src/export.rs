@@ -0,0 +1,324 @@
+//! History export formats
+//!
+//! JSON exports carry every field (including revisions) and can be imported
+//! back; CSV is meant for spreadsheets and Markdown for reading as a journal.
+
+use chrono::{DateTime, Local, Utc};
+use rusqlite::Row;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Current export format version
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Errors reading a JSON export
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Invalid history export file: {0}")]
+    Invalid(#[from] serde_json::Error),
+
+    #[error("Unsupported history export version: {0}. Maximum supported: {max}", max = EXPORT_VERSION)]
+    UnsupportedVersion(u32),
+}
+
+/// JSON export file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryExport<T> {
+    /// Format version
+    pub version: u32,
+    /// When the export was created (RFC 3339)
+    pub exported_at: String,
+    /// Entries, oldest first
+    pub entries: Vec<T>,
+}
+
+impl<T> HistoryExport<T> {
+    /// Create an export of the given entries
+    pub fn new(entries: Vec<T>) -> Self {
+        Self {
+            version: EXPORT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            entries,
+        }
+    }
+}
+
+impl<T: Serialize> HistoryExport<T> {
+    /// Serialize as pretty-printed JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl<T: for<'de> Deserialize<'de>> HistoryExport<T> {
+    /// Parse and validate a JSON export
+    pub fn from_json(content: &str) -> Result<Self, ExportError> {
+        let export: Self = serde_json::from_str(content)?;
+        if export.version > EXPORT_VERSION {
+            return Err(ExportError::UnsupportedVersion(export.version));
+        }
+        Ok(export)
+    }
+}
+
+/// An entry with its revisions, as stored in a JSON export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedEntry<E, R> {
+    #[serde(flatten)]
+    pub entry: E,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<R>,
+}
+
+/// A history entry as it appears in an export
+///
+/// Serializes like the app's history entries, with enums kept as their
+/// names, so it can be read straight from the database without the app's
+/// models.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HistoryRecord {
+    pub id: i64,
+    pub text: String,
+    pub timestamp: String,
+    pub duration_ms: u64,
+    pub model_id: String,
+    pub language: Option<String>,
+    pub gpu_used: bool,
+    pub audio_path: Option<String>,
+    pub pinned: bool,
+    pub original_text: Option<String>,
+    pub source: Option<String>,
+    pub source_app: Option<String>,
+    pub source_window: Option<String>,
+    pub streaming_mode: Option<String>,
+    pub transcription_time_ms: Option<u64>,
+    pub injection_time_ms: Option<u64>,
+    pub voice_commands: serde_json::Value,
+    pub translated: bool,
+}
+
+impl HistoryRecord {
+    /// Read a row selected with [`ENTRY_COLUMNS`](crate::schema::ENTRY_COLUMNS)
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let voice_commands: Option<String> = row.get(14)?;
+        Ok(Self {
+            id: row.get(0)?,
+            text: row.get(1)?,
+            timestamp: row.get(2)?,
+            duration_ms: row.get::<_, i64>(3)? as u64,
+            model_id: row.get(4)?,
+            language: row.get(5)?,
+            gpu_used: row.get::<_, i32>(6).unwrap_or(0) != 0,
+            audio_path: row.get(7)?,
+            pinned: row.get::<_, i32>(15).unwrap_or(0) != 0,
+            original_text: row.get(16)?,
+            source: row.get(8)?,
+            source_app: row.get(9)?,
+            source_window: row.get(10)?,
+            streaming_mode: row.get(11)?,
+            transcription_time_ms: row.get::<_, Option<i64>>(12)?.map(|ms| ms as u64),
+            injection_time_ms: row.get::<_, Option<i64>>(13)?.map(|ms| ms as u64),
+            voice_commands: voice_commands
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_else(|| serde_json::json!([])),
+            translated: row.get::<_, i32>(17).unwrap_or(0) != 0,
+        })
+    }
+}
+
+/// A re-transcription revision as it appears in an export
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RevisionRecord {
+    pub id: i64,
+    pub history_id: i64,
+    pub text: String,
+    pub model_id: String,
+    pub language: Option<String>,
+    pub gpu_used: bool,
+    pub options: serde_json::Value,
+    pub created_at: String,
+}
+
+impl RevisionRecord {
+    /// Read a row selected with
+    /// [`REVISION_COLUMNS`](crate::schema::REVISION_COLUMNS)
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let options: Option<String> = row.get(6)?;
+        Ok(Self {
+            id: row.get(0)?,
+            history_id: row.get(1)?,
+            text: row.get(2)?,
+            model_id: row.get(3)?,
+            language: row.get(4)?,
+            gpu_used: row.get::<_, i32>(5).unwrap_or(0) != 0,
+            options: options
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_else(|| serde_json::json!({})),
+            created_at: row.get(7)?,
+        })
+    }
+}
+
+/// Column headers of the CSV export
+pub const CSV_HEADER: &[&str] = &[
+    "id",
+    "timestamp",
+    "text",
+    "duration_ms",
+    "model_id",
+    "language",
+    "gpu_used",
+    "source",
+    "source_app",
+    "source_window",
+    "streaming_mode",
+    "transcription_time_ms",
+    "injection_time_ms",
+    "voice_commands",
+];
+
+/// Render entries as CSV (RFC 4180 quoting)
+pub fn to_csv(entries: &[HistoryRecord]) -> String {
+    let mut out = csv_row(CSV_HEADER.iter().map(|h| h.to_string()));
+
+    for entry in entries {
+        let voice_commands = match entry.voice_commands.as_array() {
+            Some(commands) if !commands.is_empty() => entry.voice_commands.to_string(),
+            _ => String::new(),
+        };
+        out.push_str(&csv_row([
+            entry.id.to_string(),
+            entry.timestamp.clone(),
+            entry.text.clone(),
+            entry.duration_ms.to_string(),
+            entry.model_id.clone(),
+            entry.language.clone().unwrap_or_default(),
+            entry.gpu_used.to_string(),
+            entry.source.clone().unwrap_or_default(),
+            entry.source_app.clone().unwrap_or_default(),
+            entry.source_window.clone().unwrap_or_default(),
+            entry.streaming_mode.clone().unwrap_or_default(),
+            optional(entry.transcription_time_ms),
+            optional(entry.injection_time_ms),
+            voice_commands,
+        ]));
+    }
+
+    out
+}
+
+fn csv_row(fields: impl IntoIterator<Item = String>) -> String {
+    let mut row = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push_str("\r\n");
+    row
+}
+
+fn optional(value: Option<u64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Render entries as a Markdown journal, one section per local day
+pub fn to_markdown(entries: &[HistoryRecord]) -> String {
+    let mut out = String::from("# EZ Flow History\n");
+    let mut current_day = None;
+
+    for entry in entries {
+        let time = DateTime::parse_from_rfc3339(&entry.timestamp)
+            .ok()
+            .map(|t| t.with_timezone(&Local));
+
+        let day = time.map(|t| t.date_naive());
+        if current_day != Some(day) {
+            let heading = match time {
+                Some(t) => t.format("%A, %B %-d, %Y").to_string(),
+                None => "Unknown date".to_string(),
+            };
+            out.push_str(&format!("\n## {}\n", heading));
+            current_day = Some(day);
+        }
+
+        let mut heading = time
+            .map(|t| t.format("%H:%M").to_string())
+            .unwrap_or_else(|| entry.timestamp.clone());
+        if let Some(app) = &entry.source_app {
+            heading.push_str(&format!(" · {}", app));
+        }
+        out.push_str(&format!("\n### {}\n\n{}\n", heading, entry.text.trim()));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: i64, text: &str, timestamp: &str) -> HistoryRecord {
+        HistoryRecord {
+            id,
+            text: text.to_string(),
+            timestamp: timestamp.to_string(),
+            duration_ms: 1200,
+            model_id: "base".to_string(),
+            language: Some("en".to_string()),
+            source: Some("hotkey".to_string()),
+            source_app: Some("firefox".to_string()),
+            transcription_time_ms: Some(250),
+            voice_commands: serde_json::json!([{"type": "insert_text", "value": "."}]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_json_rejects_newer_version() {
+        let json = r#"{"version": 99, "exported_at": "2024-01-01T00:00:00Z", "entries": []}"#;
+        let err = HistoryExport::<serde_json::Value>::from_json(json)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unsupported history export version"));
+    }
+
+    #[test]
+    fn test_json_rejects_invalid_file() {
+        assert!(HistoryExport::<serde_json::Value>::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let csv = to_csv(&[record(7, "Hi, \"you\"\nthere", "2024-01-01T12:00:00+00:00")]);
+        let mut lines = csv.split("\r\n");
+
+        assert_eq!(lines.next().unwrap(), CSV_HEADER.join(","));
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("7,2024-01-01T12:00:00+00:00,\"Hi, \"\"you\"\"\nthere\","));
+        assert!(csv.contains(",hotkey,firefox,,,250,,"));
+        assert!(csv.contains("\"[{\"\"type\"\":\"\"insert_text\"\",\"\"value\"\":\"\".\"\"}]\""));
+    }
+
+    #[test]
+    fn test_markdown_groups_by_day() {
+        let entries = [
+            record(1, "First note.", "2024-03-01T12:00:00+00:00"),
+            record(2, "Second note.", "2024-03-01T12:30:00+00:00"),
+            record(3, "Next day.", "2024-03-02T12:00:00+00:00"),
+        ];
+
+        let markdown = to_markdown(&entries);
+
+        assert!(markdown.starts_with("# EZ Flow History\n"));
+        assert_eq!(markdown.matches("\n## ").count(), 2);
+        assert_eq!(markdown.matches("\n### ").count(), 3);
+        assert!(markdown.contains(" · firefox\n\nFirst note.\n"));
+        assert!(markdown.find("First note.").unwrap() < markdown.find("Next day.").unwrap());
+    }
+}
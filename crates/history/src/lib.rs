@@ -0,0 +1,9 @@
+//! Transcription history shared by the EZ Flow app and CLI
+//!
+//! The app owns the history database; the CLI reads it directly. Both use
+//! the same columns, search syntax and export formats from this crate, so
+//! a query or an export gives the same result in either.
+
+pub mod export;
+pub mod schema;
+pub mod search;
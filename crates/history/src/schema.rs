@@ -0,0 +1,58 @@
+//! History database layout
+
+use std::io::Read;
+use std::path::Path;
+
+/// History columns in the order entries are read (table alias `h`)
+pub const ENTRY_COLUMNS: &str = "h.id, h.text, h.timestamp, h.duration_ms, h.model_id, \
+     h.language, h.gpu_used, h.audio_path, h.source, h.source_app, h.source_window, \
+     h.streaming_mode, h.transcription_ms, h.injection_ms, h.voice_commands, h.pinned, \
+     h.original_text, h.translated";
+
+/// Oldest schema version (`PRAGMA user_version`) with every column in
+/// `ENTRY_COLUMNS`
+pub const ENTRY_COLUMNS_VERSION: i64 = 9;
+
+/// Revision columns in the order revisions are read
+pub const REVISION_COLUMNS: &str =
+    "id, history_id, text, model_id, language, gpu_used, options, created_at";
+
+/// First bytes of every unencrypted SQLite database
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Whether the file at `path` is an encrypted (SQLCipher) database. Missing
+/// and empty files are not.
+pub fn is_encrypted(path: &Path) -> std::io::Result<bool> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    file.by_ref()
+        .take(SQLITE_HEADER.len() as u64)
+        .read_to_end(&mut header)?;
+    Ok(!header.is_empty() && header != SQLITE_HEADER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_encrypted() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        assert!(!is_encrypted(&path).unwrap());
+
+        std::fs::write(&path, b"").unwrap();
+        assert!(!is_encrypted(&path).unwrap());
+
+        std::fs::write(&path, b"SQLite format 3\0rest of the page").unwrap();
+        assert!(!is_encrypted(&path).unwrap());
+
+        std::fs::write(&path, [0x5a; 64]).unwrap();
+        assert!(is_encrypted(&path).unwrap());
+    }
+}
@@ -0,0 +1,352 @@
+//! History search query parsing and match highlighting
+//!
+//! Queries are plain words (prefix matched), `"quoted phrases"` (matched
+//! exactly) and exclusions written as `-word` or `NOT word`. They translate
+//! to FTS5 expressions for full-text search and to substrings for the `LIKE`
+//! fallback.
+
+use std::ops::Range;
+
+/// Marks the start of a match in FTS5 `highlight()`/`snippet()` output
+pub const MATCH_START: char = '\u{2}';
+
+/// Marks the end of a match in FTS5 `highlight()`/`snippet()` output
+pub const MATCH_END: char = '\u{3}';
+
+/// Characters of context kept before the first match in an excerpt
+const EXCERPT_CONTEXT: usize = 40;
+
+/// Maximum characters in an excerpt
+const EXCERPT_LENGTH: usize = 160;
+
+/// A word or phrase in a search query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchTerm {
+    pub text: String,
+    /// Quoted phrase (exact) rather than a word (prefix)
+    pub phrase: bool,
+}
+
+impl SearchTerm {
+    fn to_fts(&self) -> String {
+        // Double quotes are escaped by doubling them inside an FTS5 string
+        let escaped = self.text.replace('"', "\"\"");
+        if self.phrase {
+            format!("\"{}\"", escaped)
+        } else {
+            format!("\"{}\"*", escaped)
+        }
+    }
+}
+
+/// A parsed search query
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Terms that must all match
+    pub include: Vec<SearchTerm>,
+    /// Terms that must not match
+    pub exclude: Vec<SearchTerm>,
+}
+
+impl SearchQuery {
+    /// Parse a query typed by the user
+    pub fn parse(query: &str) -> Self {
+        let mut search = Self::default();
+        let mut chars = query.chars().peekable();
+        let mut negate_next = false;
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            let Some(&first) = chars.peek() else {
+                break;
+            };
+
+            let mut negate = std::mem::take(&mut negate_next);
+            if first == '-' {
+                chars.next();
+                negate = true;
+            }
+
+            let term = if chars.next_if_eq(&'"').is_some() {
+                let text: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                SearchTerm {
+                    text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                    phrase: true,
+                }
+            } else {
+                let mut text = String::new();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    text.push(c);
+                }
+                if text == "NOT" && !negate {
+                    negate_next = true;
+                    continue;
+                }
+                SearchTerm {
+                    text,
+                    phrase: false,
+                }
+            };
+
+            if term.text.is_empty() {
+                continue;
+            }
+            if negate {
+                search.exclude.push(term);
+            } else {
+                search.include.push(term);
+            }
+        }
+
+        search
+    }
+
+    /// Whether the query has no terms
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// FTS5 expression for the included terms minus the excluded ones, or
+    /// `None` when nothing is included
+    pub fn fts_match(&self) -> Option<String> {
+        if self.include.is_empty() {
+            return None;
+        }
+
+        let include = self
+            .include
+            .iter()
+            .map(SearchTerm::to_fts)
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.exclude.is_empty() {
+            return Some(include);
+        }
+
+        let exclude = self
+            .exclude
+            .iter()
+            .map(|term| format!(" NOT {}", term.to_fts()))
+            .collect::<String>();
+        Some(format!("({}){}", include, exclude))
+    }
+
+    /// FTS5 expression matching any excluded term, or `None` when nothing is
+    /// excluded
+    pub fn fts_excluded(&self) -> Option<String> {
+        if self.exclude.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.exclude
+                .iter()
+                .map(SearchTerm::to_fts)
+                .collect::<Vec<_>>()
+                .join(" OR "),
+        )
+    }
+}
+
+/// Remove `MATCH_START`/`MATCH_END` markers, returning the plain text and the
+/// byte ranges that were marked
+pub fn take_highlights(marked: &str) -> (String, Vec<Range<usize>>) {
+    let mut text = String::with_capacity(marked.len());
+    let mut ranges = Vec::new();
+    let mut start = None;
+
+    for c in marked.chars() {
+        match c {
+            MATCH_START => start = Some(text.len()),
+            MATCH_END => {
+                if let Some(start) = start.take() {
+                    ranges.push(start..text.len());
+                }
+            }
+            _ => text.push(c),
+        }
+    }
+
+    (text, ranges)
+}
+
+/// Byte ranges of case-insensitive (ASCII) occurrences of `needles` in
+/// `text`, sorted and merged
+pub fn find_highlights(text: &str, needles: &[SearchTerm]) -> Vec<Range<usize>> {
+    let haystack = text.to_ascii_lowercase();
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+
+    for needle in needles {
+        let needle = needle.text.to_ascii_lowercase();
+        ranges.extend(
+            haystack
+                .match_indices(&needle)
+                .map(|(start, m)| start..start + m.len()),
+        );
+    }
+
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// A window of `text` around the first match, with the matches inside it
+pub fn excerpt(text: &str, highlights: &[Range<usize>]) -> (String, Vec<Range<usize>>) {
+    let first = highlights.first().map(|r| r.start).unwrap_or(0);
+    let context_start = text[..first]
+        .char_indices()
+        .rev()
+        .nth(EXCERPT_CONTEXT - 1)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    // Start at a word boundary when one is close by
+    let start = if context_start == 0 {
+        0
+    } else {
+        text[context_start..first]
+            .find(char::is_whitespace)
+            .map(|i| context_start + i + 1)
+            .unwrap_or(context_start)
+    };
+    let end = text[start..]
+        .char_indices()
+        .nth(EXCERPT_LENGTH)
+        .map(|(i, _)| start + i)
+        .unwrap_or(text.len());
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    let shift = |i: usize| i - start + prefix.len();
+    let ranges = highlights
+        .iter()
+        .filter(|r| r.start >= start && r.end <= end)
+        .map(|r| shift(r.start)..shift(r.end))
+        .collect();
+
+    (
+        format!("{}{}{}", prefix, text[start..end].trim_end(), suffix),
+        ranges,
+    )
+}
+
+/// Convert byte ranges in `text` to `[start, end)` UTF-16 offsets
+pub fn utf16_ranges(text: &str, ranges: &[Range<usize>]) -> Vec<[usize; 2]> {
+    let offset = |byte: usize| text[..byte].encode_utf16().count();
+    ranges
+        .iter()
+        .map(|r| [offset(r.start), offset(r.end)])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> SearchTerm {
+        SearchTerm {
+            text: text.to_string(),
+            phrase: false,
+        }
+    }
+
+    fn phrase(text: &str) -> SearchTerm {
+        SearchTerm {
+            text: text.to_string(),
+            phrase: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_words_phrases_and_exclusions() {
+        let search = SearchQuery::parse(r#"meeting "action  items" -draft NOT "old notes""#);
+        assert_eq!(
+            search.include,
+            vec![word("meeting"), phrase("action items")]
+        );
+        assert_eq!(search.exclude, vec![word("draft"), phrase("old notes")]);
+    }
+
+    #[test]
+    fn test_parse_ignores_empty_terms() {
+        assert!(SearchQuery::parse("  - \"\" NOT ").is_empty());
+        // A lowercase "not" is an ordinary word
+        assert_eq!(SearchQuery::parse("not").include, vec![word("not")]);
+        // An unterminated quote runs to the end
+        assert_eq!(
+            SearchQuery::parse("\"open phrase").include,
+            vec![phrase("open phrase")]
+        );
+    }
+
+    #[test]
+    fn test_fts_match() {
+        assert_eq!(
+            SearchQuery::parse("hello world").fts_match().unwrap(),
+            "\"hello\"* \"world\"*"
+        );
+        assert_eq!(
+            SearchQuery::parse("\"hello world\" -bye")
+                .fts_match()
+                .unwrap(),
+            "(\"hello world\") NOT \"bye\"*"
+        );
+        assert!(SearchQuery::parse("-bye").fts_match().is_none());
+        assert_eq!(
+            SearchQuery::parse("-bye -ciao").fts_excluded().unwrap(),
+            "\"bye\"* OR \"ciao\"*"
+        );
+    }
+
+    #[test]
+    fn test_take_highlights() {
+        let marked = format!(
+            "say {}hello{} to {}me{}",
+            MATCH_START, MATCH_END, MATCH_START, MATCH_END
+        );
+        let (text, ranges) = take_highlights(&marked);
+        assert_eq!(text, "say hello to me");
+        assert_eq!(ranges, vec![4..9, 13..15]);
+    }
+
+    #[test]
+    fn test_find_highlights_merges_overlaps() {
+        let ranges = find_highlights("Meeting notes", &[word("ting"), word("eting n")]);
+        assert_eq!(ranges, vec![2..9]);
+    }
+
+    #[test]
+    fn test_excerpt_windows_long_text() {
+        let text = format!("{} needle {}", "lead ".repeat(30), "tail ".repeat(60));
+        let highlights = find_highlights(&text, &[word("needle")]);
+
+        let (snippet, ranges) = excerpt(&text, &highlights);
+
+        assert!(snippet.starts_with("…lead"));
+        assert!(snippet.ends_with('…'));
+        assert_eq!(&snippet[ranges[0].clone()], "needle");
+    }
+
+    #[test]
+    fn test_excerpt_keeps_short_text() {
+        let (snippet, ranges) = excerpt(
+            "short text",
+            &find_highlights("short text", &[word("text")]),
+        );
+        assert_eq!(snippet, "short text");
+        assert_eq!(ranges, vec![6..10]);
+    }
+
+    #[test]
+    fn test_utf16_ranges() {
+        // "é" is 2 bytes but 1 UTF-16 unit, "😀" is 4 bytes and 2 units
+        let text = "é😀 word";
+        let ranges = find_highlights(text, &[word("word")]);
+        assert_eq!(utf16_ranges(text, &ranges), vec![[4, 8]]);
+    }
+}
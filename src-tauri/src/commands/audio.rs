@@ -6,13 +6,12 @@
 //! for audio operations and communicate via channels.
 
 use crate::commands::TranscriptionState;
-use crate::models::HistoryEntry;
-use crate::services::audio::processing::AudioBuffer;
+use crate::models::{HistoryEntry, HistoryMetadata, HistorySource};
 use crate::services::audio::{
-    capture::save_to_temp_wav, processing::resample_for_whisper, AudioCaptureService, AudioDevice,
-    AudioError, PermissionStatus, RecordingResult,
+    AudioCaptureService, AudioDevice, AudioError, PermissionStatus, RecordedAudio,
+    RecordingResult, WindowSender,
 };
-use crate::services::storage::{DatabaseState, SettingsState};
+use crate::services::storage::{archive, DatabaseState, SettingsState};
 use crate::services::transcription::TranscriptionResult;
 use crate::services::ui::indicator::emit_audio_level;
 use chrono::Utc;
@@ -25,7 +24,12 @@ use tauri::{AppHandle, Emitter, Runtime, State};
 pub enum AudioCommand {
     Start,
     Stop,
+    /// Pause the current recording without stopping the stream
+    Pause,
+    /// Resume a paused recording
+    Resume,
     IsRecording,
+    IsPaused,
     GetDuration,
     GetLevel,
     Shutdown,
@@ -37,14 +41,16 @@ pub enum AudioCommand {
     GetChunks,
     /// Flush remaining samples as a final chunk
     FlushChunk,
-    /// Get full audio buffer for reconciliation
-    GetFullBuffer,
+    /// Send the transcription windows of the current recording as they
+    /// are recorded
+    WatchWindows(WindowSender),
 }
 
 /// Responses from the audio thread
 pub enum AudioResponse {
     Ok,
-    Buffer(Result<AudioBuffer, String>),
+    /// Finished recording (in memory or spilled to disk)
+    Recording(Result<RecordedAudio, String>),
     Bool(bool),
     Duration(f32),
     Level(f32),
@@ -53,8 +59,6 @@ pub enum AudioResponse {
     Chunks(Vec<crate::services::audio::AudioChunk>),
     /// Single chunk (e.g., flushed remaining samples)
     Chunk(Option<crate::services::audio::AudioChunk>),
-    /// Full audio buffer as samples
-    Samples(Vec<f32>),
 }
 
 /// Thread-safe handle to the audio capture thread
@@ -137,12 +141,39 @@ impl AudioState {
                         } else {
                             Err("No recording in progress".to_string())
                         };
-                        let _ = resp_tx.send(AudioResponse::Buffer(result));
+                        let _ = resp_tx.send(AudioResponse::Recording(result));
+                    }
+                    Ok(AudioCommand::Pause) => {
+                        let response = match service.as_mut() {
+                            Some(svc) if svc.is_recording() => {
+                                svc.pause();
+                                if let Ok(mut lvl) = shared_level.lock() {
+                                    *lvl = 0.0;
+                                }
+                                AudioResponse::Ok
+                            }
+                            _ => AudioResponse::Error("No recording in progress".to_string()),
+                        };
+                        let _ = resp_tx.send(response);
+                    }
+                    Ok(AudioCommand::Resume) => {
+                        let response = match service.as_mut() {
+                            Some(svc) if svc.is_recording() => {
+                                svc.resume();
+                                AudioResponse::Ok
+                            }
+                            _ => AudioResponse::Error("No recording in progress".to_string()),
+                        };
+                        let _ = resp_tx.send(response);
                     }
                     Ok(AudioCommand::IsRecording) => {
                         let is_rec = service.as_ref().map(|s| s.is_recording()).unwrap_or(false);
                         let _ = resp_tx.send(AudioResponse::Bool(is_rec));
                     }
+                    Ok(AudioCommand::IsPaused) => {
+                        let is_paused = service.as_ref().map(|s| s.is_paused()).unwrap_or(false);
+                        let _ = resp_tx.send(AudioResponse::Bool(is_paused));
+                    }
                     Ok(AudioCommand::GetDuration) => {
                         let dur = service
                             .as_ref()
@@ -197,12 +228,15 @@ impl AudioState {
                         let chunk = service.as_ref().and_then(|s| s.flush_remaining_chunk());
                         let _ = resp_tx.send(AudioResponse::Chunk(chunk));
                     }
-                    Ok(AudioCommand::GetFullBuffer) => {
-                        let samples = service
-                            .as_ref()
-                            .map(|s| s.get_full_chunked_buffer())
-                            .unwrap_or_default();
-                        let _ = resp_tx.send(AudioResponse::Samples(samples));
+                    Ok(AudioCommand::WatchWindows(sender)) => {
+                        let response = match service.as_ref() {
+                            Some(svc) if svc.is_recording() => {
+                                svc.watch_windows(sender);
+                                AudioResponse::Ok
+                            }
+                            _ => AudioResponse::Error("No recording in progress".to_string()),
+                        };
+                        let _ = resp_tx.send(response);
                     }
                     Ok(AudioCommand::Shutdown) => {
                         break;
@@ -210,7 +244,7 @@ impl AudioState {
                     Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
                         // Update shared level during recording
                         if let Some(ref svc) = service {
-                            if svc.is_recording() {
+                            if svc.is_recording() && !svc.is_paused() {
                                 let level = svc.get_current_level();
                                 // Debug: log level updates periodically
                                 static LEVEL_LOG_COUNTER: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
@@ -433,26 +467,78 @@ pub async fn stop_recording(
     let _ = app.emit("tray://update-recording-state", false);
 
     match state.send_command(AudioCommand::Stop)? {
-        AudioResponse::Buffer(Ok(buffer)) => {
-            if buffer.samples.is_empty() {
+        AudioResponse::Recording(Ok(recording)) => {
+            if recording.is_empty() {
                 return Err("No audio recorded".to_string());
             }
 
-            // Resample to 16kHz for Whisper if needed
-            let resampled = resample_for_whisper(buffer.clone()).map_err(|e| e.to_string())?;
-            let resampled_buffer = AudioBuffer::new(resampled, 16000);
-
-            // Save to temporary WAV file
-            let result = save_to_temp_wav(&resampled_buffer).map_err(|e| e.to_string())?;
+            // Resample to 16kHz for Whisper and save to a temporary WAV file
+            let result = save_recording_to_temp_wav(&recording).map_err(|e| e.to_string())?;
 
             tracing::info!("Recording stopped, saved to: {}", result.file_path);
             Ok(result)
         }
-        AudioResponse::Buffer(Err(e)) => Err(e),
+        AudioResponse::Recording(Err(e)) => Err(e),
+        _ => Err("Unexpected response from audio thread".to_string()),
+    }
+}
+
+/// Write a recording to a temporary 16kHz WAV file, one window at a time
+fn save_recording_to_temp_wav(recording: &RecordedAudio) -> Result<RecordingResult, AudioError> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    let file_path = std::env::temp_dir().join(format!("ez_flow_recording_{}.wav", timestamp));
+
+    recording.write_whisper_wav(&file_path)?;
+
+    Ok(RecordingResult {
+        file_path: file_path.to_string_lossy().to_string(),
+        duration_secs: recording.duration_secs(),
+        sample_rate: 16000,
+    })
+}
+
+/// Pause the current recording
+#[tauri::command]
+pub async fn pause_recording(app: AppHandle, state: State<'_, AudioState>) -> Result<(), String> {
+    tracing::info!("Pausing audio recording");
+
+    match state.send_command(AudioCommand::Pause)? {
+        AudioResponse::Ok => {
+            let _ = app.emit("workflow://state-changed", "paused");
+            Ok(())
+        }
+        AudioResponse::Error(e) => Err(e),
+        _ => Err("Unexpected response from audio thread".to_string()),
+    }
+}
+
+/// Resume a paused recording
+#[tauri::command]
+pub async fn resume_recording(app: AppHandle, state: State<'_, AudioState>) -> Result<(), String> {
+    tracing::info!("Resuming audio recording");
+
+    match state.send_command(AudioCommand::Resume)? {
+        AudioResponse::Ok => {
+            let _ = app.emit("workflow://state-changed", "recording");
+            Ok(())
+        }
+        AudioResponse::Error(e) => Err(e),
         _ => Err("Unexpected response from audio thread".to_string()),
     }
 }
 
+/// Check if the current recording is paused
+#[tauri::command]
+pub async fn is_recording_paused(state: State<'_, AudioState>) -> Result<bool, String> {
+    match state.send_command(AudioCommand::IsPaused) {
+        Ok(AudioResponse::Bool(b)) => Ok(b),
+        _ => Ok(false),
+    }
+}
+
 /// Check if currently recording
 #[tauri::command]
 pub async fn is_recording(state: State<'_, AudioState>) -> Result<bool, String> {
@@ -471,7 +557,17 @@ pub async fn get_recording_duration(state: State<'_, AudioState>) -> Result<f32,
     }
 }
 
+/// A finished recording and its transcription
+pub(crate) struct CompletedTranscription {
+    pub result: TranscriptionResult,
+    pub recording: RecordedAudio,
+    pub transcription_time_ms: u64,
+}
+
 /// Stop recording and immediately transcribe the audio
+///
+/// `source` records what started the recording in history (defaults to the
+/// main window's record button).
 #[tauri::command]
 pub async fn stop_recording_and_transcribe(
     app: AppHandle,
@@ -479,7 +575,36 @@ pub async fn stop_recording_and_transcribe(
     transcription_state: State<'_, TranscriptionState>,
     settings_state: State<'_, SettingsState>,
     database_state: State<'_, DatabaseState>,
+    source: Option<HistorySource>,
 ) -> Result<TranscriptionResult, String> {
+    let completed =
+        stop_and_transcribe(&app, &audio_state, &transcription_state, &settings_state).await?;
+
+    let metadata = HistoryMetadata {
+        source: Some(source.unwrap_or(HistorySource::App)),
+        transcription_time_ms: Some(completed.transcription_time_ms),
+        ..Default::default()
+    };
+    save_to_history(
+        &app,
+        &database_state,
+        &settings_state,
+        &completed.result,
+        completed.recording,
+        metadata,
+    )
+    .await;
+
+    Ok(completed.result)
+}
+
+/// Stop the current recording and transcribe it without saving to history
+pub(crate) async fn stop_and_transcribe(
+    app: &AppHandle,
+    audio_state: &AudioState,
+    transcription_state: &TranscriptionState,
+    settings_state: &SettingsState,
+) -> Result<CompletedTranscription, String> {
     let start = Instant::now();
 
     tracing::info!("Stopping recording and transcribing");
@@ -491,20 +616,17 @@ pub async fn stop_recording_and_transcribe(
     let _ = app.emit("tray://update-recording-state", false);
 
     // Stop recording
-    let buffer = match audio_state.send_command(AudioCommand::Stop)? {
-        AudioResponse::Buffer(Ok(buf)) => buf,
-        AudioResponse::Buffer(Err(e)) => return Err(e),
+    let recording = match audio_state.send_command(AudioCommand::Stop)? {
+        AudioResponse::Recording(Ok(recording)) => recording,
+        AudioResponse::Recording(Err(e)) => return Err(e),
         _ => return Err("Unexpected response from audio thread".to_string()),
     };
 
-    if buffer.samples.is_empty() {
+    if recording.is_empty() {
         return Err("No audio recorded".to_string());
     }
 
-    let audio_duration_secs = buffer.samples.len() as f32 / buffer.sample_rate as f32;
-
-    // Resample to 16kHz for Whisper if needed
-    let samples = resample_for_whisper(buffer).map_err(|e| e.to_string())?;
+    let audio_duration_secs = recording.duration_secs();
 
     // Get settings for model_id and prompt configuration
     let settings = settings_state.get().await;
@@ -517,10 +639,10 @@ pub async fn stop_recording_and_transcribe(
         settings.use_context_prompt,
     );
 
-    // Transcribe with auto-load fallback and prompt
+    // Transcribe with auto-load fallback and prompt (windowed for long recordings)
     let result = transcription_state
         .engine
-        .transcribe_with_auto_load_and_prompt(samples, &model_id, initial_prompt.as_deref())
+        .transcribe_recording_with_auto_load(&recording, &model_id, initial_prompt.as_deref())
         .await
         .map_err(|e| e.to_string())?;
 
@@ -540,8 +662,25 @@ pub async fn stop_recording_and_transcribe(
         "Transcription completed"
     );
 
-    // Save to history
-    if let Some(db) = database_state.get() {
+    Ok(CompletedTranscription {
+        result,
+        recording,
+        transcription_time_ms,
+    })
+}
+
+/// Save a transcription to history, archiving its audio if enabled
+pub(crate) async fn save_to_history(
+    app: &AppHandle,
+    database_state: &DatabaseState,
+    settings_state: &SettingsState,
+    result: &TranscriptionResult,
+    recording: RecordedAudio,
+    metadata: HistoryMetadata,
+) {
+    if let Some(db) = database_state.history_db() {
+        let archive_config = settings_state.get().await.effective_audio_archive();
+        let audio_path = archive::archive_recording(&archive_config, recording).await;
         let entry = HistoryEntry {
             id: 0,
             text: result.text.clone(),
@@ -550,11 +689,21 @@ pub async fn stop_recording_and_transcribe(
             model_id: result.model_id.clone(),
             language: result.language.clone(),
             gpu_used: result.gpu_used,
+            audio_path,
+            pinned: false,
+            original_text: None,
+            metadata,
         };
         if let Err(e) = db.insert_history(&entry).await {
             tracing::error!("Failed to save transcription to history: {}", e);
+            if let Some(path) = &entry.audio_path {
+                archive::remove_archived_audio(path);
+            }
         } else {
             tracing::debug!("Saved transcription to history");
+            if entry.audio_path.is_some() {
+                archive::enforce_retention(db, &archive_config).await;
+            }
             // Emit event to refresh history UI
             match app.emit("history://new-entry", ()) {
                 Ok(_) => tracing::debug!("Emitted history://new-entry event"),
@@ -562,8 +711,6 @@ pub async fn stop_recording_and_transcribe(
             }
         }
     }
-
-    Ok(result)
 }
 
 /// Test command to emit fake audio levels for debugging
@@ -2,21 +2,75 @@
 //!
 //! Commands for managing transcription history.
 
-use crate::models::HistoryEntry;
-use crate::services::storage::DatabaseState;
-use crate::services::transcription::TranscriptionResult;
+use crate::commands::TranscriptionState;
+use crate::models::{
+    CorrectionSummary, HistoryEntry, HistoryFilter, HistoryMetadata, HistoryRevision,
+    HistorySearchPage, RetranscribeOptions, Settings, TextCorrection,
+};
+use crate::services::storage::encryption::{self, DatabaseKey, HistoryEncryptionConfig, KeySource};
+use crate::services::storage::export::{self, ExportedEntry, HistoryExport, HistoryExportFormat};
+use crate::services::storage::retention::{self, RetentionReport};
+use crate::services::storage::{archive, Database, DatabaseState, SettingsState};
+use crate::services::transcription::engine::SharedWhisperEngine;
+use crate::services::transcription::{
+    build_initial_prompt, decode_audio_recording, DecodeOptions, TranscriptionResult,
+};
+use crate::services::tray;
 use chrono::Utc;
-use tauri::State;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, State};
+
+/// Whether a batch re-transcription job is running
+static RETRANSCRIBE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Set to stop the running batch re-transcription job after the current entry
+static RETRANSCRIBE_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Progress event payload for batch re-transcription
+#[derive(Debug, Clone, Serialize)]
+pub struct RetranscribeProgress {
+    /// Entries processed so far
+    pub completed: usize,
+    /// Entries in the job
+    pub total: usize,
+    /// Entry that was just processed
+    pub history_id: i64,
+    /// Error for this entry, if it failed
+    pub error: Option<String>,
+}
+
+/// Result of a history import
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HistoryImportSummary {
+    /// Entries added to history
+    pub imported: usize,
+    /// Entries already present (same timestamp and text)
+    pub skipped: usize,
+}
+
+/// Result of a batch re-transcription job
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetranscribeSummary {
+    /// Revisions created by the job
+    pub revisions: Vec<HistoryRevision>,
+    /// Number of entries that failed
+    pub failed: usize,
+    /// Whether the job was cancelled before finishing
+    pub cancelled: bool,
+}
 
 /// Save a transcription result to history
 #[tauri::command]
 pub async fn save_history(
     result: TranscriptionResult,
+    metadata: Option<HistoryMetadata>,
     state: State<'_, DatabaseState>,
 ) -> Result<i64, String> {
-    let db = state
-        .get()
-        .ok_or_else(|| "Database not available".to_string())?;
+    if state.is_incognito() {
+        return Err("Incognito mode is on; history is not being saved".to_string());
+    }
+    let db = state.require()?;
 
     let entry = HistoryEntry {
         id: 0, // Will be set by database
@@ -26,50 +80,175 @@ pub async fn save_history(
         model_id: result.model_id,
         language: result.language,
         gpu_used: result.gpu_used,
+        audio_path: None,
+        pinned: false,
+        original_text: None,
+        metadata: metadata.unwrap_or_default(),
     };
 
     db.insert_history(&entry).await.map_err(|e| e.to_string())
 }
 
-/// Get paginated history entries
+/// Get paginated history entries, optionally filtered
 #[tauri::command]
 pub async fn get_history(
     limit: usize,
     offset: usize,
+    filter: Option<HistoryFilter>,
     state: State<'_, DatabaseState>,
 ) -> Result<Vec<HistoryEntry>, String> {
-    let db = state
-        .get()
-        .ok_or_else(|| "Database not available".to_string())?;
+    let db = state.require()?;
 
-    db.get_history(limit, offset)
+    db.get_history_filtered(limit, offset, &filter.unwrap_or_default())
         .await
         .map_err(|e| e.to_string())
 }
 
-/// Search history entries
+/// Search history entries, optionally filtered
 #[tauri::command]
 pub async fn search_history(
     query: String,
+    filter: Option<HistoryFilter>,
     state: State<'_, DatabaseState>,
 ) -> Result<Vec<HistoryEntry>, String> {
-    let db = state
-        .get()
-        .ok_or_else(|| "Database not available".to_string())?;
+    let db = state.require()?;
 
-    if query.trim().is_empty() {
-        return db.get_history(100, 0).await.map_err(|e| e.to_string());
+    db.search_history_filtered(&query, &filter.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Search history with relevance ranking, match highlights and pagination
+#[tauri::command]
+pub async fn search_history_page(
+    query: String,
+    filter: Option<HistoryFilter>,
+    limit: usize,
+    offset: usize,
+    state: State<'_, DatabaseState>,
+) -> Result<HistorySearchPage, String> {
+    let db = state.require()?;
+
+    db.search(&query, &filter.unwrap_or_default(), limit, offset)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the applications dictated into, for the history filter
+#[tauri::command]
+pub async fn get_history_source_apps(
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<String>, String> {
+    let db = state.require()?;
+
+    db.get_source_apps().await.map_err(|e| e.to_string())
+}
+
+/// Export history entries matching a query and filter to a file
+///
+/// Returns the number of exported entries.
+#[tauri::command]
+pub async fn export_history(
+    path: String,
+    format: HistoryExportFormat,
+    query: Option<String>,
+    filter: Option<HistoryFilter>,
+    state: State<'_, DatabaseState>,
+) -> Result<usize, String> {
+    let db = state.require()?;
+
+    let entries = db
+        .find_entries(query.as_deref().unwrap_or(""), &filter.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())?;
+    let count = entries.len();
+
+    let content = match format {
+        HistoryExportFormat::Json => {
+            let mut exported = Vec::with_capacity(count);
+            for entry in entries {
+                let revisions = db
+                    .get_revisions(entry.id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                exported.push(ExportedEntry { entry, revisions });
+            }
+            HistoryExport::new(exported)
+                .to_json()
+                .map_err(|e| e.to_string())?
+        }
+        HistoryExportFormat::Csv => export::to_csv(&entries).map_err(|e| e.to_string())?,
+        HistoryExportFormat::Markdown => {
+            export::to_markdown(&entries).map_err(|e| e.to_string())?
+        }
+    };
+
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write export file: {}", e))?;
+
+    tracing::info!("Exported {} history entries to {}", count, path);
+    Ok(count)
+}
+
+/// Import history entries from a JSON export
+///
+/// Entries already in history are skipped, so importing the same file twice
+/// is harmless.
+#[tauri::command]
+pub async fn import_history(
+    path: String,
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+) -> Result<HistoryImportSummary, String> {
+    let db = state.require()?;
+
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read import file: {}", e))?;
+    let export = HistoryExport::from_json(&content).map_err(|e| e.to_string())?;
+
+    let mut summary = HistoryImportSummary::default();
+    for ExportedEntry {
+        mut entry,
+        revisions,
+    } in export.entries
+    {
+        // Archived audio from another machine is not carried over
+        if entry
+            .audio_path
+            .as_deref()
+            .is_some_and(|p| !std::path::Path::new(p).exists())
+        {
+            entry.audio_path = None;
+        }
+
+        match db
+            .import_entry(&entry, &revisions)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Some(_) => summary.imported += 1,
+            None => summary.skipped += 1,
+        }
     }
 
-    db.search_history(&query).await.map_err(|e| e.to_string())
+    tracing::info!(
+        "Imported {} history entries from {} ({} already present)",
+        summary.imported,
+        path,
+        summary.skipped
+    );
+    if summary.imported > 0 {
+        if let Err(e) = app.emit("history://new-entry", ()) {
+            tracing::error!("Failed to emit history://new-entry: {}", e);
+        }
+    }
+
+    Ok(summary)
 }
 
 /// Delete a single history entry
 #[tauri::command]
 pub async fn delete_history_entry(id: i64, state: State<'_, DatabaseState>) -> Result<(), String> {
-    let db = state
-        .get()
-        .ok_or_else(|| "Database not available".to_string())?;
+    let db = state.require()?;
 
     db.delete_entry(id).await.map_err(|e| e.to_string())
 }
@@ -77,23 +256,464 @@ pub async fn delete_history_entry(id: i64, state: State<'_, DatabaseState>) -> R
 /// Clear all history
 #[tauri::command]
 pub async fn clear_history(state: State<'_, DatabaseState>) -> Result<(), String> {
-    let db = state
-        .get()
-        .ok_or_else(|| "Database not available".to_string())?;
+    let db = state.require()?;
 
     db.clear_all().await.map_err(|e| e.to_string())
 }
 
+/// Pin or unpin a history entry; pinned entries are kept by retention
+#[tauri::command]
+pub async fn set_history_pinned(
+    id: i64,
+    pinned: bool,
+    state: State<'_, DatabaseState>,
+) -> Result<(), String> {
+    let db = state.require()?;
+
+    db.set_pinned(id, pinned).await.map_err(|e| e.to_string())
+}
+
+/// Apply the history retention limits now
+#[tauri::command]
+pub async fn apply_history_retention(
+    state: State<'_, DatabaseState>,
+    settings_state: State<'_, SettingsState>,
+) -> Result<RetentionReport, String> {
+    let db = state.require()?;
+    let config = settings_state.get().await.history_retention;
+
+    retention::apply_retention(db, &config)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Encryption and incognito state of the history
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPrivacyStatus {
+    /// Whether the database is encrypted
+    pub encrypted: bool,
+    /// Where the encryption key comes from
+    pub key_source: KeySource,
+    /// Whether the database is waiting for its passphrase
+    pub locked: bool,
+    /// Whether new history is being skipped for this session
+    pub incognito: bool,
+}
+
+/// Get the encryption and incognito state of the history
+#[tauri::command]
+pub async fn get_history_privacy_status(
+    state: State<'_, DatabaseState>,
+    settings_state: State<'_, SettingsState>,
+) -> Result<HistoryPrivacyStatus, String> {
+    let config = settings_state.get().await.history_encryption;
+
+    Ok(HistoryPrivacyStatus {
+        encrypted: state
+            .get()
+            .map(Database::is_encrypted)
+            .unwrap_or(config.enabled),
+        key_source: config.key_source,
+        locked: state.is_locked(),
+        incognito: state.is_incognito(),
+    })
+}
+
+/// Encrypt the history database (or change its key), migrating the
+/// existing history and deleting unencrypted archived recordings
+#[tauri::command]
+pub async fn enable_history_encryption(
+    key_source: KeySource,
+    passphrase: Option<String>,
+    state: State<'_, DatabaseState>,
+    settings_state: State<'_, SettingsState>,
+) -> Result<(), String> {
+    let db = state.require()?;
+
+    match key_source {
+        KeySource::Keyring => {
+            let previous = encryption::load_keyring_key().map_err(|e| e.to_string())?;
+            let key = DatabaseKey::generate().map_err(|e| e.to_string())?;
+            encryption::store_keyring_key(&key).map_err(|e| e.to_string())?;
+            if let Err(e) = db.set_encryption(Some(&key)).await {
+                // Put back the key the database is still encrypted with
+                let restored = match previous {
+                    Some(previous) => encryption::store_keyring_key(&previous),
+                    None => encryption::delete_keyring_key(),
+                };
+                if let Err(restore_error) = restored {
+                    tracing::error!("Failed to restore history key: {}", restore_error);
+                }
+                return Err(e.to_string());
+            }
+        }
+        KeySource::Passphrase => {
+            let passphrase = passphrase
+                .filter(|p| !p.is_empty())
+                .ok_or_else(|| "A passphrase is required".to_string())?;
+            db.set_encryption(Some(&DatabaseKey::Passphrase(passphrase)))
+                .await
+                .map_err(|e| e.to_string())?;
+            if let Err(e) = encryption::delete_keyring_key() {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+
+    settings_state
+        .update_field(|settings| {
+            settings.history_encryption = HistoryEncryptionConfig {
+                enabled: true,
+                key_source,
+            };
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Decrypt the history database
+#[tauri::command]
+pub async fn disable_history_encryption(
+    state: State<'_, DatabaseState>,
+    settings_state: State<'_, SettingsState>,
+) -> Result<(), String> {
+    let db = state.require()?;
+
+    db.set_encryption(None).await.map_err(|e| e.to_string())?;
+    if let Err(e) = encryption::delete_keyring_key() {
+        tracing::warn!("{}", e);
+    }
+
+    settings_state
+        .update_field(|settings| settings.history_encryption.enabled = false)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Unlock a passphrase-encrypted history database for this session
+#[tauri::command]
+pub async fn unlock_history(
+    passphrase: String,
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+) -> Result<(), String> {
+    state.unlock(&passphrase).map_err(|e| e.to_string())?;
+
+    // Let open history views load the now available entries
+    if let Err(e) = app.emit("history://new-entry", ()) {
+        tracing::error!("Failed to emit history://new-entry: {}", e);
+    }
+    Ok(())
+}
+
+/// Turn incognito mode on or off; while it is on, new transcriptions are
+/// not saved to history. It resets when the app restarts.
+#[tauri::command]
+pub async fn set_incognito(enabled: bool, app: AppHandle) -> Result<(), String> {
+    tray::set_incognito(&app, enabled);
+    Ok(())
+}
+
 /// Get history entry count
 #[tauri::command]
 pub async fn get_history_count(state: State<'_, DatabaseState>) -> Result<usize, String> {
-    let db = state
-        .get()
-        .ok_or_else(|| "Database not available".to_string())?;
+    let db = state.require()?;
 
     db.count().await.map_err(|e| e.to_string())
 }
 
+/// Get the archived recording path of a history entry (if any)
+#[tauri::command]
+pub async fn get_history_audio_path(
+    id: i64,
+    state: State<'_, DatabaseState>,
+) -> Result<Option<String>, String> {
+    let db = state.require()?;
+
+    let entry = db
+        .get_entry(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+
+    Ok(entry
+        .audio_path
+        .filter(|path| std::path::Path::new(path).exists()))
+}
+
+/// Delete the archived recording of a history entry, keeping its text
+#[tauri::command]
+pub async fn delete_history_audio(id: i64, state: State<'_, DatabaseState>) -> Result<(), String> {
+    let db = state.require()?;
+
+    db.remove_entry_audio(id).await.map_err(|e| e.to_string())
+}
+
+/// Get the directory where recordings are archived
+#[tauri::command]
+pub fn get_audio_archive_dir() -> Result<String, String> {
+    archive::get_archive_dir()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .map_err(|e| e.to_string())
+}
+
+/// Re-transcribe a history entry's archived audio and store a new revision
+#[tauri::command]
+pub async fn retranscribe_history_entry(
+    id: i64,
+    model_id: String,
+    options: Option<RetranscribeOptions>,
+    state: State<'_, DatabaseState>,
+    transcription_state: State<'_, TranscriptionState>,
+    settings_state: State<'_, SettingsState>,
+) -> Result<HistoryRevision, String> {
+    let db = state.require()?;
+    let settings = settings_state.get().await;
+
+    retranscribe_entry(
+        db,
+        &transcription_state.engine,
+        &settings,
+        id,
+        &model_id,
+        options.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Re-transcribe the most recent entries with archived audio
+///
+/// Emits `history://retranscribe-progress` after each entry. Failures are
+/// counted and the job continues with the next entry.
+#[tauri::command]
+pub async fn retranscribe_recent_history(
+    app: AppHandle,
+    count: usize,
+    model_id: String,
+    options: Option<RetranscribeOptions>,
+    state: State<'_, DatabaseState>,
+    transcription_state: State<'_, TranscriptionState>,
+    settings_state: State<'_, SettingsState>,
+) -> Result<RetranscribeSummary, String> {
+    let db = state.require()?;
+
+    if RETRANSCRIBE_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A re-transcription job is already running".to_string());
+    }
+    RETRANSCRIBE_CANCELLED.store(false, Ordering::SeqCst);
+
+    let settings = settings_state.get().await;
+    let options = options.unwrap_or_default();
+    let result = async {
+        let entries = db
+            .get_recent_with_audio(count)
+            .await
+            .map_err(|e| e.to_string())?;
+        let total = entries.len();
+        tracing::info!(
+            "Re-transcribing {} history entries with model {}",
+            total,
+            model_id
+        );
+
+        let mut summary = RetranscribeSummary::default();
+        for (index, entry) in entries.iter().enumerate() {
+            if RETRANSCRIBE_CANCELLED.load(Ordering::SeqCst) {
+                summary.cancelled = true;
+                break;
+            }
+
+            let error = match retranscribe_entry(
+                db,
+                &transcription_state.engine,
+                &settings,
+                entry.id,
+                &model_id,
+                options.clone(),
+            )
+            .await
+            {
+                Ok(revision) => {
+                    summary.revisions.push(revision);
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to re-transcribe entry {}: {}", entry.id, e);
+                    summary.failed += 1;
+                    Some(e)
+                }
+            };
+
+            let _ = app.emit(
+                "history://retranscribe-progress",
+                RetranscribeProgress {
+                    completed: index + 1,
+                    total,
+                    history_id: entry.id,
+                    error,
+                },
+            );
+        }
+
+        Ok(summary)
+    }
+    .await;
+
+    RETRANSCRIBE_RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+/// Stop the running batch re-transcription job after the current entry
+#[tauri::command]
+pub fn cancel_retranscribe_job() {
+    RETRANSCRIBE_CANCELLED.store(true, Ordering::SeqCst);
+}
+
+/// Get all revisions of a history entry
+#[tauri::command]
+pub async fn get_history_revisions(
+    id: i64,
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<HistoryRevision>, String> {
+    let db = state.require()?;
+
+    db.get_revisions(id).await.map_err(|e| e.to_string())
+}
+
+/// Make a revision the current text of a history entry
+#[tauri::command]
+pub async fn apply_history_revision(
+    id: i64,
+    revision_id: i64,
+    state: State<'_, DatabaseState>,
+) -> Result<HistoryEntry, String> {
+    let db = state.require()?;
+
+    db.apply_revision(id, revision_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the text of a history entry with an edited version, keeping the
+/// original transcription and recording the corrections
+#[tauri::command]
+pub async fn update_history_entry(
+    id: i64,
+    text: String,
+    state: State<'_, DatabaseState>,
+) -> Result<HistoryEntry, String> {
+    let db = state.require()?;
+
+    db.update_entry_text(id, &text)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the corrections made by editing a history entry
+#[tauri::command]
+pub async fn get_history_corrections(
+    id: i64,
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<TextCorrection>, String> {
+    let db = state.require()?;
+
+    db.get_corrections(id).await.map_err(|e| e.to_string())
+}
+
+/// Get corrections aggregated over all edited entries, most frequent first
+#[tauri::command]
+pub async fn get_correction_summary(
+    min_count: Option<usize>,
+    limit: Option<usize>,
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<CorrectionSummary>, String> {
+    let db = state.require()?;
+
+    db.correction_summary(min_count.unwrap_or(1), limit.unwrap_or(100))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Decode an entry's archived audio, transcribe it and store the revision
+async fn retranscribe_entry(
+    db: &Database,
+    engine: &SharedWhisperEngine,
+    settings: &Settings,
+    id: i64,
+    model_id: &str,
+    options: RetranscribeOptions,
+) -> Result<HistoryRevision, String> {
+    let entry = db
+        .get_entry(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+    let audio_path = entry
+        .audio_path
+        .as_deref()
+        .ok_or_else(|| format!("History entry {} has no archived audio", id))?;
+
+    let recording =
+        decode_audio_recording(std::path::Path::new(audio_path)).map_err(|e| e.to_string())?;
+
+    let initial_prompt = retranscribe_prompt(&options, settings);
+    let decoding = retranscribe_decoding(&options, &entry);
+    let result = engine
+        .transcribe_recording_with_model(
+            &recording,
+            model_id,
+            initial_prompt.as_deref(),
+            options.audio_processing.clone(),
+            Some(decoding),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut revision = HistoryRevision {
+        id: 0,
+        history_id: id,
+        text: result.text,
+        model_id: result.model_id,
+        language: result.language,
+        gpu_used: result.gpu_used,
+        options,
+        created_at: Utc::now().to_rfc3339(),
+    };
+    revision.id = db
+        .insert_revision(&revision)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tracing::info!("Stored revision {} for history entry {}", revision.id, id);
+    Ok(revision)
+}
+
+/// Language and task for a re-transcription: explicit override, else as the
+/// entry was transcribed
+fn retranscribe_decoding(options: &RetranscribeOptions, entry: &HistoryEntry) -> DecodeOptions {
+    DecodeOptions {
+        language: options.language.clone().or_else(|| entry.language.clone()),
+        translate: options.translate.unwrap_or(entry.metadata.translated),
+    }
+}
+
+/// Initial prompt for a re-transcription: explicit override, else from settings
+fn retranscribe_prompt(options: &RetranscribeOptions, settings: &Settings) -> Option<String> {
+    if options.initial_prompt.is_some() {
+        return options.initial_prompt.clone();
+    }
+    if !options.use_prompt {
+        return None;
+    }
+    build_initial_prompt(
+        &settings.custom_vocabulary,
+        settings.context_prompt.as_deref(),
+        settings.use_context_prompt,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +737,10 @@ mod tests {
             model_id: result.model_id.clone(),
             language: result.language.clone(),
             gpu_used: result.gpu_used,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
 
         assert_eq!(entry.text, "Hello world");
@@ -145,6 +769,10 @@ mod tests {
             model_id: result.model_id.clone(),
             language: result.language.clone(),
             gpu_used: result.gpu_used,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
 
         assert_eq!(entry.language, None);
@@ -179,6 +807,10 @@ mod tests {
             model_id: result.model_id.clone(),
             language: result.language.clone(),
             gpu_used: result.gpu_used,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
 
         assert!(entry.text.is_empty());
@@ -204,6 +836,10 @@ mod tests {
             model_id: result.model_id.clone(),
             language: result.language.clone(),
             gpu_used: result.gpu_used,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
 
         assert_eq!(entry.text.len(), 10000);
@@ -228,6 +864,10 @@ mod tests {
             model_id: result.model_id.clone(),
             language: result.language.clone(),
             gpu_used: result.gpu_used,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
 
         assert!(entry.text.contains("'"));
@@ -254,6 +894,10 @@ mod tests {
             model_id: result.model_id.clone(),
             language: result.language.clone(),
             gpu_used: result.gpu_used,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
 
         assert!(entry.text.contains("世界"));
@@ -284,6 +928,10 @@ mod tests {
                 model_id: result.model_id,
                 language: result.language,
                 gpu_used: result.gpu_used,
+                audio_path: None,
+                pinned: false,
+                original_text: None,
+                metadata: Default::default(),
             };
 
             assert_eq!(entry.model_id, model_id);
@@ -319,9 +967,76 @@ mod tests {
                 model_id: result.model_id,
                 language: result.language,
                 gpu_used: result.gpu_used,
+                audio_path: None,
+                pinned: false,
+                original_text: None,
+                metadata: Default::default(),
             };
 
             assert_eq!(entry.language, lang);
         }
     }
+
+    #[test]
+    fn test_retranscribe_prompt_prefers_explicit_prompt() {
+        let mut settings = Settings::default();
+        settings.custom_vocabulary = vec!["Kubernetes".to_string()];
+
+        let options = RetranscribeOptions {
+            initial_prompt: Some("Meeting notes".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            retranscribe_prompt(&options, &settings),
+            Some("Meeting notes".to_string())
+        );
+    }
+
+    #[test]
+    fn test_retranscribe_prompt_from_settings() {
+        let mut settings = Settings::default();
+        settings.custom_vocabulary = vec!["Kubernetes".to_string()];
+
+        let prompt = retranscribe_prompt(&RetranscribeOptions::default(), &settings);
+        assert!(prompt.unwrap().contains("Kubernetes"));
+
+        let options = RetranscribeOptions {
+            use_prompt: false,
+            ..Default::default()
+        };
+        assert!(retranscribe_prompt(&options, &settings).is_none());
+    }
+
+    #[test]
+    fn test_retranscribe_decoding_defaults_to_entry() {
+        let entry = HistoryEntry {
+            id: 1,
+            text: "Hello".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: Some("fr".to_string()),
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: HistoryMetadata {
+                translated: true,
+                ..Default::default()
+            },
+        };
+
+        let decoding = retranscribe_decoding(&RetranscribeOptions::default(), &entry);
+        assert_eq!(decoding.language.as_deref(), Some("fr"));
+        assert!(decoding.translate);
+
+        let options = RetranscribeOptions {
+            language: Some("de".to_string()),
+            translate: Some(false),
+            ..Default::default()
+        };
+        let decoding = retranscribe_decoding(&options, &entry);
+        assert_eq!(decoding.language.as_deref(), Some("de"));
+        assert!(!decoding.translate);
+    }
 }
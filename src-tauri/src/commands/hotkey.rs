@@ -3,8 +3,10 @@
 //! Provides commands for managing global keyboard shortcuts.
 
 use crate::services::hotkey::{
-    get_default_hotkey, register_hotkey, test_hotkey, unregister_hotkey, HotkeyState,
+    check_hotkey_conflict, get_default_hotkey, register_bindings, register_hotkey, test_hotkey,
+    unregister_hotkey, HotkeyBackend, HotkeyBinding, HotkeyState,
 };
+use crate::services::storage::SettingsState;
 use tauri::{AppHandle, State};
 
 /// Register a new global hotkey
@@ -14,6 +16,9 @@ pub async fn set_hotkey(
     state: State<'_, HotkeyState>,
     hotkey: String,
 ) -> Result<(), String> {
+    // Keep the current hotkey if the new one is bound to another action
+    check_hotkey_conflict(&hotkey, &state).map_err(|e| e.to_string())?;
+
     // First unregister existing hotkey if any
     let _ = unregister_hotkey(&app, &state);
 
@@ -33,6 +38,40 @@ pub async fn get_current_hotkey(state: State<'_, HotkeyState>) -> Result<Option<
     Ok(state.current_hotkey.read().await.clone())
 }
 
+/// Register further hotkey bindings, replacing the current ones. Fails
+/// without changes if bindings conflict with each other or the main hotkey.
+#[tauri::command]
+pub async fn set_hotkey_bindings(
+    app: AppHandle,
+    state: State<'_, HotkeyState>,
+    settings_state: State<'_, SettingsState>,
+    bindings: Vec<HotkeyBinding>,
+) -> Result<(), String> {
+    let settings = settings_state.get().await;
+    let primary = state
+        .current_hotkey
+        .read()
+        .await
+        .clone()
+        .unwrap_or(settings.hotkey);
+    register_bindings(
+        &app,
+        &primary,
+        &bindings,
+        &settings.dictation_profiles,
+        &state,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Get the currently registered hotkey bindings
+#[tauri::command]
+pub async fn get_hotkey_bindings(
+    state: State<'_, HotkeyState>,
+) -> Result<Vec<HotkeyBinding>, String> {
+    Ok(state.bindings.lock().unwrap().clone())
+}
+
 /// Get the default hotkey for this platform
 #[tauri::command]
 pub fn get_platform_default_hotkey() -> String {
@@ -43,6 +82,8 @@ pub fn get_platform_default_hotkey() -> String {
 /// Returns true if:
 /// - The hotkey is the same as the currently registered one (we own it)
 /// - The hotkey is not registered by our app (new hotkey to try)
+///
+/// Returns false if one of the further bindings already uses it.
 #[tauri::command]
 pub async fn check_hotkey_available(
     app: AppHandle,
@@ -56,8 +97,11 @@ pub async fn check_hotkey_available(
             return Ok(true);
         }
     }
+    if check_hotkey_conflict(&hotkey, &state).is_err() {
+        return Ok(false);
+    }
 
-    test_hotkey(&app, &hotkey).map_err(|e| e.to_string())
+    test_hotkey(&app, &hotkey, &state).map_err(|e| e.to_string())
 }
 
 /// Check if hotkey is currently registered
@@ -68,6 +112,12 @@ pub async fn is_hotkey_registered(state: State<'_, HotkeyState>) -> Result<bool,
         .load(std::sync::atomic::Ordering::SeqCst))
 }
 
+/// Get the backend global hotkeys are registered with
+#[tauri::command]
+pub async fn get_hotkey_backend(state: State<'_, HotkeyState>) -> Result<HotkeyBackend, String> {
+    Ok(state.backend())
+}
+
 /// Get the last hotkey error if any
 #[tauri::command]
 pub async fn get_hotkey_error(state: State<'_, HotkeyState>) -> Result<Option<String>, String> {
@@ -7,10 +7,12 @@ pub mod history;
 pub mod hotkey;
 pub mod indicator;
 pub mod models;
+pub mod replacements;
 pub mod settings;
 pub mod text_inject;
 pub mod transcription;
 pub mod vocabulary;
+pub mod voice_commands;
 pub mod workflow;
 
 use tauri::AppHandle;
@@ -18,7 +20,8 @@ use tauri_plugin_autostart::ManagerExt;
 
 pub use audio::{
     check_microphone_permission, get_audio_devices, get_recording_duration, is_recording,
-    start_recording, stop_recording, stop_recording_and_transcribe, AudioState,
+    is_recording_paused, pause_recording, resume_recording, start_recording, stop_recording,
+    stop_recording_and_transcribe, AudioState,
 };
 
 pub use transcription::{
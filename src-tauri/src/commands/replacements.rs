@@ -0,0 +1,200 @@
+//! Text replacement Tauri commands
+//!
+//! Commands for importing, exporting and previewing replacement rules.
+
+use crate::services::text_replacements::{
+    validate_rule, ReplacementConfig, ReplacementRule, TextReplacer,
+};
+use serde::{Deserialize, Serialize};
+
+/// Replacement rules export format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextReplacementExport {
+    /// Format version
+    pub version: u32,
+    /// Optional name for the rule set
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Optional description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Replacement rules, in the order they are applied
+    pub rules: Vec<ReplacementRule>,
+}
+
+impl TextReplacementExport {
+    /// Current export format version
+    pub const CURRENT_VERSION: u32 = 1;
+
+    /// Create a new replacement rules export
+    pub fn new(rules: Vec<ReplacementRule>) -> Self {
+        Self {
+            version: Self::CURRENT_VERSION,
+            name: None,
+            description: None,
+            rules,
+        }
+    }
+
+    /// Validate the export data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version > Self::CURRENT_VERSION {
+            return Err(format!(
+                "Unsupported replacement rules version: {}. Maximum supported: {}",
+                self.version,
+                Self::CURRENT_VERSION
+            ));
+        }
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            validate_rule(rule).map_err(|e| format!("Rule {}: {}", index + 1, e))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Export replacement rules to a JSON file
+#[tauri::command]
+pub async fn export_replacements(path: String, rules: Vec<ReplacementRule>) -> Result<(), String> {
+    let export = TextReplacementExport::new(rules);
+    let content = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    tracing::info!(
+        "Replacement rules exported to {} ({} rules)",
+        path,
+        export.rules.len()
+    );
+    Ok(())
+}
+
+/// Import replacement rules from a JSON file
+///
+/// Returns the list of imported rules.
+#[tauri::command]
+pub async fn import_replacements(path: String) -> Result<Vec<ReplacementRule>, String> {
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+
+    let imported: TextReplacementExport = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid replacement rules file format: {}", e))?;
+
+    // Validate the imported data
+    imported.validate()?;
+
+    tracing::info!(
+        "Replacement rules imported from {} ({} rules)",
+        path,
+        imported.rules.len()
+    );
+    Ok(imported.rules)
+}
+
+/// Validate a single replacement rule
+#[tauri::command]
+pub fn validate_replacement_rule(rule: ReplacementRule) -> Result<(), String> {
+    validate_rule(&rule)
+}
+
+/// Apply replacement rules to sample text, for trying rules out
+#[tauri::command]
+pub fn preview_replacements(text: String, rules: Vec<ReplacementRule>) -> String {
+    TextReplacer::new(&ReplacementConfig {
+        enabled: true,
+        rules,
+    })
+    .apply(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::text_replacements::RuleKind;
+
+    fn literal(find: &str, replace: &str) -> ReplacementRule {
+        ReplacementRule {
+            find: find.to_string(),
+            replace: replace.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_replacement_export_new() {
+        let rules = vec![literal("e z flow", "EZ Flow")];
+        let export = TextReplacementExport::new(rules.clone());
+
+        assert_eq!(export.version, TextReplacementExport::CURRENT_VERSION);
+        assert!(export.name.is_none());
+        assert!(export.description.is_none());
+        assert_eq!(export.rules, rules);
+    }
+
+    #[test]
+    fn test_replacement_export_validate_invalid_rule() {
+        let export = TextReplacementExport::new(vec![
+            literal("e z flow", "EZ Flow"),
+            ReplacementRule {
+                find: "(unclosed".to_string(),
+                kind: RuleKind::Regex,
+                ..Default::default()
+            },
+        ]);
+        let result = export.validate();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().starts_with("Rule 2:"));
+    }
+
+    #[test]
+    fn test_replacement_export_validate_future_version() {
+        let mut export = TextReplacementExport::new(vec![literal("a", "b")]);
+        export.version = 999;
+        let result = export.validate();
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .contains("Unsupported replacement rules version"));
+    }
+
+    #[test]
+    fn test_replacement_export_round_trip() {
+        let mut export = TextReplacementExport::new(vec![
+            literal("e z flow", "EZ Flow"),
+            ReplacementRule {
+                find: "insert signature".to_string(),
+                replace: "Best regards,\nJane".to_string(),
+                kind: RuleKind::Snippet,
+                ..Default::default()
+            },
+        ]);
+        export.name = Some("Work".to_string());
+
+        let json = serde_json::to_string_pretty(&export).unwrap();
+        assert!(json.contains("\"version\": 1"));
+        assert!(json.contains("\"kind\": \"snippet\""));
+
+        let parsed: TextReplacementExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.name, Some("Work".to_string()));
+        assert_eq!(parsed.rules, export.rules);
+    }
+
+    #[test]
+    fn test_replacement_export_minimal_rules() {
+        let json = r#"{
+            "version": 1,
+            "rules": [{"find": "post gress", "replace": "Postgres"}]
+        }"#;
+
+        let parsed: TextReplacementExport = serde_json::from_str(json).unwrap();
+        assert!(parsed.validate().is_ok());
+        assert_eq!(parsed.rules[0], literal("post gress", "Postgres"));
+    }
+
+    #[test]
+    fn test_preview_replacements() {
+        let text = preview_replacements(
+            "deploy to post gress".to_string(),
+            vec![literal("post gress", "Postgres")],
+        );
+        assert_eq!(text, "deploy to Postgres");
+    }
+}
@@ -2,13 +2,27 @@
 //!
 //! Commands for managing user preferences.
 
+use crate::commands::TranscriptionState;
 use crate::models::Settings;
-use crate::services::hotkey::{register_hotkey, unregister_hotkey, HotkeyState};
+use crate::services::hotkey::{
+    register_bindings, register_hotkey, unregister_bindings, unregister_hotkey, validate_bindings,
+    HotkeyState,
+};
 use crate::services::storage::SettingsState;
 use crate::services::transcription::{
     detect_gpu_backend, get_languages, is_gpu_available, GpuBackend, GpuInfo, Language,
 };
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
+
+/// Push the audio preprocessing configuration to the transcription engine
+async fn sync_audio_processing(app: &AppHandle, settings: &Settings) {
+    if let Some(transcription_state) = app.try_state::<TranscriptionState>() {
+        transcription_state
+            .engine
+            .set_preprocessing(settings.audio_processing.clone())
+            .await;
+    }
+}
 
 /// Get current settings
 #[tauri::command]
@@ -19,9 +33,17 @@ pub async fn get_settings(state: State<'_, SettingsState>) -> Result<Settings, S
 /// Update all settings
 #[tauri::command]
 pub async fn update_settings(
+    app: AppHandle,
     settings: Settings,
     state: State<'_, SettingsState>,
 ) -> Result<(), String> {
+    // Changing encryption re-keys the database, so it only happens through
+    // the history encryption commands
+    let settings = Settings {
+        history_encryption: state.get().await.history_encryption,
+        ..settings
+    };
+    sync_audio_processing(&app, &settings).await;
     state.update(settings).await.map_err(|e| e.to_string())
 }
 
@@ -41,6 +63,35 @@ pub async fn update_setting(
         None
     };
 
+    // Hotkeys must not conflict and bindings must refer to existing profiles
+    if matches!(
+        key.as_str(),
+        "hotkey" | "hotkey_bindings" | "dictation_profiles"
+    ) {
+        let mut candidate = state.get().await;
+        match key.as_str() {
+            "hotkey" => {
+                if let Some(v) = value.as_str() {
+                    candidate.hotkey = v.to_string();
+                }
+            }
+            "hotkey_bindings" => {
+                candidate.hotkey_bindings =
+                    serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+            }
+            _ => {
+                candidate.dictation_profiles =
+                    serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+            }
+        }
+        validate_bindings(
+            &candidate.hotkey,
+            &candidate.hotkey_bindings,
+            &candidate.dictation_profiles,
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
     let result = state
         .update_field(|settings| {
             match key.as_str() {
@@ -88,11 +139,31 @@ pub async fn update_setting(
                         settings.auto_copy = v;
                     }
                 }
+                "clipboard_restore_delay_ms" => {
+                    if let Some(v) = value.as_u64() {
+                        settings.clipboard_restore_delay_ms = v as u32;
+                    }
+                }
                 "injection_delay_ms" => {
                     if let Some(v) = value.as_u64() {
                         settings.injection_delay_ms = v as u32;
                     }
                 }
+                "injection_method" => {
+                    if let Ok(method) = serde_json::from_value(value.clone()) {
+                        settings.injection_method = method;
+                    }
+                }
+                "app_profiles" => {
+                    if let Ok(profiles) = serde_json::from_value(value.clone()) {
+                        settings.app_profiles = profiles;
+                    }
+                }
+                "dictation_sink" => {
+                    if let Ok(sink) = serde_json::from_value(value.clone()) {
+                        settings.dictation_sink = sink;
+                    }
+                }
                 "onboarding_completed" => {
                     if let Some(v) = value.as_bool() {
                         settings.onboarding_completed = v;
@@ -153,6 +224,31 @@ pub async fn update_setting(
                 "preview_position_y" => {
                     settings.preview_position_y = value.as_i64().map(|v| v as i32);
                 }
+                "audio_archive" => {
+                    if let Ok(config) = serde_json::from_value(value.clone()) {
+                        settings.audio_archive = config;
+                    }
+                }
+                "audio_processing" => {
+                    if let Ok(config) = serde_json::from_value(value.clone()) {
+                        settings.audio_processing = config;
+                    }
+                }
+                "text_normalization" => {
+                    if let Some(v) = value.as_bool() {
+                        settings.text_normalization = v;
+                    }
+                }
+                "hotkey_bindings" => {
+                    if let Ok(bindings) = serde_json::from_value(value.clone()) {
+                        settings.hotkey_bindings = bindings;
+                    }
+                }
+                "dictation_profiles" => {
+                    if let Ok(profiles) = serde_json::from_value(value.clone()) {
+                        settings.dictation_profiles = profiles;
+                    }
+                }
                 _ => {
                     tracing::warn!("Unknown setting key: {}", key);
                 }
@@ -161,6 +257,21 @@ pub async fn update_setting(
         .await
         .map_err(|e| e.to_string())?;
 
+    if key == "audio_processing" {
+        sync_audio_processing(&app, &result).await;
+    }
+
+    if key == "hotkey_bindings" {
+        register_bindings(
+            &app,
+            &result.hotkey,
+            &result.hotkey_bindings,
+            &result.dictation_profiles,
+            &hotkey_state,
+        )
+        .map_err(|e| format!("Failed to register hotkey bindings: {}", e))?;
+    }
+
     // If hotkey was changed, re-register the global shortcut
     if let Some(hotkey) = new_hotkey {
         tracing::info!("Hotkey setting changed to '{}', re-registering", hotkey);
@@ -188,8 +299,10 @@ pub async fn reset_settings(
         .update(defaults.clone())
         .await
         .map_err(|e| e.to_string())?;
+    sync_audio_processing(&app, &defaults).await;
 
-    // Re-register the default hotkey
+    // Re-register the default hotkey, which has no further bindings
+    unregister_bindings(&app, &hotkey_state);
     let _ = unregister_hotkey(&app, &hotkey_state);
     if let Err(e) = register_hotkey(&app, &defaults.hotkey, &hotkey_state) {
         tracing::warn!("Failed to register default hotkey: {}", e);
@@ -211,6 +324,7 @@ pub async fn export_settings(path: String, state: State<'_, SettingsState>) -> R
 /// Import settings from a JSON file
 #[tauri::command]
 pub async fn import_settings(
+    app: AppHandle,
     path: String,
     state: State<'_, SettingsState>,
 ) -> Result<Settings, String> {
@@ -221,6 +335,7 @@ pub async fn import_settings(
         .update(imported.clone())
         .await
         .map_err(|e| e.to_string())?;
+    sync_audio_processing(&app, &imported).await;
     tracing::info!("Settings imported from {}", path);
     Ok(imported)
 }
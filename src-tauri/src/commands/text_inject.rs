@@ -2,7 +2,7 @@
 //!
 //! Exposes text injection functionality to the frontend.
 
-use crate::services::platform::TextInjectorState;
+use crate::services::platform::{InjectionMethod, TextInjectorState};
 use tauri::State;
 
 /// Inject text at the current cursor position
@@ -24,6 +24,17 @@ pub async fn set_injection_delay(
     Ok(())
 }
 
+/// Choose whether text is pasted or typed
+#[tauri::command]
+pub async fn set_injection_method(
+    method: InjectionMethod,
+    state: State<'_, TextInjectorState>,
+) -> Result<(), String> {
+    tracing::debug!("Setting injection method to {:?}", method);
+    state.set_method(method).await;
+    Ok(())
+}
+
 /// Get permission instructions for text injection (if needed)
 #[tauri::command]
 pub async fn get_injection_permission_instructions(
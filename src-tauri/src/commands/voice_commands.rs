@@ -0,0 +1,55 @@
+//! Voice command Tauri commands
+//!
+//! Commands for inspecting the voice command registry.
+
+use crate::services::voice_commands::{
+    CommandConfig, CommandConflict, CommandRegistry, RegisteredCommand,
+};
+use serde::Serialize;
+
+/// Registered voice commands and the conflicts found building them
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceCommandList {
+    /// Recognized phrases, sorted by phrase
+    pub commands: Vec<RegisteredCommand>,
+    /// Entries that were ignored or override another
+    pub conflicts: Vec<CommandConflict>,
+}
+
+/// List the commands recognized with a configuration
+///
+/// Takes the configuration rather than reading settings so unsaved edits
+/// can be checked for conflicts.
+#[tauri::command]
+pub fn get_voice_commands(config: CommandConfig) -> VoiceCommandList {
+    let registry = CommandRegistry::from_config(&config);
+    VoiceCommandList {
+        commands: registry.commands(),
+        conflicts: registry.conflicts().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::voice_commands::{CommandAction, ConflictKind, CustomCommand};
+
+    #[test]
+    fn test_get_voice_commands_reports_conflicts() {
+        let config = CommandConfig {
+            custom_commands: vec![CustomCommand {
+                phrase: "comma".to_string(),
+                action: CommandAction::InsertText("、".to_string()),
+            }],
+            ..Default::default()
+        };
+
+        let list = get_voice_commands(config);
+        assert_eq!(list.conflicts.len(), 1);
+        assert_eq!(list.conflicts[0].kind, ConflictKind::OverridesBuiltIn);
+
+        let comma = list.commands.iter().find(|c| c.phrase == "comma").unwrap();
+        assert_eq!(comma.action, CommandAction::InsertText("、".to_string()));
+        assert!(list.commands.iter().any(|c| c.phrase == "question mark"));
+    }
+}
@@ -2,11 +2,19 @@
 //!
 //! Provides commands that orchestrate multiple services for complete workflows.
 
+use crate::commands::audio::{save_to_history, stop_and_transcribe};
 use crate::commands::{AudioState, TranscriptionState};
-use crate::services::platform::TextInjectorState;
+use crate::models::{HistoryMetadata, HistorySource, Settings};
+use crate::services::platform::{
+    active_window, find_profile, ActiveWindow, AppProfile, ClipboardConfig, PlatformError,
+    TextInjectorState,
+};
+use crate::services::sinks::{self, DictationSink};
 use crate::services::storage::{DatabaseState, SettingsState};
+use crate::services::text_normalization::TextNormalizer;
+use crate::services::text_replacements::TextReplacer;
 use crate::services::ui::{emit_preview_text, position_preview, PreviewState};
-use crate::services::voice_commands::{CommandAction, CommandParser};
+use crate::services::voice_commands::{CommandAction, CommandConfig, CommandParser, ParseStep};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter, State};
@@ -93,14 +101,16 @@ pub async fn push_to_talk_complete(
     tracing::info!("Starting push-to-talk completion flow");
     let _ = app.emit("workflow://state-changed", "transcribing");
 
+    // Note where the text is going while that window still has focus
+    let target = active_window();
+
     // Stop recording and transcribe (with auto-load fallback)
     let transcribe_start = Instant::now();
-    let transcription_result = match crate::commands::audio::stop_recording_and_transcribe(
-        app.clone(),
-        audio_state.clone(),
-        transcription_state.clone(),
-        settings_state.clone(),
-        database_state.clone(),
+    let completed = match stop_and_transcribe(
+        &app,
+        &audio_state,
+        &transcription_state,
+        &settings_state,
     )
     .await
     {
@@ -120,18 +130,49 @@ pub async fn push_to_talk_complete(
     };
     let transcription_time_ms = transcribe_start.elapsed().as_millis() as u64;
 
-    let raw_text = transcription_result.text.trim().to_string();
+    let raw_text = completed.result.text.trim().to_string();
 
-    // Get settings
+    // Get settings, with the overrides of the target application's profile
     let settings = settings_state.get().await;
+    let profile = find_profile(&settings.app_profiles, target.as_ref()).cloned();
+    let settings = match &profile {
+        Some(profile) => {
+            tracing::info!("Using app profile '{}'", profile.name);
+            settings.with_profile(profile)
+        }
+        None => settings,
+    };
     let preview_enabled = settings.preview_enabled;
 
     // Parse voice commands AFTER transcription, BEFORE text injection
     let parser = CommandParser::new(settings.voice_commands.clone());
-    let parse_result = parser.parse_advanced(&raw_text);
-    let text = parse_result.text.clone();
+    let parse_result = parser.parse(&raw_text);
     let actions = parse_result.actions;
 
+    // Write spoken numbers in digits, then apply the user's replacement rules
+    // and snippets to the remaining text
+    let language = completed
+        .result
+        .language
+        .as_deref()
+        .or(settings.language.as_deref());
+    let normalizer = TextNormalizer::new(settings.text_normalization, language);
+    let replacer = TextReplacer::new(&settings.text_replacements);
+    let finish = |segment: &str| replacer.apply(&normalizer.apply(segment));
+    let mut text = finish(&parse_result.text);
+    let mut steps: Vec<ParseStep> = parse_result
+        .steps
+        .into_iter()
+        .map(|step| match step {
+            ParseStep::Text(segment) => ParseStep::Text(finish(&segment)),
+            action => action,
+        })
+        .collect();
+    if profile.as_ref().is_some_and(|p| p.strip_trailing_newline) {
+        text.truncate(text.trim_end_matches(['\n', '\r']).len());
+        strip_trailing_newlines(&mut steps);
+    }
+
     tracing::debug!(
         "Voice command parsing: raw='{}' -> processed='{}', actions={:?}",
         raw_text,
@@ -139,14 +180,32 @@ pub async fn push_to_talk_complete(
         actions.len()
     );
 
+    let mut metadata = HistoryMetadata {
+        source: Some(HistorySource::Hotkey),
+        source_app: target.as_ref().and_then(|w| w.app.clone()),
+        source_window: target.as_ref().and_then(|w| w.title.clone()),
+        transcription_time_ms: Some(transcription_time_ms),
+        voice_commands: actions.clone(),
+        ..Default::default()
+    };
+
     // Skip injection if text is empty and no actions
     if text.is_empty() && actions.is_empty() {
         tracing::warn!("Transcription returned empty text and no actions, skipping injection");
+        save_to_history(
+            &app,
+            &database_state,
+            &settings_state,
+            &completed.result,
+            completed.recording,
+            metadata,
+        )
+        .await;
         let _ = app.emit("workflow://state-changed", "idle");
         update_completion_time();
         return Ok(PushToTalkResult {
             text: String::new(),
-            audio_duration_secs: transcription_result.duration_ms as f32 / 1000.0,
+            audio_duration_secs: completed.result.duration_ms as f32 / 1000.0,
             transcription_time_ms,
             injection_time_ms: 0,
             total_latency_ms: start.elapsed().as_millis() as u64,
@@ -172,54 +231,64 @@ pub async fn push_to_talk_complete(
     }
     let inject_start = Instant::now();
 
-    // Inject the processed text (if not empty)
-    if !text.is_empty() {
-        if let Err(e) = text_injector_state.inject_text(&text).await {
-            tracing::error!("Text injection failed: {}", e);
-            if preview_enabled {
-                let _ = emit_preview_text(&app, &text, PreviewState::Error);
-            }
-            let _ = app.emit(
-                "workflow://error",
-                PushToTalkError {
-                    phase: "injection".to_string(),
-                    message: e.to_string(),
-                },
-            );
-            let _ = app.emit("workflow://state-changed", "idle");
-            return Err(format!("Text injection failed: {}", e));
-        }
-    }
-
-    // Execute voice command actions (delete, undo, etc.)
-    for action in &actions {
-        match action {
-            CommandAction::DeleteCharacters(count) => {
-                tracing::debug!("Executing delete action: {} characters", count);
-                if let Err(e) = text_injector_state.delete_characters(*count).await {
-                    tracing::warn!("Delete action failed: {}", e);
-                }
-            }
-            CommandAction::Undo => {
-                tracing::debug!("Executing undo action");
-                if let Err(e) = text_injector_state.send_undo().await {
-                    tracing::warn!("Undo action failed: {}", e);
-                }
-            }
-            CommandAction::InsertText(_) => {
-                // Text insertion is already handled in the parsed text
-            }
-            CommandAction::CapitalizeNext => {
-                // Capitalization is already handled in the parsed text
-            }
+    // Inject the text, or hand it to the configured sink instead
+    let delivered = match &settings.dictation_sink {
+        DictationSink::Inject => inject_steps(
+            &text_injector_state,
+            &settings,
+            profile.as_ref(),
+            target.as_ref(),
+            &steps,
+            parse_result.capitalize_next,
+        )
+        .await
+        .map_err(|e| ("injection", e.to_string())),
+        sink => sinks::deliver(&app, sink, &text)
+            .await
+            .map_err(|e| ("delivery", e.to_string())),
+    };
+    if let Err((phase, message)) = delivered {
+        tracing::error!("Text {} failed: {}", phase, message);
+        metadata.injection_time_ms = Some(inject_start.elapsed().as_millis() as u64);
+        save_to_history(
+            &app,
+            &database_state,
+            &settings_state,
+            &completed.result,
+            completed.recording,
+            metadata,
+        )
+        .await;
+        if preview_enabled {
+            let _ = emit_preview_text(&app, &text, PreviewState::Error);
         }
+        let _ = app.emit(
+            "workflow://error",
+            PushToTalkError {
+                phase: phase.to_string(),
+                message: message.clone(),
+            },
+        );
+        let _ = app.emit("workflow://state-changed", "idle");
+        return Err(format!("Text {} failed: {}", phase, message));
     }
 
     let injection_time_ms = inject_start.elapsed().as_millis() as u64;
 
+    metadata.injection_time_ms = Some(injection_time_ms);
+    save_to_history(
+        &app,
+        &database_state,
+        &settings_state,
+        &completed.result,
+        completed.recording,
+        metadata,
+    )
+    .await;
+
     let total_latency_ms = start.elapsed().as_millis() as u64;
 
-    let audio_duration_secs = transcription_result.duration_ms as f32 / 1000.0;
+    let audio_duration_secs = completed.result.duration_ms as f32 / 1000.0;
 
     // Log metrics
     tracing::info!(
@@ -265,6 +334,105 @@ pub async fn push_to_talk_complete(
     })
 }
 
+/// Inject the text and run injector actions (delete, undo, keys) in the
+/// order they were spoken. Text starting the dictation continues what the
+/// previous dictation typed. Text is pasted or typed as the settings say,
+/// and the user's clipboard is restored after pasting unless auto-copy
+/// keeps the transcript there.
+async fn inject_steps(
+    injector: &TextInjectorState,
+    settings: &Settings,
+    profile: Option<&AppProfile>,
+    target: Option<&ActiveWindow>,
+    steps: &[ParseStep],
+    capitalize_next: bool,
+) -> Result<(), PlatformError> {
+    injector.set_method(settings.injection_method).await;
+    injector.set_delay(settings.injection_delay_ms).await;
+    injector
+        .set_clipboard_config(ClipboardConfig {
+            keep_transcript: settings.auto_copy,
+            restore_delay_ms: settings.clipboard_restore_delay_ms,
+            paste_shortcut: profile.map(|p| p.paste_shortcut).unwrap_or_default(),
+        })
+        .await;
+    injector.begin_utterance(target).await;
+    for step in steps {
+        match step {
+            ParseStep::Text(segment) => {
+                let segment = injector.continue_text(segment).await;
+                injector.inject_text(&segment).await?;
+            }
+            ParseStep::Action(action) => execute_action(injector, action).await,
+        }
+    }
+    injector.set_capitalize_next(capitalize_next).await;
+    Ok(())
+}
+
+/// Drop line breaks that would end the dictation, for apps where Enter sends
+fn strip_trailing_newlines(steps: &mut Vec<ParseStep>) {
+    while let Some(ParseStep::Text(segment)) = steps.last_mut() {
+        segment.truncate(segment.trim_end_matches(['\n', '\r']).len());
+        if !segment.is_empty() {
+            break;
+        }
+        steps.pop();
+    }
+}
+
+/// Run only the voice commands in a transcript, for command-mode hotkeys.
+/// Everything said counts as a command, so no prefix is needed, and text
+/// that isn't a command is dropped. Returns the number of actions run.
+pub(crate) async fn execute_commands(
+    injector: &TextInjectorState,
+    config: &CommandConfig,
+    text: &str,
+) -> usize {
+    let parser = CommandParser::new(CommandConfig {
+        enabled: true,
+        require_prefix: false,
+        ..config.clone()
+    });
+    let actions = parser.parse(text).actions;
+    for action in &actions {
+        execute_action(injector, action).await;
+    }
+    actions.len()
+}
+
+/// Perform a voice command action that acts on the focused application
+async fn execute_action(injector: &TextInjectorState, action: &CommandAction) {
+    tracing::debug!("Executing voice command action: {:?}", action);
+    let result = match action {
+        CommandAction::DeleteCharacters(count) => injector.delete_characters(*count).await,
+        CommandAction::Undo => injector.send_undo().await,
+        CommandAction::ScratchThat => injector.scratch_that().await,
+        CommandAction::DeleteLastWord => injector.delete_last_word().await,
+        CommandAction::DeleteLastSentence => injector.delete_last_sentence().await,
+        CommandAction::SelectAll => injector.select_all().await,
+        CommandAction::PressKey(key) => injector.press_key(*key).await,
+        CommandAction::GoToLineEnd => injector.go_to_line_end().await,
+        // Text insertion and formatting are already handled in the parsed text
+        CommandAction::InsertText(_)
+        | CommandAction::CapitalizeNext
+        | CommandAction::StartAllCaps
+        | CommandAction::EndAllCaps
+        | CommandAction::StartIdentifier(_)
+        | CommandAction::EndIdentifier
+        | CommandAction::Open(_)
+        | CommandAction::Close(_)
+        | CommandAction::BulletPoint
+        | CommandAction::NumberedList
+        | CommandAction::NextItem
+        | CommandAction::NoSpace => Ok(()),
+    };
+
+    if let Err(e) = result {
+        tracing::warn!("Voice command action {:?} failed: {}", action, e);
+    }
+}
+
 /// Check if push-to-talk cooldown is currently active
 #[tauri::command]
 pub fn is_push_to_talk_cooldown_active() -> bool {
@@ -286,6 +454,7 @@ pub async fn get_workflow_state(audio_state: State<'_, AudioState>) -> Result<St
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::platform::Key;
 
     #[test]
     fn test_cooldown_functions_exist() {
@@ -303,4 +472,31 @@ mod tests {
         // With timestamp 0 (1970), definitely more than 500ms have passed
         assert!(!is_cooldown_active());
     }
+
+    #[test]
+    fn test_strip_trailing_newlines() {
+        let mut steps = vec![
+            ParseStep::Text("Hello\n".to_string()),
+            ParseStep::Action(CommandAction::DeleteLastWord),
+            ParseStep::Text("there\n".to_string()),
+            ParseStep::Text("\r\n".to_string()),
+        ];
+        strip_trailing_newlines(&mut steps);
+        assert_eq!(
+            steps,
+            vec![
+                ParseStep::Text("Hello\n".to_string()),
+                ParseStep::Action(CommandAction::DeleteLastWord),
+                ParseStep::Text("there".to_string()),
+            ]
+        );
+
+        // A key press the user asked for is kept
+        let mut steps = vec![
+            ParseStep::Text("Hi\n".to_string()),
+            ParseStep::Action(CommandAction::PressKey(Key::Enter)),
+        ];
+        strip_trailing_newlines(&mut steps);
+        assert_eq!(steps.len(), 2);
+    }
 }
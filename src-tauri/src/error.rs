@@ -13,6 +13,9 @@ pub enum AppError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("Storage error: {0}")]
+    Storage(String),
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
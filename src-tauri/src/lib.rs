@@ -53,6 +53,23 @@ pub fn run() {
             let settings_state = app.state::<services::storage::SettingsState>();
             let saved_hotkey = settings_state.get_hotkey_sync();
             services::hotkey::setup_hotkey_with_key(app.handle(), &hotkey_state, &saved_hotkey);
+            services::hotkey::setup_bindings(
+                app.handle(),
+                &hotkey_state,
+                &saved_hotkey,
+                &settings_state.get_hotkey_bindings_sync(),
+                &settings_state.get_dictation_profiles_sync(),
+            );
+
+            // Apply saved audio preprocessing settings to the transcription engine
+            let audio_processing = settings_state.get_audio_processing_sync();
+            let engine = app.state::<commands::TranscriptionState>().engine.clone();
+            tauri::async_runtime::spawn(async move {
+                engine.set_preprocessing(audio_processing).await;
+            });
+
+            // Enforce history retention on startup and periodically
+            services::storage::retention::spawn_retention_task(app.handle().clone());
 
             // Listen for tray update events from hotkey module
             let app_handle = app.handle().clone();
@@ -86,6 +103,9 @@ pub fn run() {
             commands::audio::start_recording,
             commands::audio::stop_recording,
             commands::audio::stop_recording_and_transcribe,
+            commands::audio::pause_recording,
+            commands::audio::resume_recording,
+            commands::audio::is_recording_paused,
             commands::audio::get_audio_devices,
             commands::audio::check_microphone_permission,
             commands::audio::is_recording,
@@ -115,13 +135,17 @@ pub fn run() {
             commands::hotkey::set_hotkey,
             commands::hotkey::clear_hotkey,
             commands::hotkey::get_current_hotkey,
+            commands::hotkey::set_hotkey_bindings,
+            commands::hotkey::get_hotkey_bindings,
             commands::hotkey::get_platform_default_hotkey,
             commands::hotkey::check_hotkey_available,
             commands::hotkey::is_hotkey_registered,
             commands::hotkey::get_hotkey_error,
+            commands::hotkey::get_hotkey_backend,
             // Text injection commands
             commands::text_inject::inject_text,
             commands::text_inject::set_injection_delay,
+            commands::text_inject::set_injection_method,
             commands::text_inject::get_injection_permission_instructions,
             // Workflow commands
             commands::workflow::push_to_talk_complete,
@@ -151,9 +175,38 @@ pub fn run() {
             commands::history::delete_history_entry,
             commands::history::clear_history,
             commands::history::get_history_count,
+            commands::history::set_history_pinned,
+            commands::history::apply_history_retention,
+            commands::history::get_history_privacy_status,
+            commands::history::enable_history_encryption,
+            commands::history::disable_history_encryption,
+            commands::history::unlock_history,
+            commands::history::set_incognito,
+            commands::history::search_history_page,
+            commands::history::get_history_source_apps,
+            commands::history::export_history,
+            commands::history::import_history,
+            commands::history::get_history_audio_path,
+            commands::history::delete_history_audio,
+            commands::history::get_audio_archive_dir,
+            commands::history::retranscribe_history_entry,
+            commands::history::retranscribe_recent_history,
+            commands::history::cancel_retranscribe_job,
+            commands::history::get_history_revisions,
+            commands::history::apply_history_revision,
+            commands::history::update_history_entry,
+            commands::history::get_history_corrections,
+            commands::history::get_correction_summary,
             // Vocabulary commands
             commands::vocabulary::export_vocabulary,
             commands::vocabulary::import_vocabulary,
+            // Text replacement commands
+            commands::replacements::export_replacements,
+            commands::replacements::import_replacements,
+            commands::replacements::validate_replacement_rule,
+            commands::replacements::preview_replacements,
+            // Voice command commands
+            commands::voice_commands::get_voice_commands,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,5 +1,8 @@
 //! History entry model for transcription history
 
+use crate::models::settings::StreamingMode;
+use crate::services::audio::dsp::DspConfig;
+use crate::services::voice_commands::CommandAction;
 use serde::{Deserialize, Serialize};
 
 /// A history entry representing a past transcription
@@ -20,6 +23,191 @@ pub struct HistoryEntry {
     /// Whether GPU acceleration was used
     #[serde(default)]
     pub gpu_used: bool,
+    /// Path to the archived recording (if audio archiving was enabled)
+    #[serde(default)]
+    pub audio_path: Option<String>,
+    /// Pinned entries are never removed by retention
+    #[serde(default)]
+    pub pinned: bool,
+    /// The transcription as first recorded, if the text has been edited
+    #[serde(default)]
+    pub original_text: Option<String>,
+    /// Where the dictation came from and how it was processed
+    #[serde(default, flatten)]
+    pub metadata: HistoryMetadata,
+}
+
+/// What started a transcription
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistorySource {
+    /// Global hotkey dictation
+    Hotkey,
+    /// Recording started from the tray menu
+    Tray,
+    /// Record button in the main window
+    App,
+    /// Transcription of an audio file
+    File,
+    /// Command-line interface
+    Cli,
+}
+
+/// Context captured alongside a transcription
+///
+/// All fields are optional; entries recorded before this metadata existed
+/// simply leave them empty.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryMetadata {
+    /// What started the transcription
+    pub source: Option<HistorySource>,
+    /// Application that had focus when the text was dictated
+    pub source_app: Option<String>,
+    /// Title of the focused window
+    pub source_window: Option<String>,
+    /// Streaming mode used (None = batch transcription)
+    pub streaming_mode: Option<StreamingMode>,
+    /// Time spent transcribing in milliseconds
+    pub transcription_time_ms: Option<u64>,
+    /// Time spent injecting text and running voice commands in milliseconds
+    pub injection_time_ms: Option<u64>,
+    /// Voice command actions applied to the text
+    pub voice_commands: Vec<CommandAction>,
+    /// The speech was translated to English
+    pub translated: bool,
+}
+
+/// Filters for listing and searching history
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryFilter {
+    /// Only entries from this source
+    pub source: Option<HistorySource>,
+    /// Only entries dictated into this application (case-insensitive)
+    pub source_app: Option<String>,
+    /// Only streaming (true) or batch (false) transcriptions
+    pub streaming: Option<bool>,
+    /// Only entries transcribed with this streaming mode
+    pub streaming_mode: Option<StreamingMode>,
+    /// Only entries with (true) or without (false) voice commands
+    pub has_voice_commands: Option<bool>,
+    /// Only entries whose transcription took at least this long
+    pub min_transcription_time_ms: Option<u64>,
+    /// Only entries whose injection took at least this long
+    pub min_injection_time_ms: Option<u64>,
+    /// Only entries at or after this date (`YYYY-MM-DD`) or RFC 3339 time
+    pub from: Option<String>,
+    /// Only entries at or before this date (inclusive) or RFC 3339 time
+    pub to: Option<String>,
+    /// Only entries transcribed with this model
+    pub model_id: Option<String>,
+    /// Only entries in this language
+    pub language: Option<String>,
+    /// Only pinned (true) or unpinned (false) entries
+    pub pinned: Option<bool>,
+}
+
+/// Options for re-transcribing a history entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetranscribeOptions {
+    /// Build the initial prompt from the custom vocabulary and context prompt
+    pub use_prompt: bool,
+    /// Explicit initial prompt (overrides the settings-based prompt)
+    pub initial_prompt: Option<String>,
+    /// Audio preprocessing override (None = use current settings)
+    pub audio_processing: Option<DspConfig>,
+    /// Spoken language code (None = the entry's language)
+    pub language: Option<String>,
+    /// Translate to English (None = as the entry was)
+    pub translate: Option<bool>,
+}
+
+impl Default for RetranscribeOptions {
+    fn default() -> Self {
+        Self {
+            use_prompt: true,
+            initial_prompt: None,
+            audio_processing: None,
+            language: None,
+            translate: None,
+        }
+    }
+}
+
+/// An alternative transcript of a history entry's archived audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRevision {
+    /// Unique identifier
+    pub id: i64,
+    /// History entry this revision belongs to
+    pub history_id: i64,
+    /// The transcribed text
+    pub text: String,
+    /// Model used for transcription
+    pub model_id: String,
+    /// Language detected/used (if any)
+    pub language: Option<String>,
+    /// Whether GPU acceleration was used
+    #[serde(default)]
+    pub gpu_used: bool,
+    /// Options the revision was produced with
+    #[serde(default)]
+    pub options: RetranscribeOptions,
+    /// ISO 8601 timestamp when the revision was created
+    pub created_at: String,
+}
+
+/// A history entry matching a search, with match positions
+///
+/// Highlight ranges are `[start, end)` offsets in UTF-16 code units, so the
+/// frontend can slice the strings directly.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySearchResult {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    /// BM25 relevance (higher is better); `None` for substring matches
+    pub score: Option<f64>,
+    /// Excerpt of the text around the matches
+    pub snippet: String,
+    /// Match ranges within `snippet`
+    pub snippet_highlights: Vec<[usize; 2]>,
+    /// Match ranges within the entry text
+    pub highlights: Vec<[usize; 2]>,
+}
+
+/// One page of search results
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySearchPage {
+    /// Results on this page, best match first
+    pub results: Vec<HistorySearchResult>,
+    /// Matching entries across all pages
+    pub total: usize,
+}
+
+/// Words the user replaced when editing a transcription
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextCorrection {
+    /// What was transcribed
+    pub original: String,
+    /// What the user changed it to
+    pub corrected: String,
+}
+
+/// A correction aggregated over all edited entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CorrectionSummary {
+    /// What was transcribed
+    pub original: String,
+    /// What the user changed it to
+    pub corrected: String,
+    /// How often the correction was made
+    pub count: usize,
+    /// Number of entries it was made in
+    pub entries: usize,
+    /// ISO 8601 timestamp of the latest edit that made it
+    pub last_corrected: String,
 }
 
 impl HistoryEntry {
@@ -50,6 +238,10 @@ mod tests {
             model_id: "base".to_string(),
             language: None,
             gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
         assert_eq!(entry.preview(50), "Hello world");
     }
@@ -64,6 +256,10 @@ mod tests {
             model_id: "base".to_string(),
             language: None,
             gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
         let preview = entry.preview(20);
         assert!(preview.ends_with("..."));
@@ -80,6 +276,10 @@ mod tests {
             model_id: "tiny".to_string(),
             language: Some("en".to_string()),
             gpu_used: true,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
         let json = serde_json::to_string(&entry).unwrap();
         let parsed: HistoryEntry = serde_json::from_str(&json).unwrap();
@@ -88,4 +288,61 @@ mod tests {
         assert_eq!(parsed.language, Some("en".to_string()));
         assert!(parsed.gpu_used);
     }
+
+    #[test]
+    fn test_history_metadata_serialization() {
+        let entry = HistoryEntry {
+            id: 1,
+            text: "Hello.".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            duration_ms: 1500,
+            model_id: "tiny".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: HistoryMetadata {
+                source: Some(HistorySource::Hotkey),
+                source_app: Some("firefox".to_string()),
+                source_window: Some("Inbox".to_string()),
+                streaming_mode: Some(StreamingMode::Balanced),
+                transcription_time_ms: Some(420),
+                injection_time_ms: Some(35),
+                voice_commands: vec![CommandAction::InsertText(".".to_string())],
+                translated: false,
+            },
+        };
+
+        let json = serde_json::to_value(&entry).unwrap();
+        // Metadata is flattened into the entry
+        assert_eq!(json["source"], "hotkey");
+        assert_eq!(json["source_app"], "firefox");
+        assert_eq!(json["streaming_mode"], "balanced");
+
+        let parsed: HistoryEntry = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.metadata, entry.metadata);
+    }
+
+    #[test]
+    fn test_history_entry_without_metadata() {
+        let json = r#"{
+            "id": 1,
+            "text": "Hello",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "duration_ms": 1000,
+            "model_id": "base",
+            "language": null
+        }"#;
+        let parsed: HistoryEntry = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.metadata, HistoryMetadata::default());
+    }
+
+    #[test]
+    fn test_retranscribe_options_defaults() {
+        let options: RetranscribeOptions = serde_json::from_str("{}").unwrap();
+        assert!(options.use_prompt);
+        assert!(options.initial_prompt.is_none());
+        assert!(options.audio_processing.is_none());
+    }
 }
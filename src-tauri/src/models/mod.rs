@@ -5,7 +5,10 @@
 pub mod history;
 pub mod settings;
 
-pub use history::HistoryEntry;
+pub use history::{
+    CorrectionSummary, HistoryEntry, HistoryFilter, HistoryMetadata, HistoryRevision,
+    HistorySearchPage, HistorySearchResult, HistorySource, RetranscribeOptions, TextCorrection,
+};
 pub use settings::{RecordingMode, Settings};
 
 #[cfg(test)]
@@ -2,6 +2,14 @@
 //!
 //! Defines the user-configurable settings for EZ Flow.
 
+use crate::services::audio::dsp::DspConfig;
+use crate::services::hotkey::{DictationProfile, HotkeyBinding};
+use crate::services::platform::{clipboard, AppProfile, InjectionMethod};
+use crate::services::sinks::DictationSink;
+use crate::services::storage::{
+    AudioArchiveConfig, HistoryEncryptionConfig, HistoryRetentionConfig,
+};
+use crate::services::text_replacements::ReplacementConfig;
 use crate::services::ui::IndicatorPosition;
 use crate::services::voice_commands::CommandConfig;
 use serde::{Deserialize, Serialize};
@@ -35,6 +43,12 @@ pub enum StreamingMode {
 pub struct Settings {
     /// Global hotkey for recording
     pub hotkey: String,
+    /// Further hotkeys bound to other actions
+    #[serde(default)]
+    pub hotkey_bindings: Vec<HotkeyBinding>,
+    /// Transcription overrides that hotkey bindings refer to by name
+    #[serde(default)]
+    pub dictation_profiles: Vec<DictationProfile>,
     /// Recording activation mode
     pub recording_mode: RecordingMode,
     /// Whisper model ID to use
@@ -47,10 +61,24 @@ pub struct Settings {
     pub indicator_position: IndicatorPosition,
     /// Automatically paste transcribed text
     pub auto_paste: bool,
-    /// Also copy to clipboard
+    /// Leave the transcript on the clipboard; otherwise the previous
+    /// clipboard contents are restored after pasting
     pub auto_copy: bool,
+    /// Time the target app gets to read a paste before the clipboard is
+    /// restored (ms)
+    #[serde(default = "default_clipboard_restore_delay")]
+    pub clipboard_restore_delay_ms: u32,
     /// Delay between keystrokes in ms
     pub injection_delay_ms: u32,
+    /// Paste text via the clipboard or type it as keystrokes
+    #[serde(default)]
+    pub injection_method: InjectionMethod,
+    /// Per-application overrides, the first match for the focused app applies
+    #[serde(default)]
+    pub app_profiles: Vec<AppProfile>,
+    /// Where finished transcripts go instead of being injected
+    #[serde(default)]
+    pub dictation_sink: DictationSink,
     /// Whether onboarding has been completed
     #[serde(default)]
     pub onboarding_completed: bool,
@@ -93,12 +121,30 @@ pub struct Settings {
     /// Voice command settings
     #[serde(default)]
     pub voice_commands: CommandConfig,
+    /// Find/replace rules and snippets applied to transcripts before injection
+    #[serde(default)]
+    pub text_replacements: ReplacementConfig,
+    /// Write spoken numbers, dates, times and units in written form
+    #[serde(default = "default_true")]
+    pub text_normalization: bool,
     /// Whether streaming transcription is enabled
     #[serde(default = "default_true")]
     pub streaming_enabled: bool,
     /// Streaming transcription mode (speed/balanced/accuracy)
     #[serde(default)]
     pub streaming_mode: StreamingMode,
+    /// Audio preprocessing (high-pass, noise suppression, AGC) before inference
+    #[serde(default)]
+    pub audio_processing: DspConfig,
+    /// Keep recordings linked to history entries
+    #[serde(default)]
+    pub audio_archive: AudioArchiveConfig,
+    /// Limits for automatically removing old history entries
+    #[serde(default)]
+    pub history_retention: HistoryRetentionConfig,
+    /// Encryption of the history database at rest
+    #[serde(default)]
+    pub history_encryption: HistoryEncryptionConfig,
 }
 
 fn default_use_gpu() -> bool {
@@ -117,6 +163,10 @@ fn default_preview_duration() -> u32 {
     3 // 3 seconds
 }
 
+fn default_clipboard_restore_delay() -> u32 {
+    clipboard::DEFAULT_RESTORE_DELAY_MS
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -125,6 +175,8 @@ impl Default for Settings {
             } else {
                 "Ctrl+Shift+Space".into()
             },
+            hotkey_bindings: Vec::new(),
+            dictation_profiles: Vec::new(),
             recording_mode: RecordingMode::PushToTalk,
             model_id: "base".into(),
             language: None,
@@ -132,7 +184,11 @@ impl Default for Settings {
             indicator_position: IndicatorPosition::TopRight,
             auto_paste: true,
             auto_copy: true,
+            clipboard_restore_delay_ms: default_clipboard_restore_delay(),
             injection_delay_ms: 0,
+            injection_method: InjectionMethod::default(),
+            app_profiles: Vec::new(),
+            dictation_sink: DictationSink::default(),
             onboarding_completed: false,
             onboarding_skipped: false,
             use_gpu: default_use_gpu(),
@@ -147,9 +203,64 @@ impl Default for Settings {
             preview_position_x: None,
             preview_position_y: None,
             voice_commands: CommandConfig::default(),
+            text_replacements: ReplacementConfig::default(),
+            text_normalization: default_true(),
             streaming_enabled: default_true(),
             streaming_mode: StreamingMode::default(),
+            audio_processing: DspConfig::default(),
+            audio_archive: AudioArchiveConfig::default(),
+            history_retention: HistoryRetentionConfig::default(),
+            history_encryption: HistoryEncryptionConfig::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// These settings with an app profile's overrides applied
+    pub fn with_profile(mut self, profile: &AppProfile) -> Self {
+        if let Some(method) = profile.injection_method {
+            self.injection_method = method;
+        }
+        if let Some(delay) = profile.injection_delay_ms {
+            self.injection_delay_ms = delay;
+        }
+        if let Some(delay) = profile.clipboard_restore_delay_ms {
+            self.clipboard_restore_delay_ms = delay;
+        }
+        if let Some(commands) = &profile.voice_commands {
+            self.voice_commands = commands.clone();
+        }
+        if let Some(replacements) = &profile.text_replacements {
+            self.text_replacements = replacements.clone();
+        }
+        self
+    }
+
+    /// The audio archive settings in effect. Recordings are stored
+    /// unencrypted, so archiving is off while the history is encrypted.
+    pub fn effective_audio_archive(&self) -> AudioArchiveConfig {
+        AudioArchiveConfig {
+            enabled: self.audio_archive.enabled && !self.history_encryption.enabled,
+            ..self.audio_archive.clone()
+        }
+    }
+
+    /// These settings with a dictation profile's overrides applied
+    pub fn with_dictation_profile(mut self, profile: &DictationProfile) -> Self {
+        if let Some(model_id) = &profile.model_id {
+            self.model_id = model_id.clone();
         }
+        if let Some(language) = &profile.language {
+            self.language = Some(language.clone());
+        }
+        if let Some(prompt) = &profile.context_prompt {
+            self.context_prompt = Some(prompt.clone());
+            self.use_context_prompt = true;
+        }
+        if let Some(sink) = &profile.sink {
+            self.dictation_sink = sink.clone();
+        }
+        self
     }
 }
 
@@ -157,6 +268,21 @@ impl Default for Settings {
 mod tests {
     use super::*;
 
+    /// Settings saved before any of the optional fields existed
+    const OLD_SETTINGS_JSON: &str = r#"{
+        "hotkey": "Ctrl+Shift+Space",
+        "recording_mode": "push_to_talk",
+        "model_id": "base",
+        "language": null,
+        "launch_at_login": false,
+        "indicator_position": "top_right",
+        "auto_paste": true,
+        "auto_copy": true,
+        "injection_delay_ms": 0,
+        "onboarding_completed": false,
+        "onboarding_skipped": false
+    }"#;
+
     #[test]
     fn test_settings_default() {
         let settings = Settings::default();
@@ -205,24 +331,29 @@ mod tests {
     #[test]
     fn test_settings_backward_compatibility() {
         // Test that settings without new fields can still be parsed
-        let old_json = r#"{
-            "hotkey": "Ctrl+Shift+Space",
-            "recording_mode": "push_to_talk",
-            "model_id": "base",
-            "language": null,
-            "launch_at_login": false,
-            "indicator_position": "top_right",
-            "auto_paste": true,
-            "auto_copy": true,
-            "injection_delay_ms": 0,
-            "onboarding_completed": false,
-            "onboarding_skipped": false
-        }"#;
-
-        let parsed: Settings = serde_json::from_str(old_json).unwrap();
+        let parsed: Settings = serde_json::from_str(OLD_SETTINGS_JSON).unwrap();
         assert!(parsed.custom_vocabulary.is_empty());
         assert!(parsed.context_prompt.is_none());
         assert!(!parsed.use_context_prompt);
+        assert_eq!(parsed.audio_archive, AudioArchiveConfig::default());
+        assert_eq!(parsed.history_retention, HistoryRetentionConfig::default());
+        assert!(!parsed.history_retention.is_limited());
+        assert_eq!(
+            parsed.history_encryption,
+            HistoryEncryptionConfig::default()
+        );
+        assert!(parsed.text_replacements.enabled);
+        assert!(parsed.text_replacements.rules.is_empty());
+        assert!(parsed.text_normalization);
+        assert_eq!(parsed.injection_method, InjectionMethod::Paste);
+        assert_eq!(
+            parsed.clipboard_restore_delay_ms,
+            clipboard::DEFAULT_RESTORE_DELAY_MS
+        );
+        assert!(parsed.app_profiles.is_empty());
+        assert_eq!(parsed.dictation_sink, DictationSink::Inject);
+        assert!(parsed.hotkey_bindings.is_empty());
+        assert!(parsed.dictation_profiles.is_empty());
     }
 
     #[test]
@@ -292,22 +423,62 @@ mod tests {
     #[test]
     fn test_streaming_settings_backward_compatibility() {
         // Old settings without streaming fields should default correctly
-        let old_json = r#"{
-            "hotkey": "Ctrl+Shift+Space",
-            "recording_mode": "push_to_talk",
-            "model_id": "base",
-            "language": null,
-            "launch_at_login": false,
-            "indicator_position": "top_right",
-            "auto_paste": true,
-            "auto_copy": true,
-            "injection_delay_ms": 0,
-            "onboarding_completed": false,
-            "onboarding_skipped": false
-        }"#;
-
-        let parsed: Settings = serde_json::from_str(old_json).unwrap();
+        let parsed: Settings = serde_json::from_str(OLD_SETTINGS_JSON).unwrap();
         assert!(parsed.streaming_enabled);
         assert_eq!(parsed.streaming_mode, StreamingMode::Balanced);
     }
+
+    #[test]
+    fn test_audio_processing_settings() {
+        let settings = Settings::default();
+        assert!(!settings.audio_processing.enabled);
+
+        let mut settings = settings;
+        settings.audio_processing.enabled = true;
+        settings.audio_processing.agc_enabled = false;
+
+        let json = serde_json::to_string(&settings).unwrap();
+        let parsed: Settings = serde_json::from_str(&json).unwrap();
+        assert!(parsed.audio_processing.enabled);
+        assert!(!parsed.audio_processing.agc_enabled);
+        assert!(parsed.audio_processing.high_pass_enabled);
+    }
+
+    #[test]
+    fn test_audio_archive_off_while_encrypted() {
+        let mut settings = Settings::default();
+        settings.audio_archive.enabled = true;
+        settings.audio_archive.max_entries = 10;
+        assert!(settings.effective_audio_archive().enabled);
+
+        settings.history_encryption.enabled = true;
+        let archive = settings.effective_audio_archive();
+        assert!(!archive.enabled);
+        assert_eq!(archive.max_entries, 10);
+    }
+
+    #[test]
+    fn test_with_profile_overrides_only_set_fields() {
+        let profile = AppProfile {
+            name: "Editor".to_string(),
+            apps: vec!["gvim".to_string()],
+            injection_method: Some(InjectionMethod::Type),
+            injection_delay_ms: Some(10),
+            voice_commands: Some(CommandConfig {
+                enabled: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let settings = Settings::default().with_profile(&profile);
+        assert_eq!(settings.injection_method, InjectionMethod::Type);
+        assert_eq!(settings.injection_delay_ms, 10);
+        assert!(!settings.voice_commands.enabled);
+        assert_eq!(
+            settings.clipboard_restore_delay_ms,
+            clipboard::DEFAULT_RESTORE_DELAY_MS
+        );
+        assert!(settings.text_replacements.enabled);
+    }
 }
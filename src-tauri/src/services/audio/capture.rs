@@ -3,8 +3,9 @@
 //! Handles microphone input and buffering for transcription.
 
 use super::{
-    chunking::{AudioChunk, ChunkedAudioBuffer},
+    chunking::{AudioChunk, ChunkConfig, ChunkedAudioBuffer},
     processing::{calculate_audio_level, AudioBuffer},
+    spool::{RecordedAudio, RecordingSpool, WindowSender},
     AudioDevice, AudioError, RecordingResult,
 };
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
@@ -12,9 +13,6 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-/// Maximum recording duration in seconds (5 minutes)
-const MAX_RECORDING_DURATION_SECS: u64 = 300;
-
 /// Number of samples to use for level calculation (~100ms at 16kHz)
 const LEVEL_CALCULATION_SAMPLES: usize = 1600;
 
@@ -23,9 +21,16 @@ pub struct AudioCaptureService {
     device: cpal::Device,
     config: cpal::SupportedStreamConfig,
     stream: Option<cpal::Stream>,
-    buffer: Arc<Mutex<Vec<f32>>>,
+    /// Recording storage, spilled to disk for long recordings
+    spool: Arc<Mutex<RecordingSpool>>,
     is_recording: Arc<AtomicBool>,
+    /// Whether capture is paused (samples are dropped while paused)
+    is_paused: Arc<AtomicBool>,
     recording_start: Option<Instant>,
+    /// When the current pause started
+    paused_at: Option<Instant>,
+    /// Total time spent paused during the current recording
+    paused_total: Duration,
     channels: u16,
     /// Current audio level (0.0-1.0), updated during recording
     current_level: Arc<Mutex<f32>>,
@@ -62,12 +67,15 @@ impl AudioCaptureService {
             channels: config.channels(),
             config,
             stream: None,
-            buffer: Arc::new(Mutex::new(Vec::new())),
+            spool: Arc::new(Mutex::new(RecordingSpool::with_defaults(sample_rate))),
             is_recording: Arc::new(AtomicBool::new(false)),
+            is_paused: Arc::new(AtomicBool::new(false)),
             recording_start: None,
+            paused_at: None,
+            paused_total: Duration::ZERO,
             current_level: Arc::new(Mutex::new(0.0)),
             level_buffer: Arc::new(Mutex::new(Vec::with_capacity(LEVEL_CALCULATION_SAMPLES))),
-            chunked_buffer: Arc::new(Mutex::new(ChunkedAudioBuffer::with_defaults(sample_rate))),
+            chunked_buffer: Arc::new(Mutex::new(streaming_buffer(sample_rate))),
             streaming_enabled: Arc::new(AtomicBool::new(false)),
         })
     }
@@ -90,12 +98,15 @@ impl AudioCaptureService {
             channels: config.channels(),
             config,
             stream: None,
-            buffer: Arc::new(Mutex::new(Vec::new())),
+            spool: Arc::new(Mutex::new(RecordingSpool::with_defaults(sample_rate))),
             is_recording: Arc::new(AtomicBool::new(false)),
+            is_paused: Arc::new(AtomicBool::new(false)),
             recording_start: None,
+            paused_at: None,
+            paused_total: Duration::ZERO,
             current_level: Arc::new(Mutex::new(0.0)),
             level_buffer: Arc::new(Mutex::new(Vec::with_capacity(LEVEL_CALCULATION_SAMPLES))),
-            chunked_buffer: Arc::new(Mutex::new(ChunkedAudioBuffer::with_defaults(sample_rate))),
+            chunked_buffer: Arc::new(Mutex::new(streaming_buffer(sample_rate))),
             streaming_enabled: Arc::new(AtomicBool::new(false)),
         })
     }
@@ -140,8 +151,11 @@ impl AudioCaptureService {
             return Ok(());
         }
 
-        // Clear any previous buffer and reset level
-        self.buffer.lock().unwrap().clear();
+        // Clear any previous recording and reset level
+        self.spool.lock().unwrap().reset();
+        self.is_paused.store(false, Ordering::SeqCst);
+        self.paused_at = None;
+        self.paused_total = Duration::ZERO;
         self.level_buffer.lock().unwrap().clear();
         *self.current_level.lock().unwrap() = 0.0;
 
@@ -150,10 +164,11 @@ impl AudioCaptureService {
             self.chunked_buffer.lock().unwrap().reset();
         }
 
-        let buffer = self.buffer.clone();
+        let spool = self.spool.clone();
         let level_buffer = self.level_buffer.clone();
         let current_level = self.current_level.clone();
         let is_recording = self.is_recording.clone();
+        let is_paused = self.is_paused.clone();
         let channels = self.channels;
         let chunked_buffer = self.chunked_buffer.clone();
         let streaming_enabled = self.streaming_enabled.clone();
@@ -166,8 +181,8 @@ impl AudioCaptureService {
             cpal::SampleFormat::F32 => self.device.build_input_stream(
                 &self.config.clone().into(),
                 move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if is_recording.load(Ordering::SeqCst) {
-                        let mut buf = buffer.lock().unwrap();
+                    if is_recording.load(Ordering::SeqCst) && !is_paused.load(Ordering::SeqCst) {
+                        let mut spool = spool.lock().unwrap();
                         let mut lvl_buf = level_buffer.lock().unwrap();
 
                         // Collect mono samples
@@ -185,8 +200,10 @@ impl AudioCaptureService {
                             data.to_vec()
                         };
 
-                        // Add to main buffer
-                        buf.extend_from_slice(&mono_samples);
+                        // Add to recording (spills to disk for long recordings)
+                        if let Err(e) = spool.push(&mono_samples) {
+                            tracing::error!("[AudioCapture] Failed to store samples: {}", e);
+                        }
                         lvl_buf.extend_from_slice(&mono_samples);
 
                         // Add to chunked buffer if streaming is enabled
@@ -214,17 +231,18 @@ impl AudioCaptureService {
                 None,
             ),
             cpal::SampleFormat::I16 => {
-                let buffer = buffer.clone();
+                let spool = spool.clone();
                 let level_buffer = level_buffer.clone();
                 let current_level = current_level.clone();
                 let is_recording = is_recording.clone();
+                let is_paused = is_paused.clone();
                 let chunked_buffer = chunked_buffer.clone();
                 let streaming_enabled = streaming_enabled.clone();
                 self.device.build_input_stream(
                     &self.config.clone().into(),
                     move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        if is_recording.load(Ordering::SeqCst) {
-                            let mut buf = buffer.lock().unwrap();
+                        if is_recording.load(Ordering::SeqCst) && !is_paused.load(Ordering::SeqCst) {
+                            let mut spool = spool.lock().unwrap();
                             let mut lvl_buf = level_buffer.lock().unwrap();
 
                             // Collect mono samples, converting from i16
@@ -246,8 +264,10 @@ impl AudioCaptureService {
                                     .collect()
                             };
 
-                            // Add to main buffer
-                            buf.extend_from_slice(&mono_samples);
+                            // Add to recording (spills to disk for long recordings)
+                            if let Err(e) = spool.push(&mono_samples) {
+                                tracing::error!("[AudioCapture] Failed to store samples: {}", e);
+                            }
                             lvl_buf.extend_from_slice(&mono_samples);
 
                             // Add to chunked buffer if streaming is enabled
@@ -291,32 +311,62 @@ impl AudioCaptureService {
         Ok(())
     }
 
-    /// Stop recording and return the audio buffer
-    pub fn stop(&mut self) -> Result<AudioBuffer, AudioError> {
+    /// Stop recording and return the recorded audio
+    ///
+    /// Recordings longer than the spill threshold are returned as a temp
+    /// file that is removed when the [`RecordedAudio`] is dropped.
+    pub fn stop(&mut self) -> Result<RecordedAudio, AudioError> {
+        let duration = self.recording_duration();
         self.is_recording.store(false, Ordering::SeqCst);
+        self.is_paused.store(false, Ordering::SeqCst);
 
         // Drop the stream to stop recording
         self.stream.take();
 
-        let samples = std::mem::take(&mut *self.buffer.lock().unwrap());
-        let sample_rate = self.config.sample_rate().0;
-        let duration = self
-            .recording_start
-            .map(|s| s.elapsed())
-            .unwrap_or(Duration::ZERO);
+        let recording = self.spool.lock().unwrap().finish()?;
 
         tracing::info!(
-            "Recording stopped: {} samples, {:.2}s",
-            samples.len(),
-            duration.as_secs_f32()
+            "Recording stopped: {} samples, {:.2}s (spilled: {})",
+            recording.len(),
+            duration.as_secs_f32(),
+            recording.is_spilled()
         );
 
         self.recording_start = None;
+        self.paused_at = None;
+        self.paused_total = Duration::ZERO;
 
-        Ok(AudioBuffer {
-            samples,
-            sample_rate,
-        })
+        Ok(recording)
+    }
+
+    /// Send the transcription windows of the current recording as they are
+    /// recorded, see [`RecordingSpool::watch_windows`]
+    pub fn watch_windows(&self, sender: WindowSender) {
+        self.spool.lock().unwrap().watch_windows(sender);
+    }
+
+    /// Pause recording without closing the stream
+    pub fn pause(&mut self) {
+        if !self.is_recording() || self.is_paused() {
+            return;
+        }
+        self.is_paused.store(true, Ordering::SeqCst);
+        self.paused_at = Some(Instant::now());
+        *self.current_level.lock().unwrap() = 0.0;
+        tracing::info!("[AudioCapture] Recording paused");
+    }
+
+    /// Resume a paused recording
+    pub fn resume(&mut self) {
+        if !self.is_paused() {
+            return;
+        }
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_total += paused_at.elapsed();
+        }
+        self.level_buffer.lock().unwrap().clear();
+        self.is_paused.store(false, Ordering::SeqCst);
+        tracing::info!("[AudioCapture] Recording resumed");
     }
 
     /// Check if currently recording
@@ -324,16 +374,23 @@ impl AudioCaptureService {
         self.is_recording.load(Ordering::SeqCst)
     }
 
-    /// Get the elapsed recording duration
-    pub fn recording_duration(&self) -> Duration {
-        self.recording_start
-            .map(|s| s.elapsed())
-            .unwrap_or(Duration::ZERO)
+    /// Check if the recording is paused
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::SeqCst)
     }
 
-    /// Check if recording has exceeded maximum duration
-    pub fn has_exceeded_max_duration(&self) -> bool {
-        self.recording_duration() >= Duration::from_secs(MAX_RECORDING_DURATION_SECS)
+    /// Get the elapsed recording duration, excluding paused time
+    pub fn recording_duration(&self) -> Duration {
+        let Some(start) = self.recording_start else {
+            return Duration::ZERO;
+        };
+        let current_pause = self
+            .paused_at
+            .map(|p| p.elapsed())
+            .unwrap_or(Duration::ZERO);
+        start
+            .elapsed()
+            .saturating_sub(self.paused_total + current_pause)
     }
 
     /// Get the device sample rate
@@ -391,14 +448,6 @@ impl AudioCaptureService {
         self.chunked_buffer.lock().unwrap().flush_remaining()
     }
 
-    /// Get the full audio buffer from chunked storage (for final reconciliation)
-    pub fn get_full_chunked_buffer(&self) -> Vec<f32> {
-        if !self.streaming_enabled.load(Ordering::SeqCst) {
-            return Vec::new();
-        }
-        self.chunked_buffer.lock().unwrap().get_full_buffer().to_vec()
-    }
-
     /// Get total duration recorded in streaming mode
     pub fn streaming_duration_secs(&self) -> f32 {
         if !self.streaming_enabled.load(Ordering::SeqCst) {
@@ -408,6 +457,18 @@ impl AudioCaptureService {
     }
 }
 
+/// Chunked buffer used for streaming mode
+///
+/// The capture service already keeps the full recording in its spool, so the
+/// chunked buffer only needs to produce chunks.
+fn streaming_buffer(sample_rate: u32) -> ChunkedAudioBuffer {
+    let config = ChunkConfig {
+        retain_full_buffer: false,
+        ..Default::default()
+    };
+    ChunkedAudioBuffer::new(config, sample_rate)
+}
+
 /// Save audio buffer to a temporary WAV file
 pub fn save_to_temp_wav(buffer: &AudioBuffer) -> Result<RecordingResult, AudioError> {
     use hound::{SampleFormat, WavSpec, WavWriter};
@@ -76,6 +76,11 @@ pub struct ChunkConfig {
     pub overlap_samples: usize,
     /// Maximum number of chunks to keep in queue
     pub max_queue_size: usize,
+    /// Keep a full 16kHz copy of the recording for final reconciliation
+    ///
+    /// Disable when the caller already keeps the recording elsewhere, so long
+    /// recordings don't hold two copies in memory.
+    pub retain_full_buffer: bool,
 }
 
 impl Default for ChunkConfig {
@@ -87,6 +92,7 @@ impl Default for ChunkConfig {
             overlap_samples: (DEFAULT_OVERLAP_SECS * WHISPER_SAMPLE_RATE as f32) as usize,
             // Keep up to 30 chunks (60 seconds worth)
             max_queue_size: 30,
+            retain_full_buffer: true,
         }
     }
 }
@@ -113,6 +119,8 @@ pub struct ChunkedAudioBuffer {
     chunks: VecDeque<AudioChunk>,
     /// Full audio buffer for final reconciliation (resampled to 16kHz)
     full_buffer: Vec<f32>,
+    /// Total number of 16kHz samples emitted as chunks
+    total_samples: usize,
     /// Pending samples not yet forming a complete chunk (at input sample rate)
     pending_samples: Vec<f32>,
     /// Current chunk index counter
@@ -170,6 +178,7 @@ impl ChunkedAudioBuffer {
             config,
             chunks: VecDeque::new(),
             full_buffer: Vec::new(),
+            total_samples: 0,
             pending_samples: Vec::new(),
             chunk_counter: AtomicU32::new(0),
             start_time_ms: std::time::SystemTime::now()
@@ -191,6 +200,7 @@ impl ChunkedAudioBuffer {
     pub fn reset(&mut self) {
         self.chunks.clear();
         self.full_buffer.clear();
+        self.total_samples = 0;
         self.pending_samples.clear();
         self.chunk_counter.store(0, Ordering::SeqCst);
         self.start_time_ms = std::time::SystemTime::now()
@@ -304,7 +314,10 @@ impl ChunkedAudioBuffer {
         };
 
         // Add resampled samples to full buffer for final reconciliation
-        self.full_buffer.extend_from_slice(&chunk_samples);
+        self.total_samples += chunk_samples.len();
+        if self.config.retain_full_buffer {
+            self.full_buffer.extend_from_slice(&chunk_samples);
+        }
 
         // Keep overlap samples for next chunk's context
         // This means we prepend overlap to next chunk, not keep it in pending
@@ -363,6 +376,8 @@ impl ChunkedAudioBuffer {
     }
 
     /// Get the full audio buffer for final reconciliation
+    ///
+    /// Empty when the buffer was created with `retain_full_buffer` disabled.
     pub fn get_full_buffer(&self) -> &[f32] {
         &self.full_buffer
     }
@@ -374,7 +389,7 @@ impl ChunkedAudioBuffer {
 
     /// Get total duration of recorded audio in seconds
     pub fn total_duration_secs(&self) -> f32 {
-        self.full_buffer.len() as f32 / WHISPER_SAMPLE_RATE as f32
+        self.total_samples as f32 / WHISPER_SAMPLE_RATE as f32
     }
 
     /// Get number of pending chunks
@@ -444,7 +459,10 @@ impl ChunkedAudioBuffer {
         };
 
         // Add to full buffer
-        self.full_buffer.extend_from_slice(&chunk_samples);
+        self.total_samples += chunk_samples.len();
+        if self.config.retain_full_buffer {
+            self.full_buffer.extend_from_slice(&chunk_samples);
+        }
 
         Some(AudioChunk::new(
             chunk_samples,
@@ -589,6 +607,21 @@ mod tests {
         assert!((buffer.total_duration_secs() - 2.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_buffer_without_full_copy() {
+        let config = ChunkConfig {
+            retain_full_buffer: false,
+            ..Default::default()
+        };
+        let mut buffer = ChunkedAudioBuffer::new(config, 16000);
+        buffer.add_samples(&vec![0.0; 48000]); // 3 seconds = 1 chunk + 1 second remaining
+        buffer.flush_remaining();
+
+        // Chunks are still produced, but no second copy of the audio is kept
+        assert!(buffer.get_full_buffer().is_empty());
+        assert!((buffer.total_duration_secs() - 3.0).abs() < 0.001);
+    }
+
     #[test]
     fn test_chunk_timestamps() {
         let mut buffer = ChunkedAudioBuffer::with_defaults(16000);
@@ -0,0 +1,536 @@
+//! Audio preprocessing chain
+//!
+//! Optional DSP applied to 16kHz audio before Whisper inference: a high-pass
+//! filter for DC offset and HVAC hum, a spectral noise gate for steady
+//! background noise, and automatic gain control for quiet speakers.
+
+use realfft::num_complex::Complex;
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// FFT frame size for noise suppression (32ms at 16kHz)
+const NS_FRAME_SIZE: usize = 512;
+
+/// Hop size for noise suppression (50% overlap)
+const NS_HOP_SIZE: usize = NS_FRAME_SIZE / 2;
+
+/// Percentile of per-bin magnitudes used as the noise floor estimate
+const NS_NOISE_PERCENTILE: f32 = 0.2;
+
+/// Over-subtraction factor: low percentiles underestimate the mean noise level
+const NS_OVER_SUBTRACTION: f32 = 2.0;
+
+/// Minimum number of frames needed to estimate a noise profile
+const NS_MIN_FRAMES: usize = 8;
+
+/// AGC analysis frame size (10ms at 16kHz)
+const AGC_FRAME_SIZE: usize = 160;
+
+/// Frames quieter than this (dBFS) are treated as silence and not amplified
+const AGC_SILENCE_DBFS: f32 = -60.0;
+
+/// Configuration for the preprocessing chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DspConfig {
+    /// Master switch for the whole chain
+    pub enabled: bool,
+    /// Remove DC offset and low-frequency rumble
+    pub high_pass_enabled: bool,
+    /// High-pass cutoff frequency in Hz
+    pub high_pass_cutoff_hz: f32,
+    /// Suppress stationary background noise
+    pub noise_suppression_enabled: bool,
+    /// Maximum attenuation applied to noise in dB
+    pub noise_reduction_db: f32,
+    /// Automatically raise or lower the speech level
+    pub agc_enabled: bool,
+    /// Target RMS level in dBFS
+    pub agc_target_dbfs: f32,
+    /// Maximum gain AGC may apply in dB
+    pub agc_max_gain_db: f32,
+}
+
+impl Default for DspConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            high_pass_enabled: true,
+            high_pass_cutoff_hz: 80.0,
+            noise_suppression_enabled: true,
+            noise_reduction_db: 12.0,
+            agc_enabled: true,
+            agc_target_dbfs: -20.0,
+            agc_max_gain_db: 20.0,
+        }
+    }
+}
+
+/// Preprocessing chain: high-pass → noise suppression → AGC
+pub struct DspChain {
+    high_pass: Option<HighPassFilter>,
+    noise_suppressor: Option<NoiseSuppressor>,
+    agc: Option<AutomaticGainControl>,
+}
+
+impl DspChain {
+    /// Build a chain from configuration
+    pub fn new(config: &DspConfig, sample_rate: u32) -> Self {
+        let enabled = |stage: bool| config.enabled && stage;
+
+        Self {
+            high_pass: enabled(config.high_pass_enabled)
+                .then(|| HighPassFilter::new(config.high_pass_cutoff_hz, sample_rate)),
+            noise_suppressor: enabled(config.noise_suppression_enabled)
+                .then(|| NoiseSuppressor::new(config.noise_reduction_db)),
+            agc: enabled(config.agc_enabled)
+                .then(|| AutomaticGainControl::new(config.agc_target_dbfs, config.agc_max_gain_db)),
+        }
+    }
+
+    /// Whether any stage is active
+    pub fn is_active(&self) -> bool {
+        self.high_pass.is_some() || self.noise_suppressor.is_some() || self.agc.is_some()
+    }
+
+    /// Process samples in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if let Some(hpf) = self.high_pass.as_mut() {
+            hpf.process(samples);
+        }
+        if let Some(ns) = self.noise_suppressor.as_mut() {
+            ns.process(samples);
+        }
+        if let Some(agc) = self.agc.as_mut() {
+            agc.process(samples);
+        }
+    }
+}
+
+/// Apply the configured chain to a buffer of 16kHz samples
+pub fn preprocess(samples: &mut [f32], config: &DspConfig, sample_rate: u32) {
+    if !config.enabled || samples.is_empty() {
+        return;
+    }
+    DspChain::new(config, sample_rate).process(samples);
+}
+
+/// Second-order Butterworth high-pass filter (RBJ biquad)
+pub struct HighPassFilter {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl HighPassFilter {
+    /// Create a filter with the given cutoff frequency
+    pub fn new(cutoff_hz: f32, sample_rate: u32) -> Self {
+        let nyquist = sample_rate as f32 / 2.0;
+        let cutoff = cutoff_hz.clamp(1.0, nyquist * 0.9);
+        let q = std::f32::consts::FRAC_1_SQRT_2;
+
+        let w0 = 2.0 * std::f32::consts::PI * cutoff / sample_rate as f32;
+        let cos_w0 = w0.cos();
+        let alpha = w0.sin() / (2.0 * q);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 + cos_w0) / 2.0 / a0,
+            b1: -(1.0 + cos_w0) / a0,
+            b2: (1.0 + cos_w0) / 2.0 / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    /// Filter samples in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            let x0 = *sample;
+            let y0 = self.b0 * x0 + self.b1 * self.x1 + self.b2 * self.x2
+                - self.a1 * self.y1
+                - self.a2 * self.y2;
+            self.x2 = self.x1;
+            self.x1 = x0;
+            self.y2 = self.y1;
+            self.y1 = y0;
+            *sample = y0;
+        }
+    }
+}
+
+/// Spectral noise gate
+///
+/// Estimates a per-frequency noise floor from the quietest frames of the
+/// buffer and attenuates bins that don't rise above it. Works on whole
+/// buffers (a batch recording or a streaming chunk) using a Hann-windowed
+/// STFT with 50% overlap-add.
+pub struct NoiseSuppressor {
+    /// Minimum gain applied to a bin (linear)
+    floor_gain: f32,
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    window: Vec<f32>,
+}
+
+impl NoiseSuppressor {
+    /// Create a noise suppressor with the given maximum reduction in dB
+    pub fn new(reduction_db: f32) -> Self {
+        let mut planner = RealFftPlanner::<f32>::new();
+        let window = (0..NS_FRAME_SIZE)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / NS_FRAME_SIZE as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+
+        Self {
+            floor_gain: db_to_linear(-reduction_db.abs()),
+            forward: planner.plan_fft_forward(NS_FRAME_SIZE),
+            inverse: planner.plan_fft_inverse(NS_FRAME_SIZE),
+            window,
+        }
+    }
+
+    /// Suppress noise in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        let frame_count = samples.len().saturating_sub(NS_FRAME_SIZE) / NS_HOP_SIZE + 1;
+        if samples.len() < NS_FRAME_SIZE || frame_count < NS_MIN_FRAMES {
+            return;
+        }
+
+        // Analysis: magnitude spectra of all frames
+        let mut spectra = Vec::with_capacity(frame_count);
+        let mut frame = self.forward.make_input_vec();
+        for index in 0..frame_count {
+            let start = index * NS_HOP_SIZE;
+            for (i, value) in frame.iter_mut().enumerate() {
+                *value = samples[start + i] * self.window[i];
+            }
+            let mut spectrum = self.forward.make_output_vec();
+            if let Err(e) = self.forward.process(&mut frame, &mut spectrum) {
+                tracing::warn!("Noise suppression FFT failed: {}", e);
+                return;
+            }
+            spectra.push(spectrum);
+        }
+
+        let noise_floor = estimate_noise_floor(&spectra);
+
+        // Synthesis: apply per-bin gains and overlap-add
+        let mut output = vec![0.0f32; samples.len()];
+        let mut previous_gains = vec![1.0f32; noise_floor.len()];
+        let mut time_frame = self.inverse.make_output_vec();
+        let scale = 1.0 / NS_FRAME_SIZE as f32;
+
+        for (index, spectrum) in spectra.iter_mut().enumerate() {
+            for (bin, value) in spectrum.iter_mut().enumerate() {
+                let magnitude = value.norm();
+                let raw_gain = if magnitude > 0.0 {
+                    (1.0 - NS_OVER_SUBTRACTION * noise_floor[bin] / magnitude).max(self.floor_gain)
+                } else {
+                    self.floor_gain
+                };
+                // Smooth over time to avoid "musical noise" artifacts
+                let gain = 0.5 * previous_gains[bin] + 0.5 * raw_gain;
+                previous_gains[bin] = gain;
+                *value *= gain;
+            }
+            // DC and Nyquist bins must stay real for the inverse transform
+            if let Some(first) = spectrum.first_mut() {
+                first.im = 0.0;
+            }
+            if let Some(last) = spectrum.last_mut() {
+                last.im = 0.0;
+            }
+
+            if let Err(e) = self.inverse.process(spectrum, &mut time_frame) {
+                tracing::warn!("Noise suppression inverse FFT failed: {}", e);
+                return;
+            }
+
+            let start = index * NS_HOP_SIZE;
+            for (i, value) in time_frame.iter().enumerate() {
+                output[start + i] += value * scale;
+            }
+        }
+
+        // Hann windows at 50% overlap sum to one, except in the first and last
+        // half-frames which only receive one window; keep the input there.
+        let covered_end = (frame_count - 1) * NS_HOP_SIZE + NS_FRAME_SIZE;
+        samples[NS_HOP_SIZE..covered_end - NS_HOP_SIZE]
+            .copy_from_slice(&output[NS_HOP_SIZE..covered_end - NS_HOP_SIZE]);
+    }
+}
+
+/// Estimate the noise magnitude of each frequency bin
+fn estimate_noise_floor(spectra: &[Vec<Complex<f32>>]) -> Vec<f32> {
+    let bins = spectra.first().map(|s| s.len()).unwrap_or(0);
+    let percentile_index = ((spectra.len() as f32 * NS_NOISE_PERCENTILE) as usize)
+        .min(spectra.len().saturating_sub(1));
+
+    let mut magnitudes = Vec::with_capacity(spectra.len());
+    (0..bins)
+        .map(|bin| {
+            magnitudes.clear();
+            magnitudes.extend(spectra.iter().map(|s| s[bin].norm()));
+            magnitudes.sort_by(|a, b| a.total_cmp(b));
+            magnitudes[percentile_index]
+        })
+        .collect()
+}
+
+/// Automatic gain control
+///
+/// Tracks the RMS level in 10ms frames and smoothly moves the gain towards the
+/// target level, reacting quickly to loud input and slowly to quiet input.
+pub struct AutomaticGainControl {
+    target_rms: f32,
+    max_gain: f32,
+    gain: f32,
+}
+
+impl AutomaticGainControl {
+    /// Create an AGC with a target level and maximum gain in dB
+    pub fn new(target_dbfs: f32, max_gain_db: f32) -> Self {
+        Self {
+            target_rms: db_to_linear(target_dbfs.min(0.0)),
+            max_gain: db_to_linear(max_gain_db.max(0.0)),
+            gain: 1.0,
+        }
+    }
+
+    /// Current gain (linear)
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Apply gain in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        let silence = db_to_linear(AGC_SILENCE_DBFS);
+
+        for frame in samples.chunks_mut(AGC_FRAME_SIZE) {
+            let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+
+            // Hold the gain during silence so background noise isn't pumped up
+            let desired = if rms > silence {
+                (self.target_rms / rms).clamp(1.0 / self.max_gain, self.max_gain)
+            } else {
+                self.gain
+            };
+
+            // Fast attack when the signal gets louder, slow release otherwise
+            let coefficient = if desired < self.gain { 0.5 } else { 0.05 };
+            let start_gain = self.gain;
+            let end_gain = start_gain + (desired - start_gain) * coefficient;
+
+            let len = frame.len() as f32;
+            for (i, sample) in frame.iter_mut().enumerate() {
+                let gain = start_gain + (end_gain - start_gain) * (i as f32 / len);
+                *sample = (*sample * gain).clamp(-1.0, 1.0);
+            }
+            self.gain = end_gain;
+        }
+    }
+}
+
+/// Convert decibels to a linear amplitude factor
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn sine(freq: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    /// Deterministic white noise in [-amplitude, amplitude]
+    fn noise(amplitude: f32, len: usize) -> Vec<f32> {
+        let mut state: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                amplitude * ((state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn enabled_config() -> DspConfig {
+        DspConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_config_is_disabled() {
+        let config = DspConfig::default();
+        assert!(!config.enabled);
+        assert!(!DspChain::new(&config, RATE).is_active());
+    }
+
+    #[test]
+    fn test_config_deserializes_partial_json() {
+        let config: DspConfig = serde_json::from_str(r#"{"enabled": true}"#).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.high_pass_cutoff_hz, 80.0);
+    }
+
+    #[test]
+    fn test_disabled_chain_leaves_samples_untouched() {
+        let original = sine(440.0, 0.3, 1600);
+        let mut samples = original.clone();
+        preprocess(&mut samples, &DspConfig::default(), RATE);
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn test_high_pass_removes_dc_offset() {
+        let mut samples: Vec<f32> = sine(1000.0, 0.2, RATE as usize)
+            .into_iter()
+            .map(|s| s + 0.5)
+            .collect();
+        HighPassFilter::new(80.0, RATE).process(&mut samples);
+
+        // Ignore the filter's settling time
+        let settled = &samples[RATE as usize / 4..];
+        let mean = settled.iter().sum::<f32>() / settled.len() as f32;
+        assert!(mean.abs() < 0.01, "DC offset remained: {}", mean);
+    }
+
+    #[test]
+    fn test_high_pass_attenuates_rumble_but_keeps_speech_band() {
+        let mut rumble = sine(30.0, 0.5, RATE as usize);
+        let mut voice = sine(1000.0, 0.5, RATE as usize);
+        HighPassFilter::new(80.0, RATE).process(&mut rumble);
+        HighPassFilter::new(80.0, RATE).process(&mut voice);
+
+        let skip = RATE as usize / 4;
+        assert!(rms(&rumble[skip..]) < 0.2 * rms(&sine(30.0, 0.5, RATE as usize)));
+        assert!(rms(&voice[skip..]) > 0.95 * rms(&sine(1000.0, 0.5, RATE as usize)));
+    }
+
+    #[test]
+    fn test_noise_suppression_reduces_noise_only_sections() {
+        let len = RATE as usize * 2;
+        let background = noise(0.02, len);
+        let tone = sine(800.0, 0.3, len);
+
+        // First second noise only, second second tone over noise
+        let mut samples: Vec<f32> = background
+            .iter()
+            .zip(tone.iter())
+            .enumerate()
+            .map(|(i, (n, t))| if i < len / 2 { *n } else { n + t })
+            .collect();
+        let noise_before = rms(&samples[1000..len / 2 - 1000]);
+        let tone_before = rms(&samples[len / 2 + 1000..len - 1000]);
+
+        NoiseSuppressor::new(12.0).process(&mut samples);
+
+        let noise_after = rms(&samples[1000..len / 2 - 1000]);
+        let tone_after = rms(&samples[len / 2 + 1000..len - 1000]);
+
+        assert!(
+            noise_after < noise_before * 0.5,
+            "noise {} -> {}",
+            noise_before,
+            noise_after
+        );
+        assert!(
+            tone_after > tone_before * 0.8,
+            "tone {} -> {}",
+            tone_before,
+            tone_after
+        );
+    }
+
+    #[test]
+    fn test_noise_suppression_skips_short_buffers() {
+        let original = noise(0.1, NS_FRAME_SIZE * 2);
+        let mut samples = original.clone();
+        NoiseSuppressor::new(12.0).process(&mut samples);
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn test_agc_raises_quiet_speech_towards_target() {
+        let mut samples = sine(300.0, 0.01, RATE as usize * 3);
+        let mut agc = AutomaticGainControl::new(-20.0, 30.0);
+        agc.process(&mut samples);
+
+        let settled = rms(&samples[RATE as usize * 2..]);
+        let target = db_to_linear(-20.0);
+        assert!(
+            (settled - target).abs() < target * 0.2,
+            "settled RMS {} far from target {}",
+            settled,
+            target
+        );
+    }
+
+    #[test]
+    fn test_agc_respects_max_gain() {
+        let mut samples = sine(300.0, 0.001, RATE as usize * 3);
+        let mut agc = AutomaticGainControl::new(-10.0, 6.0);
+        agc.process(&mut samples);
+        assert!(agc.gain() <= db_to_linear(6.0) + 1e-4);
+    }
+
+    #[test]
+    fn test_agc_does_not_amplify_silence() {
+        let mut samples = vec![0.0; RATE as usize];
+        let mut agc = AutomaticGainControl::new(-20.0, 20.0);
+        agc.process(&mut samples);
+        assert_eq!(agc.gain(), 1.0);
+        assert!(samples.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_agc_output_stays_in_range() {
+        let mut samples = sine(300.0, 0.9, RATE as usize);
+        AutomaticGainControl::new(-3.0, 20.0).process(&mut samples);
+        assert!(samples.iter().all(|s| (-1.0..=1.0).contains(s)));
+    }
+
+    #[test]
+    fn test_full_chain_on_noisy_quiet_speech() {
+        let len = RATE as usize * 2;
+        let mut samples: Vec<f32> = sine(500.0, 0.02, len)
+            .iter()
+            .zip(noise(0.002, len).iter())
+            .zip(sine(50.0, 0.05, len).iter())
+            .map(|((speech, noise), hum)| speech + noise + hum + 0.1)
+            .collect();
+
+        preprocess(&mut samples, &enabled_config(), RATE);
+
+        let tail = &samples[len / 2..];
+        let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+        assert!(mean.abs() < 0.01, "DC offset remained: {}", mean);
+        // Quiet speech was brought up
+        assert!(rms(tail) > 0.05, "chain output too quiet: {}", rms(tail));
+    }
+}
@@ -0,0 +1,497 @@
+//! Minimal FLAC encoder for archiving recordings
+//!
+//! Encodes 16-bit mono audio using fixed linear predictors and Rice-coded
+//! residuals. The output is a standard FLAC stream, readable by any decoder
+//! including the symphonia decoder used for file transcription.
+
+use super::AudioError;
+use std::io::{Seek, SeekFrom, Write};
+
+/// Samples per FLAC frame
+const BLOCK_SIZE: usize = 4096;
+
+/// Bits per encoded sample
+const BITS_PER_SAMPLE: u32 = 16;
+
+/// Largest Rice parameter usable without the escape code
+const MAX_RICE_PARAM: u32 = 14;
+
+/// Highest fixed predictor order defined by the format
+const MAX_FIXED_ORDER: usize = 4;
+
+/// Offset of the STREAMINFO body (after "fLaC" and the block header)
+const STREAMINFO_OFFSET: u64 = 8;
+
+/// Length of the STREAMINFO body in bytes
+const STREAMINFO_LEN: u32 = 34;
+
+/// Streaming FLAC writer for mono audio
+pub struct FlacWriter<W: Write + Seek> {
+    writer: W,
+    sample_rate: u32,
+    pending: Vec<i32>,
+    total_samples: u64,
+    frame_number: u64,
+    min_frame_size: u32,
+    max_frame_size: u32,
+}
+
+impl<W: Write + Seek> FlacWriter<W> {
+    /// Start a new FLAC stream
+    pub fn new(mut writer: W, sample_rate: u32) -> Result<Self, AudioError> {
+        writer.write_all(b"fLaC")?;
+        // Placeholder STREAMINFO, rewritten by `finish` once totals are known
+        writer.write_all(&streaminfo_block(sample_rate, 0, 0, 0, 0))?;
+
+        Ok(Self {
+            writer,
+            sample_rate,
+            pending: Vec::with_capacity(BLOCK_SIZE),
+            total_samples: 0,
+            frame_number: 0,
+            min_frame_size: u32::MAX,
+            max_frame_size: 0,
+        })
+    }
+
+    /// Append f32 samples in [-1.0, 1.0]
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        for &sample in samples {
+            self.pending
+                .push((sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i32);
+            if self.pending.len() == BLOCK_SIZE {
+                self.flush_block()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the final frame, patch STREAMINFO and return the inner writer
+    pub fn finish(mut self) -> Result<W, AudioError> {
+        if !self.pending.is_empty() {
+            self.flush_block()?;
+        }
+
+        let block_size = if self.total_samples >= BLOCK_SIZE as u64 {
+            BLOCK_SIZE as u16
+        } else {
+            (self.total_samples as u16).max(16)
+        };
+        let min_frame_size = if self.frame_number == 0 {
+            0
+        } else {
+            self.min_frame_size
+        };
+
+        self.writer.seek(SeekFrom::Start(STREAMINFO_OFFSET - 4))?;
+        self.writer.write_all(&streaminfo_block(
+            self.sample_rate,
+            block_size,
+            min_frame_size,
+            self.max_frame_size,
+            self.total_samples,
+        ))?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    fn flush_block(&mut self) -> Result<(), AudioError> {
+        let frame = encode_frame(&self.pending, self.frame_number, self.sample_rate);
+        self.writer.write_all(&frame)?;
+
+        let frame_size = frame.len() as u32;
+        self.min_frame_size = self.min_frame_size.min(frame_size);
+        self.max_frame_size = self.max_frame_size.max(frame_size);
+        self.total_samples += self.pending.len() as u64;
+        self.frame_number += 1;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+/// Build the STREAMINFO metadata block (header + body)
+fn streaminfo_block(
+    sample_rate: u32,
+    block_size: u16,
+    min_frame_size: u32,
+    max_frame_size: u32,
+    total_samples: u64,
+) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    // Last metadata block, type 0 (STREAMINFO)
+    bits.write(1, 1);
+    bits.write(0, 7);
+    bits.write(STREAMINFO_LEN as u64, 24);
+
+    bits.write(block_size as u64, 16);
+    bits.write(block_size as u64, 16);
+    bits.write(min_frame_size as u64, 24);
+    bits.write(max_frame_size as u64, 24);
+    bits.write(sample_rate as u64, 20);
+    bits.write(0, 3); // channels - 1
+    bits.write((BITS_PER_SAMPLE - 1) as u64, 5);
+    bits.write(total_samples >> 32, 4);
+    bits.write(total_samples & 0xFFFF_FFFF, 32);
+    // MD5 signature unknown
+    for _ in 0..4 {
+        bits.write(0, 32);
+    }
+    bits.into_bytes()
+}
+
+/// Encode one frame of samples
+fn encode_frame(samples: &[i32], frame_number: u64, sample_rate: u32) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+
+    // Sync code, reserved bit, fixed-blocksize stream
+    bits.write(0xFFF8, 16);
+
+    let block_size_code = if samples.len() == BLOCK_SIZE {
+        0b1100
+    } else {
+        0b0111
+    };
+    bits.write(block_size_code, 4);
+    bits.write(sample_rate_code(sample_rate), 4);
+    bits.write(0, 4); // mono
+    bits.write(0b100, 3); // 16 bits per sample
+    bits.write(0, 1);
+    write_utf8_number(&mut bits, frame_number);
+    if block_size_code == 0b0111 {
+        bits.write((samples.len() - 1) as u64, 16);
+    }
+    let header_crc = crc8(bits.bytes());
+    bits.write(header_crc as u64, 8);
+
+    encode_subframe(&mut bits, samples);
+    bits.align();
+
+    let frame_crc = crc16(bits.bytes());
+    bits.write(frame_crc as u64, 16);
+    bits.into_bytes()
+}
+
+/// Sample rate code for the frame header (0 = read from STREAMINFO)
+fn sample_rate_code(sample_rate: u32) -> u64 {
+    match sample_rate {
+        8000 => 0b0100,
+        16000 => 0b0101,
+        22050 => 0b0110,
+        24000 => 0b0111,
+        32000 => 0b1000,
+        44100 => 0b1001,
+        48000 => 0b1010,
+        96000 => 0b1011,
+        _ => 0,
+    }
+}
+
+/// Write a frame number using FLAC's UTF-8-like variable length coding
+fn write_utf8_number(bits: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        bits.write(value, 8);
+        return;
+    }
+
+    // Payload bits available for 2..=7 byte encodings
+    let len = (2..=7u32)
+        .find(|len| value < 1u64 << (5 * len + 1))
+        .unwrap_or(7);
+    let first_payload_bits = 7 - len;
+    let prefix = (0xFFu64 << (8 - len)) & 0xFF;
+    bits.write(prefix | (value >> (6 * (len - 1))), 8);
+    debug_assert!(value >> (6 * (len - 1)) < 1 << first_payload_bits);
+    for i in (0..len - 1).rev() {
+        bits.write(0x80 | ((value >> (6 * i)) & 0x3F), 8);
+    }
+}
+
+/// Encode a subframe choosing between constant, fixed predictor and verbatim
+fn encode_subframe(bits: &mut BitWriter, samples: &[i32]) {
+    if samples.iter().all(|&s| s == samples[0]) {
+        bits.write(0, 1);
+        bits.write(0b000000, 6);
+        bits.write(0, 1);
+        bits.write(samples[0] as u64 & 0xFFFF, BITS_PER_SAMPLE);
+        return;
+    }
+
+    let max_order = MAX_FIXED_ORDER.min(samples.len() - 1);
+    let (order, residuals) = (0..=max_order)
+        .map(|order| (order, fixed_residuals(samples, order)))
+        .min_by_key(|(_, residuals)| {
+            residuals
+                .iter()
+                .map(|r| r.unsigned_abs() as u64)
+                .sum::<u64>()
+        })
+        .unwrap_or_else(|| (0, fixed_residuals(samples, 0)));
+
+    let (rice_param, rice_bits) = best_rice_param(&residuals);
+    let fixed_bits = 8 + order as u64 * BITS_PER_SAMPLE as u64 + 10 + rice_bits;
+    let verbatim_bits = 8 + samples.len() as u64 * BITS_PER_SAMPLE as u64;
+
+    bits.write(0, 1);
+    if fixed_bits >= verbatim_bits {
+        bits.write(0b000001, 6);
+        bits.write(0, 1);
+        for &sample in samples {
+            bits.write(sample as u64 & 0xFFFF, BITS_PER_SAMPLE);
+        }
+        return;
+    }
+
+    bits.write(0b001000 | order as u64, 6);
+    bits.write(0, 1);
+    for &sample in &samples[..order] {
+        bits.write(sample as u64 & 0xFFFF, BITS_PER_SAMPLE);
+    }
+
+    // Rice coding, partition order 0
+    bits.write(0b00, 2);
+    bits.write(0, 4);
+    bits.write(rice_param as u64, 4);
+    for &residual in &residuals {
+        let folded = fold(residual);
+        bits.write_unary(folded >> rice_param);
+        bits.write(folded as u64, rice_param);
+    }
+}
+
+/// Residuals of a fixed polynomial predictor
+fn fixed_residuals(samples: &[i32], order: usize) -> Vec<i32> {
+    (order..samples.len())
+        .map(|i| {
+            let s = |k: usize| samples[i - k] as i64;
+            let residual = match order {
+                0 => s(0),
+                1 => s(0) - s(1),
+                2 => s(0) - 2 * s(1) + s(2),
+                3 => s(0) - 3 * s(1) + 3 * s(2) - s(3),
+                _ => s(0) - 4 * s(1) + 6 * s(2) - 4 * s(3) + s(4),
+            };
+            residual as i32
+        })
+        .collect()
+}
+
+/// Map signed residuals to unsigned values (0, -1, 1, -2, ... -> 0, 1, 2, 3, ...)
+fn fold(residual: i32) -> u32 {
+    ((residual << 1) ^ (residual >> 31)) as u32
+}
+
+/// Find the Rice parameter with the smallest encoded size
+fn best_rice_param(residuals: &[i32]) -> (u32, u64) {
+    (0..=MAX_RICE_PARAM)
+        .map(|param| {
+            let size = residuals
+                .iter()
+                .map(|&r| (fold(r) >> param) as u64 + 1 + param as u64)
+                .sum::<u64>();
+            (param, size)
+        })
+        .min_by_key(|(_, size)| *size)
+        .unwrap_or((0, 0))
+}
+
+/// CRC-8 (polynomial 0x07) over frame header bytes
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// CRC-16 (polynomial 0x8005) over frame bytes
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// MSB-first bit writer
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    /// Write the low `count` bits of `value` (count <= 32)
+    fn write(&mut self, value: u64, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let mask = (1u64 << count) - 1;
+        self.acc = (self.acc << count) | (value & mask);
+        self.bits += count;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+        self.acc &= (1u64 << self.bits) - 1;
+    }
+
+    /// Write `count` zero bits followed by a one bit
+    fn write_unary(&mut self, mut count: u32) {
+        while count >= 32 {
+            self.write(0, 32);
+            count -= 32;
+        }
+        self.write(1, count + 1);
+    }
+
+    /// Pad with zero bits to the next byte boundary
+    fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+
+    /// Completed bytes written so far
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    fn encode(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+        let mut writer = FlacWriter::new(Cursor::new(Vec::new()), sample_rate).unwrap();
+        writer.write_samples(samples).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn decode(bytes: Vec<u8>) -> (u32, Vec<f32>) {
+        let source = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension("flac");
+        let probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                source,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .unwrap();
+        let mut format = probed.format;
+        let track = format.default_track().unwrap().clone();
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions { verify: true })
+            .unwrap();
+
+        let mut samples = Vec::new();
+        while let Ok(packet) = format.next_packet() {
+            let decoded = decoder.decode(&packet).unwrap();
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+            buffer.copy_interleaved_ref(decoded);
+            samples.extend_from_slice(buffer.samples());
+        }
+        (track.codec_params.sample_rate.unwrap(), samples)
+    }
+
+    fn tone(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| 0.4 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_roundtrip_matches_input() {
+        // Not a multiple of the block size, so the last frame is short
+        let input = tone(BLOCK_SIZE * 3 + 1234);
+        let (sample_rate, output) = decode(encode(&input, 16000));
+
+        assert_eq!(sample_rate, 16000);
+        assert_eq!(output.len(), input.len());
+        for (a, b) in input.iter().zip(output.iter()) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_tone_compresses_below_pcm_size() {
+        let input = tone(16000 * 2);
+        let encoded = encode(&input, 16000);
+        // Less than half the size of 16-bit PCM
+        assert!(encoded.len() < input.len());
+    }
+
+    #[test]
+    fn test_silence_and_noise_roundtrip() {
+        let mut input = vec![0.0f32; BLOCK_SIZE];
+        let mut state: u32 = 42;
+        input.extend((0..BLOCK_SIZE).map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0
+        }));
+        let (_, output) = decode(encode(&input, 16000));
+        assert_eq!(output.len(), input.len());
+        assert!(output[..BLOCK_SIZE].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_short_stream() {
+        let input = tone(100);
+        let (_, output) = decode(encode(&input, 16000));
+        assert_eq!(output.len(), 100);
+    }
+
+    #[test]
+    fn test_utf8_frame_numbers() {
+        for value in [0u64, 0x7F, 0x80, 0x7FF, 0x800, 0xFFFF, 0x10000, 0x1F_FFFF] {
+            let mut bits = BitWriter::default();
+            write_utf8_number(&mut bits, value);
+            let bytes = bits.into_bytes();
+            let decoded = if bytes.len() == 1 {
+                bytes[0] as u64
+            } else {
+                let mut v = (bytes[0] & (0x7F >> bytes.len())) as u64;
+                for b in &bytes[1..] {
+                    v = (v << 6) | (b & 0x3F) as u64;
+                }
+                v
+            };
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn test_crc_known_values() {
+        // Check values for the ASCII string "123456789"
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xFEE8);
+    }
+}
@@ -6,7 +6,10 @@ use std::path::Path;
 
 pub mod capture;
 pub mod chunking;
+pub mod dsp;
+pub mod flac;
 pub mod processing;
+pub mod spool;
 
 #[cfg(test)]
 mod level_test;
@@ -25,6 +28,7 @@ pub fn is_supported_format(path: &Path) -> bool {
 pub use capture::AudioCaptureService;
 pub use chunking::{AudioChunk, ChunkConfig, ChunkedAudioBuffer};
 pub use processing::{calculate_audio_level, resample_for_whisper, stereo_to_mono, AudioBuffer};
+pub use spool::{RecordedAudio, RecordingSpool, WindowSender};
 
 use thiserror::Error;
 
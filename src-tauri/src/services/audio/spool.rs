@@ -0,0 +1,768 @@
+//! Disk-backed recording storage
+//!
+//! Keeps short recordings in memory and spills long ones to temporary WAV
+//! files so that lectures and meetings can be recorded without a duration cap.
+//! Spilled audio is written by a background thread, so the audio callback
+//! never waits on the disk, and rolls over to a new file every
+//! [`SEGMENT_SAMPLES`] to stay clear of the 4 GiB WAV size limit. The writer
+//! can also hand out each transcription window as soon as it is recorded,
+//! so long recordings are transcribed while they continue.
+
+use super::processing::{resample_for_whisper, AudioBuffer, WHISPER_SAMPLE_RATE};
+use super::AudioError;
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Seconds of audio kept in memory before the recording spills to disk
+pub const DEFAULT_SPILL_THRESHOLD_SECS: u32 = 60;
+
+/// Window size in seconds used when transcribing spilled recordings
+pub const TRANSCRIPTION_WINDOW_SECS: u32 = 120;
+
+/// Seconds each transcription window shares with the previous one, so that
+/// words cut at one window's edge are heard whole in the other
+pub const TRANSCRIPTION_WINDOW_OVERLAP_SECS: u32 = 5;
+
+/// Samples per spill file (1 GiB of 32-bit floats, ~93 minutes at 48kHz)
+pub const SEGMENT_SAMPLES: u64 = 1 << 28;
+
+/// Receives the transcription windows of a recording in progress
+pub type WindowSender = UnboundedSender<AudioBuffer>;
+
+/// Recording sink that starts in memory and spills to temp WAV files
+pub struct RecordingSpool {
+    sample_rate: u32,
+    /// Samples kept in memory until the spill threshold is reached
+    memory: Vec<f32>,
+    /// Number of in-memory samples allowed before spilling
+    spill_threshold: usize,
+    /// Samples per spill file
+    segment_samples: u64,
+    /// Background writer once the recording has spilled
+    writer: Option<SpillWriter>,
+    /// Total number of samples pushed since the last reset
+    total_samples: u64,
+    /// Where to send transcription windows once the recording spills
+    window_sender: Option<WindowSender>,
+}
+
+impl RecordingSpool {
+    /// Create a spool with a custom spill threshold in samples
+    pub fn new(sample_rate: u32, spill_threshold: usize) -> Self {
+        Self {
+            sample_rate,
+            memory: Vec::new(),
+            spill_threshold,
+            segment_samples: SEGMENT_SAMPLES,
+            writer: None,
+            total_samples: 0,
+            window_sender: None,
+        }
+    }
+
+    /// Create a spool with the default spill threshold
+    pub fn with_defaults(sample_rate: u32) -> Self {
+        Self::new(
+            sample_rate,
+            sample_rate as usize * DEFAULT_SPILL_THRESHOLD_SECS as usize,
+        )
+    }
+
+    /// Discard any previous recording and prepare for a new one
+    pub fn reset(&mut self) {
+        self.memory = Vec::new();
+        self.total_samples = 0;
+        self.window_sender = None;
+        if let Some(writer) = self.writer.take() {
+            remove_files(&writer.finish().unwrap_or_default());
+        }
+    }
+
+    /// Append mono samples, spilling to disk once the threshold is exceeded
+    pub fn push(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        self.total_samples += samples.len() as u64;
+
+        if let Some(writer) = self.writer.as_ref() {
+            return writer.send(samples.to_vec());
+        }
+
+        self.memory.extend_from_slice(samples);
+        if self.memory.len() > self.spill_threshold {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Send each transcription window of the current recording as soon as
+    /// the recording continues past it, with the bounds of
+    /// [`RecordedAudio::overlapping_whisper_windows`]. Only recordings that
+    /// spill are windowed, so nothing is sent before then; the sender is
+    /// dropped when the recording finishes.
+    pub fn watch_windows(&mut self, sender: WindowSender) {
+        if self.is_spilled() {
+            tracing::warn!("Recording already spilled, not watching its windows");
+            return;
+        }
+        self.window_sender = Some(sender);
+    }
+
+    /// Hand the in-memory samples to a spill writer and keep sending there
+    fn spill(&mut self) -> Result<(), AudioError> {
+        let windows = self
+            .window_sender
+            .take()
+            .map(|sender| WindowTap::new(sender, self.sample_rate));
+        let writer = SpillWriter::start(
+            spool_file_path(),
+            self.sample_rate,
+            self.segment_samples,
+            windows,
+        )?;
+        tracing::info!(
+            "Recording exceeded {} in-memory samples, spilling to {:?}",
+            self.spill_threshold,
+            writer.base
+        );
+
+        // Release the in-memory copy so long recordings don't hold both
+        writer.send(std::mem::take(&mut self.memory))?;
+        self.writer = Some(writer);
+        Ok(())
+    }
+
+    /// Whether the recording has been moved to disk
+    pub fn is_spilled(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Total number of samples recorded
+    pub fn len(&self) -> u64 {
+        self.total_samples
+    }
+
+    /// Check if nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.total_samples == 0
+    }
+
+    /// Sample rate of the recorded audio
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Finish the recording, handing ownership of the audio to the caller
+    pub fn finish(&mut self) -> Result<RecordedAudio, AudioError> {
+        let total_samples = std::mem::take(&mut self.total_samples);
+        self.window_sender = None;
+
+        match self.writer.take() {
+            Some(writer) => Ok(RecordedAudio::File {
+                paths: writer.finish()?,
+                sample_rate: self.sample_rate,
+                total_samples,
+                segment_samples: self.segment_samples,
+            }),
+            None => Ok(RecordedAudio::Memory(AudioBuffer::new(
+                std::mem::take(&mut self.memory),
+                self.sample_rate,
+            ))),
+        }
+    }
+}
+
+impl Drop for RecordingSpool {
+    fn drop(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            remove_files(&writer.finish().unwrap_or_default());
+        }
+    }
+}
+
+/// Thread writing spilled samples to segment files
+struct SpillWriter {
+    /// Path of the first segment, later ones get a numbered suffix
+    base: PathBuf,
+    sender: mpsc::Sender<Vec<f32>>,
+    thread: JoinHandle<Result<Vec<PathBuf>, AudioError>>,
+}
+
+impl SpillWriter {
+    fn start(
+        base: PathBuf,
+        sample_rate: u32,
+        segment_samples: u64,
+        mut windows: Option<WindowTap>,
+    ) -> Result<Self, AudioError> {
+        let (sender, receiver) = mpsc::channel();
+        let thread_base = base.clone();
+        let thread = std::thread::Builder::new()
+            .name("recording-spool".to_string())
+            .spawn(move || {
+                let mut paths = Vec::new();
+                let result = write_segments(
+                    receiver,
+                    &thread_base,
+                    sample_rate,
+                    segment_samples,
+                    &mut paths,
+                    &mut windows,
+                );
+                match result {
+                    Ok(()) => Ok(paths),
+                    Err(e) => {
+                        tracing::error!("[AudioCapture] Spill writer failed: {}", e);
+                        remove_files(&paths);
+                        Err(e)
+                    }
+                }
+            })?;
+        Ok(Self {
+            base,
+            sender,
+            thread,
+        })
+    }
+
+    fn send(&self, samples: Vec<f32>) -> Result<(), AudioError> {
+        self.sender
+            .send(samples)
+            .map_err(|_| AudioError::StreamError("recording spill writer stopped".to_string()))
+    }
+
+    /// Write out what was sent and return the segment files
+    fn finish(self) -> Result<Vec<PathBuf>, AudioError> {
+        drop(self.sender);
+        self.thread
+            .join()
+            .map_err(|_| AudioError::StreamError("recording spill writer panicked".to_string()))?
+    }
+}
+
+/// Write everything received to segments of `segment_samples`, recording
+/// each file in `paths` as soon as it is created, and pass it on to the
+/// window tap
+fn write_segments(
+    receiver: mpsc::Receiver<Vec<f32>>,
+    base: &Path,
+    sample_rate: u32,
+    segment_samples: u64,
+    paths: &mut Vec<PathBuf>,
+    windows: &mut Option<WindowTap>,
+) -> Result<(), AudioError> {
+    let mut writer: Option<WavWriter<BufWriter<File>>> = None;
+    let mut written = 0u64;
+
+    for samples in receiver {
+        if windows.as_mut().is_some_and(|tap| !tap.push(&samples)) {
+            *windows = None;
+        }
+        let mut rest = samples.as_slice();
+        while !rest.is_empty() {
+            if writer.is_none() || written == segment_samples {
+                if let Some(full) = writer.take() {
+                    full.finalize()?;
+                }
+                let path = segment_path(base, paths.len());
+                paths.push(path.clone());
+                writer = Some(WavWriter::create(&path, spool_spec(sample_rate))?);
+                written = 0;
+            }
+            let count = rest.len().min((segment_samples - written) as usize);
+            if let Some(writer) = writer.as_mut() {
+                for sample in &rest[..count] {
+                    writer.write_sample(*sample)?;
+                }
+            }
+            written += count as u64;
+            rest = &rest[count..];
+        }
+    }
+
+    if let Some(writer) = writer {
+        writer.finalize()?;
+    }
+    Ok(())
+}
+
+/// Cuts the samples passing through the spill writer into transcription
+/// windows
+struct WindowTap {
+    sender: WindowSender,
+    sample_rate: u32,
+    window_len: usize,
+    /// Distance between window starts
+    step: usize,
+    /// Samples from the start of the next window on
+    buffer: Vec<f32>,
+}
+
+impl WindowTap {
+    fn new(sender: WindowSender, sample_rate: u32) -> Self {
+        let window_len = (sample_rate as usize * TRANSCRIPTION_WINDOW_SECS as usize).max(1);
+        let overlap_len = sample_rate as usize * TRANSCRIPTION_WINDOW_OVERLAP_SECS as usize;
+        Self {
+            sender,
+            sample_rate,
+            window_len,
+            step: window_len.saturating_sub(overlap_len).max(1),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Add samples, sending each window the recording has continued past.
+    /// The window ending the recording is left for when it stops. Returns
+    /// false once nobody is listening.
+    fn push(&mut self, samples: &[f32]) -> bool {
+        self.buffer.extend_from_slice(samples);
+        while self.buffer.len() > self.window_len {
+            let window = self.buffer[..self.window_len].to_vec();
+            self.buffer.drain(..self.step);
+            if self
+                .sender
+                .send(AudioBuffer::new(window, self.sample_rate))
+                .is_err()
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Path of the spill segment with this index
+fn segment_path(base: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return base.to_path_buf();
+    }
+    base.with_extension(format!("{}.wav", index))
+}
+
+fn remove_files(paths: &[PathBuf]) {
+    for path in paths {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// A finished recording, either held in memory or stored in a spill file
+#[derive(Debug)]
+pub enum RecordedAudio {
+    /// Short recording kept entirely in memory
+    Memory(AudioBuffer),
+    /// Long recording stored in temporary WAV segments (removed on drop)
+    File {
+        paths: Vec<PathBuf>,
+        sample_rate: u32,
+        total_samples: u64,
+        /// Samples per segment, all but the last segment are full
+        segment_samples: u64,
+    },
+}
+
+impl RecordedAudio {
+    /// Sample rate of the recorded audio
+    pub fn sample_rate(&self) -> u32 {
+        match self {
+            RecordedAudio::Memory(buffer) => buffer.sample_rate,
+            RecordedAudio::File { sample_rate, .. } => *sample_rate,
+        }
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> u64 {
+        match self {
+            RecordedAudio::Memory(buffer) => buffer.samples.len() as u64,
+            RecordedAudio::File { total_samples, .. } => *total_samples,
+        }
+    }
+
+    /// Check if the recording is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the recording was spilled to disk
+    pub fn is_spilled(&self) -> bool {
+        matches!(self, RecordedAudio::File { .. })
+    }
+
+    /// Get duration in seconds
+    pub fn duration_secs(&self) -> f32 {
+        let sample_rate = self.sample_rate();
+        if sample_rate == 0 {
+            return 0.0;
+        }
+        self.len() as f32 / sample_rate as f32
+    }
+
+    /// Read a range of samples at the recording's sample rate
+    pub fn read_range(&self, start: u64, len: usize) -> Result<Vec<f32>, AudioError> {
+        match self {
+            RecordedAudio::Memory(buffer) => {
+                let start = (start as usize).min(buffer.samples.len());
+                let end = start.saturating_add(len).min(buffer.samples.len());
+                Ok(buffer.samples[start..end].to_vec())
+            }
+            RecordedAudio::File {
+                paths,
+                total_samples,
+                segment_samples,
+                ..
+            } => {
+                let end = start.saturating_add(len as u64).min(*total_samples);
+                let mut samples = Vec::with_capacity(end.saturating_sub(start) as usize);
+                let mut position = start;
+                while position < end {
+                    let index = (position / segment_samples) as usize;
+                    let offset = position % segment_samples;
+                    let count = (end - position).min(segment_samples - offset) as usize;
+                    let Some(path) = paths.get(index) else {
+                        break;
+                    };
+                    let read = read_wav_range(path, offset, count)?;
+                    if read.is_empty() {
+                        break;
+                    }
+                    position += read.len() as u64;
+                    samples.extend(read);
+                }
+                Ok(samples)
+            }
+        }
+    }
+
+    /// Load the whole recording resampled to 16kHz
+    ///
+    /// Prefer [`RecordedAudio::whisper_windows`] for spilled recordings to
+    /// avoid loading hours of audio into memory at once.
+    pub fn to_whisper_samples(&self) -> Result<Vec<f32>, AudioError> {
+        match self {
+            RecordedAudio::Memory(buffer) => resample_for_whisper(buffer.clone()),
+            RecordedAudio::File { sample_rate, .. } => {
+                let samples = self.read_range(0, self.len() as usize)?;
+                resample_for_whisper(AudioBuffer::new(samples, *sample_rate))
+            }
+        }
+    }
+
+    /// Iterate over the recording in fixed-size windows resampled to 16kHz
+    pub fn whisper_windows(&self, window_secs: u32) -> WhisperWindows<'_> {
+        self.overlapping_whisper_windows(window_secs, 0)
+    }
+
+    /// Like [`RecordedAudio::whisper_windows`], but each window repeats the
+    /// last `overlap_secs` of the one before
+    pub fn overlapping_whisper_windows(
+        &self,
+        window_secs: u32,
+        overlap_secs: u32,
+    ) -> WhisperWindows<'_> {
+        let window_len = (self.sample_rate() as usize * window_secs as usize).max(1);
+        let overlap_len = self.sample_rate() as usize * overlap_secs as usize;
+        WhisperWindows {
+            recording: self,
+            window_len,
+            step: window_len.saturating_sub(overlap_len).max(1) as u64,
+            position: 0,
+        }
+    }
+
+    /// Write the recording to a 16kHz WAV file, one window at a time
+    pub fn write_whisper_wav(&self, path: &Path) -> Result<(), AudioError> {
+        let mut writer = WavWriter::create(path, spool_spec(WHISPER_SAMPLE_RATE))?;
+        for window in self.whisper_windows(TRANSCRIPTION_WINDOW_SECS) {
+            for sample in window? {
+                writer.write_sample(sample)?;
+            }
+        }
+        writer.finalize()?;
+        Ok(())
+    }
+}
+
+impl Drop for RecordedAudio {
+    fn drop(&mut self) {
+        if let RecordedAudio::File { paths, .. } = self {
+            remove_files(paths);
+        }
+    }
+}
+
+/// Iterator over 16kHz windows of a recording
+pub struct WhisperWindows<'a> {
+    recording: &'a RecordedAudio,
+    window_len: usize,
+    /// Distance between window starts, less than the length when overlapping
+    step: u64,
+    position: u64,
+}
+
+impl WhisperWindows<'_> {
+    /// Skip the first `count` windows without reading them
+    pub fn skip_windows(mut self, count: usize) -> Self {
+        self.position = (self.step * count as u64).min(self.recording.len());
+        self
+    }
+}
+
+impl Iterator for WhisperWindows<'_> {
+    type Item = Result<Vec<f32>, AudioError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.recording.len() {
+            return None;
+        }
+
+        let samples = match self.recording.read_range(self.position, self.window_len) {
+            Ok(s) => s,
+            Err(e) => {
+                // Stop iterating after an IO error
+                self.position = self.recording.len();
+                return Some(Err(e));
+            }
+        };
+        self.position = if self.position + samples.len() as u64 >= self.recording.len() {
+            self.recording.len()
+        } else {
+            self.position + self.step
+        };
+
+        Some(resample_for_whisper(AudioBuffer::new(
+            samples,
+            self.recording.sample_rate(),
+        )))
+    }
+}
+
+/// WAV format used for spill files (mono 32-bit float)
+fn spool_spec(sample_rate: u32) -> WavSpec {
+    WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    }
+}
+
+/// Generate a unique path for a spill file in the temp directory
+fn spool_file_path() -> PathBuf {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!("ez_flow_spool_{}.wav", timestamp))
+}
+
+/// Read `len` samples starting at `start` from a mono float WAV file
+fn read_wav_range(path: &Path, start: u64, len: usize) -> Result<Vec<f32>, AudioError> {
+    let mut reader = WavReader::open(path)?;
+    let total = reader.duration() as u64;
+    if start >= total {
+        return Ok(Vec::new());
+    }
+
+    reader.seek(start as u32)?;
+    let samples = reader
+        .samples::<f32>()
+        .take(len)
+        .collect::<Result<Vec<f32>, _>>()?;
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(len: usize) -> Vec<f32> {
+        (0..len).map(|i| (i % 100) as f32 / 100.0).collect()
+    }
+
+    #[test]
+    fn test_short_recording_stays_in_memory() {
+        let mut spool = RecordingSpool::new(16000, 1000);
+        spool.push(&ramp(500)).unwrap();
+        assert!(!spool.is_spilled());
+
+        let recording = spool.finish().unwrap();
+        assert!(!recording.is_spilled());
+        assert_eq!(recording.len(), 500);
+    }
+
+    #[test]
+    fn test_long_recording_spills_to_disk() {
+        let mut spool = RecordingSpool::new(16000, 1000);
+        spool.push(&ramp(800)).unwrap();
+        spool.push(&ramp(800)).unwrap();
+        assert!(spool.is_spilled());
+        assert_eq!(spool.len(), 1600);
+
+        let recording = spool.finish().unwrap();
+        assert!(recording.is_spilled());
+        assert_eq!(recording.len(), 1600);
+
+        // Samples written before and after the spill are preserved in order
+        let head = recording.read_range(0, 800).unwrap();
+        assert_eq!(head, ramp(800));
+        let tail = recording.read_range(800, 800).unwrap();
+        assert_eq!(tail, ramp(800));
+    }
+
+    #[test]
+    fn test_spill_file_removed_on_drop() {
+        let mut spool = RecordingSpool::new(16000, 10);
+        spool.push(&ramp(100)).unwrap();
+        let recording = spool.finish().unwrap();
+
+        let path = match &recording {
+            RecordedAudio::File { paths, .. } => paths[0].clone(),
+            RecordedAudio::Memory(_) => panic!("expected spilled recording"),
+        };
+        assert!(path.exists());
+
+        drop(recording);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_reset_discards_spill_file() {
+        let mut spool = RecordingSpool::new(16000, 10);
+        spool.push(&ramp(100)).unwrap();
+        let path = spool.writer.as_ref().unwrap().base.clone();
+        // The writer thread creates the file shortly after the spill
+        for _ in 0..100 {
+            if path.exists() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(path.exists());
+
+        spool.reset();
+        assert!(!path.exists());
+        assert!(spool.is_empty());
+        assert!(!spool.is_spilled());
+    }
+
+    #[test]
+    fn test_whisper_windows_cover_recording() {
+        let mut spool = RecordingSpool::new(16000, 16000);
+        // 5 seconds of audio, spilled after 1 second
+        spool.push(&vec![0.1; 80000]).unwrap();
+        let recording = spool.finish().unwrap();
+
+        let windows: Vec<Vec<f32>> = recording
+            .whisper_windows(2)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(windows.len(), 3);
+        let total: usize = windows.iter().map(|w| w.len()).sum();
+        assert_eq!(total, 80000);
+    }
+
+    #[test]
+    fn test_spill_rolls_over_to_segments() {
+        let mut spool = RecordingSpool::new(16000, 10);
+        spool.segment_samples = 300;
+        for _ in 0..10 {
+            spool.push(&ramp(100)).unwrap();
+        }
+        let recording = spool.finish().unwrap();
+
+        let paths = match &recording {
+            RecordedAudio::File { paths, .. } => paths.clone(),
+            RecordedAudio::Memory(_) => panic!("expected spilled recording"),
+        };
+        assert_eq!(paths.len(), 4);
+        assert_eq!(recording.len(), 1000);
+
+        // Ranges spanning a segment boundary read across both files
+        assert_eq!(
+            recording.read_range(250, 100).unwrap(),
+            ramp(100)[50..]
+                .iter()
+                .chain(&ramp(50))
+                .copied()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(recording.read_range(0, 1000).unwrap().len(), 1000);
+        assert_eq!(recording.read_range(950, 100).unwrap().len(), 50);
+
+        drop(recording);
+        assert!(paths.iter().all(|p| !p.exists()));
+    }
+
+    #[test]
+    fn test_overlapping_windows() {
+        let recording = RecordedAudio::Memory(AudioBuffer::new(ramp(72000), 16000));
+        let windows: Vec<Vec<f32>> = recording
+            .overlapping_whisper_windows(2, 1)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        // Windows start every second and the last one ends the recording
+        assert_eq!(windows.len(), 4);
+        assert!(windows[..3].iter().all(|w| w.len() == 32000));
+        assert_eq!(windows[3].len(), 24000);
+        assert_eq!(windows[0][16000..], windows[1][..16000]);
+    }
+
+    #[test]
+    fn test_watched_windows_match_recording() {
+        // At 100Hz a window is 12000 samples and they start 11500 apart
+        let mut spool = RecordingSpool::new(100, 1000);
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        spool.watch_windows(sender);
+        for _ in 0..30 {
+            spool.push(&ramp(1000)).unwrap();
+        }
+        let recording = spool.finish().unwrap();
+
+        let mut sent = Vec::new();
+        while let Ok(window) = receiver.try_recv() {
+            sent.push(window);
+        }
+        assert_eq!(sent.len(), 2);
+        for (index, window) in sent.iter().enumerate() {
+            assert_eq!(window.sample_rate, 100);
+            assert_eq!(
+                window.samples,
+                recording.read_range(index as u64 * 11500, 12000).unwrap()
+            );
+        }
+
+        // The rest of the recording is left to the last window
+        let rest = recording
+            .overlapping_whisper_windows(
+                TRANSCRIPTION_WINDOW_SECS,
+                TRANSCRIPTION_WINDOW_OVERLAP_SECS,
+            )
+            .skip_windows(sent.len());
+        assert_eq!(rest.position, 23000);
+        assert!(rest.position + rest.window_len as u64 >= recording.len());
+    }
+
+    #[test]
+    fn test_short_recording_sends_no_windows() {
+        let mut spool = RecordingSpool::new(100, 1000);
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        spool.watch_windows(sender);
+        spool.push(&ramp(12000)).unwrap();
+        let _recording = spool.finish().unwrap();
+
+        // A window reaching the end of the recording isn't sent
+        assert!(receiver.try_recv().is_err());
+        assert!(receiver.is_closed());
+    }
+
+    #[test]
+    fn test_read_range_past_end() {
+        let recording = RecordedAudio::Memory(AudioBuffer::new(ramp(10), 16000));
+        assert!(recording.read_range(20, 5).unwrap().is_empty());
+        assert_eq!(recording.read_range(8, 5).unwrap().len(), 2);
+    }
+}
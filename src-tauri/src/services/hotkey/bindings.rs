@@ -0,0 +1,314 @@
+//! Hotkey bindings
+//!
+//! Besides the main dictation hotkey, further hotkeys can be bound to other
+//! actions, such as dictating to the clipboard or translating to English.
+//! Each binding may name a dictation profile that overrides the model,
+//! language, prompt and sink for recordings it starts.
+
+use super::HotkeyError;
+use crate::models::Settings;
+use crate::services::sinks::DictationSink;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// What a hotkey does with its recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// Transcribe and deliver to the dictation sink
+    #[default]
+    Dictate,
+    /// Transcribe and only copy to the clipboard
+    Clipboard,
+    /// Transcribe and translate to English
+    Translate,
+    /// Run the spoken voice commands and drop any other text
+    Command,
+}
+
+/// Transcription overrides a binding can refer to by name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DictationProfile {
+    /// Name bindings refer to
+    pub name: String,
+    /// Model instead of the global model
+    pub model_id: Option<String>,
+    /// Spoken language instead of detecting it
+    pub language: Option<String>,
+    /// Context prompt instead of the global one
+    pub context_prompt: Option<String>,
+    /// Where transcripts go instead of the global sink
+    pub sink: Option<DictationSink>,
+}
+
+impl DictationProfile {
+    /// Whether recordings need a full transcription pass with their own
+    /// model or language, which streaming previews can't provide
+    pub fn overrides_decoding(&self) -> bool {
+        self.model_id.is_some() || self.language.is_some()
+    }
+}
+
+/// A hotkey bound to an action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyBinding {
+    /// Name shown in settings
+    pub name: String,
+    /// Shortcut string, e.g. "Ctrl+Alt+Space"
+    pub hotkey: String,
+    /// What happens with the recording
+    pub action: HotkeyAction,
+    /// Name of the dictation profile to use
+    pub profile: Option<String>,
+    /// Whether the hotkey is registered
+    pub enabled: bool,
+}
+
+impl Default for HotkeyBinding {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            hotkey: String::new(),
+            action: HotkeyAction::default(),
+            profile: None,
+            enabled: true,
+        }
+    }
+}
+
+impl HotkeyBinding {
+    /// The main dictation hotkey from `Settings::hotkey`
+    pub fn primary(hotkey: &str) -> Self {
+        Self {
+            name: "Dictate".to_string(),
+            hotkey: hotkey.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The dictation profile the binding refers to, if it exists
+    pub fn find_profile<'a>(
+        &self,
+        profiles: &'a [DictationProfile],
+    ) -> Option<&'a DictationProfile> {
+        let name = self.profile.as_deref()?.trim();
+        profiles.iter().find(|profile| profile.name.trim() == name)
+    }
+
+    /// Whether recordings need a full transcription pass, see
+    /// [`DictationProfile::overrides_decoding`]
+    pub fn overrides_decoding(&self, profiles: &[DictationProfile]) -> bool {
+        self.action == HotkeyAction::Translate
+            || self
+                .find_profile(profiles)
+                .is_some_and(DictationProfile::overrides_decoding)
+    }
+
+    /// Settings for a recording started by this binding
+    pub fn apply(&self, settings: Settings) -> Settings {
+        let mut settings = match self.find_profile(&settings.dictation_profiles).cloned() {
+            Some(profile) => settings.with_dictation_profile(&profile),
+            None => settings,
+        };
+        if self.action == HotkeyAction::Clipboard {
+            settings.dictation_sink = DictationSink::Clipboard;
+        }
+        settings
+    }
+}
+
+/// Canonical form of a shortcut string for comparing bindings: lowercase,
+/// modifier aliases unified and sorted, `KeyA`/`Digit1` reduced to the key
+pub fn normalize_hotkey(hotkey: &str) -> String {
+    let mut modifiers = Vec::new();
+    let mut keys = Vec::new();
+
+    for part in hotkey.split('+').map(str::trim).filter(|p| !p.is_empty()) {
+        let part = part.to_ascii_lowercase();
+        let modifier = match part.as_str() {
+            "ctrl" | "control" => "ctrl",
+            "shift" => "shift",
+            "alt" | "option" => "alt",
+            "super" | "cmd" | "command" | "meta" | "win" => "super",
+            "cmdorctrl" | "cmdorcontrol" | "commandorctrl" | "commandorcontrol" => {
+                if cfg!(target_os = "macos") {
+                    "super"
+                } else {
+                    "ctrl"
+                }
+            }
+            _ => {
+                let key = part
+                    .strip_prefix("key")
+                    .or_else(|| part.strip_prefix("digit"))
+                    .filter(|rest| rest.len() == 1)
+                    .map(str::to_string)
+                    .unwrap_or(part);
+                keys.push(key);
+                continue;
+            }
+        };
+        modifiers.push(modifier.to_string());
+    }
+
+    modifiers.sort();
+    modifiers.dedup();
+    modifiers.extend(keys);
+    modifiers.join("+")
+}
+
+/// Check the enabled bindings against each other and the main hotkey:
+/// every hotkey must be set, used once and refer to an existing profile
+pub fn validate_bindings(
+    primary: &str,
+    bindings: &[HotkeyBinding],
+    profiles: &[DictationProfile],
+) -> Result<(), HotkeyError> {
+    let mut seen = HashMap::new();
+    if !primary.trim().is_empty() {
+        seen.insert(normalize_hotkey(primary), "Dictate");
+    }
+
+    for binding in bindings.iter().filter(|b| b.enabled) {
+        if binding.hotkey.trim().is_empty() {
+            return Err(HotkeyError::InvalidFormat(format!(
+                "no hotkey set for '{}'",
+                binding.name
+            )));
+        }
+        if let Some(profile) = &binding.profile {
+            if binding.find_profile(profiles).is_none() {
+                return Err(HotkeyError::UnknownProfile(profile.clone()));
+            }
+        }
+        if let Some(other) = seen.insert(normalize_hotkey(&binding.hotkey), &binding.name) {
+            return Err(HotkeyError::Duplicate {
+                hotkey: binding.hotkey.clone(),
+                first: other.to_string(),
+                second: binding.name.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(name: &str, hotkey: &str, action: HotkeyAction) -> HotkeyBinding {
+        HotkeyBinding {
+            name: name.to_string(),
+            hotkey: hotkey.to_string(),
+            action,
+            ..Default::default()
+        }
+    }
+
+    fn profile(name: &str) -> DictationProfile {
+        DictationProfile {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_hotkey() {
+        assert_eq!(normalize_hotkey("Ctrl+Shift+Space"), "ctrl+shift+space");
+        assert_eq!(
+            normalize_hotkey("shift + control + space"),
+            normalize_hotkey("Ctrl+Shift+Space")
+        );
+        assert_eq!(normalize_hotkey("Option+KeyA"), normalize_hotkey("Alt+A"));
+        assert_eq!(normalize_hotkey("Cmd+Digit1"), normalize_hotkey("Super+1"));
+        assert_ne!(normalize_hotkey("Ctrl+A"), normalize_hotkey("Ctrl+Shift+A"));
+    }
+
+    #[test]
+    fn test_validate_detects_duplicates() {
+        let bindings = vec![
+            binding("Clipboard", "Ctrl+Alt+Space", HotkeyAction::Clipboard),
+            binding("Translate", "Alt+Control+Space", HotkeyAction::Translate),
+        ];
+        let err = validate_bindings("Ctrl+Shift+Space", &bindings, &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            HotkeyError::Duplicate { ref first, ref second, .. }
+                if first == "Clipboard" && second == "Translate"
+        ));
+
+        let bindings = vec![binding(
+            "Commands",
+            "Shift+Ctrl+Space",
+            HotkeyAction::Command,
+        )];
+        assert!(validate_bindings("Ctrl+Shift+Space", &bindings, &[]).is_err());
+    }
+
+    #[test]
+    fn test_validate_skips_disabled_and_checks_profiles() {
+        let mut disabled = binding("Old", "Ctrl+Shift+Space", HotkeyAction::Dictate);
+        disabled.enabled = false;
+        assert!(validate_bindings("Ctrl+Shift+Space", &[disabled], &[]).is_ok());
+
+        let mut german = binding("German", "Ctrl+Alt+G", HotkeyAction::Dictate);
+        german.profile = Some("German".to_string());
+        assert!(matches!(
+            validate_bindings("Ctrl+Shift+Space", &[german.clone()], &[]),
+            Err(HotkeyError::UnknownProfile(_))
+        ));
+        assert!(validate_bindings("Ctrl+Shift+Space", &[german], &[profile("German")]).is_ok());
+
+        let empty = binding("Empty", " ", HotkeyAction::Dictate);
+        assert!(matches!(
+            validate_bindings("Ctrl+Shift+Space", &[empty], &[]),
+            Err(HotkeyError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_profile_and_action() {
+        let mut settings = Settings::default();
+        settings.dictation_profiles = vec![DictationProfile {
+            name: "Notes".to_string(),
+            model_id: Some("small".to_string()),
+            context_prompt: Some("Meeting notes".to_string()),
+            sink: Some(DictationSink::File {
+                path: "~/notes.md".to_string(),
+            }),
+            ..Default::default()
+        }];
+
+        let mut notes = binding("Notes", "Ctrl+Alt+N", HotkeyAction::Dictate);
+        notes.profile = Some("Notes".to_string());
+        assert!(notes.overrides_decoding(&settings.dictation_profiles));
+        let applied = notes.apply(settings.clone());
+        assert_eq!(applied.model_id, "small");
+        assert_eq!(applied.context_prompt.as_deref(), Some("Meeting notes"));
+        assert!(applied.use_context_prompt);
+        assert!(matches!(applied.dictation_sink, DictationSink::File { .. }));
+
+        notes.action = HotkeyAction::Clipboard;
+        assert_eq!(
+            notes.apply(settings.clone()).dictation_sink,
+            DictationSink::Clipboard
+        );
+
+        let primary = HotkeyBinding::primary("Ctrl+Shift+Space");
+        assert!(!primary.overrides_decoding(&settings.dictation_profiles));
+        assert_eq!(primary.apply(settings).model_id, "base");
+    }
+
+    #[test]
+    fn test_binding_defaults_for_missing_fields() {
+        let parsed: HotkeyBinding = serde_json::from_str(
+            r#"{"name": "Translate", "hotkey": "Ctrl+Alt+T", "action": "translate"}"#,
+        )
+        .unwrap();
+        assert!(parsed.enabled);
+        assert_eq!(parsed.action, HotkeyAction::Translate);
+        assert!(parsed.profile.is_none());
+    }
+}
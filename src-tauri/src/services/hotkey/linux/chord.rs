@@ -0,0 +1,243 @@
+//! Shortcut strings as the native backends see them
+//!
+//! The portal wants triggers in the notation of the XDG shortcuts spec,
+//! evdev sees Linux input event codes. Both are derived from the same
+//! plugin-style shortcut string.
+
+use crate::services::hotkey::trigger::input_trigger_code;
+use crate::services::hotkey::{normalize_hotkey, HotkeyError};
+use crate::services::platform::linux::keys::us_layout_key;
+
+/// A modifier key of a chord
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Modifier {
+    Ctrl,
+    Shift,
+    Alt,
+    Super,
+}
+
+impl Modifier {
+    /// Left and right key codes
+    pub fn codes(self) -> [u16; 2] {
+        match self {
+            Modifier::Ctrl => [29, 97],
+            Modifier::Shift => [42, 54],
+            Modifier::Alt => [56, 100],
+            Modifier::Super => [125, 126],
+        }
+    }
+
+    /// The modifier a key code belongs to
+    pub fn from_code(code: u16) -> Option<Self> {
+        [
+            Modifier::Ctrl,
+            Modifier::Shift,
+            Modifier::Alt,
+            Modifier::Super,
+        ]
+        .into_iter()
+        .find(|modifier| modifier.codes().contains(&code))
+    }
+
+    fn xdg_name(self) -> &'static str {
+        match self {
+            Modifier::Ctrl => "CTRL",
+            Modifier::Shift => "SHIFT",
+            Modifier::Alt => "ALT",
+            Modifier::Super => "LOGO",
+        }
+    }
+}
+
+/// Named keys in normalized form with their XKB keysym name and key code
+const NAMED_KEYS: &[(&str, &str, u16)] = &[
+    ("space", "space", 57),
+    ("enter", "Return", 28),
+    ("return", "Return", 28),
+    ("tab", "Tab", 15),
+    ("escape", "Escape", 1),
+    ("esc", "Escape", 1),
+    ("backspace", "BackSpace", 14),
+    ("delete", "Delete", 111),
+    ("insert", "Insert", 110),
+    ("home", "Home", 102),
+    ("end", "End", 107),
+    ("pageup", "Page_Up", 104),
+    ("pagedown", "Page_Down", 109),
+    ("arrowup", "Up", 103),
+    ("up", "Up", 103),
+    ("arrowdown", "Down", 108),
+    ("down", "Down", 108),
+    ("arrowleft", "Left", 105),
+    ("left", "Left", 105),
+    ("arrowright", "Right", 106),
+    ("right", "Right", 106),
+    ("capslock", "Caps_Lock", 58),
+    ("printscreen", "Print", 99),
+    ("scrolllock", "Scroll_Lock", 70),
+    ("pause", "Pause", 119),
+    ("minus", "minus", 12),
+    ("equal", "equal", 13),
+    ("bracketleft", "bracketleft", 26),
+    ("bracketright", "bracketright", 27),
+    ("semicolon", "semicolon", 39),
+    ("quote", "apostrophe", 40),
+    ("backquote", "grave", 41),
+    ("backslash", "backslash", 43),
+    ("comma", "comma", 51),
+    ("period", "period", 52),
+    ("slash", "slash", 53),
+];
+
+/// Key code of F1 to F24
+fn function_key_code(n: u16) -> Option<u16> {
+    match n {
+        1..=10 => Some(58 + n),
+        11 => Some(87),
+        12 => Some(88),
+        13..=24 => Some(170 + n),
+        _ => None,
+    }
+}
+
+/// A shortcut split into modifiers and one key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    /// Modifiers that must be held, sorted
+    pub modifiers: Vec<Modifier>,
+    /// XKB keysym name of the key, e.g. "space" or "F5"
+    pub keysym: String,
+    /// Linux input event code of the key
+    pub code: u16,
+}
+
+impl Chord {
+    /// Parse a shortcut string such as "Ctrl+Shift+Space"
+    pub fn parse(hotkey: &str) -> Result<Self, HotkeyError> {
+        let mut modifiers = Vec::new();
+        let mut key = None;
+
+        for part in normalize_hotkey(hotkey)
+            .split('+')
+            .filter(|p| !p.is_empty())
+        {
+            let modifier = match part {
+                "ctrl" => Modifier::Ctrl,
+                "shift" => Modifier::Shift,
+                "alt" => Modifier::Alt,
+                "super" => Modifier::Super,
+                _ if key.is_none() => {
+                    key = Some(part.to_string());
+                    continue;
+                }
+                _ => {
+                    return Err(HotkeyError::InvalidFormat(format!(
+                        "'{}' has more than one key",
+                        hotkey
+                    )))
+                }
+            };
+            modifiers.push(modifier);
+        }
+        modifiers.sort();
+
+        let key =
+            key.ok_or_else(|| HotkeyError::InvalidFormat(format!("'{}' has no key", hotkey)))?;
+        let (keysym, code) = key_for_name(&key).ok_or_else(|| {
+            HotkeyError::InvalidFormat(format!("unknown key '{}' in '{}'", key, hotkey))
+        })?;
+
+        Ok(Self {
+            modifiers,
+            keysym,
+            code,
+        })
+    }
+
+    /// The chord in the notation of the XDG shortcuts spec, e.g.
+    /// "CTRL+SHIFT+space"
+    pub fn xdg_trigger(&self) -> String {
+        self.modifiers
+            .iter()
+            .map(|modifier| modifier.xdg_name())
+            .chain(std::iter::once(self.keysym.as_str()))
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+/// Keysym name and key code for a normalized key name. Input triggers such
+/// as "rightctrl" or "mouse4" keep their name, only evdev can listen for them.
+fn key_for_name(name: &str) -> Option<(String, u16)> {
+    if let Some(&(_, keysym, code)) = NAMED_KEYS.iter().find(|(n, _, _)| *n == name) {
+        return Some((keysym.to_string(), code));
+    }
+    if let Some(code) = input_trigger_code(name) {
+        return Some((name.to_string(), code));
+    }
+    if let Some(code) = name
+        .strip_prefix('f')
+        .and_then(|n| n.parse().ok())
+        .and_then(function_key_code)
+    {
+        return Some((name.to_uppercase(), code));
+    }
+
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => {
+            us_layout_key(c).map(|(code, _)| (c.to_string(), code))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chord() {
+        let chord = Chord::parse("Shift+Ctrl+Space").unwrap();
+        assert_eq!(chord.modifiers, vec![Modifier::Ctrl, Modifier::Shift]);
+        assert_eq!(chord.code, 57);
+        assert_eq!(chord.xdg_trigger(), "CTRL+SHIFT+space");
+
+        let chord = Chord::parse("Super+Alt+KeyD").unwrap();
+        assert_eq!(chord.code, 32);
+        assert_eq!(chord.xdg_trigger(), "ALT+LOGO+d");
+
+        assert_eq!(Chord::parse("Ctrl+F12").unwrap().code, 88);
+        assert_eq!(Chord::parse("F13").unwrap().xdg_trigger(), "F13");
+        assert_eq!(Chord::parse("Alt+ArrowUp").unwrap().xdg_trigger(), "ALT+Up");
+    }
+
+    #[test]
+    fn test_parse_input_triggers() {
+        let chord = Chord::parse("RightCtrl").unwrap();
+        assert!(chord.modifiers.is_empty());
+        assert_eq!(chord.code, 97);
+        assert_eq!(Chord::parse("CapsLock").unwrap().code, 58);
+
+        let chord = Chord::parse("Shift+Mouse4").unwrap();
+        assert_eq!(chord.modifiers, vec![Modifier::Shift]);
+        assert_eq!(chord.code, 275);
+        assert_eq!(Chord::parse("Shift+Fn").unwrap().code, 464);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_chords() {
+        assert!(Chord::parse("Ctrl+Shift").is_err());
+        assert!(Chord::parse("Ctrl+A+B").is_err());
+        assert!(Chord::parse("Ctrl+Nonsense").is_err());
+        assert!(Chord::parse("F25").is_err());
+    }
+
+    #[test]
+    fn test_modifier_codes() {
+        assert_eq!(Modifier::from_code(97), Some(Modifier::Ctrl));
+        assert_eq!(Modifier::from_code(126), Some(Modifier::Super));
+        assert_eq!(Modifier::from_code(57), None);
+    }
+}
@@ -0,0 +1,681 @@
+//! Hotkeys read directly from keyboard devices
+//!
+//! Works with every compositor but needs read access to /dev/input, which
+//! members of the `input` group have. Mice are read too while a mouse
+//! button is bound.
+//!
+//! The devices are only read, so the keys still reach the focused
+//! application. Triggers that do something on their own, CapsLock and the
+//! mouse buttons, are the exception: while one is bound, the devices that
+//! have it are grabbed and every other event is replayed through a uinput
+//! device, which needs write access to /dev/uinput as well. The lock lights
+//! of a grabbed keyboard don't follow the lock keys.
+
+use super::{Chord, HotkeyCallback, HotkeyListener, Modifier};
+use crate::services::hotkey::{HotkeyBinding, HotkeyError, HotkeyEvent};
+use crate::services::platform::linux::{DeviceCapabilities, UinputForwarder, DEVICE_NAME_PREFIX};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const INPUT_DIR: &str = "/dev/input";
+
+// linux/input-event-codes.h
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const EV_ABS: u16 = 0x03;
+const EV_MSC: u16 = 0x04;
+const KEY_A: u16 = 30;
+const KEY_SPACE: u16 = 57;
+const KEY_CAPSLOCK: u16 = 58;
+const KEY_FN: u16 = 0x1d0;
+const BTN_LEFT: u16 = 0x110;
+const BTN_TASK: u16 = 0x117;
+/// Bytes of a key bitmask (KEY_MAX + 1 bits)
+const KEY_BITS_LEN: usize = 0x300 / 8;
+/// Bytes of the event type, relative axis and misc bitmasks
+const EV_BITS_LEN: usize = 4;
+const REL_BITS_LEN: usize = 2;
+const MSC_BITS_LEN: usize = 1;
+/// Longest device name read
+const NAME_LEN: usize = 256;
+
+/// Key event values
+const KEY_RELEASED: i32 = 0;
+const KEY_PRESSED: i32 = 1;
+const KEY_REPEATED: i32 = 2;
+
+/// linux/input.h: a read request on an event device
+const fn eviocg(number: u16, len: usize) -> libc::c_ulong {
+    (2 << 30)
+        | ((len as libc::c_ulong) << 16)
+        | ((b'E' as libc::c_ulong) << 8)
+        | number as libc::c_ulong
+}
+
+// linux/input.h ioctls
+const EVIOCGNAME: libc::c_ulong = eviocg(0x06, NAME_LEN);
+const EVIOCGKEY: libc::c_ulong = eviocg(0x18, KEY_BITS_LEN);
+const EVIOCGBIT_EV: libc::c_ulong = eviocg(0x20, EV_BITS_LEN);
+const EVIOCGBIT_KEY: libc::c_ulong = eviocg(0x20 + EV_KEY, KEY_BITS_LEN);
+const EVIOCGBIT_REL: libc::c_ulong = eviocg(0x20 + EV_REL, REL_BITS_LEN);
+const EVIOCGBIT_MSC: libc::c_ulong = eviocg(0x20 + EV_MSC, MSC_BITS_LEN);
+/// EVIOCGRAB, _IOW('E', 0x90, int)
+const EVIOCGRAB: libc::c_ulong = (1 << 30) | (4 << 16) | ((b'E' as libc::c_ulong) << 8) | 0x90;
+
+/// How long a poll waits before checking whether to stop
+const POLL_TIMEOUT_MS: libc::c_int = 500;
+
+/// How often to look for devices plugged in later
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether the code is a mouse button
+fn is_mouse_button(code: u16) -> bool {
+    (BTN_LEFT..=BTN_TASK).contains(&code)
+}
+
+/// Whether the key does something on its own, so its presses are kept
+/// from the focused application while it is a trigger
+fn acts_alone(code: u16) -> bool {
+    code == KEY_CAPSLOCK || is_mouse_button(code)
+}
+
+/// Whether the key is mostly held together with others
+fn is_modifier_key(code: u16) -> bool {
+    Modifier::from_code(code).is_some() || matches!(code, KEY_CAPSLOCK | KEY_FN)
+}
+
+/// Turns key events from all devices into presses and releases of the
+/// bound chords. A chord is pressed when its key goes down while exactly
+/// its modifiers are held, and released with its key. A lone modifier
+/// chord is cancelled when another key goes down while it is held.
+#[derive(Default)]
+pub struct ChordMatcher {
+    chords: Vec<(Chord, HotkeyBinding)>,
+    held: HashSet<u16>,
+    active: Vec<usize>,
+}
+
+impl ChordMatcher {
+    /// Match these chords from now on
+    pub fn set_chords(&mut self, chords: Vec<(Chord, HotkeyBinding)>) {
+        self.chords = chords;
+        self.active.clear();
+    }
+
+    /// Bound keys that must not reach the focused application
+    pub fn kept_keys(&self) -> Vec<u16> {
+        let mut keys: Vec<u16> = self
+            .chords
+            .iter()
+            .map(|(chord, _)| chord.code)
+            .filter(|&code| acts_alone(code))
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    /// Modifiers held besides the key `except`
+    fn held_modifiers(&self, except: u16) -> Vec<Modifier> {
+        let mut modifiers: Vec<_> = self
+            .held
+            .iter()
+            .filter(|&&code| code != except)
+            .filter_map(|&code| Modifier::from_code(code))
+            .collect();
+        modifiers.sort();
+        modifiers.dedup();
+        modifiers
+    }
+
+    /// Feed one key press or release, returning the events it caused
+    pub fn key_event(&mut self, code: u16, pressed: bool) -> Vec<(HotkeyBinding, HotkeyEvent)> {
+        let mut fired = Vec::new();
+
+        if pressed {
+            self.held.insert(code);
+            let chords = &self.chords;
+            self.active.retain(|&index| {
+                let (chord, binding) = &chords[index];
+                if chord.code != code && chord.modifiers.is_empty() && is_modifier_key(chord.code) {
+                    fired.push((binding.clone(), HotkeyEvent::Cancelled));
+                    false
+                } else {
+                    true
+                }
+            });
+            for (index, (chord, binding)) in self.chords.iter().enumerate() {
+                if chord.code == code
+                    && chord.modifiers == self.held_modifiers(code)
+                    && !self.active.contains(&index)
+                {
+                    self.active.push(index);
+                    fired.push((binding.clone(), HotkeyEvent::Pressed));
+                }
+            }
+        } else {
+            self.held.remove(&code);
+            let chords = &self.chords;
+            self.active.retain(|&index| {
+                let (chord, binding) = &chords[index];
+                if chord.code == code {
+                    fired.push((binding.clone(), HotkeyEvent::Released));
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        fired
+    }
+}
+
+/// Read a bitmask or string from the device, or None if it can't be read
+fn read_bits<const N: usize>(file: &File, request: libc::c_ulong) -> Option<[u8; N]> {
+    let mut bits = [0u8; N];
+    // SAFETY: the request writes at most N bytes into the buffer
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), request as _, bits.as_mut_ptr()) };
+    (result >= 0).then_some(bits)
+}
+
+/// Codes set in a bitmask
+fn set_bits(bits: &[u8]) -> Vec<u16> {
+    (0..bits.len() * 8)
+        .filter(|&code| bits[code / 8] & (1 << (code % 8)) != 0)
+        .map(|code| code as u16)
+        .collect()
+}
+
+/// An open input device
+struct Device {
+    path: PathBuf,
+    file: File,
+    name: String,
+    /// Keys and buttons the device reports
+    keys: [u8; KEY_BITS_LEN],
+    /// Replays the device's events while it is grabbed
+    forwarder: Option<UinputForwarder>,
+    /// Grabbing failed, so it isn't tried again until the triggers change
+    grab_failed: bool,
+    /// Trigger keys held down that the focused application didn't see
+    kept: HashSet<u16>,
+}
+
+impl Device {
+    /// Open the device if it's readable, skipping the ones EZ Flow created
+    fn open(path: PathBuf) -> Option<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&path)
+            .ok()?;
+        let keys = read_bits(&file, EVIOCGBIT_KEY)?;
+        let name = read_bits::<NAME_LEN>(&file, EVIOCGNAME)
+            .map(|name| {
+                let len = name.iter().position(|&b| b == 0).unwrap_or(NAME_LEN);
+                String::from_utf8_lossy(&name[..len]).into_owned()
+            })
+            .unwrap_or_default();
+        if name.starts_with(DEVICE_NAME_PREFIX) {
+            return None;
+        }
+        Some(Self {
+            path,
+            file,
+            name,
+            keys,
+            forwarder: None,
+            grab_failed: false,
+            kept: HashSet::new(),
+        })
+    }
+
+    fn has_key(&self, code: u16) -> bool {
+        self.keys[code as usize / 8] & (1 << (code % 8)) != 0
+    }
+
+    /// Whether the device reports the keys of a keyboard, or with `mice`
+    /// the buttons of a mouse
+    fn is_wanted(&self, mice: bool) -> bool {
+        (self.has_key(KEY_A) && self.has_key(KEY_SPACE)) || (mice && self.has_key(BTN_LEFT))
+    }
+
+    /// Grab the device while it has one of `keys`, and let go of it
+    /// otherwise. It is only grabbed while no key is down, so no release
+    /// goes missing.
+    fn update_grab(&mut self, keys: &[u16]) {
+        if !keys.iter().any(|&code| self.has_key(code)) {
+            if self.forwarder.is_some() {
+                self.release();
+                tracing::debug!("Released input device {}", self.path.display());
+            }
+            self.grab_failed = false;
+            return;
+        }
+        if self.forwarder.is_some() || self.grab_failed {
+            return;
+        }
+        match read_bits::<KEY_BITS_LEN>(&self.file, EVIOCGKEY) {
+            Some(down) if down.iter().all(|&bits| bits == 0) => {}
+            _ => return,
+        }
+
+        match self.grab() {
+            Ok(()) => tracing::debug!("Grabbed input device {}", self.path.display()),
+            Err(e) => {
+                tracing::warn!(
+                    "Can't keep the trigger of {} from reaching applications: {}",
+                    self.name,
+                    e
+                );
+                self.grab_failed = true;
+            }
+        }
+    }
+
+    fn grab(&mut self) -> Result<(), String> {
+        let types = read_bits::<EV_BITS_LEN>(&self.file, EVIOCGBIT_EV).unwrap_or_default();
+        if set_bits(&types).contains(&EV_ABS) {
+            return Err("it reports absolute positions, which can't be replayed".to_string());
+        }
+        let capabilities = DeviceCapabilities {
+            keys: set_bits(&self.keys),
+            relative_axes: read_bits::<REL_BITS_LEN>(&self.file, EVIOCGBIT_REL)
+                .map(|bits| set_bits(&bits))
+                .unwrap_or_default(),
+            misc: read_bits::<MSC_BITS_LEN>(&self.file, EVIOCGBIT_MSC)
+                .map(|bits| set_bits(&bits))
+                .unwrap_or_default(),
+        };
+        let forwarder =
+            UinputForwarder::create(&self.name, &capabilities).map_err(|e| e.to_string())?;
+
+        // SAFETY: EVIOCGRAB takes an int argument
+        if unsafe { libc::ioctl(self.file.as_raw_fd(), EVIOCGRAB as _, 1 as libc::c_int) } < 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        self.forwarder = Some(forwarder);
+        Ok(())
+    }
+
+    fn release(&mut self) {
+        // SAFETY: EVIOCGRAB takes an int argument
+        unsafe { libc::ioctl(self.file.as_raw_fd(), EVIOCGRAB as _, 0 as libc::c_int) };
+        self.forwarder = None;
+        self.kept.clear();
+    }
+
+    /// Match a key event against the chords, and replay the event unless
+    /// it's a trigger the focused application must not see
+    fn handle_event(
+        &mut self,
+        event: &libc::input_event,
+        matcher: &Mutex<ChordMatcher>,
+        callback: &HotkeyCallback,
+    ) {
+        let mut keep = false;
+        if event.type_ == EV_KEY {
+            match event.value {
+                KEY_PRESSED => {
+                    let fired = matcher.lock().unwrap().key_event(event.code, true);
+                    keep = self.forwarder.is_some()
+                        && acts_alone(event.code)
+                        && fired.iter().any(|(_, e)| *e == HotkeyEvent::Pressed);
+                    if keep {
+                        self.kept.insert(event.code);
+                    }
+                    for (binding, event) in fired {
+                        callback(&binding, event);
+                    }
+                }
+                KEY_RELEASED => {
+                    let fired = matcher.lock().unwrap().key_event(event.code, false);
+                    keep = self.kept.remove(&event.code);
+                    for (binding, event) in fired {
+                        callback(&binding, event);
+                    }
+                }
+                KEY_REPEATED => keep = self.kept.contains(&event.code),
+                _ => {}
+            }
+        }
+
+        let Some(forwarder) = &self.forwarder else {
+            return;
+        };
+        if keep {
+            return;
+        }
+        if let Err(e) = forwarder.forward(event) {
+            // Never hold on to a device whose input goes nowhere
+            tracing::warn!("Releasing {}: {}", self.name, e);
+            self.release();
+            self.grab_failed = true;
+        }
+    }
+}
+
+/// Open the readable keyboards in /dev/input, and mice with `mice`, except
+/// those in `known`
+fn open_devices(known: &HashSet<PathBuf>, mice: bool) -> Vec<Device> {
+    let Ok(entries) = std::fs::read_dir(INPUT_DIR) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("event"))
+                && !known.contains(path)
+        })
+        .filter_map(Device::open)
+        .filter(|device| device.is_wanted(mice))
+        .collect()
+}
+
+/// Whether at least one keyboard can be read
+pub fn is_available() -> bool {
+    !open_devices(&HashSet::new(), false).is_empty()
+}
+
+/// Read one event, or None when no more are pending
+fn read_event(file: &File) -> std::io::Result<Option<libc::input_event>> {
+    let size = std::mem::size_of::<libc::input_event>();
+    // SAFETY: input_event is plain data, so all zeroes is a valid value
+    let mut event: libc::input_event = unsafe { std::mem::zeroed() };
+    // SAFETY: reads at most `size` bytes into `event`
+    let read = unsafe {
+        libc::read(
+            file.as_raw_fd(),
+            &mut event as *mut libc::input_event as *mut libc::c_void,
+            size,
+        )
+    };
+    if read < 0 {
+        let e = std::io::Error::last_os_error();
+        return match e.kind() {
+            ErrorKind::WouldBlock => Ok(None),
+            _ => Err(e),
+        };
+    }
+    if read as usize != size {
+        return Err(std::io::Error::new(
+            ErrorKind::UnexpectedEof,
+            "short input event",
+        ));
+    }
+    Ok(Some(event))
+}
+
+/// Poll the devices until `stop` is set, dropping unplugged ones and
+/// picking up new ones, and mice once `mice` is set
+fn run(
+    mut devices: Vec<Device>,
+    matcher: Arc<Mutex<ChordMatcher>>,
+    callback: HotkeyCallback,
+    mice: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut last_scan = Instant::now();
+    let mut scanned_mice = false;
+
+    while !stop.load(Ordering::SeqCst) {
+        let want_mice = mice.load(Ordering::SeqCst);
+        if last_scan.elapsed() >= RESCAN_INTERVAL || want_mice != scanned_mice {
+            let known = devices.iter().map(|device| device.path.clone()).collect();
+            for device in open_devices(&known, want_mice) {
+                tracing::debug!("Reading hotkeys from new device {}", device.path.display());
+                devices.push(device);
+            }
+            last_scan = Instant::now();
+            scanned_mice = want_mice;
+        }
+
+        let kept_keys = matcher.lock().unwrap().kept_keys();
+        for device in &mut devices {
+            device.update_grab(&kept_keys);
+        }
+
+        let mut fds: Vec<libc::pollfd> = devices
+            .iter()
+            .map(|device| libc::pollfd {
+                fd: device.file.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            })
+            .collect();
+        // SAFETY: `fds` holds fds.len() initialized pollfd structs
+        let ready =
+            unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, POLL_TIMEOUT_MS) };
+        if ready <= 0 {
+            continue;
+        }
+
+        let mut gone = Vec::new();
+        for (index, pollfd) in fds.iter().enumerate() {
+            if pollfd.revents & (libc::POLLERR | libc::POLLHUP | libc::POLLNVAL) != 0 {
+                gone.push(index);
+                continue;
+            }
+            if pollfd.revents & libc::POLLIN == 0 {
+                continue;
+            }
+            let device = &mut devices[index];
+            loop {
+                match read_event(&device.file) {
+                    Ok(Some(event)) => device.handle_event(&event, &matcher, &callback),
+                    Ok(None) => break,
+                    Err(_) => {
+                        gone.push(index);
+                        break;
+                    }
+                }
+            }
+        }
+
+        for index in gone.into_iter().rev() {
+            let device = devices.remove(index);
+            tracing::debug!("Input device {} went away", device.path.display());
+        }
+    }
+}
+
+/// Hotkeys read from the keyboards and mice in /dev/input
+pub struct EvdevListener {
+    matcher: Arc<Mutex<ChordMatcher>>,
+    mice: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+}
+
+impl EvdevListener {
+    /// Open the keyboards and report hotkey events to `callback`
+    pub fn start(callback: HotkeyCallback) -> Result<Self, HotkeyError> {
+        let devices = open_devices(&HashSet::new(), false);
+        if devices.is_empty() {
+            return Err(HotkeyError::RegistrationFailed(format!(
+                "no readable keyboard in {}. Add yourself to the input group",
+                INPUT_DIR
+            )));
+        }
+        tracing::info!("Reading hotkeys from {} keyboards", devices.len());
+
+        let matcher = Arc::new(Mutex::new(ChordMatcher::default()));
+        let mice = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_matcher = matcher.clone();
+        let thread_mice = mice.clone();
+        let thread_stop = stop.clone();
+        std::thread::Builder::new()
+            .name("evdev-hotkeys".to_string())
+            .spawn(move || run(devices, thread_matcher, callback, thread_mice, thread_stop))
+            .map_err(|e| HotkeyError::RegistrationFailed(e.to_string()))?;
+
+        Ok(Self {
+            matcher,
+            mice,
+            stop,
+        })
+    }
+}
+
+impl Drop for EvdevListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+impl HotkeyListener for EvdevListener {
+    fn name(&self) -> &'static str {
+        "evdev"
+    }
+
+    fn set_bindings(&mut self, bindings: &[HotkeyBinding]) -> Result<(), HotkeyError> {
+        let chords = bindings
+            .iter()
+            .map(|binding| Ok((Chord::parse(&binding.hotkey)?, binding.clone())))
+            .collect::<Result<Vec<_>, HotkeyError>>()?;
+        let mice = chords.iter().any(|(chord, _)| is_mouse_button(chord.code));
+        self.mice.store(mice, Ordering::SeqCst);
+        self.matcher.lock().unwrap().set_chords(chords);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(hotkeys: &[&str]) -> ChordMatcher {
+        let mut matcher = ChordMatcher::default();
+        matcher.set_chords(
+            hotkeys
+                .iter()
+                .map(|hotkey| {
+                    (
+                        Chord::parse(hotkey).unwrap(),
+                        HotkeyBinding::primary(hotkey),
+                    )
+                })
+                .collect(),
+        );
+        matcher
+    }
+
+    #[test]
+    fn test_chord_press_and_release() {
+        let mut matcher = matcher(&["Ctrl+Shift+Space"]);
+        assert!(matcher.key_event(29, true).is_empty());
+        assert!(matcher.key_event(42, true).is_empty());
+
+        let fired = matcher.key_event(57, true);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].0.hotkey, "Ctrl+Shift+Space");
+        assert_eq!(fired[0].1, HotkeyEvent::Pressed);
+
+        // Letting go of a modifier first doesn't end push-to-talk
+        assert!(matcher.key_event(42, false).is_empty());
+        let fired = matcher.key_event(57, false);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1, HotkeyEvent::Released);
+    }
+
+    #[test]
+    fn test_chord_needs_exact_modifiers() {
+        let mut matcher = matcher(&["Ctrl+Space"]);
+        assert!(matcher.key_event(57, true).is_empty());
+        assert!(matcher.key_event(57, false).is_empty());
+
+        // An extra modifier makes it a different shortcut
+        matcher.key_event(29, true);
+        matcher.key_event(56, true);
+        assert!(matcher.key_event(57, true).is_empty());
+        matcher.key_event(57, false);
+        matcher.key_event(56, false);
+
+        // Either side's modifier counts
+        matcher.key_event(29, false);
+        matcher.key_event(97, true);
+        assert_eq!(matcher.key_event(57, true).len(), 1);
+    }
+
+    #[test]
+    fn test_matches_between_bindings() {
+        let mut matcher = matcher(&["Ctrl+Space", "Ctrl+Alt+Space"]);
+        matcher.key_event(29, true);
+        matcher.key_event(56, true);
+        let fired = matcher.key_event(57, true);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].0.hotkey, "Ctrl+Alt+Space");
+    }
+
+    #[test]
+    fn test_lone_modifier_trigger() {
+        let mut matcher = matcher(&["RightCtrl", "Ctrl+Space"]);
+        let fired = matcher.key_event(97, true);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].0.hotkey, "RightCtrl");
+        assert_eq!(fired[0].1, HotkeyEvent::Pressed);
+        assert_eq!(matcher.key_event(97, false)[0].1, HotkeyEvent::Released);
+
+        // Left Ctrl is a different key
+        assert!(matcher.key_event(29, true).is_empty());
+        matcher.key_event(29, false);
+
+        // Holding it for another shortcut cancels the trigger
+        matcher.key_event(97, true);
+        let fired = matcher.key_event(57, true);
+        assert_eq!(fired.len(), 2);
+        assert_eq!(fired[0].0.hotkey, "RightCtrl");
+        assert_eq!(fired[0].1, HotkeyEvent::Cancelled);
+        assert_eq!(fired[1].0.hotkey, "Ctrl+Space");
+        assert_eq!(fired[1].1, HotkeyEvent::Pressed);
+        matcher.key_event(57, false);
+        assert!(matcher.key_event(97, false).is_empty());
+    }
+
+    #[test]
+    fn test_mouse_button_trigger() {
+        let mut matcher = matcher(&["Mouse4"]);
+        assert_eq!(matcher.key_event(275, true)[0].1, HotkeyEvent::Pressed);
+        // Typing while holding a mouse button doesn't cancel it
+        assert!(matcher.key_event(30, true).is_empty());
+        matcher.key_event(30, false);
+        assert_eq!(matcher.key_event(275, false)[0].1, HotkeyEvent::Released);
+    }
+
+    #[test]
+    fn test_fn_trigger() {
+        let mut matcher = matcher(&["Fn"]);
+        assert_eq!(matcher.key_event(464, true)[0].1, HotkeyEvent::Pressed);
+        assert_eq!(matcher.key_event(464, false)[0].1, HotkeyEvent::Released);
+
+        // Fn held for a media key isn't push-to-talk
+        matcher.key_event(464, true);
+        assert_eq!(matcher.key_event(115, true)[0].1, HotkeyEvent::Cancelled);
+        matcher.key_event(115, false);
+        assert!(matcher.key_event(464, false).is_empty());
+    }
+
+    #[test]
+    fn test_kept_keys() {
+        // Only triggers that do something on their own are kept from apps
+        let matcher = matcher(&["CapsLock", "Shift+Mouse4", "RightCtrl", "Fn", "Ctrl+Space"]);
+        assert_eq!(matcher.kept_keys(), vec![58, 275]);
+        assert!(ChordMatcher::default().kept_keys().is_empty());
+    }
+
+    #[test]
+    fn test_set_bits() {
+        assert_eq!(set_bits(&[0b0000_0101, 0b1000_0000]), vec![0, 2, 15]);
+        assert!(set_bits(&[0, 0]).is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+//! Native hotkey backends for Wayland
+//!
+//! The global-shortcut plugin grabs keys through X11, which Wayland
+//! compositors only allow while an XWayland window has focus, so its
+//! shortcuts never fire there. Wayland sessions use the GlobalShortcuts
+//! portal instead, or read the keyboards through evdev when there is no
+//! portal but /dev/input is readable.
+//!
+//! Input triggers such as a lone Right Ctrl or a mouse button (see
+//! [`super::trigger`]) can't be bound by the plugin or the portal, so they
+//! are always read through evdev, on X11 too.
+
+mod chord;
+mod evdev;
+mod portal;
+
+pub use chord::{Chord, Modifier};
+
+use super::{normalize_hotkey, HotkeyBackend, HotkeyBinding, HotkeyError, HotkeyEvent};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Receives the events of bound hotkeys
+pub type HotkeyCallback = Arc<dyn Fn(&HotkeyBinding, HotkeyEvent) + Send + Sync>;
+
+/// A backend that listens for a set of hotkeys
+pub trait HotkeyListener: Send {
+    /// Backend name for logs
+    fn name(&self) -> &'static str;
+
+    /// Listen for exactly these bindings from now on. Returns once the
+    /// backend has taken them, or with the reason it didn't.
+    fn set_bindings(&mut self, bindings: &[HotkeyBinding]) -> Result<(), HotkeyError>;
+}
+
+/// A native listener and the bindings it listens for
+pub struct NativeHotkeys {
+    listener: Box<dyn HotkeyListener>,
+    bindings: Vec<HotkeyBinding>,
+}
+
+impl NativeHotkeys {
+    pub fn new(listener: Box<dyn HotkeyListener>) -> Self {
+        Self {
+            listener,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Listen for a binding, replacing one with the same hotkey
+    pub fn bind(&mut self, binding: HotkeyBinding) -> Result<(), HotkeyError> {
+        self.replace(&[], vec![binding])
+    }
+
+    /// Stop listening for a hotkey
+    pub fn unbind(&mut self, hotkey: &str) -> Result<(), HotkeyError> {
+        if !self.is_bound(hotkey) {
+            return Err(HotkeyError::NotRegistered);
+        }
+        self.replace(&[hotkey], Vec::new())
+    }
+
+    /// Stop listening for the `removed` hotkeys and listen for the `added`
+    /// bindings in one update, since each portal update may ask the user
+    pub fn replace(
+        &mut self,
+        removed: &[&str],
+        added: Vec<HotkeyBinding>,
+    ) -> Result<(), HotkeyError> {
+        let replaced: HashSet<String> = removed
+            .iter()
+            .map(|hotkey| normalize_hotkey(hotkey))
+            .chain(added.iter().map(|b| normalize_hotkey(&b.hotkey)))
+            .collect();
+        let mut bindings: Vec<_> = self
+            .bindings
+            .iter()
+            .filter(|b| !replaced.contains(&normalize_hotkey(&b.hotkey)))
+            .cloned()
+            .collect();
+        bindings.extend(added);
+        self.listener.set_bindings(&bindings)?;
+        self.bindings = bindings;
+        Ok(())
+    }
+
+    /// Whether a binding listens for the hotkey
+    pub fn is_bound(&self, hotkey: &str) -> bool {
+        let hotkey = normalize_hotkey(hotkey);
+        self.bindings
+            .iter()
+            .any(|b| normalize_hotkey(&b.hotkey) == hotkey)
+    }
+}
+
+/// Whether the desktop session runs on Wayland
+pub fn is_wayland_session() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"))
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// The backend for this session: the plugin on X11, on Wayland the portal
+/// if the desktop has one, else evdev if a keyboard is readable
+fn choose_backend(wayland: bool, portal: bool, evdev: bool) -> HotkeyBackend {
+    if !wayland {
+        HotkeyBackend::GlobalShortcut
+    } else if portal {
+        HotkeyBackend::Portal
+    } else if evdev {
+        HotkeyBackend::Evdev
+    } else {
+        HotkeyBackend::GlobalShortcut
+    }
+}
+
+/// Probe the session and pick its backend, see [`choose_backend`]
+pub fn select_backend() -> HotkeyBackend {
+    let wayland = is_wayland_session();
+    let portal = wayland && portal::is_available();
+    let evdev = wayland && !portal && evdev::is_available();
+    let backend = choose_backend(wayland, portal, evdev);
+
+    if wayland && backend == HotkeyBackend::GlobalShortcut {
+        tracing::warn!(
+            "No GlobalShortcuts portal and no readable keyboard in /dev/input: hotkeys only \
+             work while an XWayland window has focus. Joining the input group enables them."
+        );
+    }
+    backend
+}
+
+/// Start the listener of a native backend
+pub fn start_listener(
+    backend: HotkeyBackend,
+    callback: HotkeyCallback,
+) -> Result<Box<dyn HotkeyListener>, HotkeyError> {
+    match backend {
+        HotkeyBackend::Portal => Ok(Box::new(portal::PortalListener::start(callback)?)),
+        HotkeyBackend::Evdev => Ok(Box::new(evdev::EvdevListener::start(callback)?)),
+        HotkeyBackend::GlobalShortcut => Err(HotkeyError::RegistrationFailed(
+            "the global-shortcut plugin has no native listener".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records every set of hotkeys it was asked to listen for
+    struct FakeListener(Arc<Mutex<Vec<Vec<String>>>>);
+
+    impl HotkeyListener for FakeListener {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn set_bindings(&mut self, bindings: &[HotkeyBinding]) -> Result<(), HotkeyError> {
+            for binding in bindings {
+                Chord::parse(&binding.hotkey)?;
+            }
+            let hotkeys = bindings.iter().map(|b| b.hotkey.clone()).collect();
+            self.0.lock().unwrap().push(hotkeys);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_choose_backend() {
+        assert_eq!(
+            choose_backend(false, true, true),
+            HotkeyBackend::GlobalShortcut
+        );
+        assert_eq!(choose_backend(true, true, true), HotkeyBackend::Portal);
+        assert_eq!(choose_backend(true, false, true), HotkeyBackend::Evdev);
+        assert_eq!(
+            choose_backend(true, false, false),
+            HotkeyBackend::GlobalShortcut
+        );
+    }
+
+    #[test]
+    fn test_native_hotkeys_bind_and_unbind() {
+        let bound = Arc::new(Mutex::new(Vec::new()));
+        let mut native = NativeHotkeys::new(Box::new(FakeListener(bound.clone())));
+
+        native
+            .bind(HotkeyBinding::primary("Ctrl+Shift+Space"))
+            .unwrap();
+        native.bind(HotkeyBinding::primary("Ctrl+Alt+T")).unwrap();
+        // The same shortcut spelled differently replaces the binding
+        native
+            .bind(HotkeyBinding::primary("Shift+Control+Space"))
+            .unwrap();
+        assert_eq!(
+            bound.lock().unwrap().last().unwrap(),
+            &["Ctrl+Alt+T", "Shift+Control+Space"]
+        );
+        assert!(native.is_bound("Ctrl+Shift+Space"));
+
+        // A rejected binding leaves the others in place
+        assert!(native
+            .bind(HotkeyBinding::primary("Ctrl+Nonsense"))
+            .is_err());
+        assert!(!native.is_bound("Ctrl+Nonsense"));
+
+        native.unbind("Alt+Ctrl+T").unwrap();
+        assert_eq!(
+            bound.lock().unwrap().last().unwrap(),
+            &["Shift+Control+Space"]
+        );
+        assert!(matches!(
+            native.unbind("Ctrl+Alt+T"),
+            Err(HotkeyError::NotRegistered)
+        ));
+    }
+
+    #[test]
+    fn test_native_hotkeys_replace_in_one_update() {
+        let bound = Arc::new(Mutex::new(Vec::new()));
+        let mut native = NativeHotkeys::new(Box::new(FakeListener(bound.clone())));
+        native
+            .bind(HotkeyBinding::primary("Ctrl+Shift+Space"))
+            .unwrap();
+        native.bind(HotkeyBinding::primary("Ctrl+Alt+T")).unwrap();
+
+        native
+            .replace(
+                &["Ctrl+Alt+T"],
+                vec![
+                    HotkeyBinding::primary("Ctrl+Alt+U"),
+                    HotkeyBinding::primary("Ctrl+Alt+V"),
+                ],
+            )
+            .unwrap();
+        let updates = bound.lock().unwrap();
+        assert_eq!(updates.len(), 3);
+        assert_eq!(updates[2], ["Ctrl+Shift+Space", "Ctrl+Alt+U", "Ctrl+Alt+V"]);
+    }
+}
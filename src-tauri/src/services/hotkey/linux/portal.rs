@@ -0,0 +1,272 @@
+//! Hotkeys through the xdg-desktop-portal GlobalShortcuts interface
+//!
+//! KDE Plasma and GNOME 48+ implement the portal. The compositor owns the
+//! shortcuts: it may ask the user to confirm or change the triggers, then
+//! reports presses and releases per shortcut id. The session lives on its
+//! own thread with its own async runtime, like the RemoteDesktop keyboard.
+
+use super::{Chord, HotkeyCallback, HotkeyListener};
+use crate::services::hotkey::{normalize_hotkey, HotkeyBinding, HotkeyError, HotkeyEvent};
+use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+use ashpd::desktop::Session;
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::mpsc as async_mpsc;
+
+/// How long the portal may take to answer whether it is there
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long the user has to answer the compositor's shortcut dialog
+const BIND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A binding with its shortcut id and preferred trigger
+#[derive(Clone)]
+struct PortalShortcut {
+    id: String,
+    trigger: String,
+    binding: HotkeyBinding,
+}
+
+/// A set of shortcuts to bind and where to report the outcome
+struct Update {
+    shortcuts: Vec<PortalShortcut>,
+    done: mpsc::Sender<Result<(), String>>,
+}
+
+fn portal_error(e: impl std::fmt::Display) -> HotkeyError {
+    HotkeyError::RegistrationFailed(format!("GlobalShortcuts portal: {}", e))
+}
+
+/// Run `future` on a fresh thread with its own runtime, so this also works
+/// from inside the app's runtime
+fn block_on_thread<T: Send + 'static>(
+    name: &str,
+    future: impl std::future::Future<Output = T> + Send + 'static,
+) -> Option<T> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            if let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                let _ = sender.send(runtime.block_on(future));
+            }
+        })
+        .ok()?;
+    receiver.recv_timeout(CONNECT_TIMEOUT).ok()
+}
+
+/// Whether the desktop portal implements GlobalShortcuts
+pub fn is_available() -> bool {
+    block_on_thread("global-shortcuts-probe", async {
+        GlobalShortcuts::new().await.is_ok()
+    })
+    .unwrap_or(false)
+}
+
+/// Hotkeys bound through the GlobalShortcuts portal
+pub struct PortalListener {
+    updates: async_mpsc::UnboundedSender<Update>,
+}
+
+impl PortalListener {
+    /// Connect to the portal and report hotkey events to `callback`
+    pub fn start(callback: HotkeyCallback) -> Result<Self, HotkeyError> {
+        let (updates, receiver) = async_mpsc::unbounded_channel();
+        let (events, event_receiver) = mpsc::channel::<(HotkeyBinding, HotkeyEvent)>();
+        let (started, start_result) = mpsc::channel();
+
+        // Hotkey handling blocks on the app's runtime, which it can't do on
+        // the thread driving the portal's runtime
+        std::thread::Builder::new()
+            .name("global-shortcuts-events".to_string())
+            .spawn(move || {
+                for (binding, event) in event_receiver {
+                    callback(&binding, event);
+                }
+            })
+            .map_err(portal_error)?;
+
+        std::thread::Builder::new()
+            .name("global-shortcuts-portal".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = started.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                runtime.block_on(run_session(receiver, events, started));
+            })
+            .map_err(portal_error)?;
+
+        match start_result.recv_timeout(CONNECT_TIMEOUT) {
+            Ok(Ok(())) => Ok(Self { updates }),
+            Ok(Err(e)) => Err(portal_error(e)),
+            Err(_) => Err(portal_error("no answer from the portal")),
+        }
+    }
+}
+
+/// Create a session and bind the shortcuts in it. The compositor may show
+/// a dialog first, so this can take as long as the user needs.
+async fn bind_shortcuts(
+    proxy: &GlobalShortcuts<'static>,
+    shortcuts: &[PortalShortcut],
+) -> Result<Session<'static, GlobalShortcuts<'static>>, ashpd::Error> {
+    let session = proxy.create_session().await?;
+    let new_shortcuts: Vec<_> = shortcuts
+        .iter()
+        .map(|shortcut| {
+            NewShortcut::new(shortcut.id.as_str(), shortcut.binding.name.as_str())
+                .preferred_trigger(shortcut.trigger.as_str())
+        })
+        .collect();
+
+    let response = proxy
+        .bind_shortcuts(&session, &new_shortcuts, None)
+        .await?
+        .response()?;
+    for shortcut in response.shortcuts() {
+        tracing::info!(
+            "GlobalShortcuts portal bound '{}' to {}",
+            shortcut.description(),
+            shortcut.trigger_description()
+        );
+    }
+    Ok(session)
+}
+
+/// Connect, report whether that worked, then rebind on every update and
+/// forward presses and releases until the listener is dropped. When an
+/// update fails to bind, the shortcuts bound before are bound again.
+async fn run_session(
+    mut updates: async_mpsc::UnboundedReceiver<Update>,
+    events: mpsc::Sender<(HotkeyBinding, HotkeyEvent)>,
+    started: mpsc::Sender<Result<(), String>>,
+) {
+    let connection = async {
+        let proxy = GlobalShortcuts::new().await?;
+        let activated = proxy.receive_activated().await?;
+        let deactivated = proxy.receive_deactivated().await?;
+        Ok::<_, ashpd::Error>((proxy, activated, deactivated))
+    }
+    .await;
+
+    let (proxy, activated, deactivated) = match connection {
+        Ok(connection) => {
+            let _ = started.send(Ok(()));
+            connection
+        }
+        Err(e) => {
+            let _ = started.send(Err(e.to_string()));
+            return;
+        }
+    };
+    tokio::pin!(activated, deactivated);
+    tracing::debug!("GlobalShortcuts portal connected");
+
+    let mut session: Option<Session<'static, GlobalShortcuts<'static>>> = None;
+    let mut current: Vec<PortalShortcut> = Vec::new();
+    let mut bound: HashMap<String, HotkeyBinding> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => {
+                let Some(Update { shortcuts, done }) = update else {
+                    break;
+                };
+                // Each set of shortcuts gets a fresh session, since portals
+                // don't have to support binding twice in one session
+                if let Some(old) = session.take() {
+                    let _ = old.close().await;
+                }
+                bound.clear();
+                let outcome = if shortcuts.is_empty() {
+                    Ok(())
+                } else {
+                    bind_shortcuts(&proxy, &shortcuts).await.map(|new_session| {
+                        session = Some(new_session);
+                    })
+                };
+                let outcome = match outcome {
+                    Ok(()) => {
+                        current = shortcuts;
+                        Ok(())
+                    }
+                    Err(e) => {
+                        tracing::error!("GlobalShortcuts portal: failed to bind: {}", e);
+                        if !current.is_empty() {
+                            match bind_shortcuts(&proxy, &current).await {
+                                Ok(old_session) => session = Some(old_session),
+                                Err(e) => {
+                                    tracing::error!(
+                                        "GlobalShortcuts portal: failed to rebind: {}",
+                                        e
+                                    );
+                                    current.clear();
+                                }
+                            }
+                        }
+                        Err(e.to_string())
+                    }
+                };
+                bound = current
+                    .iter()
+                    .map(|shortcut| (shortcut.id.clone(), shortcut.binding.clone()))
+                    .collect();
+                let _ = done.send(outcome);
+            }
+            Some(event) = activated.next() => {
+                if let Some(binding) = bound.get(event.shortcut_id()) {
+                    let _ = events.send((binding.clone(), HotkeyEvent::Pressed));
+                }
+            }
+            Some(event) = deactivated.next() => {
+                if let Some(binding) = bound.get(event.shortcut_id()) {
+                    let _ = events.send((binding.clone(), HotkeyEvent::Released));
+                }
+            }
+        }
+    }
+
+    if let Some(session) = session {
+        let _ = session.close().await;
+    }
+}
+
+impl HotkeyListener for PortalListener {
+    fn name(&self) -> &'static str {
+        "GlobalShortcuts portal"
+    }
+
+    fn set_bindings(&mut self, bindings: &[HotkeyBinding]) -> Result<(), HotkeyError> {
+        let shortcuts = bindings
+            .iter()
+            .map(|binding| {
+                Ok(PortalShortcut {
+                    id: normalize_hotkey(&binding.hotkey),
+                    trigger: Chord::parse(&binding.hotkey)?.xdg_trigger(),
+                    binding: binding.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, HotkeyError>>()?;
+        let (done, result) = mpsc::channel();
+        self.updates
+            .send(Update { shortcuts, done })
+            .map_err(|_| portal_error("session closed"))?;
+        // Binding may wait for the user to confirm the shortcuts
+        match result.recv_timeout(BIND_TIMEOUT) {
+            Ok(outcome) => outcome.map_err(portal_error),
+            Err(_) => Err(portal_error("no answer to the shortcut dialog")),
+        }
+    }
+}
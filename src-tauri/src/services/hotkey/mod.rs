@@ -1,23 +1,46 @@
 //! Global hotkey service
 //!
 //! Handles global keyboard shortcuts for push-to-talk recording.
-
+//! Shortcuts go through tauri-plugin-global-shortcut, except on Wayland,
+//! where the plugin can't see key presses and a native backend listens.
+//! Lone modifiers and mouse buttons are input triggers, read from the input
+//! devices on Linux.
+
+pub mod bindings;
+#[cfg(target_os = "linux")]
+pub mod linux;
+pub mod trigger;
+
+pub use bindings::{
+    normalize_hotkey, validate_bindings, DictationProfile, HotkeyAction, HotkeyBinding,
+};
+
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 use thiserror::Error;
 use tokio::sync::RwLock;
+use trigger::{TapAction, TapDetector, DOUBLE_TAP_WINDOW};
 
 use crate::commands::audio::{AudioCommand, AudioResponse, AudioState};
+use crate::commands::workflow::execute_commands;
 use crate::commands::TranscriptionState;
-use crate::models::HistoryEntry;
-use crate::services::audio::processing::resample_for_whisper;
-use crate::services::storage::{DatabaseState, SettingsState};
+use crate::models::{HistoryEntry, HistoryMetadata, HistorySource, Settings};
+use crate::services::platform::{active_window, find_profile, ActiveWindow, TextInjectorState};
+use crate::services::sinks::{self, DictationSink};
+use crate::services::storage::{archive, DatabaseState, SettingsState};
 use crate::services::streaming::SharedStreamingService;
+use crate::services::text_normalization::TextNormalizer;
+use crate::services::text_replacements::TextReplacer;
+use crate::services::transcription::{
+    build_initial_prompt, DecodeOptions, PartialTranscription, TranscriptionError, TranscriptionJob,
+};
+use crate::services::voice_commands::CommandConfig;
 use chrono::Utc;
-use tauri_plugin_clipboard_manager::ClipboardExt;
 
 /// Errors that can occur during hotkey operations
 #[derive(Error, Debug)]
@@ -33,13 +56,50 @@ pub enum HotkeyError {
 
     #[error("Hotkey not registered")]
     NotRegistered,
+
+    #[error("Hotkey '{hotkey}' is bound to both '{first}' and '{second}'")]
+    Duplicate {
+        hotkey: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("Dictation profile '{0}' does not exist")]
+    UnknownProfile(String),
+}
+
+/// Where global hotkeys come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyBackend {
+    /// tauri-plugin-global-shortcut (X11, macOS and Windows)
+    GlobalShortcut,
+    /// The XDG GlobalShortcuts portal on Wayland
+    Portal,
+    /// Keyboards read from /dev/input on Wayland
+    Evdev,
+}
+
+/// What happened to a bound hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyEvent {
+    Pressed,
+    Released,
+    /// The hotkey turned out to be part of another shortcut, e.g. Right Ctrl
+    /// held for Ctrl+C
+    Cancelled,
 }
 
 /// Minimum recording duration in milliseconds before release is processed
 /// This prevents accidental immediate release when pressing key combinations
 const MIN_RECORDING_DURATION_MS: u64 = 200;
 
+/// Transcription of a recording's windows running while it is recorded
+type BackgroundTranscription =
+    tauri::async_runtime::JoinHandle<Result<PartialTranscription, TranscriptionError>>;
+
 /// Hotkey service state
+#[derive(Clone)]
 pub struct HotkeyState {
     /// Currently registered hotkey string
     pub current_hotkey: Arc<RwLock<Option<String>>>,
@@ -53,6 +113,22 @@ pub struct HotkeyState {
     pub recording_start_time: Arc<AtomicU64>,
     /// Whether streaming mode is active for current recording
     pub is_streaming_active: Arc<AtomicBool>,
+    /// Further bindings currently registered
+    pub bindings: Arc<Mutex<Vec<HotkeyBinding>>>,
+    /// Binding that started the current recording
+    pub active_binding: Arc<Mutex<Option<HotkeyBinding>>>,
+    /// Transcription of the current recording's windows while it continues
+    background_transcription: Arc<Mutex<Option<BackgroundTranscription>>>,
+    /// Backend chosen on first registration
+    backend: Arc<OnceLock<HotkeyBackend>>,
+    /// Tap state of each input trigger, by normalized hotkey
+    taps: Arc<Mutex<HashMap<String, TapDetector>>>,
+    /// Listener of a native backend, started on first registration
+    #[cfg(target_os = "linux")]
+    native: Arc<Mutex<Option<linux::NativeHotkeys>>>,
+    /// Evdev listener for input triggers when the backend is another one
+    #[cfg(target_os = "linux")]
+    input: Arc<Mutex<Option<linux::NativeHotkeys>>>,
 }
 
 impl Default for HotkeyState {
@@ -64,10 +140,35 @@ impl Default for HotkeyState {
             last_error: Arc::new(RwLock::new(None)),
             recording_start_time: Arc::new(AtomicU64::new(0)),
             is_streaming_active: Arc::new(AtomicBool::new(false)),
+            bindings: Arc::new(Mutex::new(Vec::new())),
+            active_binding: Arc::new(Mutex::new(None)),
+            background_transcription: Arc::new(Mutex::new(None)),
+            backend: Arc::new(OnceLock::new()),
+            taps: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(target_os = "linux")]
+            native: Arc::new(Mutex::new(None)),
+            #[cfg(target_os = "linux")]
+            input: Arc::new(Mutex::new(None)),
         }
     }
 }
 
+impl HotkeyState {
+    /// The backend hotkeys are registered with, chosen on first use
+    pub fn backend(&self) -> HotkeyBackend {
+        *self.backend.get_or_init(|| {
+            #[cfg(target_os = "linux")]
+            {
+                linux::select_backend()
+            }
+            #[cfg(not(target_os = "linux"))]
+            {
+                HotkeyBackend::GlobalShortcut
+            }
+        })
+    }
+}
+
 /// Get current time in milliseconds since epoch
 fn current_time_ms() -> u64 {
     SystemTime::now()
@@ -95,7 +196,7 @@ fn parse_shortcut(hotkey: &str) -> Result<Shortcut, HotkeyError> {
         .map_err(|e| HotkeyError::InvalidFormat(format!("{:?}", e)))
 }
 
-/// Register the global hotkey with push-to-talk behavior
+/// Register the main dictation hotkey with push-to-talk behavior
 pub fn register_hotkey<R: Runtime>(
     app: &AppHandle<R>,
     hotkey: &str,
@@ -103,159 +204,9 @@ pub fn register_hotkey<R: Runtime>(
 ) -> Result<(), HotkeyError> {
     tracing::info!("Registering global hotkey: {}", hotkey);
 
-    // Parse the hotkey string
-    let shortcut = parse_shortcut(hotkey)?;
-
-    let is_recording = state.is_hotkey_recording.clone();
-    let recording_start_time = state.recording_start_time.clone();
-    let is_streaming_active = state.is_streaming_active.clone();
+    check_hotkey_conflict(hotkey, state)?;
 
-    // Register with the global shortcut manager
-    let result = app
-        .global_shortcut()
-        .on_shortcut(shortcut, move |app, _shortcut, event| match event.state {
-            ShortcutState::Pressed => {
-                println!("\n\n========== HOTKEY PRESSED ==========\n");
-                println!("[Hotkey] is_recording={}", is_recording.load(Ordering::SeqCst));
-                tracing::info!("========== HOTKEY PRESSED ==========");
-                tracing::info!("[Hotkey] is_recording={}", is_recording.load(Ordering::SeqCst));
-                if !is_recording.load(Ordering::SeqCst) {
-                    tracing::info!("[Hotkey] Starting recording sequence...");
-                    is_recording.store(true, Ordering::SeqCst);
-                    // Store the start time
-                    recording_start_time.store(current_time_ms(), Ordering::SeqCst);
-
-                    // Check if streaming is enabled in settings
-                    let settings_state = app.state::<SettingsState>();
-                    let streaming_enabled = settings_state.get_streaming_enabled_sync();
-                    let streaming_mode = settings_state.get_streaming_mode_sync();
-
-                    // Get audio state
-                    let audio_state = app.state::<AudioState>();
-
-                    // Enable streaming mode if configured
-                    if streaming_enabled {
-                        tracing::info!("Streaming mode enabled");
-                        is_streaming_active.store(true, Ordering::SeqCst);
-
-                        // Enable streaming on audio capture
-                        if let Err(e) = audio_state.send_command(AudioCommand::EnableStreaming) {
-                            tracing::warn!("Failed to enable streaming: {:?}", e);
-                            is_streaming_active.store(false, Ordering::SeqCst);
-                        }
-
-                        // Start streaming session
-                        let streaming_service = app.state::<SharedStreamingService>();
-                        let service = streaming_service.get();
-                        let mode = streaming_mode;
-                        tauri::async_runtime::spawn(async move {
-                            service.start(mode).await;
-                        });
-
-                        // Spawn chunk processing task
-                        let app_for_chunks = app.clone();
-                        let is_recording_clone = is_recording.clone();
-                        tauri::async_runtime::spawn(async move {
-                            process_streaming_chunks(app_for_chunks, is_recording_clone).await;
-                        });
-                    } else {
-                        is_streaming_active.store(false, Ordering::SeqCst);
-                    }
-
-                    // Actually start recording
-                    tracing::info!("[Hotkey] About to send AudioCommand::Start");
-                    match audio_state.send_command(AudioCommand::Start) {
-                        Ok(AudioResponse::Ok) => {
-                            tracing::info!("[Hotkey] Recording started successfully from hotkey");
-                            println!("[Hotkey] Recording started successfully");
-
-                            // Show the indicator window directly
-                            if let Some(window) = app.get_webview_window("recording-indicator") {
-                                tracing::info!("[Hotkey] Showing indicator window");
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            } else {
-                                tracing::warn!("[Hotkey] Indicator window not found!");
-                            }
-
-                            // Start emitting audio levels
-                            tracing::info!("[Hotkey] Starting level emitter...");
-                            if let Err(e) = audio_state.start_level_emitter(app.clone()) {
-                                tracing::error!("[Hotkey] Failed to start level emitter: {}", e);
-                            } else {
-                                tracing::info!("[Hotkey] Level emitter started successfully");
-                            }
-                            // Emit events for tray update and UI
-                            tracing::info!("[Hotkey] Emitting recording events");
-                            let _ = app.emit("hotkey://recording-started", ());
-                            let _ = app.emit("tray://update-recording-state", true);
-                            let _ = app.emit("workflow://state-changed", "recording");
-
-                            // Also emit directly to the indicator window
-                            if let Some(window) = app.get_webview_window("recording-indicator") {
-                                let _ = window.emit("workflow://state-changed", "recording");
-                                let _ = window.emit("hotkey://recording-started", ());
-                            }
-                        }
-                        Ok(AudioResponse::Error(e)) => {
-                            tracing::error!("Failed to start recording from hotkey: {}", e);
-                            is_recording.store(false, Ordering::SeqCst);
-                            is_streaming_active.store(false, Ordering::SeqCst);
-                        }
-                        Ok(_) => {
-                            tracing::error!(
-                                "Unexpected response when starting recording from hotkey"
-                            );
-                            is_recording.store(false, Ordering::SeqCst);
-                            is_streaming_active.store(false, Ordering::SeqCst);
-                        }
-                        Err(e) => {
-                            tracing::error!("Error starting recording from hotkey: {}", e);
-                            is_recording.store(false, Ordering::SeqCst);
-                            is_streaming_active.store(false, Ordering::SeqCst);
-                        }
-                    }
-                }
-            }
-            ShortcutState::Released => {
-                if is_recording.load(Ordering::SeqCst) {
-                    // Check if minimum recording duration has passed
-                    let start_time = recording_start_time.load(Ordering::SeqCst);
-                    let elapsed = current_time_ms().saturating_sub(start_time);
-
-                    if elapsed < MIN_RECORDING_DURATION_MS {
-                        tracing::debug!(
-                            "Ignoring early release ({}ms < {}ms minimum)",
-                            elapsed,
-                            MIN_RECORDING_DURATION_MS
-                        );
-                        return;
-                    }
-
-                    tracing::info!("Hotkey released after {}ms - stopping recording", elapsed);
-                    let was_streaming = is_streaming_active.load(Ordering::SeqCst);
-                    is_recording.store(false, Ordering::SeqCst);
-                    is_streaming_active.store(false, Ordering::SeqCst);
-
-                    // Emit event for tray update
-                    let _ = app.emit("tray://update-recording-state", false);
-
-                    // Stop recording and transcribe
-                    let audio_state = app.state::<AudioState>();
-                    audio_state.stop_level_emitter();
-
-                    if was_streaming {
-                        // Handle streaming completion
-                        handle_streaming_completion(app.clone());
-                    } else {
-                        // Use batch transcription (existing logic)
-                        handle_batch_transcription(app.clone());
-                    }
-                }
-            }
-        });
-
-    match result {
+    match register_binding(app, HotkeyBinding::primary(hotkey), state) {
         Ok(_) => {
             state.is_registered.store(true, Ordering::SeqCst);
             // Update current hotkey
@@ -268,27 +219,636 @@ pub fn register_hotkey<R: Runtime>(
             Ok(())
         }
         Err(e) => {
-            let error_str = e.to_string();
             state.is_registered.store(false, Ordering::SeqCst);
 
             // Store error for later retrieval
             let last_error = state.last_error.clone();
-            let err_clone = error_str.clone();
+            let err_clone = e.to_string();
             tauri::async_runtime::spawn(async move {
                 *last_error.write().await = Some(err_clone);
             });
+            Err(e)
+        }
+    }
+}
+
+/// Check that the main hotkey doesn't take a shortcut another binding uses
+pub fn check_hotkey_conflict(hotkey: &str, state: &HotkeyState) -> Result<(), HotkeyError> {
+    let normalized = normalize_hotkey(hotkey);
+    match state
+        .bindings
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|b| normalize_hotkey(&b.hotkey) == normalized)
+    {
+        Some(other) => Err(HotkeyError::Duplicate {
+            hotkey: hotkey.to_string(),
+            first: other.name.clone(),
+            second: HotkeyBinding::primary(hotkey).name,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Register further hotkey bindings, replacing the ones registered before.
+/// The bindings are checked for conflicts with each other and `primary`
+/// first. Bindings that fail to register are reported together while the
+/// others stay active.
+pub fn register_bindings<R: Runtime>(
+    app: &AppHandle<R>,
+    primary: &str,
+    bindings: &[HotkeyBinding],
+    profiles: &[DictationProfile],
+    state: &HotkeyState,
+) -> Result<(), HotkeyError> {
+    validate_bindings(primary, bindings, profiles)?;
+    let previous = std::mem::take(&mut *state.bindings.lock().unwrap());
+    let enabled: Vec<_> = bindings.iter().filter(|b| b.enabled).cloned().collect();
+
+    let mut registered = Vec::new();
+    let mut failures = Vec::new();
+    let mut record = |binding: HotkeyBinding, result: Result<(), String>| match result {
+        Ok(()) => {
+            tracing::info!(
+                "Hotkey binding '{}' registered: {}",
+                binding.name,
+                binding.hotkey
+            );
+            registered.push(binding);
+        }
+        Err(e) => failures.push(format!("{}: {}", binding.name, e)),
+    };
+
+    #[cfg(target_os = "linux")]
+    let (previous, enabled) = replace_native_bindings(app, state, previous, enabled, &mut record);
+    unregister_plugin_bindings(app, state, previous);
+    for binding in enabled {
+        let result = register_binding(app, binding.clone(), state).map_err(|e| e.to_string());
+        record(binding, result);
+    }
+    *state.bindings.lock().unwrap() = registered;
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(HotkeyError::RegistrationFailed(failures.join("; ")))
+    }
+}
+
+/// Unregister all further hotkey bindings
+pub fn unregister_bindings<R: Runtime>(app: &AppHandle<R>, state: &HotkeyState) {
+    let previous = std::mem::take(&mut *state.bindings.lock().unwrap());
+    #[cfg(target_os = "linux")]
+    let (previous, _) = replace_native_bindings(app, state, previous, Vec::new(), &mut |_, _| {});
+    unregister_plugin_bindings(app, state, previous);
+}
+
+/// Unregister bindings of the global-shortcut plugin, or any left over
+fn unregister_plugin_bindings<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    bindings: Vec<HotkeyBinding>,
+) {
+    for binding in bindings {
+        if let Err(e) = unregister_shortcut(app, state, &binding.hotkey) {
+            tracing::warn!(
+                "Failed to unregister hotkey binding '{}': {}",
+                binding.name,
+                e
+            );
+        }
+    }
+}
+
+/// Swap the `previous` bindings of each native listener for the `added`
+/// ones in a single update, since every portal update opens a session that
+/// may ask the user to confirm. Reports each added binding to `record` and
+/// returns the previous and added bindings left for the plugin.
+#[cfg(target_os = "linux")]
+fn replace_native_bindings<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    mut previous: Vec<HotkeyBinding>,
+    mut added: Vec<HotkeyBinding>,
+    record: &mut impl FnMut(HotkeyBinding, Result<(), String>),
+) -> (Vec<HotkeyBinding>, Vec<HotkeyBinding>) {
+    for listener in [Listener::Native, Listener::Input] {
+        let on_listener = |b: &HotkeyBinding| listener_for(state, &b.hotkey) == Some(listener);
+        let removed: Vec<_>;
+        let mut adding: Vec<_>;
+        (removed, previous) = previous.into_iter().partition(on_listener);
+        (adding, added) = added.into_iter().partition(on_listener);
+
+        // A malformed hotkey fails on its own rather than the whole update
+        adding.retain(|binding| match linux::Chord::parse(&binding.hotkey) {
+            Ok(_) => true,
+            Err(e) => {
+                record(binding.clone(), Err(e.to_string()));
+                false
+            }
+        });
+        if removed.is_empty() && adding.is_empty() {
+            continue;
+        }
+
+        let hotkeys: Vec<&str> = removed.iter().map(|b| b.hotkey.as_str()).collect();
+        let result = with_listener(app, state, listener, |native| {
+            native.replace(&hotkeys, adding.clone())
+        });
+        match result {
+            Ok(()) => adding.into_iter().for_each(|b| record(b, Ok(()))),
+            Err(e) => {
+                let e = e.to_string();
+                adding.into_iter().for_each(|b| record(b, Err(e.clone())));
+            }
+        }
+    }
+    (previous, added)
+}
+
+/// Register one binding's shortcut with push-to-talk behavior
+fn register_binding<R: Runtime>(
+    app: &AppHandle<R>,
+    binding: HotkeyBinding,
+    state: &HotkeyState,
+) -> Result<(), HotkeyError> {
+    #[cfg(target_os = "linux")]
+    if let Some(result) = with_native(app, state, &binding.hotkey, |native| {
+        native.bind(binding.clone())
+    }) {
+        return result;
+    }
+    #[cfg(not(target_os = "linux"))]
+    if trigger::is_input_trigger(&binding.hotkey) {
+        return Err(HotkeyError::InvalidFormat(format!(
+            "'{}' needs the input listener, which is only available on Linux",
+            binding.hotkey
+        )));
+    }
+
+    // Parse the hotkey string
+    let shortcut = parse_shortcut(&binding.hotkey)?;
+    let hotkey = binding.hotkey.clone();
+    let handler_state = state.clone();
+
+    // Register with the global shortcut manager
+    let result = app
+        .global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            let event = match event.state {
+                ShortcutState::Pressed => HotkeyEvent::Pressed,
+                ShortcutState::Released => HotkeyEvent::Released,
+            };
+            handle_shortcut_event(app, &handler_state, &binding, event)
+        });
+
+    result.map_err(|e| {
+        let error_str = e.to_string();
+        if error_str.contains("already") || error_str.contains("conflict") {
+            tracing::warn!("Hotkey conflict detected: {}", hotkey);
+            HotkeyError::Conflict(hotkey)
+        } else {
+            tracing::error!("Failed to register hotkey: {}", error_str);
+            HotkeyError::RegistrationFailed(error_str)
+        }
+    })
+}
+
+/// Start recording when a binding's hotkey goes down and stop when it comes
+/// back up, whichever backend reported it
+fn handle_shortcut_event<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    binding: &HotkeyBinding,
+    event: HotkeyEvent,
+) {
+    if trigger::is_input_trigger(&binding.hotkey) {
+        handle_trigger_event(app, state, binding, event);
+        return;
+    }
+
+    let is_recording = &state.is_hotkey_recording;
+
+    match event {
+        HotkeyEvent::Pressed => {
+            println!(
+                "\n\n========== HOTKEY PRESSED ({}) ==========\n",
+                binding.name
+            );
+            println!(
+                "[Hotkey] is_recording={}",
+                is_recording.load(Ordering::SeqCst)
+            );
+            tracing::info!("========== HOTKEY PRESSED ==========");
+            tracing::info!(
+                "[Hotkey] is_recording={}",
+                is_recording.load(Ordering::SeqCst)
+            );
+            if !is_recording.load(Ordering::SeqCst) {
+                start_recording(app, state, binding);
+            }
+        }
+        HotkeyEvent::Released => {
+            // Only the hotkey that started the recording stops it
+            if is_recording_for(state, binding) {
+                // Check if minimum recording duration has passed
+                let start_time = state.recording_start_time.load(Ordering::SeqCst);
+                let elapsed = current_time_ms().saturating_sub(start_time);
+
+                if elapsed < MIN_RECORDING_DURATION_MS {
+                    tracing::debug!(
+                        "Ignoring early release ({}ms < {}ms minimum)",
+                        elapsed,
+                        MIN_RECORDING_DURATION_MS
+                    );
+                    return;
+                }
+
+                tracing::info!("Hotkey released after {}ms - stopping recording", elapsed);
+                stop_recording(app, state, binding);
+            }
+        }
+        HotkeyEvent::Cancelled => cancel_recording(app, state, binding),
+    }
+}
+
+/// Push-to-talk for input triggers: hold to record, or double-tap to keep
+/// recording until the next press
+fn handle_trigger_event<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    binding: &HotkeyBinding,
+    event: HotkeyEvent,
+) {
+    let key = normalize_hotkey(&binding.hotkey);
+    let now = Instant::now();
+    let action = {
+        let mut taps = state.taps.lock().unwrap();
+        let tap = taps.entry(key.clone()).or_default();
+        match event {
+            HotkeyEvent::Pressed => {
+                // The recording may have ended elsewhere, e.g. from the tray
+                if !state.is_hotkey_recording.load(Ordering::SeqCst) {
+                    tap.reset();
+                }
+                tap.press(now)
+            }
+            HotkeyEvent::Released => tap.release(now),
+            HotkeyEvent::Cancelled => {
+                tap.reset();
+                TapAction::Cancel
+            }
+        }
+    };
+
+    match action {
+        TapAction::Start => {
+            tracing::info!("Input trigger '{}' pressed", binding.name);
+            if !state.is_hotkey_recording.load(Ordering::SeqCst) {
+                start_recording(app, state, binding);
+            }
+        }
+        TapAction::Stop => {
+            if is_recording_for(state, binding) {
+                tracing::info!("Input trigger '{}' - stopping recording", binding.name);
+                stop_recording(app, state, binding);
+            }
+        }
+        TapAction::Cancel => cancel_recording(app, state, binding),
+        TapAction::AwaitSecondTap => {
+            let app = app.clone();
+            let state = state.clone();
+            let binding = binding.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(DOUBLE_TAP_WINDOW);
+                let action = state
+                    .taps
+                    .lock()
+                    .unwrap()
+                    .get_mut(&key)
+                    .map_or(TapAction::None, |tap| tap.expire(Instant::now()));
+                if action == TapAction::Cancel {
+                    cancel_recording(&app, &state, &binding);
+                }
+            });
+        }
+        TapAction::Latch => tracing::info!(
+            "Input trigger '{}' double-tapped - recording until the next press",
+            binding.name
+        ),
+        TapAction::None => {}
+    }
+}
+
+/// Whether a recording is running that this binding may stop: the one it
+/// started, or one without a binding
+fn is_recording_for(state: &HotkeyState, binding: &HotkeyBinding) -> bool {
+    state.is_hotkey_recording.load(Ordering::SeqCst)
+        && !state
+            .active_binding
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|a| a.hotkey != binding.hotkey)
+}
+
+/// Start recording for a binding, streaming if the settings ask for it
+fn start_recording<R: Runtime>(app: &AppHandle<R>, state: &HotkeyState, binding: &HotkeyBinding) {
+    let is_recording = &state.is_hotkey_recording;
+    let recording_start_time = &state.recording_start_time;
+    let is_streaming_active = &state.is_streaming_active;
+    let active_binding = &state.active_binding;
+
+    tracing::info!("[Hotkey] Starting recording sequence...");
+    is_recording.store(true, Ordering::SeqCst);
+    // Store the start time
+    recording_start_time.store(current_time_ms(), Ordering::SeqCst);
+    state.background_transcription.lock().unwrap().take();
+
+    // Check if streaming is enabled in settings. Bindings with their
+    // own model, language or translation need a full pass instead.
+    let settings_state = app.state::<SettingsState>();
+    let profiles = settings_state.get_dictation_profiles_sync();
+    let streaming_enabled =
+        settings_state.get_streaming_enabled_sync() && !binding.overrides_decoding(&profiles);
+    let streaming_mode = settings_state.get_streaming_mode_sync();
 
-            if error_str.contains("already") || error_str.contains("conflict") {
-                tracing::warn!("Hotkey conflict detected: {}", hotkey);
-                Err(HotkeyError::Conflict(hotkey.to_string()))
+    // Get audio state
+    let audio_state = app.state::<AudioState>();
+
+    // Enable streaming mode if configured
+    if streaming_enabled {
+        tracing::info!("Streaming mode enabled");
+        is_streaming_active.store(true, Ordering::SeqCst);
+
+        // Enable streaming on audio capture
+        if let Err(e) = audio_state.send_command(AudioCommand::EnableStreaming) {
+            tracing::warn!("Failed to enable streaming: {:?}", e);
+            is_streaming_active.store(false, Ordering::SeqCst);
+        }
+
+        // Start streaming session
+        let streaming_service = app.state::<SharedStreamingService>();
+        let service = streaming_service.get();
+        let mode = streaming_mode;
+        tauri::async_runtime::spawn(async move {
+            service.start(mode).await;
+        });
+
+        // Spawn chunk processing task
+        let app_for_chunks = app.clone();
+        let is_recording_clone = is_recording.clone();
+        tauri::async_runtime::spawn(async move {
+            process_streaming_chunks(app_for_chunks, is_recording_clone).await;
+        });
+    } else {
+        is_streaming_active.store(false, Ordering::SeqCst);
+    }
+
+    // Actually start recording
+    tracing::info!("[Hotkey] About to send AudioCommand::Start");
+    match audio_state.send_command(AudioCommand::Start) {
+        Ok(AudioResponse::Ok) => {
+            tracing::info!("[Hotkey] Recording started successfully from hotkey");
+            println!("[Hotkey] Recording started successfully");
+            *active_binding.lock().unwrap() = Some(binding.clone());
+            if !streaming_enabled {
+                start_background_transcription(app, state, binding);
+            }
+
+            // Show the indicator window directly
+            if let Some(window) = app.get_webview_window("recording-indicator") {
+                tracing::info!("[Hotkey] Showing indicator window");
+                let _ = window.show();
+                let _ = window.set_focus();
             } else {
-                tracing::error!("Failed to register hotkey: {}", error_str);
-                Err(HotkeyError::RegistrationFailed(error_str))
+                tracing::warn!("[Hotkey] Indicator window not found!");
             }
+
+            // Start emitting audio levels
+            tracing::info!("[Hotkey] Starting level emitter...");
+            if let Err(e) = audio_state.start_level_emitter(app.clone()) {
+                tracing::error!("[Hotkey] Failed to start level emitter: {}", e);
+            } else {
+                tracing::info!("[Hotkey] Level emitter started successfully");
+            }
+            // Emit events for tray update and UI
+            tracing::info!("[Hotkey] Emitting recording events");
+            let _ = app.emit("hotkey://recording-started", ());
+            let _ = app.emit("tray://update-recording-state", true);
+            let _ = app.emit("workflow://state-changed", "recording");
+
+            // Also emit directly to the indicator window
+            if let Some(window) = app.get_webview_window("recording-indicator") {
+                let _ = window.emit("workflow://state-changed", "recording");
+                let _ = window.emit("hotkey://recording-started", ());
+            }
+        }
+        Ok(AudioResponse::Error(e)) => {
+            tracing::error!("Failed to start recording from hotkey: {}", e);
+            is_recording.store(false, Ordering::SeqCst);
+            is_streaming_active.store(false, Ordering::SeqCst);
+        }
+        Ok(_) => {
+            tracing::error!("Unexpected response when starting recording from hotkey");
+            is_recording.store(false, Ordering::SeqCst);
+            is_streaming_active.store(false, Ordering::SeqCst);
+        }
+        Err(e) => {
+            tracing::error!("Error starting recording from hotkey: {}", e);
+            is_recording.store(false, Ordering::SeqCst);
+            is_streaming_active.store(false, Ordering::SeqCst);
         }
     }
 }
 
+/// Transcribe the windows of a long recording while it continues, so that
+/// only the last one is left when it stops
+fn start_background_transcription<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    binding: &HotkeyBinding,
+) {
+    let (sender, windows) = tokio::sync::mpsc::unbounded_channel();
+    match app
+        .state::<AudioState>()
+        .send_command(AudioCommand::WatchWindows(sender))
+    {
+        Ok(AudioResponse::Ok) => {}
+        Ok(AudioResponse::Error(e)) | Err(e) => {
+            tracing::warn!("Not transcribing while recording: {}", e);
+            return;
+        }
+        Ok(_) => {
+            tracing::warn!("Unexpected response when watching the recording");
+            return;
+        }
+    }
+
+    let settings_state = app.state::<SettingsState>();
+    let settings = tauri::async_runtime::block_on(async { settings_state.get().await });
+    let settings = hotkey_settings(binding, settings, active_window().as_ref());
+    let job = transcription_job(binding, &settings);
+    let engine = app.state::<TranscriptionState>().engine.clone();
+    let task =
+        tauri::async_runtime::spawn(async move { engine.transcribe_windows(windows, job).await });
+    *state.background_transcription.lock().unwrap() = Some(task);
+}
+
+/// Stop recording and transcribe what was recorded
+fn stop_recording<R: Runtime>(app: &AppHandle<R>, state: &HotkeyState, binding: &HotkeyBinding) {
+    let is_recording = &state.is_hotkey_recording;
+    let is_streaming_active = &state.is_streaming_active;
+    let active_binding = &state.active_binding;
+
+    let was_streaming = is_streaming_active.load(Ordering::SeqCst);
+    is_recording.store(false, Ordering::SeqCst);
+    is_streaming_active.store(false, Ordering::SeqCst);
+    let binding = active_binding
+        .lock()
+        .unwrap()
+        .take()
+        .unwrap_or_else(|| binding.clone());
+
+    // Emit event for tray update
+    let _ = app.emit("tray://update-recording-state", false);
+
+    // Stop recording and transcribe
+    let audio_state = app.state::<AudioState>();
+    audio_state.stop_level_emitter();
+
+    let background = state.background_transcription.lock().unwrap().take();
+    if was_streaming {
+        // Handle streaming completion
+        handle_streaming_completion(app.clone(), binding);
+    } else {
+        // Use batch transcription (existing logic)
+        handle_batch_transcription(app.clone(), binding, background);
+    }
+}
+
+/// Stop recording and throw the audio away, e.g. when a lone modifier
+/// turned out to be part of another shortcut
+fn cancel_recording<R: Runtime>(app: &AppHandle<R>, state: &HotkeyState, binding: &HotkeyBinding) {
+    if !is_recording_for(state, binding) {
+        return;
+    }
+    tracing::info!("Hotkey '{}' - discarding recording", binding.name);
+    let was_streaming = state.is_streaming_active.load(Ordering::SeqCst);
+    state.is_hotkey_recording.store(false, Ordering::SeqCst);
+    state.is_streaming_active.store(false, Ordering::SeqCst);
+    state.active_binding.lock().unwrap().take();
+    state.background_transcription.lock().unwrap().take();
+
+    let audio_state = app.state::<AudioState>();
+    audio_state.stop_level_emitter();
+    if let Err(e) = audio_state.send_command(AudioCommand::Stop) {
+        tracing::warn!("Error stopping discarded recording: {}", e);
+    }
+    if was_streaming {
+        let _ = audio_state.send_command(AudioCommand::DisableStreaming);
+        let service = app.state::<SharedStreamingService>().get();
+        tauri::async_runtime::spawn(async move {
+            service.stop().await;
+        });
+    }
+
+    let _ = app.emit("tray://update-recording-state", false);
+    let _ = app.emit("hotkey://recording-stopped", ());
+    let _ = app.emit("workflow://state-changed", "idle");
+    if let Some(window) = app.get_webview_window("recording-indicator") {
+        let _ = window.emit("workflow://state-changed", "idle");
+    }
+}
+
+/// A native listener of [`HotkeyState`]
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Listener {
+    /// The session's native backend
+    Native,
+    /// Evdev for input triggers when the backend is another one
+    Input,
+}
+
+/// The native listener for `hotkey`: input triggers always get the evdev
+/// listener, other hotkeys the session's native backend. None means the
+/// plugin is used.
+#[cfg(target_os = "linux")]
+fn listener_for(state: &HotkeyState, hotkey: &str) -> Option<Listener> {
+    let backend = state.backend();
+    if trigger::is_input_trigger(hotkey) && backend != HotkeyBackend::Evdev {
+        Some(Listener::Input)
+    } else if backend == HotkeyBackend::GlobalShortcut {
+        None
+    } else {
+        Some(Listener::Native)
+    }
+}
+
+/// Run `f` on the native listener for `hotkey` if there is one, see
+/// [`listener_for`]
+#[cfg(target_os = "linux")]
+fn with_native<R: Runtime, T>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    hotkey: &str,
+    f: impl FnOnce(&mut linux::NativeHotkeys) -> Result<T, HotkeyError>,
+) -> Option<Result<T, HotkeyError>> {
+    let listener = listener_for(state, hotkey)?;
+    Some(with_listener(app, state, listener, f))
+}
+
+/// Run `f` on a native listener, starting it on first use
+#[cfg(target_os = "linux")]
+fn with_listener<R: Runtime, T>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    listener: Listener,
+    f: impl FnOnce(&mut linux::NativeHotkeys) -> Result<T, HotkeyError>,
+) -> Result<T, HotkeyError> {
+    let (slot, backend) = match listener {
+        Listener::Input => (&state.input, HotkeyBackend::Evdev),
+        Listener::Native => (&state.native, state.backend()),
+    };
+
+    let mut slot = slot.lock().unwrap();
+    let native = match &mut *slot {
+        Some(native) => native,
+        empty => {
+            let app = app.clone();
+            let handler_state = state.clone();
+            let callback: linux::HotkeyCallback =
+                Arc::new(move |binding: &HotkeyBinding, event: HotkeyEvent| {
+                    handle_shortcut_event(&app, &handler_state, binding, event);
+                });
+            let listener = linux::start_listener(backend, callback)?;
+            tracing::info!("Started the {} hotkey backend", listener.name());
+            empty.insert(linux::NativeHotkeys::new(listener))
+        }
+    };
+    f(native)
+}
+
+/// Stop listening for a hotkey on whichever backend registered it
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+fn unregister_shortcut<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    hotkey: &str,
+) -> Result<(), HotkeyError> {
+    #[cfg(target_os = "linux")]
+    if let Some(result) = with_native(app, state, hotkey, |native| native.unbind(hotkey)) {
+        return result;
+    }
+
+    let shortcut = parse_shortcut(hotkey)?;
+    app.global_shortcut()
+        .unregister(shortcut)
+        .map_err(|e| HotkeyError::RegistrationFailed(e.to_string()))
+}
+
 /// Unregister the current hotkey
 pub fn unregister_hotkey<R: Runtime>(
     app: &AppHandle<R>,
@@ -301,11 +861,7 @@ pub fn unregister_hotkey<R: Runtime>(
     let hotkey_opt = tauri::async_runtime::block_on(async { current.read().await.clone() });
 
     if let Some(hotkey) = hotkey_opt {
-        let shortcut = parse_shortcut(&hotkey)?;
-
-        app.global_shortcut()
-            .unregister(shortcut)
-            .map_err(|e| HotkeyError::RegistrationFailed(e.to_string()))?;
+        unregister_shortcut(app, state, &hotkey)?;
 
         is_registered.store(false, Ordering::SeqCst);
 
@@ -322,7 +878,17 @@ pub fn unregister_hotkey<R: Runtime>(
 }
 
 /// Test if a hotkey is available (not conflicting)
-pub fn test_hotkey<R: Runtime>(app: &AppHandle<R>, hotkey: &str) -> Result<bool, HotkeyError> {
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+pub fn test_hotkey<R: Runtime>(
+    app: &AppHandle<R>,
+    hotkey: &str,
+    state: &HotkeyState,
+) -> Result<bool, HotkeyError> {
+    #[cfg(target_os = "linux")]
+    if let Some(bound) = with_native(app, state, hotkey, |native| Ok(native.is_bound(hotkey))) {
+        return bound.map(|bound| !bound);
+    }
+
     let shortcut = parse_shortcut(hotkey)?;
 
     // is_registered returns bool directly
@@ -359,6 +925,23 @@ pub fn setup_hotkey_with_key<R: Runtime>(app: &AppHandle<R>, state: &HotkeyState
     }
 }
 
+/// Register the saved hotkey bindings on startup with graceful degradation
+pub fn setup_bindings<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &HotkeyState,
+    primary: &str,
+    bindings: &[HotkeyBinding],
+    profiles: &[DictationProfile],
+) {
+    if bindings.is_empty() {
+        return;
+    }
+    if let Err(e) = register_bindings(app, primary, bindings, profiles, state) {
+        tracing::warn!("Failed to register hotkey bindings: {}", e);
+        let _ = app.emit("hotkey://registration-failed", e.to_string());
+    }
+}
+
 /// Process audio chunks in streaming mode while recording is active
 async fn process_streaming_chunks<R: Runtime + 'static>(
     app: AppHandle<R>,
@@ -456,7 +1039,7 @@ async fn process_streaming_chunks<R: Runtime + 'static>(
 }
 
 /// Handle completion of streaming transcription
-fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>) {
+fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>, binding: HotkeyBinding) {
     tracing::info!("Handling streaming transcription completion");
 
     let audio_state = app.state::<AudioState>();
@@ -464,10 +1047,10 @@ fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>) {
     let transcription_state = app.state::<TranscriptionState>();
     let settings_state = app.state::<SettingsState>();
 
-    // Stop the audio recording and get buffer for batch fallback
-    let buffer = match audio_state.send_command(AudioCommand::Stop) {
-        Ok(AudioResponse::Buffer(Ok(buf))) => buf,
-        Ok(AudioResponse::Buffer(Err(e))) => {
+    // Stop the audio recording and keep it for reconciliation
+    let recording = match audio_state.send_command(AudioCommand::Stop) {
+        Ok(AudioResponse::Recording(Ok(recording))) => recording,
+        Ok(AudioResponse::Recording(Err(e))) => {
             tracing::error!("Failed to stop recording: {}", e);
             let _ = app.emit("hotkey://recording-stopped", ());
             return;
@@ -486,7 +1069,7 @@ fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>) {
 
     let _ = app.emit("hotkey://recording-stopped", ());
 
-    if buffer.samples.is_empty() {
+    if recording.is_empty() {
         tracing::warn!("No audio recorded");
         return;
     }
@@ -497,21 +1080,6 @@ fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>) {
         _ => None,
     };
 
-    // Get full buffer for reconciliation
-    let full_samples = match audio_state.send_command(AudioCommand::GetFullBuffer) {
-        Ok(AudioResponse::Samples(s)) if !s.is_empty() => s,
-        _ => {
-            // Fall back to regular buffer if streaming buffer is empty
-            match resample_for_whisper(buffer) {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::error!("Failed to resample audio: {}", e);
-                    return;
-                }
-            }
-        }
-    };
-
     // Disable streaming mode
     let _ = audio_state.send_command(AudioCommand::DisableStreaming);
 
@@ -522,14 +1090,25 @@ fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>) {
 
     // Build initial prompt from settings
     let settings = tauri::async_runtime::block_on(async { settings_state.get().await });
-    let initial_prompt = crate::services::transcription::build_initial_prompt(
+    let target = active_window();
+    let settings = hotkey_settings(&binding, settings, target.as_ref());
+    let initial_prompt = build_initial_prompt(
         &settings.custom_vocabulary,
         settings.context_prompt.as_deref(),
         settings.use_context_prompt,
     );
+    let archive_config = settings.effective_audio_archive();
+    let streaming_mode = settings.streaming_mode;
+    let normalizer =
+        TextNormalizer::new(settings.text_normalization, settings.language.as_deref());
+    let replacer = TextReplacer::new(&settings.text_replacements);
+    let sink = settings.dictation_sink.clone();
+    let voice_commands = settings.voice_commands.clone();
 
     // Spawn async task for reconciliation and final processing
     tauri::async_runtime::spawn(async move {
+        let transcribe_start = Instant::now();
+
         // Process final chunk if available
         if let Some(chunk) = final_chunk {
             tracing::debug!("Processing final chunk {}", chunk.chunk_index);
@@ -541,7 +1120,7 @@ fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>) {
 
         // Perform reconciliation based on streaming mode
         let final_text = match service
-            .reconcile(&app, &engine, &full_samples, &model_id, initial_prompt.as_deref())
+            .reconcile_recording(&app, &engine, &recording, &model_id, initial_prompt.as_deref())
             .await
         {
             Ok(text) => text,
@@ -562,20 +1141,39 @@ fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>) {
 
         // Save to history
         let database_state = app.state::<DatabaseState>();
-        if let Some(db) = database_state.get() {
+        if let Some(db) = database_state.history_db() {
+            let duration_ms = (recording.duration_secs() * 1000.0) as u64;
+            let audio_path = archive::archive_recording(&archive_config, recording).await;
             let entry = HistoryEntry {
                 id: 0,
                 text: final_text.clone(),
                 timestamp: Utc::now().to_rfc3339(),
-                duration_ms: (full_samples.len() as f32 / 16.0) as u64, // approximate
+                duration_ms,
                 model_id: model_id.clone(),
                 language: None,
                 gpu_used: false, // we don't have this info easily
+                audio_path,
+                pinned: false,
+                original_text: None,
+                metadata: HistoryMetadata {
+                    source: Some(HistorySource::Hotkey),
+                    source_app: target.as_ref().and_then(|w| w.app.clone()),
+                    source_window: target.and_then(|w| w.title),
+                    streaming_mode: Some(streaming_mode),
+                    transcription_time_ms: Some(transcribe_start.elapsed().as_millis() as u64),
+                    ..Default::default()
+                },
             };
             if let Err(e) = db.insert_history(&entry).await {
                 tracing::error!("Failed to save transcription to history: {}", e);
+                if let Some(path) = &entry.audio_path {
+                    archive::remove_archived_audio(path);
+                }
             } else {
                 tracing::debug!("Saved transcription to history");
+                if entry.audio_path.is_some() {
+                    archive::enforce_retention(db, &archive_config).await;
+                }
                 match app.emit("history://new-entry", ()) {
                     Ok(_) => tracing::debug!("Emitted history://new-entry event"),
                     Err(e) => tracing::error!("Failed to emit history://new-entry: {}", e),
@@ -583,26 +1181,94 @@ fn handle_streaming_completion<R: Runtime + 'static>(app: AppHandle<R>) {
             }
         }
 
-        // Copy to clipboard
-        if !final_text.is_empty() {
-            if let Err(e) = app.clipboard().write_text(&final_text) {
-                tracing::error!("Failed to copy to clipboard: {}", e);
-            } else {
-                tracing::info!("Transcription copied to clipboard");
-            }
-        }
+        let final_text = if binding.action == HotkeyAction::Command {
+            run_voice_commands(&app, &voice_commands, &final_text).await;
+            final_text
+        } else {
+            // Normalize numbers and apply replacement rules to what is copied and delivered
+            let final_text = replacer.apply(&normalizer.apply(&final_text));
+            deliver_transcript(&app, &sink, &final_text).await;
+            final_text
+        };
 
         let _ = app.emit("hotkey://transcription-complete", &final_text);
     });
 }
 
-/// Handle batch transcription (non-streaming mode)
-fn handle_batch_transcription<R: Runtime + 'static>(app: AppHandle<R>) {
+/// Settings for a recording of `binding`, with the overrides of the target
+/// application's profile as for push-to-talk
+fn hotkey_settings(
+    binding: &HotkeyBinding,
+    settings: Settings,
+    target: Option<&ActiveWindow>,
+) -> Settings {
+    let settings = binding.apply(settings);
+    match find_profile(&settings.app_profiles, target).cloned() {
+        Some(profile) => {
+            tracing::info!("Using app profile '{}'", profile.name);
+            settings.with_profile(&profile)
+        }
+        None => settings,
+    }
+}
+
+/// Send a finished hotkey transcript to the configured sink. The hotkey
+/// leaves pasting to the user, so injection means copying to the clipboard.
+async fn deliver_transcript<R: Runtime>(app: &AppHandle<R>, sink: &DictationSink, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let sink = match sink {
+        DictationSink::Inject => &DictationSink::Clipboard,
+        sink => sink,
+    };
+    match sinks::deliver(app, sink, text).await {
+        Ok(()) => tracing::info!("Transcription delivered to {:?}", sink),
+        Err(e) => tracing::error!("Failed to deliver transcription: {}", e),
+    }
+}
+
+/// Run a command-mode hotkey transcript as voice commands
+async fn run_voice_commands<R: Runtime>(app: &AppHandle<R>, config: &CommandConfig, text: &str) {
+    let injector = app.state::<TextInjectorState>();
+    let count = execute_commands(&injector, config, text).await;
+    tracing::info!("Ran {} voice commands from command mode", count);
+}
+
+/// What a batch recording of `binding` is transcribed with
+fn transcription_job(binding: &HotkeyBinding, settings: &Settings) -> TranscriptionJob {
+    let initial_prompt = build_initial_prompt(
+        &settings.custom_vocabulary,
+        settings.context_prompt.as_deref(),
+        settings.use_context_prompt,
+    );
+    // Bindings with their own model, language or translation decode with
+    // exactly that model instead of whichever one is loaded
+    let decoding = binding
+        .overrides_decoding(&settings.dictation_profiles)
+        .then(|| DecodeOptions {
+            language: settings.language.clone(),
+            translate: binding.action == HotkeyAction::Translate,
+        });
+    TranscriptionJob {
+        model_id: settings.model_id.clone(),
+        initial_prompt,
+        decoding,
+    }
+}
+
+/// Handle batch transcription (non-streaming mode), continuing the
+/// `background` transcription of the recording's first windows
+fn handle_batch_transcription<R: Runtime + 'static>(
+    app: AppHandle<R>,
+    binding: HotkeyBinding,
+    background: Option<BackgroundTranscription>,
+) {
     let audio_state = app.state::<AudioState>();
 
-    let buffer = match audio_state.send_command(AudioCommand::Stop) {
-        Ok(AudioResponse::Buffer(Ok(buf))) => buf,
-        Ok(AudioResponse::Buffer(Err(e))) => {
+    let recording = match audio_state.send_command(AudioCommand::Stop) {
+        Ok(AudioResponse::Recording(Ok(recording))) => recording,
+        Ok(AudioResponse::Recording(Err(e))) => {
             tracing::error!("Failed to stop recording from hotkey: {}", e);
             let _ = app.emit("hotkey://recording-stopped", ());
             return;
@@ -621,32 +1287,55 @@ fn handle_batch_transcription<R: Runtime + 'static>(app: AppHandle<R>) {
 
     let _ = app.emit("hotkey://recording-stopped", ());
 
-    if buffer.samples.is_empty() {
+    if recording.is_empty() {
         tracing::warn!("No audio recorded from hotkey");
         return;
     }
 
-    // Resample for Whisper
-    let samples = match resample_for_whisper(buffer) {
-        Ok(s) => s,
-        Err(e) => {
-            tracing::error!("Failed to resample audio from hotkey: {}", e);
-            return;
-        }
-    };
-
-    // Get states for async task
+    // Get states and the binding's settings for async task
     let transcription_state = app.state::<TranscriptionState>();
     let settings_state = app.state::<SettingsState>();
     let engine = transcription_state.engine.clone();
-    let model_id = settings_state.get_model_id_sync();
+    let settings = tauri::async_runtime::block_on(async { settings_state.get().await });
+    let target = active_window();
+    let settings = hotkey_settings(&binding, settings, target.as_ref());
+    let job = transcription_job(&binding, &settings);
+    let archive_config = settings.effective_audio_archive();
+    let normalize = settings.text_normalization;
+    let replacer = TextReplacer::new(&settings.text_replacements);
+    let sink = settings.dictation_sink.clone();
+    let voice_commands = settings.voice_commands.clone();
+    let translated = job
+        .decoding
+        .as_ref()
+        .is_some_and(|decoding| decoding.translate);
 
     // Spawn async task for transcription
     tauri::async_runtime::spawn(async move {
         tracing::info!("Starting transcription from hotkey...");
+        let transcribe_start = Instant::now();
+
+        let partial = match background {
+            Some(task) => match task.await {
+                Ok(Ok(partial)) => Some(partial),
+                Ok(Err(e)) => {
+                    tracing::warn!("Transcription while recording failed: {}", e);
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("Transcription while recording stopped: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let transcription = engine
+            .transcribe_recording_job(&recording, &job, partial)
+            .await;
 
-        match engine.transcribe_with_auto_load(samples, &model_id).await {
+        match transcription {
             Ok(result) => {
+                let transcription_time_ms = transcribe_start.elapsed().as_millis() as u64;
                 tracing::info!(
                     "Hotkey transcription complete: {} chars",
                     result.text.len()
@@ -654,7 +1343,8 @@ fn handle_batch_transcription<R: Runtime + 'static>(app: AppHandle<R>) {
 
                 // Save to history
                 let database_state = app.state::<DatabaseState>();
-                if let Some(db) = database_state.get() {
+                if let Some(db) = database_state.history_db() {
+                    let audio_path = archive::archive_recording(&archive_config, recording).await;
                     let entry = HistoryEntry {
                         id: 0,
                         text: result.text.clone(),
@@ -663,11 +1353,28 @@ fn handle_batch_transcription<R: Runtime + 'static>(app: AppHandle<R>) {
                         model_id: result.model_id.clone(),
                         language: result.language.clone(),
                         gpu_used: result.gpu_used,
+                        audio_path,
+                        pinned: false,
+                        original_text: None,
+                        metadata: HistoryMetadata {
+                            source: Some(HistorySource::Hotkey),
+                            source_app: target.as_ref().and_then(|w| w.app.clone()),
+                            source_window: target.and_then(|w| w.title),
+                            transcription_time_ms: Some(transcription_time_ms),
+                            translated,
+                            ..Default::default()
+                        },
                     };
                     if let Err(e) = db.insert_history(&entry).await {
                         tracing::error!("Failed to save transcription to history: {}", e);
+                        if let Some(path) = &entry.audio_path {
+                            archive::remove_archived_audio(path);
+                        }
                     } else {
                         tracing::debug!("Saved transcription to history");
+                        if entry.audio_path.is_some() {
+                            archive::enforce_retention(db, &archive_config).await;
+                        }
                         // Emit event to refresh history UI
                         match app.emit("history://new-entry", ()) {
                             Ok(_) => tracing::debug!("Emitted history://new-entry event"),
@@ -676,16 +1383,18 @@ fn handle_batch_transcription<R: Runtime + 'static>(app: AppHandle<R>) {
                     }
                 }
 
-                // Copy to clipboard
-                if !result.text.is_empty() {
-                    if let Err(e) = app.clipboard().write_text(&result.text) {
-                        tracing::error!("Failed to copy to clipboard: {}", e);
-                    } else {
-                        tracing::info!("Transcription copied to clipboard");
-                    }
-                }
-
-                let _ = app.emit("hotkey://transcription-complete", &result.text);
+                let text = if binding.action == HotkeyAction::Command {
+                    run_voice_commands(&app, &voice_commands, &result.text).await;
+                    result.text
+                } else {
+                    // Normalize numbers and apply replacement rules to what is copied and delivered
+                    let normalizer = TextNormalizer::new(normalize, result.language.as_deref());
+                    let text = replacer.apply(&normalizer.apply(&result.text));
+                    deliver_transcript(&app, &sink, &text).await;
+                    text
+                };
+
+                let _ = app.emit("hotkey://transcription-complete", &text);
             }
             Err(e) => {
                 tracing::error!("Hotkey transcription failed: {}", e);
@@ -0,0 +1,212 @@
+//! Push-to-talk triggers for the input listener
+//!
+//! Lone modifiers such as Right Ctrl, CapsLock, Fn and mouse buttons can't
+//! be registered with the global-shortcut plugin, so they are read from the
+//! input devices instead, which only works on Linux. CapsLock and the
+//! mouse buttons are kept from the focused application while they are
+//! bound, so they don't toggle the case or navigate back.
+//!
+//! Holding a trigger records until it is let go; tapping it twice keeps
+//! recording until the next press. A single tap records nothing.
+
+use super::normalize_hotkey;
+use std::time::{Duration, Instant};
+
+/// Longest press that counts as a tap
+pub const TAP_MAX: Duration = Duration::from_millis(250);
+
+/// Longest wait for the second tap of a double tap
+pub const DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(400);
+
+/// Trigger keys by normalized name, with their Linux input event code
+const INPUT_TRIGGERS: &[(&str, u16)] = &[
+    ("leftctrl", 29),
+    ("controlleft", 29),
+    ("rightctrl", 97),
+    ("controlright", 97),
+    ("leftshift", 42),
+    ("shiftleft", 42),
+    ("rightshift", 54),
+    ("shiftright", 54),
+    ("leftalt", 56),
+    ("altleft", 56),
+    ("rightalt", 100),
+    ("altright", 100),
+    ("altgr", 100),
+    ("leftsuper", 125),
+    ("metaleft", 125),
+    ("rightsuper", 126),
+    ("metaright", 126),
+    ("capslock", 58),
+    ("fn", 464),
+    ("mousemiddle", 274),
+    ("mouse3", 274),
+    ("mouseback", 275),
+    ("mouse4", 275),
+    ("mouseforward", 276),
+    ("mouse5", 276),
+];
+
+/// Linux input event code of a trigger key, by normalized name
+pub fn input_trigger_code(name: &str) -> Option<u16> {
+    INPUT_TRIGGERS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, code)| code)
+}
+
+/// Whether the hotkey's key needs the input listener, e.g. "RightCtrl" or
+/// "Ctrl+Mouse4"
+pub fn is_input_trigger(hotkey: &str) -> bool {
+    normalize_hotkey(hotkey)
+        .rsplit('+')
+        .next()
+        .and_then(input_trigger_code)
+        .is_some()
+}
+
+/// What to do with the recording after a trigger event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapAction {
+    None,
+    /// Start recording
+    Start,
+    /// Stop recording and transcribe
+    Stop,
+    /// Drop the recording without transcribing it
+    Cancel,
+    /// Tapped once: keep recording and call [`TapDetector::expire`] after
+    /// [`DOUBLE_TAP_WINDOW`]
+    AwaitSecondTap,
+    /// Tapped twice: keep recording until the next press
+    Latch,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+enum TapState {
+    #[default]
+    Idle,
+    Held(Instant),
+    Tapped(Instant),
+    Latched {
+        held: bool,
+    },
+    /// Recording stopped on press, waiting for the release
+    Stopping,
+}
+
+/// Tells holds, taps and double taps of one trigger apart
+#[derive(Debug, Default)]
+pub struct TapDetector {
+    state: TapState,
+}
+
+impl TapDetector {
+    pub fn press(&mut self, now: Instant) -> TapAction {
+        let (state, action) = match self.state {
+            TapState::Idle => (TapState::Held(now), TapAction::Start),
+            TapState::Tapped(at) if now.duration_since(at) <= DOUBLE_TAP_WINDOW => {
+                (TapState::Latched { held: true }, TapAction::Latch)
+            }
+            TapState::Tapped(_) | TapState::Latched { held: false } => {
+                (TapState::Stopping, TapAction::Stop)
+            }
+            state => (state, TapAction::None),
+        };
+        self.state = state;
+        action
+    }
+
+    pub fn release(&mut self, now: Instant) -> TapAction {
+        let (state, action) = match self.state {
+            TapState::Held(since) if now.duration_since(since) < TAP_MAX => {
+                (TapState::Tapped(now), TapAction::AwaitSecondTap)
+            }
+            TapState::Held(_) => (TapState::Idle, TapAction::Stop),
+            TapState::Latched { held: true } => {
+                (TapState::Latched { held: false }, TapAction::None)
+            }
+            TapState::Stopping => (TapState::Idle, TapAction::None),
+            state => (state, TapAction::None),
+        };
+        self.state = state;
+        action
+    }
+
+    /// Drop a recording started by a single tap once no second tap came
+    pub fn expire(&mut self, now: Instant) -> TapAction {
+        match self.state {
+            TapState::Tapped(at) if now.duration_since(at) >= DOUBLE_TAP_WINDOW => {
+                self.state = TapState::Idle;
+                TapAction::Cancel
+            }
+            _ => TapAction::None,
+        }
+    }
+
+    /// Forget the trigger's state, e.g. when its recording was dropped
+    pub fn reset(&mut self) {
+        self.state = TapState::Idle;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_input_triggers() {
+        assert!(is_input_trigger("RightCtrl"));
+        assert!(is_input_trigger("ControlRight"));
+        assert!(is_input_trigger("Fn"));
+        assert!(is_input_trigger("CapsLock"));
+        assert!(is_input_trigger("Ctrl+Mouse4"));
+        assert!(!is_input_trigger("Ctrl+Shift+Space"));
+        assert!(!is_input_trigger("Ctrl"));
+        assert_eq!(input_trigger_code("mouseback"), Some(275));
+        assert_eq!(input_trigger_code("altgr"), Some(100));
+    }
+
+    #[test]
+    fn test_hold_records_until_release() {
+        let start = Instant::now();
+        let mut tap = TapDetector::default();
+        assert_eq!(tap.press(start), TapAction::Start);
+        assert_eq!(tap.release(start + ms(1500)), TapAction::Stop);
+        assert_eq!(tap.press(start + ms(2000)), TapAction::Start);
+    }
+
+    #[test]
+    fn test_double_tap_latches_until_next_press() {
+        let start = Instant::now();
+        let mut tap = TapDetector::default();
+        assert_eq!(tap.press(start), TapAction::Start);
+        assert_eq!(tap.release(start + ms(100)), TapAction::AwaitSecondTap);
+        assert_eq!(tap.press(start + ms(300)), TapAction::Latch);
+        assert_eq!(tap.release(start + ms(400)), TapAction::None);
+        // The expiry of the first tap doesn't end the latched recording
+        assert_eq!(tap.expire(start + ms(500)), TapAction::None);
+
+        assert_eq!(tap.press(start + ms(9000)), TapAction::Stop);
+        assert_eq!(tap.release(start + ms(9100)), TapAction::None);
+        assert_eq!(tap.press(start + ms(10000)), TapAction::Start);
+    }
+
+    #[test]
+    fn test_single_tap_cancels_after_window() {
+        let start = Instant::now();
+        let mut tap = TapDetector::default();
+        tap.press(start);
+        assert_eq!(tap.release(start + ms(100)), TapAction::AwaitSecondTap);
+        assert_eq!(tap.expire(start + ms(300)), TapAction::None);
+        assert_eq!(
+            tap.expire(start + ms(100) + DOUBLE_TAP_WINDOW),
+            TapAction::Cancel
+        );
+        assert_eq!(tap.press(start + ms(1000)), TapAction::Start);
+    }
+}
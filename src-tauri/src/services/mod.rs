@@ -5,8 +5,11 @@
 pub mod audio;
 pub mod hotkey;
 pub mod platform;
+pub mod sinks;
 pub mod storage;
 pub mod streaming;
+pub mod text_normalization;
+pub mod text_replacements;
 pub mod transcription;
 pub mod tray;
 pub mod ui;
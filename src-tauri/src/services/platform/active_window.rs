@@ -0,0 +1,225 @@
+//! Focused application detection
+//!
+//! Reports which application and window had focus when a dictation started,
+//! so history entries can record where the text was sent. Detection is best
+//! effort: it returns `None` when the platform gives no answer (for example
+//! on Wayland compositors, which do not expose the focused window). The
+//! application is also what selects an app profile at injection time.
+
+use serde::Serialize;
+
+/// The focused application and window
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ActiveWindow {
+    /// Application (process) name
+    pub app: Option<String>,
+    /// Stable application identifier: the window class on Linux, the bundle
+    /// id on macOS, the executable file name on Windows
+    pub app_id: Option<String>,
+    /// Window title
+    pub title: Option<String>,
+}
+
+impl ActiveWindow {
+    /// Build from raw platform output, treating blank values as unknown
+    fn from_parts(app: &str, app_id: &str, title: &str) -> Option<Self> {
+        let non_empty = |s: &str| {
+            let s = s.trim();
+            (!s.is_empty()).then(|| s.to_string())
+        };
+        let window = Self {
+            app: non_empty(app),
+            app_id: non_empty(app_id),
+            title: non_empty(title),
+        };
+        (window.app.is_some() || window.title.is_some()).then_some(window)
+    }
+}
+
+/// Get the currently focused application and window, if it can be determined
+pub fn active_window() -> Option<ActiveWindow> {
+    let window = detect();
+    if window.is_none() {
+        tracing::debug!("Could not determine the focused window");
+    }
+    window
+}
+
+#[cfg(target_os = "linux")]
+fn detect() -> Option<ActiveWindow> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, Window};
+
+    // Only X11 (and XWayland) windows are visible
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let atom = |name: &str| -> Option<Atom> {
+        let reply = conn.intern_atom(false, name.as_bytes()).ok()?.reply();
+        reply.ok().map(|reply| reply.atom)
+    };
+    let property = |window: Window, property: Atom, kind: Atom| {
+        conn.get_property(false, window, property, kind, 0, u32::MAX / 4)
+            .ok()?
+            .reply()
+            .ok()
+    };
+
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let window = property(root, atom("_NET_ACTIVE_WINDOW")?, AtomEnum::WINDOW.into())?
+        .value32()?
+        .next()
+        .filter(|&window| window != x11rb::NONE)?;
+
+    let title = property(window, atom("_NET_WM_NAME")?, atom("UTF8_STRING")?)
+        .filter(|reply| !reply.value.is_empty())
+        .or_else(|| property(window, AtomEnum::WM_NAME.into(), AtomEnum::STRING.into()))
+        .map(|reply| String::from_utf8_lossy(&reply.value).into_owned())
+        .unwrap_or_default();
+    let class = property(window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING.into())
+        .map(|reply| window_class(&reply.value).to_string())
+        .unwrap_or_default();
+    let app = property(window, atom("_NET_WM_PID")?, AtomEnum::CARDINAL.into())
+        .and_then(|reply| reply.value32()?.next())
+        .and_then(|pid| std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+        .unwrap_or_default();
+
+    ActiveWindow::from_parts(&app, &class, &title)
+}
+
+/// The class part of a `WM_CLASS` value, which holds the instance and class
+/// names, each followed by a NUL
+#[cfg(target_os = "linux")]
+fn window_class(value: &[u8]) -> std::borrow::Cow<'_, str> {
+    let class = value.split(|&b| b == 0).nth(1).unwrap_or_default();
+    String::from_utf8_lossy(class)
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> Option<ActiveWindow> {
+    use std::process::Command;
+
+    const SCRIPT: &str = r#"
+        tell application "System Events"
+            set frontApp to first application process whose frontmost is true
+            set appName to name of frontApp
+            set bundleId to ""
+            try
+                set bundleId to bundle identifier of frontApp
+            end try
+            set windowName to ""
+            try
+                set windowName to name of front window of frontApp
+            end try
+        end tell
+        return appName & linefeed & bundleId & linefeed & windowName
+    "#;
+
+    let output = Command::new("osascript")
+        .args(["-e", SCRIPT])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let app = lines.next().unwrap_or_default();
+    let bundle_id = lines.next().unwrap_or_default();
+    let title = lines.next().unwrap_or_default();
+    ActiveWindow::from_parts(app, bundle_id, title)
+}
+
+#[cfg(target_os = "windows")]
+fn detect() -> Option<ActiveWindow> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0 == 0 {
+            return None;
+        }
+
+        let mut title = [0u16; 512];
+        let len = GetWindowTextW(hwnd, &mut title).max(0) as usize;
+        let title = String::from_utf16_lossy(&title[..len]);
+
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        let exe = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)
+            .ok()
+            .and_then(|process| {
+                let mut path = [0u16; 260];
+                let mut size = path.len() as u32;
+                let result = QueryFullProcessImageNameW(
+                    process,
+                    PROCESS_NAME_WIN32,
+                    PWSTR(path.as_mut_ptr()),
+                    &mut size,
+                );
+                let _ = CloseHandle(process);
+                result.ok()?;
+                Some(std::path::PathBuf::from(String::from_utf16_lossy(
+                    &path[..size as usize],
+                )))
+            });
+        let name = |part: Option<&std::ffi::OsStr>| {
+            part.map(|part| part.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let app = name(exe.as_deref().and_then(|exe| exe.file_stem()));
+        let exe_name = name(exe.as_deref().and_then(|exe| exe.file_name()));
+
+        ActiveWindow::from_parts(&app, &exe_name, &title)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn detect() -> Option<ActiveWindow> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_parts_trims_values() {
+        let window =
+            ActiveWindow::from_parts("firefox\n", "firefox ", "  Inbox - Mozilla Firefox\n")
+                .unwrap();
+        assert_eq!(window.app.as_deref(), Some("firefox"));
+        assert_eq!(window.app_id.as_deref(), Some("firefox"));
+        assert_eq!(window.title.as_deref(), Some("Inbox - Mozilla Firefox"));
+    }
+
+    #[test]
+    fn test_from_parts_blank_is_unknown() {
+        assert!(ActiveWindow::from_parts("", "", " \n").is_none());
+
+        let window = ActiveWindow::from_parts("Terminal", "", "").unwrap();
+        assert_eq!(window.app.as_deref(), Some("Terminal"));
+        assert!(window.app_id.is_none());
+        assert!(window.title.is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_window_class() {
+        assert_eq!(window_class(b"Navigator\0firefox\0"), "firefox");
+        assert_eq!(window_class(b"code\0Code\0"), "Code");
+        assert_eq!(window_class(b""), "");
+    }
+
+    #[test]
+    fn test_active_window_does_not_panic() {
+        let _ = active_window();
+    }
+}
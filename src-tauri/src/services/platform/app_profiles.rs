@@ -0,0 +1,150 @@
+//! Per-application injection profiles
+//!
+//! Applications differ in how they take text: terminals paste with
+//! Ctrl+Shift+V, some editors only work with typing, and in chat apps a
+//! trailing newline sends the message. A profile overrides the injection
+//! settings for the applications it lists; the first enabled profile that
+//! matches the focused application applies.
+
+use super::active_window::ActiveWindow;
+use super::text_inject::{InjectionMethod, PasteShortcut};
+use crate::services::text_replacements::ReplacementConfig;
+use crate::services::voice_commands::CommandConfig;
+use serde::{Deserialize, Serialize};
+
+/// Injection overrides for a set of applications
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppProfile {
+    /// Name shown in settings
+    pub name: String,
+    /// Applications the profile applies to, compared ignoring case: window
+    /// class or process name on Linux, bundle id or app name on macOS,
+    /// executable name on Windows
+    pub apps: Vec<String>,
+    /// Whether the profile is used
+    pub enabled: bool,
+    /// Paste or type instead of the global method
+    pub injection_method: Option<InjectionMethod>,
+    /// Delay between keystrokes instead of the global delay (ms)
+    pub injection_delay_ms: Option<u32>,
+    /// Clipboard restore delay instead of the global delay (ms)
+    pub clipboard_restore_delay_ms: Option<u32>,
+    /// Key combination that pastes in these applications
+    pub paste_shortcut: PasteShortcut,
+    /// Drop line breaks at the end of the dictated text
+    pub strip_trailing_newline: bool,
+    /// Voice commands instead of the global ones
+    pub voice_commands: Option<CommandConfig>,
+    /// Replacement rules instead of the global ones
+    pub text_replacements: Option<ReplacementConfig>,
+}
+
+impl Default for AppProfile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            apps: Vec::new(),
+            enabled: true,
+            injection_method: None,
+            injection_delay_ms: None,
+            clipboard_restore_delay_ms: None,
+            paste_shortcut: PasteShortcut::default(),
+            strip_trailing_newline: false,
+            voice_commands: None,
+            text_replacements: None,
+        }
+    }
+}
+
+impl AppProfile {
+    /// Whether the profile is enabled and lists the window's application
+    pub fn matches(&self, window: &ActiveWindow) -> bool {
+        let ids = [window.app_id.as_deref(), window.app.as_deref()];
+        self.enabled
+            && self
+                .apps
+                .iter()
+                .map(|app| app.trim())
+                .filter(|app| !app.is_empty())
+                .any(|app| ids.iter().flatten().any(|id| id.eq_ignore_ascii_case(app)))
+    }
+}
+
+/// The profile for the focused application, if any
+pub fn find_profile<'a>(
+    profiles: &'a [AppProfile],
+    window: Option<&ActiveWindow>,
+) -> Option<&'a AppProfile> {
+    let window = window?;
+    profiles.iter().find(|profile| profile.matches(window))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(app: &str, app_id: Option<&str>) -> ActiveWindow {
+        ActiveWindow {
+            app: Some(app.to_string()),
+            app_id: app_id.map(str::to_string),
+            title: None,
+        }
+    }
+
+    fn profile(name: &str, apps: &[&str]) -> AppProfile {
+        AppProfile {
+            name: name.to_string(),
+            apps: apps.iter().map(|app| app.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matches_app_id_or_process_ignoring_case() {
+        let terminal = profile("Terminal", &["gnome-terminal-server", "com.apple.Terminal"]);
+        assert!(terminal.matches(&window("gnome-terminal-", Some("Gnome-terminal-server"))));
+        assert!(terminal.matches(&window("Terminal", Some("com.apple.terminal"))));
+        assert!(!terminal.matches(&window("firefox", Some("firefox"))));
+
+        let code = profile("Code", &["Code.exe"]);
+        assert!(code.matches(&window("Code", Some("Code.exe"))));
+        assert!(profile("Code", &["code"]).matches(&window("Code", Some("Code.exe"))));
+    }
+
+    #[test]
+    fn test_disabled_and_blank_profiles_never_match() {
+        let mut slack = profile("Slack", &["slack"]);
+        slack.enabled = false;
+        assert!(!slack.matches(&window("slack", None)));
+
+        assert!(!profile("Blank", &[" "]).matches(&window(" ", None)));
+    }
+
+    #[test]
+    fn test_find_profile_takes_first_match() {
+        let profiles = vec![
+            profile("Chat", &["slack", "discord"]),
+            profile("Electron", &["slack", "code"]),
+        ];
+        let found = find_profile(&profiles, Some(&window("slack", None))).unwrap();
+        assert_eq!(found.name, "Chat");
+        let found = find_profile(&profiles, Some(&window("code", None))).unwrap();
+        assert_eq!(found.name, "Electron");
+        assert!(find_profile(&profiles, Some(&window("vim", None))).is_none());
+        assert!(find_profile(&profiles, None).is_none());
+    }
+
+    #[test]
+    fn test_profile_defaults_for_missing_fields() {
+        let parsed: AppProfile = serde_json::from_str(
+            r#"{"name": "Terminal", "apps": ["kitty"], "paste_shortcut": "ctrl_shift_v"}"#,
+        )
+        .unwrap();
+        assert!(parsed.enabled);
+        assert_eq!(parsed.paste_shortcut, PasteShortcut::CtrlShiftV);
+        assert!(parsed.injection_method.is_none());
+        assert!(parsed.voice_commands.is_none());
+        assert!(!parsed.strip_trailing_newline);
+    }
+}
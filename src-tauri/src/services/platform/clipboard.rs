@@ -0,0 +1,138 @@
+//! Clipboard handling around paste injection
+//!
+//! Pasting needs the transcript on the clipboard. Unless the user wants it
+//! kept there (auto-copy), whatever they had copied is saved first and put
+//! back once the target application has had time to read the paste.
+
+use super::text_inject::{PasteShortcut, PlatformError};
+use arboard::{Clipboard, ImageData};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default time the target application gets to read a paste
+pub const DEFAULT_RESTORE_DELAY_MS: u32 = 200;
+
+/// How injection treats the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipboardConfig {
+    /// Leave the transcript on the clipboard instead of restoring it
+    pub keep_transcript: bool,
+    /// Wait after pasting before the previous contents are restored
+    pub restore_delay_ms: u32,
+    /// Key combination that pastes
+    pub paste_shortcut: PasteShortcut,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            keep_transcript: true,
+            restore_delay_ms: DEFAULT_RESTORE_DELAY_MS,
+            paste_shortcut: PasteShortcut::default(),
+        }
+    }
+}
+
+/// Clipboard contents saved before pasting, in the richest format arboard
+/// could read
+enum SavedClipboard {
+    Html {
+        html: String,
+        alt_text: Option<String>,
+    },
+    Files(Vec<PathBuf>),
+    Image(ImageData<'static>),
+    Text(String),
+    Empty,
+}
+
+impl SavedClipboard {
+    fn save(clipboard: &mut Clipboard) -> Self {
+        if let Ok(html) = clipboard.get().html() {
+            let alt_text = clipboard.get_text().ok();
+            return Self::Html { html, alt_text };
+        }
+        if let Ok(files) = clipboard.get().file_list() {
+            if !files.is_empty() {
+                return Self::Files(files);
+            }
+        }
+        if let Ok(image) = clipboard.get_image() {
+            return Self::Image(image);
+        }
+        match clipboard.get_text() {
+            Ok(text) => Self::Text(text),
+            Err(_) => Self::Empty,
+        }
+    }
+
+    fn restore(self, clipboard: &mut Clipboard) -> Result<(), PlatformError> {
+        match self {
+            Self::Html { html, alt_text } => clipboard.set_html(html, alt_text)?,
+            Self::Files(files) => clipboard.set().file_list(&files)?,
+            Self::Image(image) => clipboard.set_image(image)?,
+            Self::Text(text) => clipboard.set_text(text)?,
+            Self::Empty => clipboard.clear()?,
+        }
+        Ok(())
+    }
+}
+
+/// Put `text` on the clipboard, give it `settle` to take effect and run
+/// `paste`. The previous clipboard contents are restored afterwards unless
+/// the config keeps the transcript or the user copied something else in
+/// the meantime.
+pub fn paste_via_clipboard(
+    text: &str,
+    config: ClipboardConfig,
+    settle: Duration,
+    paste: impl FnOnce() -> Result<(), PlatformError>,
+) -> Result<(), PlatformError> {
+    let mut clipboard = Clipboard::new()?;
+    let saved = (!config.keep_transcript).then(|| SavedClipboard::save(&mut clipboard));
+
+    clipboard.set_text(text)?;
+    std::thread::sleep(settle);
+    let result = paste();
+
+    if let Some(saved) = saved {
+        std::thread::sleep(Duration::from_millis(config.restore_delay_ms as u64));
+        if clipboard.get_text().ok().as_deref() != Some(text) {
+            tracing::debug!("Clipboard changed since pasting, not restoring it");
+        } else if let Err(e) = saved.restore(&mut clipboard) {
+            tracing::warn!("Failed to restore clipboard: {}", e);
+        }
+    }
+
+    result
+}
+
+/// Copy a typed transcript to the clipboard when the config keeps it there
+pub fn copy_transcript(text: &str, config: ClipboardConfig) -> Result<(), PlatformError> {
+    if config.keep_transcript {
+        Clipboard::new()?.set_text(text)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_keeps_transcript() {
+        let config = ClipboardConfig::default();
+        assert!(config.keep_transcript);
+        assert_eq!(config.restore_delay_ms, DEFAULT_RESTORE_DELAY_MS);
+    }
+
+    #[test]
+    fn test_copy_transcript_without_keep_is_noop() {
+        let config = ClipboardConfig {
+            keep_transcript: false,
+            ..Default::default()
+        };
+        // Must not touch the clipboard, so it succeeds even without a display
+        assert!(copy_transcript("hello", config).is_ok());
+    }
+}
@@ -0,0 +1,248 @@
+//! Record of recently injected dictations
+//!
+//! Key presses can't read back what is in the focused field, so editing
+//! commands that reach past the current dictation ("scratch that", "delete
+//! last sentence") work out how many characters to delete from what was
+//! injected, and a new dictation can continue the text before it. The record
+//! is dropped whenever the cursor may have moved to text we didn't type.
+
+use crate::services::voice_commands::{continue_after, last_sentence_start, last_word_start};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Number of dictations remembered
+const MAX_UTTERANCES: usize = 20;
+
+/// After this long without dictating, the cursor is assumed to have moved
+const IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Text injected by recent dictations, oldest first
+#[derive(Debug, Default)]
+pub struct InjectionHistory {
+    utterances: VecDeque<String>,
+    /// The previous dictation ended with a request to capitalize
+    capitalize_next: bool,
+    /// Application the text was typed into
+    app: Option<String>,
+    /// When the last dictation started
+    last_dictation: Option<Instant>,
+}
+
+impl InjectionHistory {
+    /// Note the application a new dictation goes to, forgetting the
+    /// record if it differs from the last one, can't be detected, or too
+    /// much time has passed
+    pub fn set_target(&mut self, app: Option<&str>, now: Instant) {
+        let switched = app.is_none() || self.app.as_deref() != app;
+        let idle = self
+            .last_dictation
+            .is_some_and(|last| now.saturating_duration_since(last) > IDLE_TIMEOUT);
+        if switched || idle {
+            self.clear();
+        }
+        self.app = app.map(str::to_string);
+        self.last_dictation = Some(now);
+    }
+
+    /// Ask for the next dictation to start with a capital
+    pub fn set_capitalize_next(&mut self, capitalize: bool) {
+        self.capitalize_next = capitalize;
+    }
+
+    /// Adjust text to continue what was typed before it. Only text starting
+    /// a dictation is changed; later text already follows on.
+    pub fn continue_text(&self, text: &str) -> String {
+        if self.utterances.back().is_some_and(|u| !u.is_empty()) {
+            return text.to_string();
+        }
+        match self.utterances.iter().rev().find(|u| !u.is_empty()) {
+            Some(preceding) => continue_after(preceding, self.capitalize_next, text),
+            None if self.capitalize_next => continue_after("", true, text),
+            None => text.to_string(),
+        }
+    }
+
+    /// Start recording a new dictation
+    pub fn begin_utterance(&mut self) {
+        if self.utterances.len() == MAX_UTTERANCES {
+            self.utterances.pop_front();
+        }
+        self.utterances.push_back(String::new());
+    }
+
+    /// Record injected text as part of the current dictation
+    pub fn record_text(&mut self, text: &str) {
+        match self.utterances.back_mut() {
+            Some(current) => current.push_str(text),
+            None => self.utterances.push_back(text.to_string()),
+        }
+    }
+
+    /// Record that `count` characters were deleted before the cursor
+    pub fn record_deleted(&mut self, mut count: usize) {
+        while count > 0 {
+            let Some(last) = self.utterances.back_mut() else {
+                return;
+            };
+            let chars = last.chars().count();
+            if chars > count {
+                let keep = last.char_indices().nth(chars - count).map_or(0, |(i, _)| i);
+                last.truncate(keep);
+                return;
+            }
+            count -= chars;
+            self.utterances.pop_back();
+        }
+    }
+
+    /// Record that the word before the cursor was deleted
+    pub fn record_word_deleted(&mut self) {
+        self.drop_empty_tail();
+        if let Some(last) = self.utterances.back_mut() {
+            last.truncate(last_word_start(last));
+        }
+    }
+
+    /// Remove the most recent dictation, returning its text
+    pub fn take_last(&mut self) -> Option<String> {
+        self.drop_empty_tail();
+        self.utterances.pop_back()
+    }
+
+    /// Number of characters making up the last sentence of the most recent
+    /// dictation
+    pub fn last_sentence_len(&mut self) -> Option<usize> {
+        self.drop_empty_tail();
+        let last = self.utterances.back()?;
+        Some(last[last_sentence_start(last)..].chars().count())
+    }
+
+    /// Forget everything; the cursor is no longer after our text
+    pub fn clear(&mut self) {
+        self.utterances.clear();
+        self.capitalize_next = false;
+    }
+
+    fn drop_empty_tail(&mut self) {
+        while self.utterances.back().is_some_and(|u| u.is_empty()) {
+            self.utterances.pop_back();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(utterances: &[&str]) -> InjectionHistory {
+        let mut history = InjectionHistory::default();
+        for text in utterances {
+            history.begin_utterance();
+            history.record_text(text);
+        }
+        history
+    }
+
+    #[test]
+    fn test_take_last_skips_empty_dictations() {
+        let mut history = history(&["Hello there.", " How are you?"]);
+        history.begin_utterance();
+
+        assert_eq!(history.take_last().as_deref(), Some(" How are you?"));
+        assert_eq!(history.take_last().as_deref(), Some("Hello there."));
+        assert_eq!(history.take_last(), None);
+    }
+
+    #[test]
+    fn test_record_deleted_spans_dictations() {
+        let mut history = history(&["abc", "dé"]);
+        history.record_deleted(3);
+        assert_eq!(history.take_last().as_deref(), Some("ab"));
+        assert_eq!(history.take_last(), None);
+    }
+
+    #[test]
+    fn test_last_sentence_len() {
+        let mut history = history(&["First one. Second one is longer."]);
+        assert_eq!(history.last_sentence_len(), Some(22));
+        assert_eq!(InjectionHistory::default().last_sentence_len(), None);
+    }
+
+    #[test]
+    fn test_record_word_deleted() {
+        let mut history = history(&["Send it now."]);
+        history.begin_utterance();
+        history.record_word_deleted();
+        assert_eq!(history.take_last().as_deref(), Some("Send it"));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = InjectionHistory::default();
+        for i in 0..(MAX_UTTERANCES + 5) {
+            history.begin_utterance();
+            history.record_text(&i.to_string());
+        }
+        assert_eq!(history.utterances.len(), MAX_UTTERANCES);
+        assert_eq!(history.utterances.front().map(String::as_str), Some("5"));
+    }
+
+    #[test]
+    fn test_continue_text_follows_previous_dictation() {
+        let mut history = history(&["I went"]);
+        history.begin_utterance();
+        assert_eq!(history.continue_text("To the shop."), " to the shop.");
+
+        // Later text in the same dictation is left alone
+        history.record_text(" to the shop.");
+        assert_eq!(history.continue_text(" Then"), " Then");
+    }
+
+    #[test]
+    fn test_continue_text_without_record() {
+        let mut history = InjectionHistory::default();
+        history.begin_utterance();
+        assert_eq!(history.continue_text("Hello"), "Hello");
+
+        history.set_capitalize_next(true);
+        assert_eq!(history.continue_text("hello"), "Hello");
+    }
+
+    #[test]
+    fn test_set_target_forgets_on_switch_or_idle() {
+        let start = Instant::now();
+
+        let mut history = InjectionHistory::default();
+        history.set_target(Some("editor"), start);
+        history.begin_utterance();
+        history.record_text("Hello");
+        history.set_target(Some("editor"), start + Duration::from_secs(5));
+        history.begin_utterance();
+        assert_eq!(history.continue_text("There"), " there");
+
+        history.set_target(Some("terminal"), start + Duration::from_secs(10));
+        assert_eq!(history.take_last(), None);
+
+        history.begin_utterance();
+        history.record_text("ls");
+        history.set_capitalize_next(true);
+        history.set_target(Some("terminal"), start + Duration::from_secs(500));
+        assert_eq!(history.take_last(), None);
+        assert_eq!(history.continue_text("next"), "next");
+    }
+
+    #[test]
+    fn test_set_target_forgets_unknown_target() {
+        let start = Instant::now();
+
+        // Without the focused window, the cursor may be in another app
+        let mut history = InjectionHistory::default();
+        history.set_target(None, start);
+        history.begin_utterance();
+        history.record_text("Hello");
+        history.set_target(None, start + Duration::from_secs(5));
+        history.begin_utterance();
+        assert_eq!(history.continue_text("there"), "there");
+        assert_eq!(history.take_last(), None);
+    }
+}
@@ -0,0 +1,145 @@
+//! Key tables shared by the native input backends
+//!
+//! XTest and the RemoteDesktop portal work with X keysyms, uinput with Linux
+//! input event codes. Keys used for shortcuts carry both.
+
+/// A key as an X keysym and a Linux input event code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NativeKey {
+    pub keysym: u32,
+    pub code: u16,
+}
+
+pub const CTRL: NativeKey = NativeKey {
+    keysym: 0xffe3, // Control_L
+    code: 29,
+};
+pub const SHIFT: NativeKey = NativeKey {
+    keysym: 0xffe1, // Shift_L
+    code: 42,
+};
+pub const BACKSPACE: NativeKey = NativeKey {
+    keysym: 0xff08,
+    code: 14,
+};
+pub const TAB: NativeKey = NativeKey {
+    keysym: 0xff09,
+    code: 15,
+};
+pub const RETURN: NativeKey = NativeKey {
+    keysym: 0xff0d,
+    code: 28,
+};
+pub const ESCAPE: NativeKey = NativeKey {
+    keysym: 0xff1b,
+    code: 1,
+};
+pub const INSERT: NativeKey = NativeKey {
+    keysym: 0xff63,
+    code: 110,
+};
+pub const END: NativeKey = NativeKey {
+    keysym: 0xff57,
+    code: 107,
+};
+pub const A: NativeKey = NativeKey {
+    keysym: 0x61,
+    code: 30,
+};
+pub const V: NativeKey = NativeKey {
+    keysym: 0x76,
+    code: 47,
+};
+pub const Z: NativeKey = NativeKey {
+    keysym: 0x7a,
+    code: 44,
+};
+
+/// The keysym that types `c`. Latin-1 characters are their own keysym, the
+/// rest of Unicode is offset by 0x01000000.
+pub fn keysym_for_char(c: char) -> u32 {
+    match c {
+        '\n' => RETURN.keysym,
+        '\t' => TAB.keysym,
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u32,
+        c => 0x0100_0000 + c as u32,
+    }
+}
+
+/// The input event code that types `c` on a US keyboard layout and whether
+/// Shift must be held. Only ASCII can be typed this way.
+pub fn us_layout_key(c: char) -> Option<(u16, bool)> {
+    const LETTERS: [u16; 26] = [
+        30, 48, 46, 32, 18, 33, 34, 35, 23, 36, 37, 38, 50, 49, 24, 25, 16, 19, 31, 20, 22, 47, 17,
+        45, 21, 44,
+    ];
+    const DIGITS: [u16; 10] = [11, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    let key = match c {
+        'a'..='z' => (LETTERS[c as usize - 'a' as usize], false),
+        'A'..='Z' => (LETTERS[c as usize - 'A' as usize], true),
+        '0'..='9' => (DIGITS[c as usize - '0' as usize], false),
+        '\n' => (RETURN.code, false),
+        '\t' => (TAB.code, false),
+        ' ' => (57, false),
+        '-' => (12, false),
+        '_' => (12, true),
+        '=' => (13, false),
+        '+' => (13, true),
+        '[' => (26, false),
+        '{' => (26, true),
+        ']' => (27, false),
+        '}' => (27, true),
+        ';' => (39, false),
+        ':' => (39, true),
+        '\'' => (40, false),
+        '"' => (40, true),
+        '`' => (41, false),
+        '~' => (41, true),
+        '\\' => (43, false),
+        '|' => (43, true),
+        ',' => (51, false),
+        '<' => (51, true),
+        '.' => (52, false),
+        '>' => (52, true),
+        '/' => (53, false),
+        '?' => (53, true),
+        '!' => (2, true),
+        '@' => (3, true),
+        '#' => (4, true),
+        '$' => (5, true),
+        '%' => (6, true),
+        '^' => (7, true),
+        '&' => (8, true),
+        '*' => (9, true),
+        '(' => (10, true),
+        ')' => (11, true),
+        _ => return None,
+    };
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keysym_for_char() {
+        assert_eq!(keysym_for_char('a'), 0x61);
+        assert_eq!(keysym_for_char('~'), 0x7e);
+        assert_eq!(keysym_for_char('é'), 0xe9);
+        assert_eq!(keysym_for_char('€'), 0x0100_20ac);
+        assert_eq!(keysym_for_char('😀'), 0x0101_f600);
+        assert_eq!(keysym_for_char('\n'), RETURN.keysym);
+    }
+
+    #[test]
+    fn test_us_layout_covers_printable_ascii() {
+        for c in (' '..='~').chain(['\n', '\t']) {
+            assert!(us_layout_key(c).is_some(), "{:?}", c);
+        }
+        assert_eq!(us_layout_key('a'), Some((A.code, false)));
+        assert_eq!(us_layout_key('V'), Some((V.code, true)));
+        assert_eq!(us_layout_key('é'), None);
+    }
+}
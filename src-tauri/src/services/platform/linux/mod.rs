@@ -0,0 +1,659 @@
+//! Linux-specific text injection implementation
+//!
+//! Sends key events natively where possible: XTest on X11, a uinput virtual
+//! keyboard or the RemoteDesktop portal on Wayland. xdotool for X11 and
+//! ydotool/wtype for Wayland are the fallback when none of those is
+//! available.
+
+pub mod keys;
+mod portal;
+mod uinput;
+mod xtest;
+
+pub use uinput::{DeviceCapabilities, UinputForwarder, DEVICE_NAME_PREFIX};
+
+use super::clipboard::{self, ClipboardConfig};
+use super::text_inject::{InjectionMethod, Key, PasteShortcut, PlatformError, TextInjector};
+use keys::NativeKey;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Display server type
+#[derive(Debug, Clone, Copy)]
+enum DisplayServer {
+    X11,
+    Wayland,
+}
+
+/// A native way to send key events
+trait VirtualKeyboard: Send {
+    /// Backend name for logs
+    fn name(&self) -> &'static str;
+
+    /// Press the keys in order, then release them in reverse order
+    fn send_chord(&mut self, keys: &[NativeKey]) -> Result<(), PlatformError>;
+
+    /// Type text, pausing `delay` after each character. Fails before
+    /// sending anything when some character can't be typed.
+    fn type_text(&mut self, text: &str, delay: Duration) -> Result<(), TypeError>;
+}
+
+/// Typing that failed, and how far it got
+struct TypeError {
+    error: PlatformError,
+    /// Bytes of the text typed before the failure; `None` when unknown
+    typed: Option<usize>,
+}
+
+impl From<PlatformError> for TypeError {
+    /// An error before anything was typed
+    fn from(error: PlatformError) -> Self {
+        Self {
+            error,
+            typed: Some(0),
+        }
+    }
+}
+
+impl std::fmt::Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+/// Opens one kind of native keyboard
+type ConnectKeyboard = fn() -> Result<Box<dyn VirtualKeyboard>, PlatformError>;
+
+/// The native keyboard, connected on first use
+enum NativeKeyboard {
+    Unprobed,
+    Unavailable,
+    Ready(Box<dyn VirtualKeyboard>),
+}
+
+/// A key combination natively and in the notation of each key tool
+struct KeyChord {
+    /// Description for errors and logs
+    name: &'static str,
+    /// Keys for the native backends
+    keys: &'static [NativeKey],
+    /// xdotool key name, e.g. "ctrl+a"
+    xdotool: &'static str,
+    /// ydotool <keycode>:<state> events
+    ydotool: &'static [&'static str],
+    /// wtype arguments
+    wtype: &'static [&'static str],
+}
+
+const ENTER: KeyChord = KeyChord {
+    name: "enter",
+    keys: &[keys::RETURN],
+    xdotool: "Return",
+    ydotool: &["28:1", "28:0"],
+    wtype: &["-k", "Return"],
+};
+
+const TAB: KeyChord = KeyChord {
+    name: "tab",
+    keys: &[keys::TAB],
+    xdotool: "Tab",
+    ydotool: &["15:1", "15:0"],
+    wtype: &["-k", "Tab"],
+};
+
+const ESCAPE: KeyChord = KeyChord {
+    name: "escape",
+    keys: &[keys::ESCAPE],
+    xdotool: "Escape",
+    ydotool: &["1:1", "1:0"],
+    wtype: &["-k", "Escape"],
+};
+
+const END: KeyChord = KeyChord {
+    name: "end",
+    keys: &[keys::END],
+    xdotool: "End",
+    ydotool: &["107:1", "107:0"],
+    wtype: &["-k", "End"],
+};
+
+const BACKSPACE: KeyChord = KeyChord {
+    name: "backspace",
+    keys: &[keys::BACKSPACE],
+    xdotool: "BackSpace",
+    ydotool: &["14:1", "14:0"],
+    wtype: &["-k", "BackSpace"],
+};
+
+// ydotool keycodes: 29=Ctrl, 42=Shift, 14=BackSpace, 110=Insert, 30=A, 47=V, 44=Z
+const DELETE_WORD: KeyChord = KeyChord {
+    name: "delete word",
+    keys: &[keys::CTRL, keys::BACKSPACE],
+    xdotool: "ctrl+BackSpace",
+    ydotool: &["29:1", "14:1", "14:0", "29:0"],
+    wtype: &["-M", "ctrl", "-k", "BackSpace", "-m", "ctrl"],
+};
+
+const SELECT_ALL: KeyChord = KeyChord {
+    name: "select all",
+    keys: &[keys::CTRL, keys::A],
+    xdotool: "ctrl+a",
+    ydotool: &["29:1", "30:1", "30:0", "29:0"],
+    wtype: &["-M", "ctrl", "-P", "a", "-p", "a", "-m", "ctrl"],
+};
+
+const PASTE: KeyChord = KeyChord {
+    name: "paste",
+    keys: &[keys::CTRL, keys::V],
+    xdotool: "ctrl+v",
+    ydotool: &["29:1", "47:1", "47:0", "29:0"],
+    wtype: &["-M", "ctrl", "-P", "v", "-p", "v", "-m", "ctrl"],
+};
+
+const PASTE_CTRL_SHIFT: KeyChord = KeyChord {
+    name: "paste (ctrl+shift+v)",
+    keys: &[keys::CTRL, keys::SHIFT, keys::V],
+    xdotool: "ctrl+shift+v",
+    ydotool: &["29:1", "42:1", "47:1", "47:0", "42:0", "29:0"],
+    wtype: &[
+        "-M", "ctrl", "-M", "shift", "-P", "v", "-p", "v", "-m", "shift", "-m", "ctrl",
+    ],
+};
+
+const PASTE_SHIFT_INSERT: KeyChord = KeyChord {
+    name: "paste (shift+insert)",
+    keys: &[keys::SHIFT, keys::INSERT],
+    xdotool: "shift+Insert",
+    ydotool: &["42:1", "110:1", "110:0", "42:0"],
+    wtype: &["-M", "shift", "-k", "Insert", "-m", "shift"],
+};
+
+const UNDO: KeyChord = KeyChord {
+    name: "undo",
+    keys: &[keys::CTRL, keys::Z],
+    xdotool: "ctrl+z",
+    ydotool: &["29:1", "44:1", "44:0", "29:0"],
+    wtype: &["-M", "ctrl", "-P", "z", "-p", "z", "-m", "ctrl"],
+};
+
+/// Linux text injector using clipboard + paste simulation or typing
+pub struct LinuxTextInjector {
+    display_server: DisplayServer,
+    delay_ms: u32,
+    method: InjectionMethod,
+    clipboard: ClipboardConfig,
+    native: Mutex<NativeKeyboard>,
+}
+
+impl LinuxTextInjector {
+    /// Create a new Linux text injector
+    pub fn new() -> Result<Self, PlatformError> {
+        let display_server = if std::env::var("WAYLAND_DISPLAY").is_ok() {
+            tracing::debug!("Detected Wayland display server");
+            DisplayServer::Wayland
+        } else {
+            tracing::debug!("Detected X11 display server");
+            DisplayServer::X11
+        };
+
+        Ok(Self {
+            display_server,
+            delay_ms: 0,
+            method: InjectionMethod::default(),
+            clipboard: ClipboardConfig::default(),
+            native: Mutex::new(NativeKeyboard::Unprobed),
+        })
+    }
+
+    /// Check if a tool is on the PATH
+    fn which(tool: &str) -> bool {
+        Command::new("which")
+            .arg(tool)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Check if xdotool is available
+    fn has_xdotool() -> bool {
+        static FOUND: OnceLock<bool> = OnceLock::new();
+        *FOUND.get_or_init(|| Self::which("xdotool"))
+    }
+
+    /// Check if ydotool is available
+    fn has_ydotool() -> bool {
+        static FOUND: OnceLock<bool> = OnceLock::new();
+        *FOUND.get_or_init(|| Self::which("ydotool"))
+    }
+
+    /// Check if wtype is available (Wayland alternative)
+    fn has_wtype() -> bool {
+        static FOUND: OnceLock<bool> = OnceLock::new();
+        *FOUND.get_or_init(|| Self::which("wtype"))
+    }
+
+    /// Connect the first native keyboard that works on this display server.
+    /// The portal sends keysyms and comes first on Wayland; uinput sends
+    /// key codes and is only used on a US layout.
+    fn probe_native(&self) -> Option<Box<dyn VirtualKeyboard>> {
+        let candidates: &[ConnectKeyboard] = match self.display_server {
+            DisplayServer::X11 => &[|| Ok(Box::new(xtest::XTestKeyboard::connect()?))],
+            DisplayServer::Wayland => &[
+                || Ok(Box::new(portal::PortalKeyboard::connect()?)),
+                || Ok(Box::new(uinput::UinputKeyboard::create()?)),
+            ],
+        };
+
+        for connect in candidates {
+            match connect() {
+                Ok(keyboard) => {
+                    tracing::info!("Injecting keys through {}", keyboard.name());
+                    return Some(keyboard);
+                }
+                Err(e) => tracing::debug!("Native keyboard unavailable: {}", e),
+            }
+        }
+        tracing::info!("No native keyboard available, falling back to command-line tools");
+        None
+    }
+
+    /// Run `send` on the native keyboard, connecting it first if needed
+    fn with_native<E: From<PlatformError> + std::fmt::Display>(
+        &self,
+        send: impl FnOnce(&mut dyn VirtualKeyboard) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let mut native = self
+            .native
+            .lock()
+            .map_err(|_| PlatformError::VirtualKeyboard("keyboard lock poisoned".to_string()))?;
+        if let NativeKeyboard::Unprobed = *native {
+            *native = match self.probe_native() {
+                Some(keyboard) => NativeKeyboard::Ready(keyboard),
+                None => NativeKeyboard::Unavailable,
+            };
+        }
+
+        match &mut *native {
+            NativeKeyboard::Ready(keyboard) => {
+                let name = keyboard.name();
+                send(keyboard.as_mut()).map_err(|e| {
+                    tracing::debug!("{} failed, trying command-line tools: {}", name, e);
+                    e
+                })
+            }
+            _ => Err(PlatformError::NotSupported.into()),
+        }
+    }
+
+    /// Type text on X11 using xdotool, reading it from stdin so it can't be
+    /// taken for options
+    fn type_x11(&self, text: &str) -> Result<(), PlatformError> {
+        let delay = self.delay_ms.to_string();
+        Self::run_with_stdin(
+            "xdotool",
+            &["type", "--clearmodifiers", "--delay", &delay, "--file", "-"],
+            text,
+        )
+    }
+
+    /// Type text on Wayland using wtype, which maps any Unicode character
+    fn type_wayland_wtype(&self, text: &str) -> Result<(), PlatformError> {
+        let delay = self.delay_ms.to_string();
+        Self::run_with_stdin("wtype", &["-d", &delay, "-"], text)
+    }
+
+    /// Type text on Wayland using ydotool, which only knows the keys of a US
+    /// layout
+    fn type_wayland_ydotool(&self, text: &str) -> Result<(), PlatformError> {
+        if !text.is_ascii() {
+            return Err(PlatformError::CommandFailed(
+                "ydotool can only type ASCII text. Install wtype to type other characters: sudo apt install wtype".to_string(),
+            ));
+        }
+
+        let delay = self.delay_ms.to_string();
+        let status = Command::new("ydotool")
+            .args(["type", "--key-delay", &delay, "--", text])
+            .status()
+            .map_err(|e| PlatformError::CommandFailed(format!("ydotool: {}", e)))?;
+
+        if !status.success() {
+            return Err(PlatformError::CommandFailed(
+                "ydotool type failed".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Run a tool with `input` written to its stdin
+    fn run_with_stdin(tool: &str, args: &[&str], input: &str) -> Result<(), PlatformError> {
+        let mut child = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| PlatformError::CommandFailed(format!("{}: {}", tool, e)))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(input.as_bytes())
+                .map_err(|e| PlatformError::CommandFailed(format!("{}: {}", tool, e)))?;
+        }
+
+        let status = child
+            .wait()
+            .map_err(|e| PlatformError::CommandFailed(format!("{}: {}", tool, e)))?;
+
+        if !status.success() {
+            return Err(PlatformError::CommandFailed(format!(
+                "{} type failed",
+                tool
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl LinuxTextInjector {
+    /// Type text as keystrokes, natively or with the available tool. The
+    /// tool only types what the native keyboard didn't get to.
+    fn type_text(&self, text: &str) -> Result<(), PlatformError> {
+        tracing::info!("Injecting text ({} chars) via typing", text.len());
+
+        let delay = Duration::from_millis(self.delay_ms as u64);
+        match self.with_native(|keyboard| keyboard.type_text(text, delay)) {
+            Ok(()) => {}
+            Err(TypeError {
+                typed: Some(typed), ..
+            }) => {
+                if typed < text.len() {
+                    self.type_text_cli(&text[typed..])?;
+                }
+            }
+            // Retyping could repeat text that got through
+            Err(TypeError { error, typed: None }) => return Err(error),
+        }
+
+        clipboard::copy_transcript(text, self.clipboard)
+    }
+
+    /// Type text with the available tool
+    fn type_text_cli(&self, text: &str) -> Result<(), PlatformError> {
+        match self.display_server {
+            DisplayServer::X11 => {
+                if Self::has_xdotool() {
+                    self.type_x11(text)
+                } else {
+                    Err(PlatformError::CommandFailed(
+                        "xdotool not found. Install with: sudo apt install xdotool".to_string(),
+                    ))
+                }
+            }
+            DisplayServer::Wayland => {
+                if Self::has_wtype() {
+                    self.type_wayland_wtype(text)
+                } else if Self::has_ydotool() {
+                    self.type_wayland_ydotool(text)
+                } else {
+                    Err(PlatformError::CommandFailed(
+                        "Neither wtype nor ydotool found. Install with: sudo apt install wtype or ydotool".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Send a key combination, natively or with the available tool
+    fn send_chord(&self, chord: &KeyChord) -> Result<(), PlatformError> {
+        self.send_chord_repeated(chord, 1)
+    }
+
+    /// Send a key combination `count` times. The tool only sends the ones
+    /// the native keyboard didn't get to.
+    fn send_chord_repeated(&self, chord: &KeyChord, count: usize) -> Result<(), PlatformError> {
+        tracing::debug!("Sending {} key x{}", chord.name, count);
+
+        let mut sent = 0;
+        let native = self.with_native(|keyboard| {
+            (0..count).try_for_each(|_| {
+                keyboard.send_chord(chord.keys)?;
+                sent += 1;
+                Ok::<_, PlatformError>(())
+            })
+        });
+        if native.is_ok() {
+            return Ok(());
+        }
+
+        (sent..count).try_for_each(|_| self.send_chord_cli(chord))
+    }
+
+    /// Send a key combination with the available tool
+    fn send_chord_cli(&self, chord: &KeyChord) -> Result<(), PlatformError> {
+        let (tool, args): (&str, Vec<&str>) = match self.display_server {
+            DisplayServer::X11 => {
+                if !Self::has_xdotool() {
+                    return Err(PlatformError::CommandFailed(
+                        "xdotool not found. Install with: sudo apt install xdotool".to_string(),
+                    ));
+                }
+                ("xdotool", vec!["key", "--clearmodifiers", chord.xdotool])
+            }
+            DisplayServer::Wayland => {
+                if Self::has_ydotool() {
+                    ("ydotool", [&["key"][..], chord.ydotool].concat())
+                } else if Self::has_wtype() {
+                    ("wtype", chord.wtype.to_vec())
+                } else {
+                    return Err(PlatformError::CommandFailed(
+                        "Neither ydotool nor wtype found. Install with: sudo apt install ydotool or wtype".to_string(),
+                    ));
+                }
+            }
+        };
+
+        let status = Command::new(tool)
+            .args(&args)
+            .status()
+            .map_err(|e| PlatformError::CommandFailed(format!("{}: {}", tool, e)))?;
+
+        if !status.success() {
+            return Err(PlatformError::CommandFailed(format!(
+                "{} {} failed",
+                tool, chord.name
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for LinuxTextInjector {
+    fn default() -> Self {
+        Self::new().unwrap_or(Self {
+            display_server: DisplayServer::X11,
+            delay_ms: 0,
+            method: InjectionMethod::default(),
+            clipboard: ClipboardConfig::default(),
+            native: Mutex::new(NativeKeyboard::Unprobed),
+        })
+    }
+}
+
+impl TextInjector for LinuxTextInjector {
+    fn inject_text(&self, text: &str) -> Result<(), PlatformError> {
+        if text.is_empty() {
+            tracing::debug!("Empty text, nothing to inject");
+            return Ok(());
+        }
+
+        if self.method == InjectionMethod::Type {
+            return self.type_text(text);
+        }
+
+        tracing::info!("Injecting text ({} chars) via clipboard+paste", text.len());
+
+        // Small delay for clipboard to update before pasting
+        let settle = Duration::from_millis(50 + self.delay_ms as u64);
+        let paste = match self.clipboard.paste_shortcut {
+            PasteShortcut::Standard => &PASTE,
+            PasteShortcut::CtrlShiftV => &PASTE_CTRL_SHIFT,
+            PasteShortcut::ShiftInsert => &PASTE_SHIFT_INSERT,
+        };
+        clipboard::paste_via_clipboard(text, self.clipboard, settle, || self.send_chord(paste))
+    }
+
+    fn set_delay(&mut self, delay_ms: u32) {
+        self.delay_ms = delay_ms.min(50); // Cap at 50ms as per story requirements
+    }
+
+    fn set_method(&mut self, method: InjectionMethod) {
+        self.method = method;
+    }
+
+    fn set_clipboard_config(&mut self, config: ClipboardConfig) {
+        self.clipboard = config;
+    }
+
+    fn get_permission_instructions(&self) -> Option<String> {
+        match self.display_server {
+            DisplayServer::X11 => Some(
+                "Keys are sent through the XTEST extension. If that fails, install xdotool: sudo apt install xdotool".to_string(),
+            ),
+            DisplayServer::Wayland => Some(
+                "Allow keyboard access when the desktop asks, or give your user write access to /dev/uinput (add it to the input group). Otherwise install ydotool or wtype; ydotool needs its daemon running: sudo ydotoold".to_string(),
+            ),
+        }
+    }
+
+    fn delete_characters(&self, count: usize) -> Result<(), PlatformError> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        tracing::debug!("Deleting {} characters", count);
+        self.send_chord_repeated(&BACKSPACE, count)
+    }
+
+    fn send_undo(&self) -> Result<(), PlatformError> {
+        tracing::debug!("Sending undo command (Ctrl+Z)");
+        self.send_chord(&UNDO)
+    }
+
+    fn press_key(&self, key: Key) -> Result<(), PlatformError> {
+        self.send_chord(match key {
+            Key::Enter => &ENTER,
+            Key::Tab => &TAB,
+            Key::Escape => &ESCAPE,
+        })
+    }
+
+    fn delete_previous_word(&self) -> Result<(), PlatformError> {
+        self.send_chord(&DELETE_WORD)
+    }
+
+    fn select_all(&self) -> Result<(), PlatformError> {
+        self.send_chord(&SELECT_ALL)
+    }
+
+    fn go_to_line_end(&self) -> Result<(), PlatformError> {
+        self.send_chord(&END)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHORDS: [&KeyChord; 11] = [
+        &ENTER,
+        &TAB,
+        &ESCAPE,
+        &END,
+        &BACKSPACE,
+        &DELETE_WORD,
+        &SELECT_ALL,
+        &PASTE,
+        &PASTE_CTRL_SHIFT,
+        &PASTE_SHIFT_INSERT,
+        &UNDO,
+    ];
+
+    #[test]
+    fn test_linux_injector_creation() {
+        let injector = LinuxTextInjector::new();
+        assert!(injector.is_ok());
+    }
+
+    #[test]
+    fn test_display_server_detection() {
+        let injector = LinuxTextInjector::new().unwrap();
+        // Just ensure it picked something
+        assert!(injector.supports_paste());
+    }
+
+    #[test]
+    fn test_set_delay() {
+        let mut injector = LinuxTextInjector::new().unwrap();
+        injector.set_delay(25);
+        assert_eq!(injector.delay_ms, 25);
+
+        // Test capping at 50ms
+        injector.set_delay(100);
+        assert_eq!(injector.delay_ms, 50);
+    }
+
+    #[test]
+    fn test_set_method() {
+        let mut injector = LinuxTextInjector::new().unwrap();
+        assert_eq!(injector.method, InjectionMethod::Paste);
+
+        injector.set_method(InjectionMethod::Type);
+        assert_eq!(injector.method, InjectionMethod::Type);
+        // Empty text is still a no-op when typing
+        assert!(injector.inject_text("").is_ok());
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let injector = LinuxTextInjector::new().unwrap();
+        // Empty text should succeed without doing anything
+        let result = injector.inject_text("");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_ydotool_chords_release_every_key() {
+        for chord in CHORDS {
+            let pressed: Vec<&str> = chord
+                .ydotool
+                .iter()
+                .filter_map(|e| e.strip_suffix(":1"))
+                .collect();
+            let mut released: Vec<&str> = chord
+                .ydotool
+                .iter()
+                .filter_map(|e| e.strip_suffix(":0"))
+                .collect();
+            released.reverse();
+            assert_eq!(pressed, released, "{}", chord.name);
+        }
+    }
+
+    #[test]
+    fn test_native_chords_match_ydotool() {
+        for chord in CHORDS {
+            let pressed: Vec<String> = chord
+                .ydotool
+                .iter()
+                .filter_map(|e| e.strip_suffix(":1"))
+                .map(str::to_string)
+                .collect();
+            let native: Vec<String> = chord.keys.iter().map(|k| k.code.to_string()).collect();
+            assert_eq!(pressed, native, "{}", chord.name);
+        }
+    }
+}
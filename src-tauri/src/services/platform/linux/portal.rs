@@ -0,0 +1,204 @@
+//! Wayland input through the xdg-desktop-portal RemoteDesktop interface
+//!
+//! The portal asks the user once for keyboard access and then accepts
+//! keysyms, so any character the compositor can map is typed regardless of
+//! layout. The session lives on its own thread with its own async runtime,
+//! since injection runs synchronously inside the app's runtime.
+
+use super::keys::{keysym_for_char, NativeKey};
+use super::{TypeError, VirtualKeyboard};
+use crate::services::platform::PlatformError;
+use ashpd::desktop::remote_desktop::{DeviceType, KeyState, RemoteDesktop};
+use ashpd::desktop::PersistMode;
+use std::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::mpsc as async_mpsc;
+
+/// How long the user has to answer the portal's permission dialog
+const START_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a batch of key events may take
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One key press or release, followed by an optional pause
+struct KeyEvent {
+    keysym: u32,
+    pressed: bool,
+    pause: Duration,
+}
+
+/// A batch of key events and where to report the outcome: on failure the
+/// number of events sent and the error
+struct Batch {
+    events: Vec<KeyEvent>,
+    done: mpsc::Sender<Result<(), (usize, String)>>,
+}
+
+fn portal_error(e: impl std::fmt::Display) -> PlatformError {
+    PlatformError::VirtualKeyboard(format!("RemoteDesktop portal: {}", e))
+}
+
+/// Keyboard access granted through the RemoteDesktop portal
+pub struct PortalKeyboard {
+    batches: async_mpsc::UnboundedSender<Batch>,
+}
+
+impl PortalKeyboard {
+    /// Start a portal session. Blocks until the user allows or denies
+    /// keyboard access.
+    pub fn connect() -> Result<Self, PlatformError> {
+        let (batches, receiver) = async_mpsc::unbounded_channel();
+        let (started, start_result) = mpsc::channel();
+
+        std::thread::Builder::new()
+            .name("remote-desktop-portal".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = started.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                runtime.block_on(run_session(receiver, started));
+            })
+            .map_err(portal_error)?;
+
+        match start_result.recv_timeout(START_TIMEOUT) {
+            Ok(Ok(())) => Ok(Self { batches }),
+            Ok(Err(e)) => Err(portal_error(e)),
+            Err(_) => Err(portal_error("no answer to the permission request")),
+        }
+    }
+
+    /// Send key events. On failure returns the number of events sent, or
+    /// `None` when the session may still be sending them.
+    fn send(&self, events: Vec<KeyEvent>) -> Result<(), (Option<usize>, PlatformError)> {
+        let (done, result) = mpsc::channel();
+        self.batches
+            .send(Batch { events, done })
+            .map_err(|_| (Some(0), portal_error("session closed")))?;
+        match result.recv_timeout(SEND_TIMEOUT) {
+            Ok(outcome) => outcome.map_err(|(sent, e)| (Some(sent), portal_error(e))),
+            Err(_) => Err((None, portal_error("timed out sending keys"))),
+        }
+    }
+}
+
+/// Open the session, report whether it started and forward key events
+/// until the keyboard is dropped
+async fn run_session(
+    mut batches: async_mpsc::UnboundedReceiver<Batch>,
+    started: mpsc::Sender<Result<(), String>>,
+) {
+    let session = async {
+        let proxy = RemoteDesktop::new().await?;
+        let session = proxy.create_session().await?;
+        proxy
+            .select_devices(
+                &session,
+                DeviceType::Keyboard.into(),
+                None,
+                PersistMode::DoNot,
+            )
+            .await?;
+        let devices = proxy.start(&session, None).await?.response()?.devices();
+        Ok::<_, ashpd::Error>((proxy, session, devices))
+    }
+    .await;
+
+    let (proxy, session) = match session {
+        Ok((proxy, session, devices)) if devices.contains(DeviceType::Keyboard) => {
+            let _ = started.send(Ok(()));
+            (proxy, session)
+        }
+        Ok(_) => {
+            let _ = started.send(Err("keyboard access was not granted".to_string()));
+            return;
+        }
+        Err(e) => {
+            let _ = started.send(Err(e.to_string()));
+            return;
+        }
+    };
+    tracing::debug!("RemoteDesktop portal session started");
+
+    while let Some(batch) = batches.recv().await {
+        let mut outcome = Ok(());
+        for (sent, event) in batch.events.into_iter().enumerate() {
+            let state = if event.pressed {
+                KeyState::Pressed
+            } else {
+                KeyState::Released
+            };
+            if let Err(e) = proxy
+                .notify_keyboard_keysym(&session, event.keysym as i32, state)
+                .await
+            {
+                outcome = Err((sent, e.to_string()));
+                break;
+            }
+            if !event.pause.is_zero() {
+                tokio::time::sleep(event.pause).await;
+            }
+        }
+        let _ = batch.done.send(outcome);
+    }
+
+    let _ = session.close().await;
+}
+
+impl VirtualKeyboard for PortalKeyboard {
+    fn name(&self) -> &'static str {
+        "RemoteDesktop portal"
+    }
+
+    fn send_chord(&mut self, keys: &[NativeKey]) -> Result<(), PlatformError> {
+        let press = keys.iter().map(|key| KeyEvent {
+            keysym: key.keysym,
+            pressed: true,
+            pause: Duration::ZERO,
+        });
+        let release = keys.iter().rev().map(|key| KeyEvent {
+            keysym: key.keysym,
+            pressed: false,
+            pause: Duration::ZERO,
+        });
+        self.send(press.chain(release).collect())
+            .map_err(|(_, e)| e)
+    }
+
+    fn type_text(&mut self, text: &str, delay: Duration) -> Result<(), TypeError> {
+        let chars: Vec<(usize, char)> = text.char_indices().filter(|&(_, c)| c != '\r').collect();
+        let events = chars
+            .iter()
+            .flat_map(|&(_, c)| {
+                let keysym = keysym_for_char(c);
+                [
+                    KeyEvent {
+                        keysym,
+                        pressed: true,
+                        pause: Duration::ZERO,
+                    },
+                    KeyEvent {
+                        keysym,
+                        pressed: false,
+                        pause: delay,
+                    },
+                ]
+            })
+            .collect();
+        self.send(events).map_err(|(sent, error)| TypeError {
+            // Each character is a press and a release
+            typed: sent.map(|sent| {
+                chars
+                    .get(sent / 2)
+                    .map_or(text.len(), |&(offset, _)| offset)
+            }),
+            error,
+        })
+    }
+}
@@ -0,0 +1,318 @@
+//! Wayland input through a uinput virtual keyboard
+//!
+//! The kernel device works with every compositor but needs write access to
+//! /dev/uinput (the `input` group or a udev rule). It sends key codes, so
+//! it is only used on a US layout, and only ASCII can be typed.
+//!
+//! The hotkey listener also replays the events of the input devices it
+//! grabs through a uinput device, see [`UinputForwarder`].
+
+use super::keys::{us_layout_key, NativeKey, SHIFT};
+use super::{TypeError, VirtualKeyboard};
+use crate::services::platform::PlatformError;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::process::Command;
+use std::time::Duration;
+
+const UINPUT_PATH: &str = "/dev/uinput";
+
+// linux/input-event-codes.h
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const EV_MSC: u16 = 0x04;
+const SYN_REPORT: u16 = 0;
+/// Highest key code the device announces (KEY_MICMUTE)
+const KEY_MAX_USED: u16 = 248;
+
+// linux/uinput.h ioctls
+const UI_SET_EVBIT: libc::c_ulong = 0x4004_5564;
+const UI_SET_KEYBIT: libc::c_ulong = 0x4004_5565;
+const UI_SET_RELBIT: libc::c_ulong = 0x4004_5566;
+const UI_SET_MSCBIT: libc::c_ulong = 0x4004_5568;
+const UI_DEV_CREATE: libc::c_ulong = 0x5501;
+const UI_DEV_DESTROY: libc::c_ulong = 0x5502;
+
+/// Time the compositor needs to pick up a new device
+const DEVICE_SETTLE: Duration = Duration::from_millis(200);
+
+/// Start of the names of the devices created here, so the hotkey listener
+/// can tell them from real ones
+pub const DEVICE_NAME_PREFIX: &str = "EZ Flow";
+
+/// `struct uinput_user_dev`
+#[repr(C)]
+struct UinputUserDev {
+    name: [u8; 80],
+    id: [u16; 4],
+    ff_effects_max: u32,
+    abs: [[i32; 64]; 4],
+}
+
+fn uinput_error(e: std::io::Error) -> PlatformError {
+    match e.kind() {
+        ErrorKind::PermissionDenied => PlatformError::PermissionDenied(format!(
+            "{} is not writable. Add yourself to the input group or install a udev rule",
+            UINPUT_PATH
+        )),
+        _ => PlatformError::VirtualKeyboard(format!("uinput: {}", e)),
+    }
+}
+
+fn ioctl(file: &File, request: libc::c_ulong, arg: libc::c_int) -> Result<(), PlatformError> {
+    // SAFETY: the uinput requests used here take an int argument or none
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), request as _, arg) };
+    if result < 0 {
+        return Err(uinput_error(std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+/// Create a device reporting the given codes of each event type
+fn create_device(name: &str, events: &[(u16, &[u16])]) -> Result<File, PlatformError> {
+    let device = OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(UINPUT_PATH)
+        .map_err(uinput_error)?;
+
+    for &(kind, codes) in events {
+        let set_bit = match kind {
+            EV_KEY => UI_SET_KEYBIT,
+            EV_REL => UI_SET_RELBIT,
+            EV_MSC => UI_SET_MSCBIT,
+            _ => continue,
+        };
+        ioctl(&device, UI_SET_EVBIT, kind as libc::c_int)?;
+        for &code in codes {
+            ioctl(&device, set_bit, code as libc::c_int)?;
+        }
+    }
+
+    let mut setup = UinputUserDev {
+        name: [0; 80],
+        // BUS_VIRTUAL
+        id: [0x06, 0, 0, 1],
+        ff_effects_max: 0,
+        abs: [[0; 64]; 4],
+    };
+    // Keep the terminating zero
+    let name = &name.as_bytes()[..name.len().min(setup.name.len() - 1)];
+    setup.name[..name.len()].copy_from_slice(name);
+    // SAFETY: UinputUserDev is plain old data with no padding
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            &setup as *const UinputUserDev as *const u8,
+            std::mem::size_of::<UinputUserDev>(),
+        )
+    };
+    (&device).write_all(bytes).map_err(uinput_error)?;
+    ioctl(&device, UI_DEV_CREATE, 0)?;
+
+    std::thread::sleep(DEVICE_SETTLE);
+    Ok(device)
+}
+
+/// Write one event to the device
+fn emit(mut device: &File, kind: u16, code: u16, value: i32) -> Result<(), PlatformError> {
+    let event = libc::input_event {
+        time: libc::timeval {
+            tv_sec: 0,
+            tv_usec: 0,
+        },
+        type_: kind,
+        code,
+        value,
+    };
+    // SAFETY: input_event is plain old data
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            &event as *const libc::input_event as *const u8,
+            std::mem::size_of::<libc::input_event>(),
+        )
+    };
+    device.write_all(bytes).map_err(uinput_error)
+}
+
+/// The XKB layout and variant, e.g. "de" and "nodeadkeys", from the
+/// environment or else the system default in localectl
+fn active_layout() -> Option<(String, String)> {
+    if let Ok(layout) = std::env::var("XKB_DEFAULT_LAYOUT") {
+        let variant = std::env::var("XKB_DEFAULT_VARIANT").unwrap_or_default();
+        return Some((layout, variant));
+    }
+    let output = Command::new("localectl").arg("status").output().ok()?;
+    let status = String::from_utf8_lossy(&output.stdout);
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .map(|value| value.trim().to_string())
+    };
+    Some((
+        field("X11 Layout:")?,
+        field("X11 Variant:").unwrap_or_default(),
+    ))
+}
+
+/// Whether key codes type the characters of a US layout. With several
+/// layouts the active one isn't known.
+fn is_us_layout(layout: &str, variant: &str) -> bool {
+    layout == "us" && variant.is_empty()
+}
+
+/// Virtual keyboard device created through uinput
+pub struct UinputKeyboard {
+    device: File,
+}
+
+impl UinputKeyboard {
+    /// Create the virtual keyboard device, if the keyboard layout is US
+    pub fn create() -> Result<Self, PlatformError> {
+        match active_layout() {
+            Some((layout, variant)) if is_us_layout(&layout, &variant) => {}
+            Some((layout, variant)) => {
+                return Err(PlatformError::VirtualKeyboard(format!(
+                    "uinput types US key codes, the layout is {:?} variant {:?}",
+                    layout, variant
+                )))
+            }
+            None => {
+                return Err(PlatformError::VirtualKeyboard(
+                    "uinput types US key codes and the layout is unknown".to_string(),
+                ))
+            }
+        }
+
+        let keys: Vec<u16> = (1..=KEY_MAX_USED).collect();
+        let name = format!("{} virtual keyboard", DEVICE_NAME_PREFIX);
+        let device = create_device(&name, &[(EV_KEY, &keys)])?;
+        tracing::debug!("Created uinput virtual keyboard");
+        Ok(Self { device })
+    }
+
+    fn key(&self, code: u16, pressed: bool) -> Result<(), PlatformError> {
+        emit(&self.device, EV_KEY, code, pressed as i32)?;
+        emit(&self.device, EV_SYN, SYN_REPORT, 0)
+    }
+}
+
+impl VirtualKeyboard for UinputKeyboard {
+    fn name(&self) -> &'static str {
+        "uinput"
+    }
+
+    fn send_chord(&mut self, keys: &[NativeKey]) -> Result<(), PlatformError> {
+        for key in keys {
+            self.key(key.code, true)?;
+        }
+        for key in keys.iter().rev() {
+            self.key(key.code, false)?;
+        }
+        Ok(())
+    }
+
+    fn type_text(&mut self, text: &str, delay: Duration) -> Result<(), TypeError> {
+        // Check everything first so nothing is typed when we can't type it all
+        let keys = text
+            .char_indices()
+            .filter(|&(_, c)| c != '\r')
+            .map(|(offset, c)| {
+                us_layout_key(c).map(|key| (offset, key)).ok_or_else(|| {
+                    PlatformError::VirtualKeyboard(format!("uinput can't type {:?}", c))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (offset, (code, shift)) in keys {
+            let tap = || {
+                if shift {
+                    self.key(SHIFT.code, true)?;
+                }
+                self.key(code, true)?;
+                self.key(code, false)?;
+                if shift {
+                    self.key(SHIFT.code, false)?;
+                }
+                Ok(())
+            };
+            tap().map_err(|error| TypeError {
+                error,
+                typed: Some(offset),
+            })?;
+            if !delay.is_zero() {
+                std::thread::sleep(delay);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for UinputKeyboard {
+    fn drop(&mut self) {
+        let _ = ioctl(&self.device, UI_DEV_DESTROY, 0);
+    }
+}
+
+/// Codes an input device reports, by event type
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceCapabilities {
+    /// Keys and buttons
+    pub keys: Vec<u16>,
+    /// Relative axes, e.g. mouse motion and wheels
+    pub relative_axes: Vec<u16>,
+    /// Miscellaneous events such as scan codes
+    pub misc: Vec<u16>,
+}
+
+/// Virtual device replaying the events of a grabbed input device, so
+/// everything but the events the hotkey listener keeps still reaches the
+/// compositor
+pub struct UinputForwarder {
+    device: File,
+}
+
+impl UinputForwarder {
+    /// Create a device with the name and capabilities of the grabbed one
+    pub fn create(name: &str, capabilities: &DeviceCapabilities) -> Result<Self, PlatformError> {
+        let name = format!("{} {}", DEVICE_NAME_PREFIX, name);
+        let device = create_device(
+            &name,
+            &[
+                (EV_KEY, &capabilities.keys),
+                (EV_REL, &capabilities.relative_axes),
+                (EV_MSC, &capabilities.misc),
+            ],
+        )?;
+        tracing::debug!("Created uinput device {:?}", name);
+        Ok(Self { device })
+    }
+
+    /// Replay one event read from the grabbed device
+    pub fn forward(&self, event: &libc::input_event) -> Result<(), PlatformError> {
+        emit(&self.device, event.type_, event.code, event.value)
+    }
+}
+
+impl Drop for UinputForwarder {
+    fn drop(&mut self) {
+        let _ = ioctl(&self.device, UI_DEV_DESTROY, 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_us_layout() {
+        assert!(is_us_layout("us", ""));
+        assert!(!is_us_layout("de", ""));
+        assert!(!is_us_layout("fr", ""));
+        assert!(!is_us_layout("us", "dvorak"));
+        assert!(!is_us_layout("us,de", ""));
+    }
+}
@@ -0,0 +1,254 @@
+//! X11 input through the XTEST extension
+//!
+//! Keysyms are looked up in the server's keyboard mapping, read again for
+//! every injection so a layout switch takes effect. Characters the layout
+//! can't type are bound to a spare keycode for the keystroke, the way
+//! xdotool does it.
+
+use super::keys::{keysym_for_char, NativeKey, SHIFT};
+use super::{TypeError, VirtualKeyboard};
+use crate::services::platform::PlatformError;
+use std::time::Duration;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xproto::{ConnectionExt as _, KEY_PRESS_EVENT, KEY_RELEASE_EVENT};
+use x11rb::protocol::xtest::{self, ConnectionExt as _};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+/// Time clients get to see a remapped key before it is pressed
+const REMAP_SETTLE: Duration = Duration::from_millis(10);
+
+fn x11_error(e: impl std::fmt::Display) -> PlatformError {
+    PlatformError::VirtualKeyboard(format!("X11: {}", e))
+}
+
+/// Keyboard connected to the X server's XTEST extension
+pub struct XTestKeyboard {
+    conn: RustConnection,
+    min_keycode: u8,
+    keysyms_per_keycode: u8,
+    /// The server's keysyms, `keysyms_per_keycode` per keycode
+    keysyms: Vec<u32>,
+    /// A keycode without keysyms, for characters the layout lacks
+    spare_keycode: Option<u8>,
+}
+
+impl XTestKeyboard {
+    /// Connect to the display in `$DISPLAY`
+    pub fn connect() -> Result<Self, PlatformError> {
+        let (conn, _) = x11rb::connect(None).map_err(x11_error)?;
+        if conn
+            .extension_information(xtest::X11_EXTENSION_NAME)
+            .map_err(x11_error)?
+            .is_none()
+        {
+            return Err(PlatformError::VirtualKeyboard(
+                "X server has no XTEST extension".to_string(),
+            ));
+        }
+
+        let min_keycode = conn.setup().min_keycode;
+        let mut keyboard = Self {
+            conn,
+            min_keycode,
+            keysyms_per_keycode: 0,
+            keysyms: Vec::new(),
+            spare_keycode: None,
+        };
+        keyboard.read_mapping()?;
+        Ok(keyboard)
+    }
+
+    /// Read the server's current keyboard mapping
+    fn read_mapping(&mut self) -> Result<(), PlatformError> {
+        let count = self.conn.setup().max_keycode - self.min_keycode + 1;
+        let mapping = self
+            .conn
+            .get_keyboard_mapping(self.min_keycode, count)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+
+        let per = mapping.keysyms_per_keycode as usize;
+        self.spare_keycode = mapping
+            .keysyms
+            .chunks(per.max(1))
+            .position(|syms| syms.iter().all(|&s| s == 0))
+            .map(|index| self.min_keycode + index as u8);
+        self.keysyms_per_keycode = mapping.keysyms_per_keycode;
+        self.keysyms = mapping.keysyms;
+        Ok(())
+    }
+
+    /// The keycode producing `keysym` and whether it needs Shift
+    fn keycode_for(&self, keysym: u32) -> Option<(u8, bool)> {
+        let per = (self.keysyms_per_keycode as usize).max(1);
+        for level in 0..per.min(2) {
+            let found = self
+                .keysyms
+                .chunks(per)
+                .position(|syms| syms[level] == keysym);
+            if let Some(index) = found {
+                return Some((self.min_keycode + index as u8, level == 1));
+            }
+        }
+        None
+    }
+
+    fn fake(&self, event: u8, keycode: u8) -> Result<(), PlatformError> {
+        self.conn
+            .xtest_fake_input(event, keycode, x11rb::CURRENT_TIME, x11rb::NONE, 0, 0, 0)
+            .map_err(x11_error)?;
+        Ok(())
+    }
+
+    fn tap(&self, keycode: u8, shift: Option<u8>) -> Result<(), PlatformError> {
+        if let Some(shift) = shift {
+            self.fake(KEY_PRESS_EVENT, shift)?;
+        }
+        self.fake(KEY_PRESS_EVENT, keycode)?;
+        self.fake(KEY_RELEASE_EVENT, keycode)?;
+        if let Some(shift) = shift {
+            self.fake(KEY_RELEASE_EVENT, shift)?;
+        }
+        Ok(())
+    }
+
+    /// Bind `keysym` to the spare keycode on every level
+    fn remap_spare(&self, keycode: u8, keysym: u32) -> Result<(), PlatformError> {
+        let syms = vec![keysym; self.keysyms_per_keycode as usize];
+        self.conn
+            .change_keyboard_mapping(1, keycode, self.keysyms_per_keycode, &syms)
+            .map_err(x11_error)?
+            .check()
+            .map_err(x11_error)?;
+        std::thread::sleep(REMAP_SETTLE);
+        Ok(())
+    }
+
+    /// Modifier keys the user is holding, like the rest of a shortcut
+    fn held_modifiers(&self) -> Result<Vec<u8>, PlatformError> {
+        let pressed = self
+            .conn
+            .query_keymap()
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .keys;
+        let modifiers = self
+            .conn
+            .get_modifier_mapping()
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .keycodes;
+
+        let mut held: Vec<u8> = modifiers
+            .into_iter()
+            .filter(|&kc| kc != 0 && pressed[kc as usize / 8] & (1 << (kc % 8)) != 0)
+            .collect();
+        held.dedup();
+        Ok(held)
+    }
+
+    /// Release held modifiers, run `send` and press them again, so they
+    /// don't turn typed text into shortcuts
+    fn without_modifiers(
+        &self,
+        send: impl FnOnce() -> Result<(), PlatformError>,
+    ) -> Result<(), PlatformError> {
+        let held = self.held_modifiers()?;
+        for &keycode in &held {
+            self.fake(KEY_RELEASE_EVENT, keycode)?;
+        }
+        let result = send();
+        for &keycode in &held {
+            self.fake(KEY_PRESS_EVENT, keycode)?;
+        }
+        self.conn.sync().map_err(x11_error)?;
+        result
+    }
+}
+
+impl VirtualKeyboard for XTestKeyboard {
+    fn name(&self) -> &'static str {
+        "XTest"
+    }
+
+    fn send_chord(&mut self, keys: &[NativeKey]) -> Result<(), PlatformError> {
+        self.read_mapping()?;
+        let keycodes = keys
+            .iter()
+            .map(|key| {
+                self.keycode_for(key.keysym)
+                    .map(|(kc, _)| kc)
+                    .ok_or_else(|| {
+                        PlatformError::VirtualKeyboard(format!(
+                            "No keycode for keysym {:#x}",
+                            key.keysym
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        self.without_modifiers(|| {
+            for &keycode in &keycodes {
+                self.fake(KEY_PRESS_EVENT, keycode)?;
+            }
+            for &keycode in keycodes.iter().rev() {
+                self.fake(KEY_RELEASE_EVENT, keycode)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn type_text(&mut self, text: &str, delay: Duration) -> Result<(), TypeError> {
+        self.read_mapping()?;
+        let shift = self.keycode_for(SHIFT.keysym).map(|(kc, _)| kc);
+        let mut remapped = false;
+        let mut typed = 0;
+
+        let result = self.without_modifiers(|| {
+            for (offset, c) in text.char_indices() {
+                typed = offset;
+                if c == '\r' {
+                    continue;
+                }
+                let keysym = keysym_for_char(c);
+                match self.keycode_for(keysym) {
+                    Some((keycode, needs_shift)) => {
+                        self.tap(keycode, shift.filter(|_| needs_shift))?;
+                    }
+                    None => {
+                        let spare = self.spare_keycode.ok_or_else(|| {
+                            PlatformError::VirtualKeyboard(format!(
+                                "No keycode free to type {:?}",
+                                c
+                            ))
+                        })?;
+                        self.remap_spare(spare, keysym)?;
+                        remapped = true;
+                        self.tap(spare, None)?;
+                        self.conn.sync().map_err(x11_error)?;
+                        std::thread::sleep(REMAP_SETTLE);
+                    }
+                }
+                self.conn.flush().map_err(x11_error)?;
+                if !delay.is_zero() {
+                    std::thread::sleep(delay);
+                }
+            }
+            typed = text.len();
+            Ok(())
+        });
+
+        let typed = Some(typed);
+        if remapped {
+            if let Some(spare) = self.spare_keycode {
+                self.remap_spare(spare, 0)
+                    .map_err(|error| TypeError { error, typed })?;
+            }
+        }
+        result.map_err(|error| TypeError { error, typed })
+    }
+}
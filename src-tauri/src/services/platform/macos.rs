@@ -1,60 +1,117 @@
 //! macOS-specific text injection implementation
 //!
-//! Uses CGEvent API to simulate Cmd+V after copying text to clipboard.
+//! Uses CGEvent API to simulate Cmd+V after copying text to clipboard, or to
+//! type the text as Unicode keyboard events.
 
 #[cfg(target_os = "macos")]
 use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, CGKeyCode};
 #[cfg(target_os = "macos")]
 use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
-use super::text_inject::{PlatformError, TextInjector};
+#[cfg(target_os = "macos")]
+use super::clipboard;
+use super::clipboard::ClipboardConfig;
+#[cfg(target_os = "macos")]
+use super::text_inject::PasteShortcut;
+use super::text_inject::{InjectionMethod, Key, PlatformError, TextInjector};
+
+/// Most UTF-16 units a single keyboard event can carry
+#[cfg(target_os = "macos")]
+const MAX_EVENT_UNITS: usize = 20;
 
-/// macOS text injector using clipboard + Cmd+V simulation
+/// macOS text injector using clipboard + Cmd+V simulation or typing
 #[cfg(target_os = "macos")]
 pub struct MacOSTextInjector {
     delay_ms: u32,
+    method: InjectionMethod,
+    clipboard: ClipboardConfig,
 }
 
 #[cfg(target_os = "macos")]
 impl MacOSTextInjector {
     /// Create a new macOS text injector
     pub fn new() -> Result<Self, PlatformError> {
-        Ok(Self { delay_ms: 0 })
+        Ok(Self {
+            delay_ms: 0,
+            method: InjectionMethod::default(),
+            clipboard: ClipboardConfig::default(),
+        })
     }
-}
 
-#[cfg(target_os = "macos")]
-impl Default for MacOSTextInjector {
-    fn default() -> Self {
-        Self::new().unwrap()
+    /// Type text as keyboard events carrying Unicode strings, so any
+    /// character can be typed regardless of the keyboard layout
+    fn type_text(&self, text: &str) -> Result<(), PlatformError> {
+        tracing::info!("Injecting text ({} chars) via typing", text.len());
+
+        const RETURN_KEY: CGKeyCode = 0x24;
+
+        let mut chunk = String::new();
+        for c in text.chars() {
+            match c {
+                // Apps expect Return rather than a typed line feed
+                '\n' => {
+                    self.type_chunk(&chunk)?;
+                    chunk.clear();
+                    self.send_key(RETURN_KEY, CGEventFlags::CGEventFlagNull)?;
+                }
+                '\r' => {}
+                c => {
+                    if chunk.encode_utf16().count() + c.len_utf16() > MAX_EVENT_UNITS {
+                        self.type_chunk(&chunk)?;
+                        chunk.clear();
+                    }
+                    chunk.push(c);
+                }
+            }
+        }
+        self.type_chunk(&chunk)?;
+
+        clipboard::copy_transcript(text, self.clipboard)
     }
-}
 
-#[cfg(target_os = "macos")]
-impl TextInjector for MacOSTextInjector {
-    fn inject_text(&self, text: &str) -> Result<(), PlatformError> {
-        if text.is_empty() {
-            tracing::debug!("Empty text, nothing to inject");
+    /// Post a key press and release carrying `chunk` as their string
+    fn type_chunk(&self, chunk: &str) -> Result<(), PlatformError> {
+        if chunk.is_empty() {
             return Ok(());
         }
 
-        tracing::info!("Injecting text ({} chars) via clipboard+paste", text.len());
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| PlatformError::EventSourceError)?;
 
-        // Copy to clipboard using arboard
-        let mut clipboard = arboard::Clipboard::new()?;
-        clipboard.set_text(text)?;
+        let key_down = CGEvent::new_keyboard_event(source.clone(), 0, true)
+            .map_err(|_| PlatformError::EventSourceError)?;
+        let key_up = CGEvent::new_keyboard_event(source, 0, false)
+            .map_err(|_| PlatformError::EventSourceError)?;
 
-        // Small delay for clipboard to update
-        std::thread::sleep(std::time::Duration::from_millis(50 + self.delay_ms as u64));
+        key_down.set_string(chunk);
+        key_up.set_string(chunk);
 
-        // Simulate Cmd+V using CGEvent
-        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
-            .map_err(|_| PlatformError::EventSourceError)?;
+        key_down.post(CGEventTapLocation::HID);
+        key_up.post(CGEventTapLocation::HID);
 
+        if self.delay_ms > 0 {
+            std::thread::sleep(std::time::Duration::from_millis(self.delay_ms as u64));
+        }
+
+        Ok(())
+    }
+
+    /// Simulate Cmd+V, or Ctrl+Shift+V when the target wants that
+    fn paste(&self) -> Result<(), PlatformError> {
         // Key codes: Command = 0x37 (55), V = 0x09 (9)
         const CMD_KEY: CGKeyCode = 0x37;
         const V_KEY: CGKeyCode = 0x09;
 
+        if self.clipboard.paste_shortcut == PasteShortcut::CtrlShiftV {
+            return self.send_key(
+                V_KEY,
+                CGEventFlags::CGEventFlagControl | CGEventFlags::CGEventFlagShift,
+            );
+        }
+
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| PlatformError::EventSourceError)?;
+
         // Create keyboard events
         let cmd_down = CGEvent::new_keyboard_event(source.clone(), CMD_KEY, true)
             .map_err(|_| PlatformError::EventSourceError)?;
@@ -75,6 +132,54 @@ impl TextInjector for MacOSTextInjector {
         v_up.post(CGEventTapLocation::HID);
         cmd_up.post(CGEventTapLocation::HID);
 
+        Ok(())
+    }
+
+    /// Press and release a key with the given modifier flags held
+    fn send_key(&self, key: CGKeyCode, flags: CGEventFlags) -> Result<(), PlatformError> {
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| PlatformError::EventSourceError)?;
+
+        let key_down = CGEvent::new_keyboard_event(source.clone(), key, true)
+            .map_err(|_| PlatformError::EventSourceError)?;
+        let key_up = CGEvent::new_keyboard_event(source, key, false)
+            .map_err(|_| PlatformError::EventSourceError)?;
+
+        key_down.set_flags(flags);
+        key_up.set_flags(flags);
+
+        key_down.post(CGEventTapLocation::HID);
+        key_up.post(CGEventTapLocation::HID);
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Default for MacOSTextInjector {
+    fn default() -> Self {
+        Self::new().unwrap()
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl TextInjector for MacOSTextInjector {
+    fn inject_text(&self, text: &str) -> Result<(), PlatformError> {
+        if text.is_empty() {
+            tracing::debug!("Empty text, nothing to inject");
+            return Ok(());
+        }
+
+        if self.method == InjectionMethod::Type {
+            return self.type_text(text);
+        }
+
+        tracing::info!("Injecting text ({} chars) via clipboard+paste", text.len());
+
+        // Small delay for clipboard to update before pasting
+        let settle = std::time::Duration::from_millis(50 + self.delay_ms as u64);
+        clipboard::paste_via_clipboard(text, self.clipboard, settle, || self.paste())?;
+
         tracing::debug!("Text injected successfully via Cmd+V");
         Ok(())
     }
@@ -83,6 +188,14 @@ impl TextInjector for MacOSTextInjector {
         self.delay_ms = delay_ms.min(50);
     }
 
+    fn set_method(&mut self, method: InjectionMethod) {
+        self.method = method;
+    }
+
+    fn set_clipboard_config(&mut self, config: ClipboardConfig) {
+        self.clipboard = config;
+    }
+
     fn get_permission_instructions(&self) -> Option<String> {
         Some(
             "Grant Accessibility permissions: System Preferences > Security & Privacy > Privacy > Accessibility. Add this application to the list."
@@ -149,6 +262,36 @@ impl TextInjector for MacOSTextInjector {
 
         Ok(())
     }
+
+    fn press_key(&self, key: Key) -> Result<(), PlatformError> {
+        tracing::debug!("Pressing {:?}", key);
+
+        // Key codes: Return = 0x24, Tab = 0x30, Escape = 0x35
+        let code: CGKeyCode = match key {
+            Key::Enter => 0x24,
+            Key::Tab => 0x30,
+            Key::Escape => 0x35,
+        };
+        self.send_key(code, CGEventFlags::CGEventFlagNull)
+    }
+
+    fn delete_previous_word(&self) -> Result<(), PlatformError> {
+        tracing::debug!("Deleting previous word (Option+Delete)");
+        const DELETE_KEY: CGKeyCode = 0x33;
+        self.send_key(DELETE_KEY, CGEventFlags::CGEventFlagAlternate)
+    }
+
+    fn select_all(&self) -> Result<(), PlatformError> {
+        tracing::debug!("Selecting all (Cmd+A)");
+        const A_KEY: CGKeyCode = 0x00;
+        self.send_key(A_KEY, CGEventFlags::CGEventFlagCommand)
+    }
+
+    fn go_to_line_end(&self) -> Result<(), PlatformError> {
+        tracing::debug!("Moving to end of line (Cmd+Right)");
+        const RIGHT_ARROW_KEY: CGKeyCode = 0x7C;
+        self.send_key(RIGHT_ARROW_KEY, CGEventFlags::CGEventFlagCommand)
+    }
 }
 
 // Stub implementation for non-macOS platforms (for compilation)
@@ -177,6 +320,10 @@ impl TextInjector for MacOSTextInjector {
 
     fn set_delay(&mut self, _delay_ms: u32) {}
 
+    fn set_method(&mut self, _method: InjectionMethod) {}
+
+    fn set_clipboard_config(&mut self, _config: ClipboardConfig) {}
+
     fn delete_characters(&self, _count: usize) -> Result<(), PlatformError> {
         Err(PlatformError::NotSupported)
     }
@@ -184,6 +331,22 @@ impl TextInjector for MacOSTextInjector {
     fn send_undo(&self) -> Result<(), PlatformError> {
         Err(PlatformError::NotSupported)
     }
+
+    fn press_key(&self, _key: Key) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn delete_previous_word(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn select_all(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn go_to_line_end(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
 }
 
 #[cfg(test)]
@@ -2,6 +2,10 @@
 //!
 //! Provides cross-platform abstractions for text injection and other platform features.
 
+pub mod active_window;
+pub mod app_profiles;
+pub mod clipboard;
+pub mod injection_history;
 pub mod text_inject;
 
 #[cfg(target_os = "linux")]
@@ -12,7 +16,11 @@ pub mod macos;
 pub mod windows;
 
 // Re-export types for convenience
-pub use text_inject::{PlatformError, TextInjector};
+pub use active_window::{active_window, ActiveWindow};
+pub use app_profiles::{find_profile, AppProfile};
+pub use clipboard::ClipboardConfig;
+pub use injection_history::InjectionHistory;
+pub use text_inject::{InjectionMethod, Key, PasteShortcut, PlatformError, TextInjector};
 
 #[cfg(target_os = "linux")]
 pub use linux::LinuxTextInjector;
@@ -50,6 +58,7 @@ pub fn get_text_injector() -> Result<Box<dyn TextInjector>, PlatformError> {
 /// Shared text injector state for use in Tauri commands
 pub struct TextInjectorState {
     injector: Arc<Mutex<Box<dyn TextInjector>>>,
+    history: Mutex<InjectionHistory>,
 }
 
 impl TextInjectorState {
@@ -58,13 +67,38 @@ impl TextInjectorState {
         let injector = get_text_injector()?;
         Ok(Self {
             injector: Arc::new(Mutex::new(injector)),
+            history: Mutex::new(InjectionHistory::default()),
         })
     }
 
     /// Inject text using the platform-specific injector
     pub async fn inject_text(&self, text: &str) -> Result<(), PlatformError> {
         let injector = self.injector.lock().await;
-        injector.inject_text(text)
+        injector.inject_text(text)?;
+        self.history.lock().await.record_text(text);
+        Ok(())
+    }
+
+    /// Start a new dictation into `target`; editing commands treat it as one
+    /// utterance
+    pub async fn begin_utterance(&self, target: Option<&ActiveWindow>) {
+        let mut history = self.history.lock().await;
+        history.set_target(
+            target.and_then(|w| w.app.as_deref()),
+            std::time::Instant::now(),
+        );
+        history.begin_utterance();
+    }
+
+    /// Adjust spacing and capitalization of text starting a dictation so it
+    /// continues the previous one
+    pub async fn continue_text(&self, text: &str) -> String {
+        self.history.lock().await.continue_text(text)
+    }
+
+    /// Carry a pending capital over to the next dictation
+    pub async fn set_capitalize_next(&self, capitalize: bool) {
+        self.history.lock().await.set_capitalize_next(capitalize);
     }
 
     /// Set the keystroke delay
@@ -73,6 +107,18 @@ impl TextInjectorState {
         injector.set_delay(delay_ms);
     }
 
+    /// Set whether text is pasted or typed
+    pub async fn set_method(&self, method: InjectionMethod) {
+        let mut injector = self.injector.lock().await;
+        injector.set_method(method);
+    }
+
+    /// Set whether the clipboard is restored after pasting
+    pub async fn set_clipboard_config(&self, config: ClipboardConfig) {
+        let mut injector = self.injector.lock().await;
+        injector.set_clipboard_config(config);
+    }
+
     /// Get permission instructions if needed
     pub async fn get_permission_instructions(&self) -> Option<String> {
         let injector = self.injector.lock().await;
@@ -82,20 +128,79 @@ impl TextInjectorState {
     /// Delete the last N characters
     pub async fn delete_characters(&self, count: usize) -> Result<(), PlatformError> {
         let injector = self.injector.lock().await;
-        injector.delete_characters(count)
+        injector.delete_characters(count)?;
+        self.history.lock().await.record_deleted(count);
+        Ok(())
     }
 
     /// Send undo command
     pub async fn send_undo(&self) -> Result<(), PlatformError> {
         let injector = self.injector.lock().await;
+        // What the undo removes is up to the application
+        self.history.lock().await.clear();
         injector.send_undo()
     }
+
+    /// Remove the most recent dictation
+    pub async fn scratch_that(&self) -> Result<(), PlatformError> {
+        let injector = self.injector.lock().await;
+        let last = self
+            .history
+            .lock()
+            .await
+            .take_last()
+            .ok_or(PlatformError::NothingDictated)?;
+        injector.delete_characters(last.chars().count())
+    }
+
+    /// Delete the word before the cursor
+    pub async fn delete_last_word(&self) -> Result<(), PlatformError> {
+        let injector = self.injector.lock().await;
+        injector.delete_previous_word()?;
+        self.history.lock().await.record_word_deleted();
+        Ok(())
+    }
+
+    /// Delete the last sentence of the most recent dictation
+    pub async fn delete_last_sentence(&self) -> Result<(), PlatformError> {
+        let injector = self.injector.lock().await;
+        let mut history = self.history.lock().await;
+        let count = history
+            .last_sentence_len()
+            .ok_or(PlatformError::NothingDictated)?;
+        injector.delete_characters(count)?;
+        history.record_deleted(count);
+        Ok(())
+    }
+
+    /// Press a single key
+    pub async fn press_key(&self, key: Key) -> Result<(), PlatformError> {
+        let injector = self.injector.lock().await;
+        // Enter may send a message and Tab may move focus
+        self.history.lock().await.clear();
+        injector.press_key(key)
+    }
+
+    /// Select all text in the focused field
+    pub async fn select_all(&self) -> Result<(), PlatformError> {
+        let injector = self.injector.lock().await;
+        self.history.lock().await.clear();
+        injector.select_all()
+    }
+
+    /// Move the cursor to the end of the line
+    pub async fn go_to_line_end(&self) -> Result<(), PlatformError> {
+        let injector = self.injector.lock().await;
+        self.history.lock().await.clear();
+        injector.go_to_line_end()
+    }
 }
 
 impl Default for TextInjectorState {
     fn default() -> Self {
         Self::new().unwrap_or_else(|_| Self {
             injector: Arc::new(Mutex::new(Box::new(StubInjector))),
+            history: Mutex::new(InjectionHistory::default()),
         })
     }
 }
@@ -110,6 +215,10 @@ impl TextInjector for StubInjector {
 
     fn set_delay(&mut self, _delay_ms: u32) {}
 
+    fn set_method(&mut self, _method: InjectionMethod) {}
+
+    fn set_clipboard_config(&mut self, _config: ClipboardConfig) {}
+
     fn delete_characters(&self, _count: usize) -> Result<(), PlatformError> {
         Err(PlatformError::NotSupported)
     }
@@ -117,6 +226,22 @@ impl TextInjector for StubInjector {
     fn send_undo(&self) -> Result<(), PlatformError> {
         Err(PlatformError::NotSupported)
     }
+
+    fn press_key(&self, _key: Key) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn delete_previous_word(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn select_all(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn go_to_line_end(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
 }
 
 #[cfg(test)]
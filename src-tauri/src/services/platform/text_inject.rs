@@ -2,6 +2,8 @@
 //!
 //! Provides platform-agnostic interface for injecting text at cursor position.
 
+use super::clipboard::ClipboardConfig;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Errors that can occur during text injection operations
@@ -16,11 +18,17 @@ pub enum PlatformError {
     #[error("Command failed: {0}")]
     CommandFailed(String),
 
+    #[error("Virtual keyboard error: {0}")]
+    VirtualKeyboard(String),
+
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 
     #[error("Text injection not supported on this platform")]
     NotSupported,
+
+    #[error("No dictated text to remove")]
+    NothingDictated,
 }
 
 impl From<arboard::Error> for PlatformError {
@@ -29,6 +37,40 @@ impl From<arboard::Error> for PlatformError {
     }
 }
 
+/// Keys that can be pressed on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Key {
+    Enter,
+    Tab,
+    Escape,
+}
+
+/// How text is delivered to the focused application
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionMethod {
+    /// Copy to the clipboard and simulate Ctrl+V / Cmd+V
+    #[default]
+    Paste,
+    /// Type each character as a keystroke, for terminals, password fields
+    /// and apps that block or intercept paste
+    Type,
+}
+
+/// Key combination that pastes into the focused application
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteShortcut {
+    /// Ctrl+V, Cmd+V on macOS
+    #[default]
+    Standard,
+    /// Ctrl+Shift+V, used by most terminals on Linux
+    CtrlShiftV,
+    /// Shift+Insert (Cmd+V on macOS, which has no Insert key)
+    ShiftInsert,
+}
+
 /// Platform-specific text injection interface
 pub trait TextInjector: Send + Sync {
     /// Inject text into the currently focused application
@@ -47,11 +89,29 @@ pub trait TextInjector: Send + Sync {
     /// Set the delay between keystrokes in milliseconds
     fn set_delay(&mut self, delay_ms: u32);
 
+    /// Choose between pasting and typing text
+    fn set_method(&mut self, method: InjectionMethod);
+
+    /// Set whether the clipboard is restored after pasting
+    fn set_clipboard_config(&mut self, config: ClipboardConfig);
+
     /// Delete the last N characters (simulates backspace)
     fn delete_characters(&self, count: usize) -> Result<(), PlatformError>;
 
     /// Send undo command (Ctrl+Z on Windows/Linux, Cmd+Z on macOS)
     fn send_undo(&self) -> Result<(), PlatformError>;
+
+    /// Press and release a single key
+    fn press_key(&self, key: Key) -> Result<(), PlatformError>;
+
+    /// Delete the word before the cursor (Ctrl+Backspace, Option+Delete on macOS)
+    fn delete_previous_word(&self) -> Result<(), PlatformError>;
+
+    /// Select all text in the focused field (Ctrl+A, Cmd+A on macOS)
+    fn select_all(&self) -> Result<(), PlatformError>;
+
+    /// Move the cursor to the end of the line (End, Cmd+Right on macOS)
+    fn go_to_line_end(&self) -> Result<(), PlatformError>;
 }
 
 #[cfg(test)]
@@ -66,4 +126,24 @@ mod tests {
         let err = PlatformError::CommandFailed("xdotool not found".to_string());
         assert!(err.to_string().contains("Command failed"));
     }
+
+    #[test]
+    fn test_injection_method_serialization() {
+        assert_eq!(InjectionMethod::default(), InjectionMethod::Paste);
+        assert_eq!(
+            serde_json::to_string(&InjectionMethod::Type).unwrap(),
+            r#""type""#
+        );
+    }
+
+    #[test]
+    fn test_paste_shortcut_serialization() {
+        assert_eq!(PasteShortcut::default(), PasteShortcut::Standard);
+        assert_eq!(
+            serde_json::to_string(&PasteShortcut::CtrlShiftV).unwrap(),
+            r#""ctrl_shift_v""#
+        );
+        let parsed: PasteShortcut = serde_json::from_str(r#""shift_insert""#).unwrap();
+        assert_eq!(parsed, PasteShortcut::ShiftInsert);
+    }
 }
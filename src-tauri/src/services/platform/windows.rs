@@ -1,26 +1,39 @@
 //! Windows-specific text injection implementation
 //!
-//! Uses SendInput API to simulate Ctrl+V after copying text to clipboard.
+//! Uses SendInput API to simulate Ctrl+V after copying text to clipboard, or
+//! to type the text as Unicode key events.
 
-use super::text_inject::{PlatformError, TextInjector};
+#[cfg(target_os = "windows")]
+use super::clipboard;
+use super::clipboard::ClipboardConfig;
+#[cfg(target_os = "windows")]
+use super::text_inject::PasteShortcut;
+use super::text_inject::{InjectionMethod, Key, PlatformError, TextInjector};
 
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::Input::KeyboardAndMouse::{
     SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
-    VIRTUAL_KEY, VK_BACK, VK_CONTROL, VK_V, VK_Z,
+    KEYEVENTF_UNICODE, VIRTUAL_KEY, VK_A, VK_BACK, VK_CONTROL, VK_END, VK_ESCAPE, VK_INSERT,
+    VK_RETURN, VK_SHIFT, VK_TAB, VK_V, VK_Z,
 };
 
-/// Windows text injector using clipboard + Ctrl+V simulation
+/// Windows text injector using clipboard + Ctrl+V simulation or typing
 #[cfg(target_os = "windows")]
 pub struct WindowsTextInjector {
     delay_ms: u32,
+    method: InjectionMethod,
+    clipboard: ClipboardConfig,
 }
 
 #[cfg(target_os = "windows")]
 impl WindowsTextInjector {
     /// Create a new Windows text injector
     pub fn new() -> Result<Self, PlatformError> {
-        Ok(Self { delay_ms: 0 })
+        Ok(Self {
+            delay_ms: 0,
+            method: InjectionMethod::default(),
+            clipboard: ClipboardConfig::default(),
+        })
     }
 
     /// Create a keyboard input event
@@ -44,6 +57,96 @@ impl WindowsTextInjector {
             },
         }
     }
+
+    /// Create a key event that types one UTF-16 code unit
+    fn create_unicode_input(unit: u16, key_up: bool) -> INPUT {
+        let flags = if key_up {
+            KEYEVENTF_UNICODE | KEYEVENTF_KEYUP
+        } else {
+            KEYEVENTF_UNICODE
+        };
+
+        INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: VIRTUAL_KEY(0),
+                    wScan: unit,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        }
+    }
+
+    /// Type text as Unicode key events, which don't depend on the keyboard
+    /// layout. Characters outside the BMP are sent as surrogate pairs.
+    fn type_text(&self, text: &str) -> Result<(), PlatformError> {
+        tracing::info!("Injecting text ({} chars) via typing", text.len());
+
+        let mut inputs = Vec::new();
+        for c in text.chars() {
+            match c {
+                // Apps expect Return rather than a typed line feed
+                '\n' => {
+                    inputs.push(Self::create_key_input(VK_RETURN, false));
+                    inputs.push(Self::create_key_input(VK_RETURN, true));
+                }
+                '\r' => continue,
+                c => {
+                    let mut units = [0; 2];
+                    for &unit in c.encode_utf16(&mut units).iter() {
+                        inputs.push(Self::create_unicode_input(unit, false));
+                        inputs.push(Self::create_unicode_input(unit, true));
+                    }
+                }
+            }
+
+            if self.delay_ms > 0 {
+                Self::send_inputs(&inputs)?;
+                inputs.clear();
+                std::thread::sleep(std::time::Duration::from_millis(self.delay_ms as u64));
+            }
+        }
+
+        Self::send_inputs(&inputs)?;
+
+        clipboard::copy_transcript(text, self.clipboard)
+    }
+
+    /// Press the keys in order, then release them in reverse order
+    fn send_chord(keys: &[VIRTUAL_KEY]) -> Result<(), PlatformError> {
+        let inputs: Vec<INPUT> = keys
+            .iter()
+            .map(|&vk| Self::create_key_input(vk, false))
+            .chain(
+                keys.iter()
+                    .rev()
+                    .map(|&vk| Self::create_key_input(vk, true)),
+            )
+            .collect();
+
+        Self::send_inputs(&inputs)
+    }
+
+    /// Send input events, failing if any were blocked
+    fn send_inputs(inputs: &[INPUT]) -> Result<(), PlatformError> {
+        if inputs.is_empty() {
+            return Ok(());
+        }
+
+        let sent = unsafe { SendInput(inputs, std::mem::size_of::<INPUT>() as i32) };
+        if sent != inputs.len() as u32 {
+            return Err(PlatformError::CommandFailed(format!(
+                "SendInput only sent {} of {} events",
+                sent,
+                inputs.len()
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -61,35 +164,22 @@ impl TextInjector for WindowsTextInjector {
             return Ok(());
         }
 
-        tracing::info!("Injecting text ({} chars) via clipboard+paste", text.len());
-
-        // Copy to clipboard using arboard
-        let mut clipboard = arboard::Clipboard::new()?;
-        clipboard.set_text(text)?;
-
-        // Small delay for clipboard to update
-        std::thread::sleep(std::time::Duration::from_millis(50 + self.delay_ms as u64));
+        if self.method == InjectionMethod::Type {
+            return self.type_text(text);
+        }
 
-        // Simulate Ctrl+V using SendInput
-        unsafe {
-            let inputs = [
-                Self::create_key_input(VK_CONTROL, false), // Ctrl down
-                Self::create_key_input(VK_V, false),       // V down
-                Self::create_key_input(VK_V, true),        // V up
-                Self::create_key_input(VK_CONTROL, true),  // Ctrl up
-            ];
+        tracing::info!("Injecting text ({} chars) via clipboard+paste", text.len());
 
-            let sent = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
-            if sent != inputs.len() as u32 {
-                return Err(PlatformError::CommandFailed(format!(
-                    "SendInput only sent {} of {} events",
-                    sent,
-                    inputs.len()
-                )));
-            }
-        }
+        // Small delay for clipboard to update before pasting
+        let settle = std::time::Duration::from_millis(50 + self.delay_ms as u64);
+        let paste: &[VIRTUAL_KEY] = match self.clipboard.paste_shortcut {
+            PasteShortcut::Standard => &[VK_CONTROL, VK_V],
+            PasteShortcut::CtrlShiftV => &[VK_CONTROL, VK_SHIFT, VK_V],
+            PasteShortcut::ShiftInsert => &[VK_SHIFT, VK_INSERT],
+        };
+        clipboard::paste_via_clipboard(text, self.clipboard, settle, || Self::send_chord(paste))?;
 
-        tracing::debug!("Text injected successfully via Ctrl+V");
+        tracing::debug!("Text injected successfully via paste shortcut");
         Ok(())
     }
 
@@ -97,6 +187,14 @@ impl TextInjector for WindowsTextInjector {
         self.delay_ms = delay_ms.min(50);
     }
 
+    fn set_method(&mut self, method: InjectionMethod) {
+        self.method = method;
+    }
+
+    fn set_clipboard_config(&mut self, config: ClipboardConfig) {
+        self.clipboard = config;
+    }
+
     fn delete_characters(&self, count: usize) -> Result<(), PlatformError> {
         if count == 0 {
             return Ok(());
@@ -150,6 +248,30 @@ impl TextInjector for WindowsTextInjector {
 
         Ok(())
     }
+
+    fn press_key(&self, key: Key) -> Result<(), PlatformError> {
+        tracing::debug!("Pressing {:?}", key);
+        Self::send_chord(&[match key {
+            Key::Enter => VK_RETURN,
+            Key::Tab => VK_TAB,
+            Key::Escape => VK_ESCAPE,
+        }])
+    }
+
+    fn delete_previous_word(&self) -> Result<(), PlatformError> {
+        tracing::debug!("Deleting previous word (Ctrl+Backspace)");
+        Self::send_chord(&[VK_CONTROL, VK_BACK])
+    }
+
+    fn select_all(&self) -> Result<(), PlatformError> {
+        tracing::debug!("Selecting all (Ctrl+A)");
+        Self::send_chord(&[VK_CONTROL, VK_A])
+    }
+
+    fn go_to_line_end(&self) -> Result<(), PlatformError> {
+        tracing::debug!("Moving to end of line (End)");
+        Self::send_chord(&[VK_END])
+    }
 }
 
 // Stub implementation for non-Windows platforms (for compilation)
@@ -178,6 +300,10 @@ impl TextInjector for WindowsTextInjector {
 
     fn set_delay(&mut self, _delay_ms: u32) {}
 
+    fn set_method(&mut self, _method: InjectionMethod) {}
+
+    fn set_clipboard_config(&mut self, _config: ClipboardConfig) {}
+
     fn delete_characters(&self, _count: usize) -> Result<(), PlatformError> {
         Err(PlatformError::NotSupported)
     }
@@ -185,6 +311,22 @@ impl TextInjector for WindowsTextInjector {
     fn send_undo(&self) -> Result<(), PlatformError> {
         Err(PlatformError::NotSupported)
     }
+
+    fn press_key(&self, _key: Key) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn delete_previous_word(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn select_all(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
+
+    fn go_to_line_end(&self) -> Result<(), PlatformError> {
+        Err(PlatformError::NotSupported)
+    }
 }
 
 #[cfg(test)]
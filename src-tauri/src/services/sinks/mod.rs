@@ -0,0 +1,381 @@
+//! Destinations for finished transcripts
+//!
+//! Transcripts are normally injected into the focused application. For
+//! scripting and accessibility setups they can instead be copied to the
+//! clipboard only, appended to a file, written to a named pipe or Unix
+//! socket, or piped into a user command.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use thiserror::Error;
+
+/// Placeholder in file paths replaced with today's date (YYYY-MM-DD)
+pub const DATE_PLACEHOLDER: &str = "{date}";
+
+/// Errors that can occur while delivering a transcript
+#[derive(Error, Debug)]
+pub enum SinkError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+
+    #[error("Command failed: {0}")]
+    CommandFailed(String),
+
+    #[error("No {0} configured")]
+    NotConfigured(&'static str),
+
+    #[error("{0} is not supported on this platform")]
+    NotSupported(&'static str),
+}
+
+/// Where finished transcripts go
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DictationSink {
+    /// Paste or type into the focused application
+    #[default]
+    Inject,
+    /// Only copy to the clipboard
+    Clipboard,
+    /// Append to a file, one transcript per line with line breaks written
+    /// as `\n`. `~/` and `{date}` are expanded, e.g. `~/journal/{date}.md`
+    /// for a daily journal.
+    File { path: String },
+    /// Write to a named pipe (FIFO) or Unix socket that another program
+    /// reads from, one transcript per line
+    Pipe { path: String },
+    /// Run a command with the transcript on stdin
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl DictationSink {
+    /// Write the transcript to a file, pipe or command. Injection is left to
+    /// the caller, which owns the text injector; the clipboard needs the app.
+    pub fn write(&self, text: &str) -> Result<(), SinkError> {
+        match self {
+            Self::Inject | Self::Clipboard => Ok(()),
+            Self::File { path } => append_to_file(&expand_path(path)?, text),
+            Self::Pipe { path } => write_to_pipe(&expand_path(path)?, text),
+            Self::Command { command, args } => run_command(command, args, text),
+        }
+    }
+}
+
+/// Deliver a transcript to any sink but injection. Runs on a blocking
+/// thread, since pipes and commands may take a while.
+pub async fn deliver<R: Runtime>(
+    app: &AppHandle<R>,
+    sink: &DictationSink,
+    text: &str,
+) -> Result<(), SinkError> {
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    if *sink == DictationSink::Clipboard {
+        return app
+            .clipboard()
+            .write_text(text)
+            .map_err(|e| SinkError::Clipboard(e.to_string()));
+    }
+
+    let sink = sink.clone();
+    let text = text.to_string();
+    tokio::task::spawn_blocking(move || sink.write(&text))
+        .await
+        .map_err(|e| SinkError::CommandFailed(e.to_string()))?
+}
+
+/// Expand `~/` and the date placeholder in a configured path
+fn expand_path(path: &str) -> Result<PathBuf, SinkError> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(SinkError::NotConfigured("path"));
+    }
+
+    let path = path.replace(
+        DATE_PLACEHOLDER,
+        &Local::now().format("%Y-%m-%d").to_string(),
+    );
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(dirs) = directories::UserDirs::new() {
+            return Ok(dirs.home_dir().join(rest));
+        }
+    }
+    Ok(PathBuf::from(path))
+}
+
+/// A transcript as one line: line breaks (from "new line" or "new
+/// paragraph") become `\n`, and backslashes are doubled so readers can undo
+/// it
+fn escape_line(text: &str) -> String {
+    let mut line = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            c => line.push(c),
+        }
+    }
+    line
+}
+
+fn append_to_file(path: &Path, text: &str) -> Result<(), SinkError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", escape_line(text))?;
+    tracing::debug!("Appended transcript to {}", path.display());
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_to_pipe(path: &Path, text: &str) -> Result<(), SinkError> {
+    use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::UnixStream;
+
+    let line = format!("{}\n", escape_line(text));
+    if std::fs::metadata(path)?.file_type().is_socket() {
+        UnixStream::connect(path)?.write_all(line.as_bytes())?;
+    } else {
+        // Don't hang when nothing is reading the FIFO; opening fails instead
+        let mut pipe = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)?;
+        // Once open, wait for the reader when the pipe is full rather than
+        // failing partway through a long transcript
+        let fd = pipe.as_raw_fd();
+        // SAFETY: fcntl on a descriptor we own, with integer arguments
+        let cleared = unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            flags >= 0 && libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK) >= 0
+        };
+        if !cleared {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        pipe.write_all(line.as_bytes())?;
+    }
+    tracing::debug!("Wrote transcript to {}", path.display());
+    Ok(())
+}
+
+#[cfg(windows)]
+fn write_to_pipe(path: &Path, text: &str) -> Result<(), SinkError> {
+    // Named pipes (\\.\pipe\name) open like files
+    let mut pipe = OpenOptions::new().write(true).open(path)?;
+    writeln!(pipe, "{}", escape_line(text))?;
+    tracing::debug!("Wrote transcript to {}", path.display());
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn write_to_pipe(_path: &Path, _text: &str) -> Result<(), SinkError> {
+    Err(SinkError::NotSupported("Writing to a pipe"))
+}
+
+fn run_command(command: &str, args: &[String], text: &str) -> Result<(), SinkError> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err(SinkError::NotConfigured("command"));
+    }
+
+    let mut child = Command::new(command)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SinkError::CommandFailed(format!("{}: {}", command, e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(SinkError::CommandFailed(format!(
+            "{} exited with {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    tracing::debug!("Piped transcript into {}", command);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sink_serialization() {
+        assert_eq!(DictationSink::default(), DictationSink::Inject);
+        assert_eq!(
+            serde_json::to_string(&DictationSink::Clipboard).unwrap(),
+            r#"{"type":"clipboard"}"#
+        );
+
+        let parsed: DictationSink =
+            serde_json::from_str(r#"{"type": "command", "command": "notify-send"}"#).unwrap();
+        assert_eq!(
+            parsed,
+            DictationSink::Command {
+                command: "notify-send".to_string(),
+                args: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_file_sink_appends_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes").join("journal.md");
+        let sink = DictationSink::File {
+            path: path.to_string_lossy().into_owned(),
+        };
+
+        sink.write("First thought.").unwrap();
+        sink.write("Second thought.").unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "First thought.\nSecond thought.\n");
+    }
+
+    #[test]
+    fn test_multi_line_transcripts_stay_on_one_line() {
+        assert_eq!(
+            escape_line("Dear Sam,\n\nThanks."),
+            "Dear Sam,\\n\\nThanks."
+        );
+        assert_eq!(escape_line("C:\\temp\r\n"), "C:\\\\temp\\r\\n");
+        assert_eq!(escape_line("plain"), "plain");
+    }
+
+    #[test]
+    fn test_expand_path_date_placeholder() {
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let path = expand_path("/tmp/journal/{date}.md").unwrap();
+        assert_eq!(path, PathBuf::from(format!("/tmp/journal/{}.md", today)));
+
+        assert!(matches!(
+            expand_path("  "),
+            Err(SinkError::NotConfigured("path"))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_sink_receives_stdin() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("out.txt");
+        let sink = DictationSink::Command {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), format!("cat > '{}'", out.display())],
+        };
+
+        sink.write("piped text").unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "piped text");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_sink_reports_failure() {
+        let sink = DictationSink::Command {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "echo broken >&2; exit 3".to_string()],
+        };
+
+        let err = sink.write("text").unwrap_err().to_string();
+        assert!(err.contains("broken"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_sink_writes_to_socket() {
+        use std::io::Read;
+        use std::os::unix::net::UnixListener;
+
+        let dir = TempDir::new().unwrap();
+        let socket = dir.path().join("dictation.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+        let sink = DictationSink::Pipe {
+            path: socket.to_string_lossy().into_owned(),
+        };
+
+        sink.write("over the socket").unwrap();
+        let mut received = String::new();
+        listener
+            .accept()
+            .unwrap()
+            .0
+            .read_to_string(&mut received)
+            .unwrap();
+        assert_eq!(received, "over the socket\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_sink_without_reader_fails() {
+        let dir = TempDir::new().unwrap();
+        let fifo = dir.path().join("dictation.fifo");
+        let status = Command::new("mkfifo").arg(&fifo).status().unwrap();
+        assert!(status.success());
+
+        let sink = DictationSink::Pipe {
+            path: fifo.to_string_lossy().into_owned(),
+        };
+        assert!(sink.write("nobody listens").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_sink_waits_for_slow_reader() {
+        use std::io::Read;
+
+        let dir = TempDir::new().unwrap();
+        let fifo = dir.path().join("dictation.fifo");
+        let status = Command::new("mkfifo").arg(&fifo).status().unwrap();
+        assert!(status.success());
+
+        // Read-write never blocks on open, and keeps the FIFO open for the sink
+        let mut reader = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&fifo)
+            .unwrap();
+        let sink = DictationSink::Pipe {
+            path: fifo.to_string_lossy().into_owned(),
+        };
+        // Larger than the pipe buffer
+        let text = "word ".repeat(40_000);
+        let len = text.len() + 1;
+        let reader = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            let mut received = vec![0; len];
+            reader.read_exact(&mut received).map(|_| received)
+        });
+
+        sink.write(&text).unwrap();
+        let received = reader.join().unwrap().unwrap();
+        assert_eq!(received, format!("{}\n", text).into_bytes());
+    }
+}
@@ -0,0 +1,232 @@
+//! Audio archive for history entries
+//!
+//! When enabled, each dictation's audio is kept as a 16kHz mono FLAC file in
+//! the app data directory and linked from its history entry, so it can be
+//! replayed, re-transcribed with another model, or collected as a test corpus.
+
+use super::Database;
+use crate::error::AppError;
+use crate::services::audio::flac::FlacWriter;
+use crate::services::audio::spool::TRANSCRIPTION_WINDOW_SECS;
+use crate::services::audio::RecordedAudio;
+use chrono::Utc;
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Sample rate of archived audio (what Whisper consumes)
+const ARCHIVE_SAMPLE_RATE: u32 = 16000;
+
+/// Audio archiving settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioArchiveConfig {
+    /// Keep audio for new history entries
+    pub enabled: bool,
+    /// Maximum number of archived recordings (0 = unlimited)
+    pub max_entries: u32,
+    /// Delete archived audio older than this many days (0 = never)
+    pub max_age_days: u32,
+}
+
+impl Default for AudioArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: 500,
+            max_age_days: 90,
+        }
+    }
+}
+
+/// Directory holding archived recordings
+pub struct AudioArchive {
+    dir: PathBuf,
+}
+
+impl AudioArchive {
+    /// Open the archive in the default app data location
+    pub fn new() -> Result<Self, AppError> {
+        Ok(Self::with_dir(get_archive_dir()?))
+    }
+
+    /// Open an archive rooted at a specific directory
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Archive directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Encode a recording to FLAC and return the stored file path
+    pub fn store(&self, recording: &RecordedAudio) -> Result<PathBuf, AppError> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| {
+            AppError::Storage(format!("Failed to create audio archive directory: {}", e))
+        })?;
+
+        let name = Utc::now().format("%Y%m%dT%H%M%S%.3f").to_string();
+        let path = self.dir.join(format!("{}.flac", name));
+        let partial = self.dir.join(format!("{}.flac.part", name));
+
+        if let Err(e) = write_flac(&partial, recording) {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+        std::fs::rename(&partial, &path)
+            .map_err(|e| AppError::Storage(format!("Failed to finalize archived audio: {}", e)))?;
+
+        Ok(path)
+    }
+}
+
+/// Stream a recording through the resampler into a FLAC file
+fn write_flac(path: &Path, recording: &RecordedAudio) -> Result<(), AppError> {
+    let file = File::create(path)
+        .map_err(|e| AppError::Storage(format!("Failed to create archive file: {}", e)))?;
+    let mut writer = FlacWriter::new(BufWriter::new(file), ARCHIVE_SAMPLE_RATE)
+        .map_err(|e| AppError::Storage(format!("Failed to encode audio: {}", e)))?;
+
+    for window in recording.whisper_windows(TRANSCRIPTION_WINDOW_SECS) {
+        let samples =
+            window.map_err(|e| AppError::Storage(format!("Failed to read recording: {}", e)))?;
+        writer
+            .write_samples(&samples)
+            .map_err(|e| AppError::Storage(format!("Failed to encode audio: {}", e)))?;
+    }
+
+    writer
+        .finish()
+        .map_err(|e| AppError::Storage(format!("Failed to encode audio: {}", e)))?;
+    Ok(())
+}
+
+/// Get the audio archive directory
+pub fn get_archive_dir() -> Result<PathBuf, AppError> {
+    let proj_dirs = ProjectDirs::from("com", "ezflow", "EZ Flow")
+        .ok_or_else(|| AppError::Config("Failed to get project directories".into()))?;
+
+    Ok(proj_dirs.data_dir().join("recordings"))
+}
+
+/// Delete an archived recording, ignoring files that are already gone
+pub fn remove_archived_audio(path: &str) {
+    match std::fs::remove_file(path) {
+        Ok(()) => tracing::debug!("Removed archived audio {}", path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!("Failed to remove archived audio {}: {}", path, e),
+    }
+}
+
+/// Archive a recording if enabled, returning the path to link from history.
+/// The FLAC encoding runs on a blocking thread.
+pub async fn archive_recording(
+    config: &AudioArchiveConfig,
+    recording: RecordedAudio,
+) -> Option<String> {
+    if !config.enabled || recording.is_empty() {
+        return None;
+    }
+
+    match tokio::task::spawn_blocking(move || store_recording(&recording)).await {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::error!("Audio archiving task failed: {}", e);
+            None
+        }
+    }
+}
+
+fn store_recording(recording: &RecordedAudio) -> Option<String> {
+    let archive = match AudioArchive::new() {
+        Ok(archive) => archive,
+        Err(e) => {
+            tracing::error!("Audio archive unavailable: {}", e);
+            return None;
+        }
+    };
+
+    match archive.store(recording) {
+        Ok(path) => {
+            tracing::debug!("Archived recording to {:?}", path);
+            Some(path.to_string_lossy().into_owned())
+        }
+        Err(e) => {
+            tracing::error!("Failed to archive recording: {}", e);
+            None
+        }
+    }
+}
+
+/// Apply the archive retention limits after a new entry was stored
+pub async fn enforce_retention(db: &Database, config: &AudioArchiveConfig) {
+    match db
+        .prune_audio_archive(config.max_entries, config.max_age_days)
+        .await
+    {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Removed {} archived recordings past retention", count),
+        Err(e) => tracing::warn!("Failed to apply audio retention: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::audio::AudioBuffer;
+    use tempfile::TempDir;
+
+    fn recording(seconds: f32) -> RecordedAudio {
+        let len = (48000.0 * seconds) as usize;
+        let samples = (0..len)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48000.0).sin())
+            .collect();
+        RecordedAudio::Memory(AudioBuffer {
+            samples,
+            sample_rate: 48000,
+        })
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config = AudioArchiveConfig::default();
+        assert!(!config.enabled);
+        assert_eq!(config.max_entries, 500);
+        assert_eq!(config.max_age_days, 90);
+    }
+
+    #[test]
+    fn test_store_writes_decodable_flac() {
+        let dir = TempDir::new().unwrap();
+        let archive = AudioArchive::with_dir(dir.path().join("recordings"));
+
+        let path = archive.store(&recording(1.0)).unwrap();
+        assert!(path.exists());
+        assert_eq!(path.extension().unwrap(), "flac");
+
+        let samples = crate::services::transcription::decoder::decode_audio_file(&path).unwrap();
+        assert!((samples.len() as i64 - 16000).abs() < 100);
+
+        // No partial files left behind
+        let leftovers = std::fs::read_dir(archive.dir())
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "part"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[tokio::test]
+    async fn test_archive_recording_disabled() {
+        let config = AudioArchiveConfig::default();
+        assert!(archive_recording(&config, recording(0.5)).await.is_none());
+    }
+
+    #[test]
+    fn test_remove_missing_file_is_noop() {
+        remove_archived_audio("/nonexistent/ez_flow_archive_test.flac");
+    }
+}
@@ -0,0 +1,182 @@
+//! Corrections made by editing history entries
+//!
+//! When the user edits a transcription, the edited text is compared word by
+//! word with the original and each replaced run of words is recorded as a
+//! correction ("Cooper Netties" → "Kubernetes"). Pure insertions, deletions
+//! and punctuation-only changes are not recorded: they say nothing about
+//! what was misheard.
+
+use crate::models::TextCorrection;
+use std::ops::Range;
+
+/// Corrections turning `original` into `edited`, in text order
+pub fn diff_corrections(original: &str, edited: &str) -> Vec<TextCorrection> {
+    let old = tokenize(original);
+    let new = tokenize(edited);
+    let same = |i: usize, j: usize| original[old[i].clone()] == edited[new[j].clone()];
+
+    // Skip the common prefix and suffix so the table only covers the edit
+    let mut start = 0;
+    while start < old.len() && start < new.len() && same(start, start) {
+        start += 1;
+    }
+    let (mut old_end, mut new_end) = (old.len(), new.len());
+    while old_end > start && new_end > start && same(old_end - 1, new_end - 1) {
+        old_end -= 1;
+        new_end -= 1;
+    }
+
+    // Longest common subsequence lengths of the remaining suffixes
+    let (rows, cols) = (old_end - start, new_end - start);
+    let mut lcs = vec![0u32; (rows + 1) * (cols + 1)];
+    let at = |i: usize, j: usize| i * (cols + 1) + j;
+    for i in (0..rows).rev() {
+        for j in (0..cols).rev() {
+            lcs[at(i, j)] = if same(start + i, start + j) {
+                lcs[at(i + 1, j + 1)] + 1
+            } else {
+                lcs[at(i + 1, j)].max(lcs[at(i, j + 1)])
+            };
+        }
+    }
+
+    // Walk the table, collecting each run of changed tokens as one hunk
+    let mut corrections = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut removed, mut added): (Vec<usize>, Vec<usize>) = (Vec::new(), Vec::new());
+    loop {
+        let matched = i < rows && j < cols && same(start + i, start + j);
+        if matched || (i == rows && j == cols) {
+            if let Some(correction) = hunk(original, &old, &removed, edited, &new, &added) {
+                corrections.push(correction);
+            }
+            removed.clear();
+            added.clear();
+            if !matched {
+                break;
+            }
+            i += 1;
+            j += 1;
+        } else if j == cols || (i < rows && lcs[at(i + 1, j)] >= lcs[at(i, j + 1)]) {
+            removed.push(start + i);
+            i += 1;
+        } else {
+            added.push(start + j);
+            j += 1;
+        }
+    }
+
+    corrections
+}
+
+/// The correction for one run of changed tokens, if words were replaced by
+/// other words. Punctuation at either end of the run is left out.
+fn hunk(
+    original: &str,
+    old: &[Range<usize>],
+    removed: &[usize],
+    edited: &str,
+    new: &[Range<usize>],
+    added: &[usize],
+) -> Option<TextCorrection> {
+    let span = |text: &str, tokens: &[Range<usize>], indices: &[usize]| {
+        let words: Vec<&Range<usize>> = indices
+            .iter()
+            .map(|&i| &tokens[i])
+            .filter(|r| text[(*r).clone()].chars().any(char::is_alphanumeric))
+            .collect();
+        let (first, last) = (words.first()?, words.last()?);
+        Some(text[first.start..last.end].to_string())
+    };
+
+    Some(TextCorrection {
+        original: span(original, old, removed)?,
+        corrected: span(edited, new, added)?,
+    })
+}
+
+/// Byte ranges of the words and punctuation marks in `text`
+fn tokenize(text: &str) -> Vec<Range<usize>> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '\'' || c == '’' || c == '-';
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        if is_word(c) {
+            while let Some((i, c)) = chars.next_if(|&(_, c)| is_word(c)) {
+                end = i + c.len_utf8();
+            }
+        }
+        tokens.push(start..end);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(original: &str, edited: &str) -> Vec<(String, String)> {
+        diff_corrections(original, edited)
+            .into_iter()
+            .map(|c| (c.original, c.corrected))
+            .collect()
+    }
+
+    fn pair(original: &str, corrected: &str) -> (String, String) {
+        (original.to_string(), corrected.to_string())
+    }
+
+    #[test]
+    fn test_multi_word_substitution() {
+        assert_eq!(
+            pairs(
+                "deploy it to Cooper Netties today",
+                "deploy it to Kubernetes today"
+            ),
+            vec![pair("Cooper Netties", "Kubernetes")]
+        );
+    }
+
+    #[test]
+    fn test_several_corrections_in_order() {
+        assert_eq!(
+            pairs(
+                "ask Jon about the post gress migration, then email Jon",
+                "ask John about the Postgres migration, then email John"
+            ),
+            vec![
+                pair("Jon", "John"),
+                pair("post gress", "Postgres"),
+                pair("Jon", "John"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_punctuation_is_kept_out_of_corrections() {
+        assert_eq!(
+            pairs("we use get hub.", "We use GitHub!"),
+            vec![pair("we", "We"), pair("get hub", "GitHub")]
+        );
+    }
+
+    #[test]
+    fn test_insertions_and_deletions_are_ignored() {
+        assert!(pairs("send the report", "please send the report now").is_empty());
+        assert!(pairs("um send the the report", "send the report").is_empty());
+        assert!(pairs("same text", "same text").is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_keeps_contractions_and_hyphens() {
+        let text = "don't re-run it, ok";
+        let words: Vec<&str> = tokenize(text).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(words, vec!["don't", "re-run", "it", ",", "ok"]);
+    }
+}
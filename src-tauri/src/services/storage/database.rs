@@ -1,25 +1,56 @@
 //! SQLite database for history storage
 //!
 //! Handles database initialization and operations for transcription history.
-
+//! The full-text index (`history_fts`) is kept in sync by triggers, so
+//! writes only touch the `history` table.
+
+use super::archive::remove_archived_audio;
+use super::corrections::diff_corrections;
+use super::encryption::{self, DatabaseKey, HistoryEncryptionConfig, KeySource};
+use super::migrations;
+use super::search::{
+    excerpt, find_highlights, take_highlights, utf16_ranges, SearchQuery, SearchTerm,
+};
+use super::settings::load_settings;
 use crate::error::AppError;
-use crate::models::HistoryEntry;
+use crate::models::{
+    CorrectionSummary, HistoryEntry, HistoryFilter, HistoryMetadata, HistoryRevision,
+    HistorySearchPage, HistorySearchResult, TextCorrection,
+};
+use chrono::{DateTime, Duration, Utc};
 use directories::ProjectDirs;
-use rusqlite::{params, Connection};
-use std::path::PathBuf;
-use std::sync::Arc;
+use ez_flow_history::schema::{ENTRY_COLUMNS, REVISION_COLUMNS};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use tokio::sync::Mutex;
 
 /// Database connection wrapper
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
+    path: PathBuf,
+    /// Key the database is encrypted with (if any)
+    key: std::sync::Mutex<Option<DatabaseKey>>,
 }
 
 impl Database {
     /// Create a new database connection
     pub fn new() -> Result<Self, AppError> {
-        let path = get_database_path()?;
+        Self::open(&get_database_path()?)
+    }
+
+    /// Open (creating if needed) the database at a specific path
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        Self::open_with_key(path, None)
+    }
 
+    /// Open (creating if needed) the database at a specific path, unlocking
+    /// it with `key` when it is encrypted
+    pub fn open_with_key(path: &Path, key: Option<&DatabaseKey>) -> Result<Self, AppError> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| {
@@ -27,44 +58,62 @@ impl Database {
             })?;
         }
 
-        let conn = Connection::open(&path)
-            .map_err(|e| AppError::Config(format!("Failed to open database: {}", e)))?;
+        if key.is_none() && encryption::is_encrypted(path)? {
+            return Err(AppError::Config(
+                "History database is encrypted; a key is needed to open it".into(),
+            ));
+        }
+
+        let conn = open_connection(path, key)?;
 
-        // Initialize schema
-        init_schema(&conn)?;
+        // Create or upgrade the schema
+        migrations::migrate(&conn, Some(path))?;
 
         tracing::info!("Database initialized at {:?}", path);
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            path: path.to_path_buf(),
+            key: std::sync::Mutex::new(key.cloned()),
         })
     }
 
-    /// Insert a history entry
-    pub async fn insert_history(&self, entry: &HistoryEntry) -> Result<i64, AppError> {
-        let conn = self.conn.lock().await;
-        conn.execute(
-            "INSERT INTO history (text, timestamp, duration_ms, model_id, language, gpu_used) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![
-                entry.text,
-                entry.timestamp,
-                entry.duration_ms as i64,
-                entry.model_id,
-                entry.language,
-                entry.gpu_used as i32,
-            ],
-        )
-        .map_err(|e| AppError::Config(format!("Failed to insert history: {}", e)))?;
+    /// Open the database at `path` encrypted with `key`, encrypting it first
+    /// if it is still unencrypted
+    pub fn open_encrypted(path: &Path, key: &DatabaseKey) -> Result<Self, AppError> {
+        if path.exists() && !encryption::is_encrypted(path)? {
+            let mut conn = open_connection(path, None)?;
+            rekey_file(&mut conn, path, None, Some(key))?;
+        }
+        Self::open_with_key(path, Some(key))
+    }
 
-        let id = conn.last_insert_rowid();
+    /// Whether the database is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.key.lock().map(|key| key.is_some()).unwrap_or(false)
+    }
 
-        // Also update FTS index
-        conn.execute(
-            "INSERT INTO history_fts (rowid, text) VALUES (?1, ?2)",
-            params![id, entry.text],
-        )
-        .map_err(|e| AppError::Config(format!("Failed to update FTS index: {}", e)))?;
+    /// Encrypt the database with `key`, or decrypt it when `key` is `None`
+    pub async fn set_encryption(&self, key: Option<&DatabaseKey>) -> Result<(), AppError> {
+        let mut conn = self.conn.lock().await;
+        let mut current = self
+            .key
+            .lock()
+            .map_err(|_| AppError::Config("Database key lock poisoned".into()))?;
+        rekey_file(&mut conn, &self.path, current.as_ref(), key)?;
+        *current = key.cloned();
+        Ok(())
+    }
 
+    /// Insert a history entry
+    pub async fn insert_history(&self, entry: &HistoryEntry) -> Result<i64, AppError> {
+        let conn = self.conn.lock().await;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::Config(format!("Failed to start transaction: {}", e)))?;
+        let id = insert_entry(&tx, entry)?;
+        tx.commit()
+            .map_err(|e| AppError::Config(format!("Failed to save history entry: {}", e)))?;
         Ok(id)
     }
 
@@ -73,29 +122,36 @@ impl Database {
         &self,
         limit: usize,
         offset: usize,
+    ) -> Result<Vec<HistoryEntry>, AppError> {
+        self.get_history_filtered(limit, offset, &HistoryFilter::default())
+            .await
+    }
+
+    /// Get paginated history entries matching a filter
+    pub async fn get_history_filtered(
+        &self,
+        limit: usize,
+        offset: usize,
+        filter: &HistoryFilter,
     ) -> Result<Vec<HistoryEntry>, AppError> {
         let conn = self.conn.lock().await;
+
+        let mut values = Vec::new();
+        let conditions = filter_conditions(filter, &mut values);
+        values.push(Value::Integer(limit as i64));
+        values.push(Value::Integer(offset as i64));
+
+        let sql = format!(
+            "SELECT {} FROM history h{} ORDER BY h.timestamp DESC LIMIT ? OFFSET ?",
+            ENTRY_COLUMNS,
+            where_clause(&conditions)
+        );
         let mut stmt = conn
-            .prepare(
-                "SELECT id, text, timestamp, duration_ms, model_id, language, gpu_used
-                 FROM history
-                 ORDER BY timestamp DESC
-                 LIMIT ?1 OFFSET ?2",
-            )
+            .prepare(&sql)
             .map_err(|e| AppError::Config(format!("Failed to prepare query: {}", e)))?;
 
         let entries = stmt
-            .query_map(params![limit as i64, offset as i64], |row| {
-                Ok(HistoryEntry {
-                    id: row.get(0)?,
-                    text: row.get(1)?,
-                    timestamp: row.get(2)?,
-                    duration_ms: row.get::<_, i64>(3)? as u64,
-                    model_id: row.get(4)?,
-                    language: row.get(5)?,
-                    gpu_used: row.get::<_, i32>(6).unwrap_or(0) != 0,
-                })
-            })
+            .query_map(params_from_iter(values), row_to_entry)
             .map_err(|e| AppError::Config(format!("Failed to query history: {}", e)))?
             .filter_map(|r| r.ok())
             .collect();
@@ -105,37 +161,170 @@ impl Database {
 
     /// Search history using FTS5 with prefix matching and LIKE fallback
     pub async fn search_history(&self, query: &str) -> Result<Vec<HistoryEntry>, AppError> {
-        let query = query.trim();
-        if query.is_empty() {
-            return self.get_history(100, 0).await;
-        }
+        self.search_history_filtered(query, &HistoryFilter::default())
+            .await
+    }
+
+    /// Search history entries matching a filter, best match first
+    pub async fn search_history_filtered(
+        &self,
+        query: &str,
+        filter: &HistoryFilter,
+    ) -> Result<Vec<HistoryEntry>, AppError> {
+        let page = self.search(query, filter, 100, 0).await?;
+        Ok(page.results.into_iter().map(|r| r.entry).collect())
+    }
 
+    /// Search history with ranking, highlights and pagination
+    ///
+    /// Full-text matches are ordered by BM25 relevance. When the full-text
+    /// index finds nothing, substring matching is used instead, newest first.
+    /// An empty query lists the filtered history.
+    pub async fn search(
+        &self,
+        query: &str,
+        filter: &HistoryFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<HistorySearchPage, AppError> {
+        let search = SearchQuery::parse(query);
         let conn = self.conn.lock().await;
 
-        // Try FTS5 with prefix matching first
-        let fts_query = prepare_fts_query(query);
-        let entries = search_fts(&conn, &fts_query)?;
+        let mut plan = SearchPlan::full_text(&search, filter);
+        let mut total = plan.count(&conn)?;
+        if total == 0 && !search.include.is_empty() {
+            plan = SearchPlan::substring(&search, filter);
+            total = plan.count(&conn)?;
+        }
+
+        let results = if total > offset {
+            plan.results(&conn, &search, Some(limit), offset)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(HistorySearchPage { results, total })
+    }
+
+    /// All entries matching a search query (may be empty) and filter, oldest first
+    pub async fn find_entries(
+        &self,
+        query: &str,
+        filter: &HistoryFilter,
+    ) -> Result<Vec<HistoryEntry>, AppError> {
+        let search = SearchQuery::parse(query);
+        let conn = self.conn.lock().await;
 
-        // Fallback to LIKE if FTS returns no results
-        if entries.is_empty() {
-            return search_like(&conn, query);
+        let mut plan = SearchPlan::full_text(&search, filter);
+        if !search.include.is_empty() && plan.count(&conn)? == 0 {
+            plan = SearchPlan::substring(&search, filter);
         }
 
+        let mut entries: Vec<HistoryEntry> = plan
+            .results(&conn, &search, None, 0)?
+            .into_iter()
+            .map(|r| r.entry)
+            .collect();
+        entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
         Ok(entries)
     }
 
+    /// Insert an imported entry and its revisions unless it is already present
+    ///
+    /// An entry counts as present when one with the same timestamp and text
+    /// exists. Returns the new ID, or `None` for duplicates.
+    pub async fn import_entry(
+        &self,
+        entry: &HistoryEntry,
+        revisions: &[HistoryRevision],
+    ) -> Result<Option<i64>, AppError> {
+        let conn = self.conn.lock().await;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::Config(format!("Failed to start transaction: {}", e)))?;
+
+        let exists: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM history WHERE timestamp = ?1 AND text = ?2)",
+                params![entry.timestamp, entry.text],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Config(format!("Failed to check for duplicates: {}", e)))?;
+        if exists {
+            return Ok(None);
+        }
+
+        let id = insert_entry(&tx, entry)?;
+        for revision in revisions {
+            insert_revision_row(
+                &tx,
+                &HistoryRevision {
+                    history_id: id,
+                    ..revision.clone()
+                },
+            )?;
+        }
+        tx.commit()
+            .map_err(|e| AppError::Config(format!("Failed to save imported entry: {}", e)))?;
+
+        Ok(Some(id))
+    }
+
+    /// Applications that dictated text was sent to, most recently used first
+    pub async fn get_source_apps(&self) -> Result<Vec<String>, AppError> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare(
+                "SELECT source_app FROM history
+                 WHERE source_app IS NOT NULL
+                 GROUP BY source_app
+                 ORDER BY MAX(timestamp) DESC",
+            )
+            .map_err(|e| AppError::Config(format!("Failed to prepare query: {}", e)))?;
+
+        let apps = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| AppError::Config(format!("Failed to query source apps: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(apps)
+    }
+
     /// Delete a single history entry
     pub async fn delete_entry(&self, id: i64) -> Result<(), AppError> {
         let conn = self.conn.lock().await;
 
-        // Delete from FTS first
-        conn.execute("DELETE FROM history_fts WHERE rowid = ?1", params![id])
-            .map_err(|e| AppError::Config(format!("Failed to delete from FTS: {}", e)))?;
+        let audio_path: Option<String> = conn
+            .query_row(
+                "SELECT audio_path FROM history WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Config(format!("Failed to look up history entry: {}", e)))?
+            .flatten();
+
+        conn.execute(
+            "DELETE FROM history_revisions WHERE history_id = ?1",
+            params![id],
+        )
+        .map_err(|e| AppError::Config(format!("Failed to delete revisions: {}", e)))?;
+
+        conn.execute(
+            "DELETE FROM history_corrections WHERE history_id = ?1",
+            params![id],
+        )
+        .map_err(|e| AppError::Config(format!("Failed to delete corrections: {}", e)))?;
 
         // Delete from main table
         conn.execute("DELETE FROM history WHERE id = ?1", params![id])
             .map_err(|e| AppError::Config(format!("Failed to delete history entry: {}", e)))?;
 
+        if let Some(path) = audio_path {
+            remove_archived_audio(&path);
+        }
+
         Ok(())
     }
 
@@ -143,272 +332,1536 @@ impl Database {
     pub async fn clear_all(&self) -> Result<(), AppError> {
         let conn = self.conn.lock().await;
 
-        conn.execute("DELETE FROM history_fts", [])
-            .map_err(|e| AppError::Config(format!("Failed to clear FTS: {}", e)))?;
+        let audio_paths = archived_audio_paths(&conn)?;
+
+        conn.execute("DELETE FROM history_revisions", [])
+            .map_err(|e| AppError::Config(format!("Failed to clear revisions: {}", e)))?;
+
+        conn.execute("DELETE FROM history_corrections", [])
+            .map_err(|e| AppError::Config(format!("Failed to clear corrections: {}", e)))?;
 
         conn.execute("DELETE FROM history", [])
             .map_err(|e| AppError::Config(format!("Failed to clear history: {}", e)))?;
 
+        for path in audio_paths {
+            remove_archived_audio(&path);
+        }
+
         Ok(())
     }
 
     /// Prune history to keep only the most recent entries
+    ///
+    /// Pinned entries are kept and do not count towards the limit.
     pub async fn prune_history(&self, max_entries: usize) -> Result<usize, AppError> {
         let conn = self.conn.lock().await;
 
-        // Get IDs to delete
-        let mut stmt = conn
-            .prepare("SELECT id FROM history ORDER BY timestamp DESC LIMIT -1 OFFSET ?1")
-            .map_err(|e| AppError::Config(format!("Failed to prepare prune query: {}", e)))?;
+        let count = delete_selected(
+            &conn,
+            "SELECT id, audio_path FROM history WHERE pinned = 0
+             ORDER BY timestamp DESC LIMIT -1 OFFSET ?1",
+            params![max_entries as i64],
+        )?;
 
-        let ids_to_delete: Vec<i64> = stmt
-            .query_map(params![max_entries as i64], |row| row.get(0))
-            .map_err(|e| AppError::Config(format!("Failed to query prune entries: {}", e)))?
-            .filter_map(|r| r.ok())
-            .collect();
+        if count > 0 {
+            tracing::debug!("Pruned {} old history entries", count);
+        }
+
+        Ok(count)
+    }
+
+    /// Delete unpinned entries older than the given number of days
+    pub async fn prune_older_than(&self, max_age_days: u32) -> Result<usize, AppError> {
+        let conn = self.conn.lock().await;
+        let cutoff = Utc::now() - Duration::days(max_age_days as i64);
+
+        let count = delete_selected(
+            &conn,
+            "SELECT id, audio_path FROM history
+             WHERE pinned = 0 AND julianday(timestamp) < julianday(?1)",
+            params![cutoff.to_rfc3339()],
+        )?;
+
+        if count > 0 {
+            tracing::debug!(
+                "Pruned {} history entries older than {} days",
+                count,
+                max_age_days
+            );
+        }
+
+        Ok(count)
+    }
+
+    /// Delete the oldest unpinned entries until the data stored in the
+    /// database fits in `max_bytes`
+    ///
+    /// Freed pages stay in the file until [`Database::vacuum`] runs.
+    pub async fn prune_to_size(&self, max_bytes: u64) -> Result<usize, AppError> {
+        let conn = self.conn.lock().await;
+        let mut count = 0;
 
-        let count = ids_to_delete.len();
+        loop {
+            let used = used_bytes(&conn)?;
+            if used <= max_bytes {
+                break;
+            }
+
+            // Estimate how many entries free the excess, from the average size
+            let entries: i64 = conn
+                .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+                .map_err(|e| AppError::Config(format!("Failed to count history: {}", e)))?;
+            let per_entry = used / entries.max(1) as u64;
+            let batch = (used - max_bytes).div_ceil(per_entry.max(1)).max(1);
+
+            let deleted = delete_selected(
+                &conn,
+                "SELECT id, audio_path FROM history WHERE pinned = 0
+                 ORDER BY timestamp ASC LIMIT ?1",
+                params![batch as i64],
+            )?;
+            if deleted == 0 {
+                break;
+            }
+            count += deleted;
 
-        for id in ids_to_delete {
-            conn.execute("DELETE FROM history_fts WHERE rowid = ?1", params![id])
-                .ok();
-            conn.execute("DELETE FROM history WHERE id = ?1", params![id])
-                .ok();
+            // FTS5 records deletions as new index data until segments merge
+            conn.execute(
+                "INSERT INTO history_fts (history_fts) VALUES ('optimize')",
+                [],
+            )
+            .map_err(|e| AppError::Config(format!("Failed to optimize FTS index: {}", e)))?;
         }
 
         if count > 0 {
-            tracing::debug!("Pruned {} old history entries", count);
+            tracing::debug!(
+                "Pruned {} history entries to fit {} bytes",
+                count,
+                max_bytes
+            );
         }
 
         Ok(count)
     }
 
-    /// Get total count of history entries
-    pub async fn count(&self) -> Result<usize, AppError> {
+    /// Size of the database file in bytes, including free pages
+    pub async fn file_size(&self) -> Result<u64, AppError> {
         let conn = self.conn.lock().await;
-        let count: i64 = conn
-            .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
-            .map_err(|e| AppError::Config(format!("Failed to count history: {}", e)))?;
-        Ok(count as usize)
+        let pages: i64 = pragma_value(&conn, "page_count")?;
+        let page_size: i64 = pragma_value(&conn, "page_size")?;
+        Ok((pages * page_size) as u64)
     }
-}
 
-/// Get the database file path
-fn get_database_path() -> Result<PathBuf, AppError> {
-    let proj_dirs = ProjectDirs::from("com", "ezflow", "EZ Flow")
-        .ok_or_else(|| AppError::Config("Failed to get project directories".into()))?;
+    /// Rebuild the database file to return free pages to the filesystem
+    pub async fn vacuum(&self) -> Result<(), AppError> {
+        let conn = self.conn.lock().await;
+        conn.execute_batch("VACUUM")
+            .map_err(|e| AppError::Config(format!("Failed to vacuum database: {}", e)))
+    }
 
-    let data_dir = proj_dirs.data_dir();
-    Ok(data_dir.join("history.db"))
-}
+    /// Pin or unpin an entry
+    pub async fn set_pinned(&self, id: i64, pinned: bool) -> Result<(), AppError> {
+        let conn = self.conn.lock().await;
+        let updated = conn
+            .execute(
+                "UPDATE history SET pinned = ?1 WHERE id = ?2",
+                params![pinned as i32, id],
+            )
+            .map_err(|e| AppError::Config(format!("Failed to update history entry: {}", e)))?;
 
-/// Prepare query for FTS5 prefix matching
-/// "hello world" -> "\"hello\"* \"world\"*"
-fn prepare_fts_query(query: &str) -> String {
-    query
-        .split_whitespace()
-        .map(|word| {
-            // Escape double quotes for FTS5
-            let escaped = word.replace('"', "\"\"");
-            format!("\"{}\"*", escaped)
-        })
-        .collect::<Vec<_>>()
-        .join(" ")
-}
+        if updated == 0 {
+            return Err(AppError::Config(format!("History entry {} not found", id)));
+        }
+        Ok(())
+    }
 
-/// Search using FTS5 with prepared query
-fn search_fts(conn: &Connection, fts_query: &str) -> Result<Vec<HistoryEntry>, AppError> {
-    let mut stmt = conn
-        .prepare(
-            "SELECT h.id, h.text, h.timestamp, h.duration_ms, h.model_id, h.language, h.gpu_used
-             FROM history h
-             JOIN history_fts fts ON h.id = fts.rowid
-             WHERE history_fts MATCH ?1
-             ORDER BY h.timestamp DESC
-             LIMIT 100",
+    /// Get a single history entry by ID
+    pub async fn get_entry(&self, id: i64) -> Result<Option<HistoryEntry>, AppError> {
+        let conn = self.conn.lock().await;
+        conn.query_row(
+            &format!("SELECT {} FROM history h WHERE h.id = ?1", ENTRY_COLUMNS),
+            params![id],
+            row_to_entry,
         )
-        .map_err(|e| AppError::Config(format!("Failed to prepare FTS search query: {}", e)))?;
-
-    let entries = stmt
-        .query_map(params![fts_query], |row| {
-            Ok(HistoryEntry {
-                id: row.get(0)?,
-                text: row.get(1)?,
-                timestamp: row.get(2)?,
-                duration_ms: row.get::<_, i64>(3)? as u64,
-                model_id: row.get(4)?,
-                language: row.get(5)?,
-                gpu_used: row.get::<_, i32>(6).unwrap_or(0) != 0,
-            })
-        })
-        .map_err(|e| AppError::Config(format!("Failed to execute FTS search: {}", e)))?
-        .filter_map(|r| r.ok())
-        .collect();
+        .optional()
+        .map_err(|e| AppError::Config(format!("Failed to get history entry: {}", e)))
+    }
 
-    Ok(entries)
-}
+    /// Remove the archived audio of a single entry, keeping its text
+    pub async fn remove_entry_audio(&self, id: i64) -> Result<(), AppError> {
+        let conn = self.conn.lock().await;
+        let audio_path: Option<String> = conn
+            .query_row(
+                "SELECT audio_path FROM history WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Config(format!("Failed to look up history entry: {}", e)))?
+            .flatten();
 
-/// Fallback search using LIKE for substring matching
-fn search_like(conn: &Connection, query: &str) -> Result<Vec<HistoryEntry>, AppError> {
-    let pattern = format!("%{}%", query.to_lowercase());
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, text, timestamp, duration_ms, model_id, language, gpu_used
-             FROM history
-             WHERE LOWER(text) LIKE ?1
-             ORDER BY timestamp DESC
-             LIMIT 100",
+        conn.execute(
+            "UPDATE history SET audio_path = NULL WHERE id = ?1",
+            params![id],
         )
-        .map_err(|e| AppError::Config(format!("Failed to prepare LIKE search query: {}", e)))?;
-
-    let entries = stmt
-        .query_map(params![pattern], |row| {
-            Ok(HistoryEntry {
-                id: row.get(0)?,
-                text: row.get(1)?,
-                timestamp: row.get(2)?,
-                duration_ms: row.get::<_, i64>(3)? as u64,
-                model_id: row.get(4)?,
-                language: row.get(5)?,
-                gpu_used: row.get::<_, i32>(6).unwrap_or(0) != 0,
-            })
-        })
-        .map_err(|e| AppError::Config(format!("Failed to execute LIKE search: {}", e)))?
-        .filter_map(|r| r.ok())
-        .collect();
+        .map_err(|e| AppError::Config(format!("Failed to unlink archived audio: {}", e)))?;
 
-    Ok(entries)
-}
+        if let Some(path) = audio_path {
+            remove_archived_audio(&path);
+        }
 
-/// Initialize the database schema
-fn init_schema(conn: &Connection) -> Result<(), AppError> {
-    conn.execute_batch(
-        r#"
-        CREATE TABLE IF NOT EXISTS history (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            text TEXT NOT NULL,
-            timestamp TEXT NOT NULL,
-            duration_ms INTEGER NOT NULL,
-            model_id TEXT NOT NULL,
-            language TEXT,
-            gpu_used INTEGER DEFAULT 0,
-            created_at TEXT DEFAULT CURRENT_TIMESTAMP
-        );
+        Ok(())
+    }
 
-        CREATE INDEX IF NOT EXISTS idx_history_timestamp ON history(timestamp DESC);
+    /// Drop archived audio beyond the retention limits (0 = unlimited)
+    ///
+    /// History text is kept; only the audio file and its link are removed.
+    /// Returns the number of recordings removed.
+    pub async fn prune_audio_archive(
+        &self,
+        max_entries: u32,
+        max_age_days: u32,
+    ) -> Result<usize, AppError> {
+        let conn = self.conn.lock().await;
 
-        CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
-            text,
-            content='history',
-            content_rowid='id'
-        );
-        "#,
-    )
-    .map_err(|e| AppError::Config(format!("Failed to initialize database schema: {}", e)))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, timestamp, audio_path FROM history
+                 WHERE audio_path IS NOT NULL
+                 ORDER BY timestamp DESC",
+            )
+            .map_err(|e| AppError::Config(format!("Failed to prepare audio prune query: {}", e)))?;
 
-    // Add gpu_used column if it doesn't exist (migration for existing databases)
-    let _ = conn.execute(
-        "ALTER TABLE history ADD COLUMN gpu_used INTEGER DEFAULT 0",
-        [],
-    );
+        let archived: Vec<(i64, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| AppError::Config(format!("Failed to query archived audio: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
 
-    Ok(())
-}
+        let cutoff = (max_age_days > 0).then(|| Utc::now() - Duration::days(max_age_days as i64));
+
+        let expired: Vec<(i64, String)> = archived
+            .into_iter()
+            .enumerate()
+            .filter(|(index, (_, timestamp, _))| {
+                let over_count = max_entries > 0 && *index >= max_entries as usize;
+                let too_old = cutoff.is_some_and(|cutoff| {
+                    DateTime::parse_from_rfc3339(timestamp)
+                        .map(|t| t.with_timezone(&Utc) < cutoff)
+                        .unwrap_or(false)
+                });
+                over_count || too_old
+            })
+            .map(|(_, (id, _, path))| (id, path))
+            .collect();
 
-/// Database state wrapper for Tauri
-pub struct DatabaseState {
-    db: Option<Database>,
-}
+        for (id, path) in &expired {
+            conn.execute(
+                "UPDATE history SET audio_path = NULL WHERE id = ?1",
+                params![id],
+            )
+            .ok();
+            remove_archived_audio(path);
+        }
 
-impl DatabaseState {
-    /// Create a new database state
-    pub fn new() -> Self {
-        match Database::new() {
-            Ok(db) => Self { db: Some(db) },
-            Err(e) => {
-                tracing::error!("Failed to initialize database: {}", e);
-                Self { db: None }
-            }
+        if !expired.is_empty() {
+            tracing::debug!("Pruned {} archived recordings", expired.len());
         }
+
+        Ok(expired.len())
     }
 
-    /// Get a reference to the database (if available)
-    pub fn get(&self) -> Option<&Database> {
-        self.db.as_ref()
+    /// Get the most recent entries that still have archived audio
+    pub async fn get_recent_with_audio(&self, limit: usize) -> Result<Vec<HistoryEntry>, AppError> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM history h
+                 WHERE h.audio_path IS NOT NULL
+                 ORDER BY h.timestamp DESC
+                 LIMIT ?1",
+                ENTRY_COLUMNS
+            ))
+            .map_err(|e| AppError::Config(format!("Failed to prepare query: {}", e)))?;
+
+        let entries = stmt
+            .query_map(params![limit as i64], row_to_entry)
+            .map_err(|e| AppError::Config(format!("Failed to query history: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
     }
-}
 
-impl Default for DatabaseState {
-    fn default() -> Self {
-        Self::new()
+    /// Store a new transcript revision for a history entry
+    pub async fn insert_revision(&self, revision: &HistoryRevision) -> Result<i64, AppError> {
+        let conn = self.conn.lock().await;
+        insert_revision_row(&conn, revision)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
+    /// Get all revisions of a history entry, newest first
+    pub async fn get_revisions(&self, history_id: i64) -> Result<Vec<HistoryRevision>, AppError> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM history_revisions
+                 WHERE history_id = ?1
+                 ORDER BY created_at DESC, id DESC",
+                REVISION_COLUMNS
+            ))
+            .map_err(|e| AppError::Config(format!("Failed to prepare revision query: {}", e)))?;
+
+        let revisions = stmt
+            .query_map(params![history_id], row_to_revision)
+            .map_err(|e| AppError::Config(format!("Failed to query revisions: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
 
-    fn create_test_db() -> (Database, TempDir) {
-        let dir = TempDir::new().unwrap();
-        let path = dir.path().join("test.db");
-        let conn = Connection::open(&path).unwrap();
-        init_schema(&conn).unwrap();
-        let db = Database {
-            conn: Arc::new(Mutex::new(conn)),
-        };
-        (db, dir) // Return dir to keep it alive
+        Ok(revisions)
     }
 
-    #[tokio::test]
-    async fn test_insert_and_get_history() {
-        let (db, _dir) = create_test_db();
+    /// Make a revision the entry's current text
+    ///
+    /// The replaced text is kept as a revision so switching back is possible.
+    /// Edits made to the replaced text stop counting as corrections.
+    pub async fn apply_revision(
+        &self,
+        history_id: i64,
+        revision_id: i64,
+    ) -> Result<HistoryEntry, AppError> {
+        let conn = self.conn.lock().await;
 
-        let entry = HistoryEntry {
-            id: 0,
-            text: "Hello world".to_string(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(),
-            duration_ms: 1000,
-            model_id: "base".to_string(),
-            language: Some("en".to_string()),
-            gpu_used: true,
-        };
+        let current = conn
+            .query_row(
+                &format!("SELECT {} FROM history h WHERE h.id = ?1", ENTRY_COLUMNS),
+                params![history_id],
+                row_to_entry,
+            )
+            .optional()
+            .map_err(|e| AppError::Config(format!("Failed to get history entry: {}", e)))?
+            .ok_or_else(|| AppError::Config(format!("History entry {} not found", history_id)))?;
+
+        let revision = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM history_revisions WHERE id = ?1 AND history_id = ?2",
+                    REVISION_COLUMNS
+                ),
+                params![revision_id, history_id],
+                row_to_revision,
+            )
+            .optional()
+            .map_err(|e| AppError::Config(format!("Failed to get revision: {}", e)))?
+            .ok_or_else(|| AppError::Config(format!("Revision {} not found", revision_id)))?;
+
+        // Swap the texts: the revision becomes current, the old text a revision
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::Config(format!("Failed to start transaction: {}", e)))?;
+        tx.execute(
+            "UPDATE history
+             SET text = ?1, model_id = ?2, language = ?3, gpu_used = ?4, original_text = NULL
+             WHERE id = ?5",
+            params![
+                revision.text,
+                revision.model_id,
+                revision.language,
+                revision.gpu_used as i32,
+                history_id,
+            ],
+        )
+        .map_err(|e| AppError::Config(format!("Failed to update history entry: {}", e)))?;
 
-        let id = db.insert_history(&entry).await.unwrap();
-        assert!(id > 0);
+        tx.execute(
+            "UPDATE history_revisions
+             SET text = ?1, model_id = ?2, language = ?3, gpu_used = ?4, options = NULL
+             WHERE id = ?5",
+            params![
+                current.text,
+                current.model_id,
+                current.language,
+                current.gpu_used as i32,
+                revision_id,
+            ],
+        )
+        .map_err(|e| AppError::Config(format!("Failed to update revision: {}", e)))?;
 
-        let entries = db.get_history(10, 0).await.unwrap();
-        assert_eq!(entries.len(), 1);
-        assert_eq!(entries[0].text, "Hello world");
-        assert!(entries[0].gpu_used);
+        tx.execute(
+            "DELETE FROM history_corrections WHERE history_id = ?1",
+            params![history_id],
+        )
+        .map_err(|e| AppError::Config(format!("Failed to delete corrections: {}", e)))?;
+        tx.commit()
+            .map_err(|e| AppError::Config(format!("Failed to apply revision: {}", e)))?;
+
+        Ok(HistoryEntry {
+            text: revision.text,
+            model_id: revision.model_id,
+            language: revision.language,
+            gpu_used: revision.gpu_used,
+            original_text: None,
+            ..current
+        })
     }
 
-    #[tokio::test]
-    async fn test_delete_entry() {
-        let (db, _dir) = create_test_db();
+    /// Replace an entry's text with an edited version
+    ///
+    /// The first transcription is kept as `original_text`, and the words the
+    /// edit replaced are recorded as corrections. Editing the text back to
+    /// the original clears both.
+    pub async fn update_entry_text(&self, id: i64, text: &str) -> Result<HistoryEntry, AppError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(AppError::Config("History text cannot be empty".into()));
+        }
 
-        let entry = HistoryEntry {
-            id: 0,
-            text: "Test entry".to_string(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(),
+        let conn = self.conn.lock().await;
+        let current = conn
+            .query_row(
+                &format!("SELECT {} FROM history h WHERE h.id = ?1", ENTRY_COLUMNS),
+                params![id],
+                row_to_entry,
+            )
+            .optional()
+            .map_err(|e| AppError::Config(format!("Failed to get history entry: {}", e)))?
+            .ok_or_else(|| AppError::Config(format!("History entry {} not found", id)))?;
+
+        let original = current
+            .original_text
+            .clone()
+            .unwrap_or(current.text.clone());
+        let original_text = (original != text).then_some(original);
+
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::Config(format!("Failed to start transaction: {}", e)))?;
+        tx.execute(
+            "UPDATE history SET text = ?1, original_text = ?2 WHERE id = ?3",
+            params![text, original_text, id],
+        )
+        .map_err(|e| AppError::Config(format!("Failed to update history entry: {}", e)))?;
+        replace_corrections(&tx, id, original_text.as_deref().unwrap_or(text), text)?;
+        tx.commit()
+            .map_err(|e| AppError::Config(format!("Failed to save history edit: {}", e)))?;
+
+        Ok(HistoryEntry {
+            text: text.to_string(),
+            original_text,
+            ..current
+        })
+    }
+
+    /// Corrections recorded for an entry, in text order
+    pub async fn get_corrections(&self, history_id: i64) -> Result<Vec<TextCorrection>, AppError> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare(
+                "SELECT original, corrected FROM history_corrections
+                 WHERE history_id = ?1
+                 ORDER BY id",
+            )
+            .map_err(|e| AppError::Config(format!("Failed to prepare correction query: {}", e)))?;
+
+        let corrections = stmt
+            .query_map(params![history_id], |row| {
+                Ok(TextCorrection {
+                    original: row.get(0)?,
+                    corrected: row.get(1)?,
+                })
+            })
+            .map_err(|e| AppError::Config(format!("Failed to query corrections: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(corrections)
+    }
+
+    /// Corrections made at least `min_count` times across all entries, most
+    /// frequent first
+    pub async fn correction_summary(
+        &self,
+        min_count: usize,
+        limit: usize,
+    ) -> Result<Vec<CorrectionSummary>, AppError> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare(
+                "SELECT original, corrected, COUNT(*), COUNT(DISTINCT history_id),
+                        MAX(created_at)
+                 FROM history_corrections
+                 GROUP BY original, corrected
+                 HAVING COUNT(*) >= ?1
+                 ORDER BY COUNT(*) DESC, MAX(created_at) DESC
+                 LIMIT ?2",
+            )
+            .map_err(|e| AppError::Config(format!("Failed to prepare correction query: {}", e)))?;
+
+        let summary = stmt
+            .query_map(params![min_count.max(1) as i64, limit as i64], |row| {
+                Ok(CorrectionSummary {
+                    original: row.get(0)?,
+                    corrected: row.get(1)?,
+                    count: row.get::<_, i64>(2)? as usize,
+                    entries: row.get::<_, i64>(3)? as usize,
+                    last_corrected: row.get(4)?,
+                })
+            })
+            .map_err(|e| AppError::Config(format!("Failed to query corrections: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(summary)
+    }
+
+    /// Get total count of history entries
+    pub async fn count(&self) -> Result<usize, AppError> {
+        let conn = self.conn.lock().await;
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+            .map_err(|e| AppError::Config(format!("Failed to count history: {}", e)))?;
+        Ok(count as usize)
+    }
+}
+
+/// Record the corrections between an entry's original and current text,
+/// replacing any recorded before
+fn replace_corrections(
+    conn: &Connection,
+    history_id: i64,
+    original: &str,
+    text: &str,
+) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM history_corrections WHERE history_id = ?1",
+        params![history_id],
+    )
+    .map_err(|e| AppError::Config(format!("Failed to delete corrections: {}", e)))?;
+
+    let created_at = Utc::now().to_rfc3339();
+    for correction in diff_corrections(original, text) {
+        conn.execute(
+            "INSERT INTO history_corrections (history_id, original, corrected, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                history_id,
+                correction.original,
+                correction.corrected,
+                created_at
+            ],
+        )
+        .map_err(|e| AppError::Config(format!("Failed to save correction: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Insert a re-transcription revision
+fn insert_revision_row(conn: &Connection, revision: &HistoryRevision) -> Result<i64, AppError> {
+    let options = serde_json::to_string(&revision.options)?;
+    conn.execute(
+        "INSERT INTO history_revisions (history_id, text, model_id, language, gpu_used, options, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            revision.history_id,
+            revision.text,
+            revision.model_id,
+            revision.language,
+            revision.gpu_used as i32,
+            options,
+            revision.created_at,
+        ],
+    )
+    .map_err(|e| AppError::Config(format!("Failed to insert revision: {}", e)))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Insert a history entry and the corrections of its edited text
+fn insert_entry(conn: &Connection, entry: &HistoryEntry) -> Result<i64, AppError> {
+    let metadata = &entry.metadata;
+    let voice_commands = if metadata.voice_commands.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&metadata.voice_commands)?)
+    };
+    conn.execute(
+        "INSERT INTO history (text, timestamp, duration_ms, model_id, language, gpu_used,
+                              audio_path, source, source_app, source_window, streaming_mode,
+                              transcription_ms, injection_ms, voice_commands, pinned,
+                              original_text, translated)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        params![
+            entry.text,
+            entry.timestamp,
+            entry.duration_ms as i64,
+            entry.model_id,
+            entry.language,
+            entry.gpu_used as i32,
+            entry.audio_path,
+            enum_to_sql(&metadata.source),
+            metadata.source_app,
+            metadata.source_window,
+            enum_to_sql(&metadata.streaming_mode),
+            metadata.transcription_time_ms.map(|ms| ms as i64),
+            metadata.injection_time_ms.map(|ms| ms as i64),
+            voice_commands,
+            entry.pinned as i32,
+            entry.original_text,
+            metadata.translated as i32,
+        ],
+    )
+    .map_err(|e| AppError::Config(format!("Failed to insert history: {}", e)))?;
+
+    let id = conn.last_insert_rowid();
+    if let Some(original) = &entry.original_text {
+        replace_corrections(conn, id, original, &entry.text)?;
+    }
+    Ok(id)
+}
+
+/// Replace the database file at `path` (open on `conn` with `current_key`)
+/// with a copy encrypted with `key`, or decrypted when `key` is `None`, and
+/// reopen `conn` on it.
+///
+/// The original file is kept until the copy opens with the new key and is
+/// put back, with `conn` reopened on it, if anything fails. Only if even that
+/// fails is `conn` left on an empty in-memory database, where every query
+/// errors. Unencrypted migration backups and archived recordings are deleted
+/// after encrypting.
+fn rekey_file(
+    conn: &mut Connection,
+    path: &Path,
+    current_key: Option<&DatabaseKey>,
+    key: Option<&DatabaseKey>,
+) -> Result<(), AppError> {
+    let rekey_path = encryption::rekey_path(path);
+    encryption::export(conn, &rekey_path, key)?;
+
+    // Close the current connection before replacing the file
+    let placeholder = Connection::open_in_memory()
+        .map_err(|e| AppError::Config(format!("Failed to open database: {}", e)))?;
+    drop(std::mem::replace(conn, placeholder));
+
+    let previous_path = encryption::previous_path(path);
+    let replaced = std::fs::rename(path, &previous_path)
+        .and_then(|_| std::fs::rename(&rekey_path, path))
+        .map_err(|e| AppError::Config(format!("Failed to replace history database: {}", e)))
+        .and_then(|_| open_connection(path, key));
+    match replaced {
+        Ok(rekeyed) => {
+            *conn = rekeyed;
+            let _ = std::fs::remove_file(&previous_path);
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&rekey_path);
+            if previous_path.exists() {
+                let _ = std::fs::rename(&previous_path, path);
+            }
+            *conn = open_connection(path, current_key).map_err(|reopen| {
+                tracing::error!(
+                    "History database unavailable after failed rekey: {}",
+                    reopen
+                );
+                AppError::Config(format!(
+                    "{}; the history database could not be reopened: {}",
+                    e, reopen
+                ))
+            })?;
+            return Err(e);
+        }
+    }
+
+    if key.is_some() {
+        let removed = encryption::remove_plaintext_backups(path)?;
+        if removed > 0 {
+            tracing::info!("Removed {} unencrypted history backups", removed);
+        }
+        let removed = remove_all_archived_audio(conn)?;
+        if removed > 0 {
+            tracing::info!("Removed {} unencrypted archived recordings", removed);
+        }
+    }
+
+    tracing::info!(
+        "History database {}",
+        if key.is_some() {
+            "encrypted"
+        } else {
+            "decrypted"
+        }
+    );
+    Ok(())
+}
+
+/// Open a connection, unlocking it with `key` when given
+fn open_connection(path: &Path, key: Option<&DatabaseKey>) -> Result<Connection, AppError> {
+    let conn = Connection::open(path)
+        .map_err(|e| AppError::Config(format!("Failed to open database: {}", e)))?;
+    if let Some(key) = key {
+        encryption::apply_key(&conn, key)?;
+    }
+    Ok(conn)
+}
+
+/// Get the database file path
+fn get_database_path() -> Result<PathBuf, AppError> {
+    let proj_dirs = ProjectDirs::from("com", "ezflow", "EZ Flow")
+        .ok_or_else(|| AppError::Config("Failed to get project directories".into()))?;
+
+    let data_dir = proj_dirs.data_dir();
+    Ok(data_dir.join("history.db"))
+}
+
+/// Map a history row (in the standard column order) to an entry
+fn row_to_entry(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    let voice_commands: Option<String> = row.get(14)?;
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        timestamp: row.get(2)?,
+        duration_ms: row.get::<_, i64>(3)? as u64,
+        model_id: row.get(4)?,
+        language: row.get(5)?,
+        gpu_used: row.get::<_, i32>(6).unwrap_or(0) != 0,
+        audio_path: row.get(7)?,
+        pinned: row.get::<_, i32>(15).unwrap_or(0) != 0,
+        original_text: row.get(16)?,
+        metadata: HistoryMetadata {
+            source: enum_from_sql(row.get(8)?),
+            source_app: row.get(9)?,
+            source_window: row.get(10)?,
+            streaming_mode: enum_from_sql(row.get(11)?),
+            transcription_time_ms: row.get::<_, Option<i64>>(12)?.map(|ms| ms as u64),
+            injection_time_ms: row.get::<_, Option<i64>>(13)?.map(|ms| ms as u64),
+            voice_commands: voice_commands
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            translated: row.get::<_, i32>(17).unwrap_or(0) != 0,
+        },
+    })
+}
+
+/// Store a unit enum as its serde name
+fn enum_to_sql<T: Serialize>(value: &Option<T>) -> Option<String> {
+    value
+        .as_ref()
+        .and_then(|v| serde_json::to_value(v).ok())
+        .and_then(|v| v.as_str().map(str::to_string))
+}
+
+/// Read a unit enum stored by `enum_to_sql`, ignoring unknown values
+fn enum_from_sql<T: DeserializeOwned>(value: Option<String>) -> Option<T> {
+    value.and_then(|v| serde_json::from_value(serde_json::Value::String(v)).ok())
+}
+
+/// SQL conditions (on alias `h`) for a history filter, appending their parameters
+fn filter_conditions(filter: &HistoryFilter, values: &mut Vec<Value>) -> Vec<String> {
+    let mut conditions = Vec::new();
+
+    if let Some(source) = enum_to_sql(&filter.source) {
+        conditions.push("h.source = ?".to_string());
+        values.push(Value::Text(source));
+    }
+    if let Some(app) = &filter.source_app {
+        conditions.push("LOWER(h.source_app) = LOWER(?)".to_string());
+        values.push(Value::Text(app.clone()));
+    }
+    match filter.streaming {
+        Some(true) => conditions.push("h.streaming_mode IS NOT NULL".to_string()),
+        Some(false) => conditions.push("h.streaming_mode IS NULL".to_string()),
+        None => {}
+    }
+    if let Some(mode) = enum_to_sql(&filter.streaming_mode) {
+        conditions.push("h.streaming_mode = ?".to_string());
+        values.push(Value::Text(mode));
+    }
+    match filter.has_voice_commands {
+        Some(true) => conditions.push("h.voice_commands IS NOT NULL".to_string()),
+        Some(false) => conditions.push("h.voice_commands IS NULL".to_string()),
+        None => {}
+    }
+    if let Some(ms) = filter.min_transcription_time_ms {
+        conditions.push("h.transcription_ms >= ?".to_string());
+        values.push(Value::Integer(ms as i64));
+    }
+    if let Some(ms) = filter.min_injection_time_ms {
+        conditions.push("h.injection_ms >= ?".to_string());
+        values.push(Value::Integer(ms as i64));
+    }
+    if let Some(model_id) = &filter.model_id {
+        conditions.push("h.model_id = ?".to_string());
+        values.push(Value::Text(model_id.clone()));
+    }
+    if let Some(language) = &filter.language {
+        conditions.push("LOWER(h.language) = LOWER(?)".to_string());
+        values.push(Value::Text(language.clone()));
+    }
+    match filter.pinned {
+        Some(true) => conditions.push("h.pinned = 1".to_string()),
+        Some(false) => conditions.push("h.pinned = 0".to_string()),
+        None => {}
+    }
+    if let Some(from) = &filter.from {
+        conditions.push("julianday(h.timestamp) >= julianday(?)".to_string());
+        values.push(Value::Text(from.clone()));
+    }
+    if let Some(to) = &filter.to {
+        // A bare date includes that whole day
+        if is_bare_date(to) {
+            conditions.push("julianday(h.timestamp) < julianday(?, '+1 day')".to_string());
+        } else {
+            conditions.push("julianday(h.timestamp) <= julianday(?)".to_string());
+        }
+        values.push(Value::Text(to.clone()));
+    }
+
+    conditions
+}
+
+/// Whether a range bound is a date without a time (`YYYY-MM-DD`)
+fn is_bare_date(value: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
+
+/// SQL LIMIT parameter (negative means no limit in SQLite)
+fn limit_value(limit: Option<usize>) -> Value {
+    Value::Integer(limit.map(|l| l as i64).unwrap_or(-1))
+}
+
+/// Join conditions into a WHERE clause (empty when there are none)
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+/// Map a revision row to a revision
+fn row_to_revision(row: &Row) -> rusqlite::Result<HistoryRevision> {
+    let options: Option<String> = row.get(6)?;
+    Ok(HistoryRevision {
+        id: row.get(0)?,
+        history_id: row.get(1)?,
+        text: row.get(2)?,
+        model_id: row.get(3)?,
+        language: row.get(4)?,
+        gpu_used: row.get::<_, i32>(5).unwrap_or(0) != 0,
+        options: options
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        created_at: row.get(7)?,
+    })
+}
+
+/// Delete the entries (with revisions and archived audio) selected by a
+/// query returning `id, audio_path`
+fn delete_selected(
+    conn: &Connection,
+    select: &str,
+    params: impl rusqlite::Params,
+) -> Result<usize, AppError> {
+    let mut stmt = conn
+        .prepare(select)
+        .map_err(|e| AppError::Config(format!("Failed to prepare prune query: {}", e)))?;
+
+    let entries: Vec<(i64, Option<String>)> = stmt
+        .query_map(params, |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| AppError::Config(format!("Failed to query prune entries: {}", e)))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    for (id, audio_path) in &entries {
+        conn.execute(
+            "DELETE FROM history_revisions WHERE history_id = ?1",
+            params![id],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM history_corrections WHERE history_id = ?1",
+            params![id],
+        )
+        .ok();
+        conn.execute("DELETE FROM history WHERE id = ?1", params![id])
+            .ok();
+        if let Some(path) = audio_path {
+            remove_archived_audio(path);
+        }
+    }
+
+    Ok(entries.len())
+}
+
+fn pragma_value(conn: &Connection, pragma: &str) -> Result<i64, AppError> {
+    conn.pragma_query_value(None, pragma, |row| row.get(0))
+        .map_err(|e| AppError::Config(format!("Failed to read {}: {}", pragma, e)))
+}
+
+/// Bytes used by data, excluding free pages
+fn used_bytes(conn: &Connection) -> Result<u64, AppError> {
+    let pages = pragma_value(conn, "page_count")? - pragma_value(conn, "freelist_count")?;
+    Ok((pages * pragma_value(conn, "page_size")?) as u64)
+}
+
+/// Unlink and delete every archived recording, returning how many there were
+fn remove_all_archived_audio(conn: &Connection) -> Result<usize, AppError> {
+    let paths = archived_audio_paths(conn)?;
+    conn.execute(
+        "UPDATE history SET audio_path = NULL WHERE audio_path IS NOT NULL",
+        [],
+    )
+    .map_err(|e| AppError::Config(format!("Failed to unlink archived audio: {}", e)))?;
+    for path in &paths {
+        remove_archived_audio(path);
+    }
+    Ok(paths.len())
+}
+
+/// Paths of all archived recordings
+fn archived_audio_paths(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT audio_path FROM history WHERE audio_path IS NOT NULL")
+        .map_err(|e| AppError::Config(format!("Failed to prepare audio query: {}", e)))?;
+
+    let paths = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| AppError::Config(format!("Failed to query archived audio: {}", e)))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(paths)
+}
+
+/// Columns appended to `ENTRY_COLUMNS` by full-text searches: BM25 rank,
+/// the highlighted text and a snippet, with matches between `MATCH_START`
+/// and `MATCH_END`
+const FTS_COLUMNS: &str = "bm25(history_fts), \
+     highlight(history_fts, 0, char(2), char(3)), \
+     snippet(history_fts, 0, char(2), char(3), '…', 24)";
+
+/// A search translated to SQL over `history h`
+struct SearchPlan {
+    /// Tables to select from
+    from: &'static str,
+    conditions: Vec<String>,
+    values: Vec<Value>,
+    /// Whether `history_fts` is matched, enabling ranking and highlighting
+    full_text: bool,
+}
+
+impl SearchPlan {
+    /// Full-text search for the query (or just the filter, if it is empty)
+    fn full_text(search: &SearchQuery, filter: &HistoryFilter) -> Self {
+        let mut values = Vec::new();
+        let mut conditions = Vec::new();
+        let mut from = "history h";
+        let mut full_text = false;
+
+        if let Some(fts) = search.fts_match() {
+            from = "history h JOIN history_fts ON h.id = history_fts.rowid";
+            conditions.push("history_fts MATCH ?".to_string());
+            values.push(Value::Text(fts));
+            full_text = true;
+        } else if let Some(excluded) = search.fts_excluded() {
+            conditions.push(
+                "h.id NOT IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)".to_string(),
+            );
+            values.push(Value::Text(excluded));
+        }
+        conditions.extend(filter_conditions(filter, &mut values));
+
+        Self {
+            from,
+            conditions,
+            values,
+            full_text,
+        }
+    }
+
+    /// Case-insensitive substring search, for text the index cannot match
+    fn substring(search: &SearchQuery, filter: &HistoryFilter) -> Self {
+        let mut values = Vec::new();
+        let mut conditions = Vec::new();
+
+        for (terms, condition) in [
+            (&search.include, "LOWER(h.text) LIKE ?"),
+            (&search.exclude, "LOWER(h.text) NOT LIKE ?"),
+        ] {
+            for term in terms {
+                conditions.push(condition.to_string());
+                values.push(Value::Text(format!("%{}%", term.text.to_lowercase())));
+            }
+        }
+        conditions.extend(filter_conditions(filter, &mut values));
+
+        Self {
+            from: "history h",
+            conditions,
+            values,
+            full_text: false,
+        }
+    }
+
+    fn count(&self, conn: &Connection) -> Result<usize, AppError> {
+        let sql = format!(
+            "SELECT COUNT(*) FROM {}{}",
+            self.from,
+            where_clause(&self.conditions)
+        );
+        let count: i64 = conn
+            .query_row(&sql, params_from_iter(self.values.iter()), |row| row.get(0))
+            .map_err(|e| AppError::Config(format!("Failed to count search results: {}", e)))?;
+        Ok(count as usize)
+    }
+
+    fn results(
+        &self,
+        conn: &Connection,
+        search: &SearchQuery,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<HistorySearchResult>, AppError> {
+        let (columns, order) = if self.full_text {
+            (
+                format!("{}, {}", ENTRY_COLUMNS, FTS_COLUMNS),
+                "bm25(history_fts), h.timestamp DESC",
+            )
+        } else {
+            (ENTRY_COLUMNS.to_string(), "h.timestamp DESC")
+        };
+        let sql = format!(
+            "SELECT {} FROM {}{} ORDER BY {} LIMIT ? OFFSET ?",
+            columns,
+            self.from,
+            where_clause(&self.conditions),
+            order
+        );
+        let mut values = self.values.clone();
+        values.push(limit_value(limit));
+        values.push(Value::Integer(offset as i64));
+
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AppError::Config(format!("Failed to prepare search query: {}", e)))?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                let entry = row_to_entry(row)?;
+                if !self.full_text {
+                    return Ok(substring_result(entry, &search.include));
+                }
+
+                let rank: f64 = row.get(18)?;
+                let highlighted: String = row.get(19)?;
+                let snippet: String = row.get(20)?;
+                let (_, highlights) = take_highlights(&highlighted);
+                let (snippet, snippet_highlights) = take_highlights(&snippet);
+                Ok(HistorySearchResult {
+                    highlights: utf16_ranges(&entry.text, &highlights),
+                    snippet_highlights: utf16_ranges(&snippet, &snippet_highlights),
+                    snippet,
+                    // BM25 is negative in SQLite, lower meaning more relevant
+                    score: Some(-rank),
+                    entry,
+                })
+            })
+            .map_err(|e| AppError::Config(format!("Failed to execute search: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(rows)
+    }
+}
+
+/// Build a result for an entry found without the full-text index
+fn substring_result(entry: HistoryEntry, terms: &[SearchTerm]) -> HistorySearchResult {
+    let highlights = find_highlights(&entry.text, terms);
+    let (snippet, snippet_highlights) = excerpt(&entry.text, &highlights);
+    HistorySearchResult {
+        highlights: utf16_ranges(&entry.text, &highlights),
+        snippet_highlights: utf16_ranges(&snippet, &snippet_highlights),
+        snippet,
+        score: None,
+        entry,
+    }
+}
+
+/// Database state wrapper for Tauri
+pub struct DatabaseState {
+    db: OnceLock<Database>,
+    error: std::sync::Mutex<Option<String>>,
+    /// Waiting for the passphrase of an encrypted database
+    locked: AtomicBool,
+    /// Skip saving new history for the rest of the session
+    incognito: AtomicBool,
+}
+
+impl DatabaseState {
+    /// Create a new database state, opening the database as configured in the
+    /// settings. A passphrase-encrypted database stays locked until
+    /// [`unlock`](Self::unlock) is called.
+    pub fn new() -> Self {
+        let state = Self {
+            db: OnceLock::new(),
+            error: std::sync::Mutex::new(None),
+            locked: AtomicBool::new(false),
+            incognito: AtomicBool::new(false),
+        };
+
+        match open_configured(&load_settings().history_encryption) {
+            Ok(Some(db)) => {
+                let _ = state.db.set(db);
+            }
+            Ok(None) => {
+                tracing::info!("History database is locked until a passphrase is entered");
+                state.locked.store(true, Ordering::SeqCst);
+                state.set_error(
+                    "History is encrypted; enter your passphrase to unlock it".to_string(),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Failed to initialize database: {}", e);
+                state.set_error(e.to_string());
+            }
+        }
+        state
+    }
+
+    /// Get a reference to the database (if available)
+    pub fn get(&self) -> Option<&Database> {
+        self.db.get()
+    }
+
+    /// Get the database, or an error explaining why it could not be opened
+    pub fn require(&self) -> Result<&Database, String> {
+        self.db
+            .get()
+            .ok_or_else(|| match self.error.lock().ok().and_then(|e| e.clone()) {
+                Some(e) => format!("Database not available: {}", e),
+                None => "Database not available".to_string(),
+            })
+    }
+
+    /// The database to save new history to, or `None` when it is
+    /// unavailable or incognito mode is on
+    pub fn history_db(&self) -> Option<&Database> {
+        if self.is_incognito() {
+            tracing::debug!("Incognito mode is on; not saving to history");
+            return None;
+        }
+        self.get()
+    }
+
+    /// Whether the database is waiting for its passphrase
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    /// Open a passphrase-encrypted database, encrypting it first if it is
+    /// still unencrypted
+    pub fn unlock(&self, passphrase: &str) -> Result<&Database, AppError> {
+        if let Some(db) = self.db.get() {
+            return Ok(db);
+        }
+
+        let key = DatabaseKey::Passphrase(passphrase.to_string());
+        let db = Database::open_encrypted(&get_database_path()?, &key)?;
+        if let Ok(mut error) = self.error.lock() {
+            *error = None;
+        }
+        self.locked.store(false, Ordering::SeqCst);
+        Ok(self.db.get_or_init(|| db))
+    }
+
+    /// Whether incognito mode is on
+    pub fn is_incognito(&self) -> bool {
+        self.incognito.load(Ordering::SeqCst)
+    }
+
+    /// Turn incognito mode on or off for the rest of the session
+    pub fn set_incognito(&self, enabled: bool) {
+        self.incognito.store(enabled, Ordering::SeqCst);
+        tracing::info!(
+            "Incognito mode {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
+    fn set_error(&self, message: String) {
+        if let Ok(mut error) = self.error.lock() {
+            *error = Some(message);
+        }
+    }
+}
+
+/// Open the database as configured, or `None` when it needs a passphrase
+fn open_configured(config: &HistoryEncryptionConfig) -> Result<Option<Database>, AppError> {
+    open_at(&get_database_path()?, config, encryption::load_keyring_key)
+}
+
+/// Open the database at `path`. Whether it is encrypted is read from the
+/// file, since the settings may not have been saved after the last change;
+/// they decide how a new database is created and where the key is looked
+/// for.
+fn open_at(
+    path: &Path,
+    config: &HistoryEncryptionConfig,
+    keyring_key: impl FnOnce() -> Result<Option<DatabaseKey>, AppError>,
+) -> Result<Option<Database>, AppError> {
+    let encrypted = encryption::is_encrypted(path)?;
+    let fresh = std::fs::metadata(path).map_or(true, |m| m.len() == 0);
+    if !fresh && encrypted != config.enabled {
+        tracing::warn!(
+            "History database is {}encrypted, which the settings don't reflect",
+            if encrypted { "" } else { "not " }
+        );
+    }
+
+    let keyring = config.key_source == KeySource::Keyring;
+    if !encrypted {
+        if !(fresh && config.enabled) {
+            return Database::open(path).map(Some);
+        }
+        // A new database encrypted as configured
+        if !keyring {
+            return Ok(None);
+        }
+        let key = match keyring_key()? {
+            Some(key) => key,
+            None => {
+                let key = DatabaseKey::generate()?;
+                encryption::store_keyring_key(&key)?;
+                key
+            }
+        };
+        return Database::open_encrypted(path, &key).map(Some);
+    }
+
+    let keyring = keyring && config.enabled;
+    let stored = match keyring_key() {
+        Ok(key) => key,
+        Err(e) if !keyring => {
+            tracing::debug!("{}", e);
+            None
+        }
+        Err(e) => return Err(e),
+    };
+    match stored {
+        Some(key) => match Database::open_encrypted(path, &key) {
+            Ok(db) => Ok(Some(db)),
+            // A key left behind by a switch to a passphrase
+            Err(e) if !keyring => {
+                tracing::debug!("History key in the keyring doesn't fit: {}", e);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        },
+        None if keyring => Err(AppError::Config(
+            "The history key is missing from the system keyring".into(),
+        )),
+        None => Ok(None),
+    }
+}
+
+impl Default for DatabaseState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::StreamingMode;
+    use crate::models::HistorySource;
+    use crate::services::voice_commands::CommandAction;
+    use tempfile::TempDir;
+
+    fn create_test_db() -> (Database, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let conn = Connection::open(&path).unwrap();
+        migrations::migrate(&conn, None).unwrap();
+        let db = Database {
+            conn: Arc::new(Mutex::new(conn)),
+            path,
+            key: std::sync::Mutex::new(None),
+        };
+        (db, dir) // Return dir to keep it alive
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_get_history() {
+        let (db, _dir) = create_test_db();
+
+        let entry = HistoryEntry {
+            id: 0,
+            text: "Hello world".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: Some("en".to_string()),
+            gpu_used: true,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        };
+
+        let id = db.insert_history(&entry).await.unwrap();
+        assert!(id > 0);
+
+        let entries = db.get_history(10, 0).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text, "Hello world");
+        assert!(entries[0].gpu_used);
+    }
+
+    #[tokio::test]
+    async fn test_delete_entry() {
+        let (db, _dir) = create_test_db();
+
+        let entry = HistoryEntry {
+            id: 0,
+            text: "Test entry".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
             duration_ms: 500,
             model_id: "tiny".to_string(),
             language: None,
             gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        };
+
+        let id = db.insert_history(&entry).await.unwrap();
+        db.delete_entry(id).await.unwrap();
+
+        let entries = db.get_history(10, 0).await.unwrap();
+        assert!(entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_clear_all() {
+        let (db, _dir) = create_test_db();
+
+        for i in 0..5 {
+            let entry = HistoryEntry {
+                id: 0,
+                text: format!("Entry {}", i),
+                timestamp: format!("2024-01-0{}T00:00:00Z", i + 1),
+                duration_ms: 1000,
+                model_id: "base".to_string(),
+                language: None,
+                gpu_used: i % 2 == 0, // Alternate GPU usage
+                audio_path: None,
+                pinned: false,
+                original_text: None,
+                metadata: Default::default(),
+            };
+            db.insert_history(&entry).await.unwrap();
+        }
+
+        assert_eq!(db.count().await.unwrap(), 5);
+
+        db.clear_all().await.unwrap();
+
+        assert_eq!(db.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_prune_history() {
+        let (db, _dir) = create_test_db();
+
+        // Insert 10 entries
+        for i in 0..10 {
+            let entry = HistoryEntry {
+                id: 0,
+                text: format!("Entry {}", i),
+                timestamp: format!("2024-01-{:02}T00:00:00Z", i + 1),
+                duration_ms: 1000,
+                model_id: "base".to_string(),
+                language: None,
+                gpu_used: false,
+                audio_path: None,
+                pinned: false,
+                original_text: None,
+                metadata: Default::default(),
+            };
+            db.insert_history(&entry).await.unwrap();
+        }
+
+        // Prune to keep only 5
+        let pruned = db.prune_history(5).await.unwrap();
+        assert_eq!(pruned, 5);
+
+        // Should have 5 entries left
+        assert_eq!(db.count().await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_search_history() {
+        let (db, _dir) = create_test_db();
+
+        let entry1 = HistoryEntry {
+            id: 0,
+            text: "Hello world".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: true,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        };
+        db.insert_history(&entry1).await.unwrap();
+
+        let entry2 = HistoryEntry {
+            id: 0,
+            text: "Goodbye world".to_string(),
+            timestamp: "2024-01-02T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        };
+        db.insert_history(&entry2).await.unwrap();
+
+        let results = db.search_history("Hello").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "Hello world");
+        assert!(results[0].gpu_used);
+    }
+
+    #[tokio::test]
+    async fn test_prefix_search() {
+        let (db, _dir) = create_test_db();
+
+        let entry = HistoryEntry {
+            id: 0,
+            text: "hello world".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        };
+        db.insert_history(&entry).await.unwrap();
+
+        // Prefix search should find "hello world"
+        let results = db.search_history("hel").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "hello world");
+    }
+
+    #[tokio::test]
+    async fn test_multi_word_prefix_search() {
+        let (db, _dir) = create_test_db();
+
+        let entry1 = HistoryEntry {
+            id: 0,
+            text: "hello world".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        };
+        db.insert_history(&entry1).await.unwrap();
+
+        let entry2 = HistoryEntry {
+            id: 0,
+            text: "hello there".to_string(),
+            timestamp: "2024-01-02T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        };
+        db.insert_history(&entry2).await.unwrap();
+
+        // Multi-word prefix should narrow results
+        let results = db.search_history("hel wor").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "hello world");
+    }
+
+    #[tokio::test]
+    async fn test_like_fallback() {
+        let (db, _dir) = create_test_db();
+
+        let entry = HistoryEntry {
+            id: 0,
+            text: "meeting notes".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        };
+        db.insert_history(&entry).await.unwrap();
+
+        // Substring in middle - FTS won't match prefix, LIKE will fallback
+        let results = db.search_history("ting not").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "meeting notes");
+    }
+
+    #[tokio::test]
+    async fn test_case_insensitive_search() {
+        let (db, _dir) = create_test_db();
+
+        let entry = HistoryEntry {
+            id: 0,
+            text: "Hello World".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
         };
+        db.insert_history(&entry).await.unwrap();
 
-        let id = db.insert_history(&entry).await.unwrap();
-        db.delete_entry(id).await.unwrap();
+        // Lowercase search should find uppercase text
+        let results = db.search_history("hello").await.unwrap();
+        assert_eq!(results.len(), 1);
 
-        let entries = db.get_history(10, 0).await.unwrap();
-        assert!(entries.is_empty());
+        // Uppercase search should find mixed case text
+        let results = db.search_history("HELLO").await.unwrap();
+        assert_eq!(results.len(), 1);
     }
 
     #[tokio::test]
-    async fn test_clear_all() {
+    async fn test_empty_search_returns_all() {
         let (db, _dir) = create_test_db();
 
-        for i in 0..5 {
+        for i in 0..3 {
             let entry = HistoryEntry {
                 id: 0,
                 text: format!("Entry {}", i),
@@ -416,276 +1869,1078 @@ mod tests {
                 duration_ms: 1000,
                 model_id: "base".to_string(),
                 language: None,
-                gpu_used: i % 2 == 0, // Alternate GPU usage
+                gpu_used: false,
+                audio_path: None,
+                pinned: false,
+                original_text: None,
+                metadata: Default::default(),
+            };
+            db.insert_history(&entry).await.unwrap();
+        }
+
+        // Empty search should return all entries
+        let results = db.search_history("").await.unwrap();
+        assert_eq!(results.len(), 3);
+
+        // Whitespace-only search should return all entries
+        let results = db.search_history("   ").await.unwrap();
+        assert_eq!(results.len(), 3);
+    }
+
+    #[test]
+    fn test_prepare_fts_query() {
+        let prepare_fts_query = |query: &str| SearchQuery::parse(query).fts_match().unwrap();
+        assert_eq!(prepare_fts_query("hello"), "\"hello\"*");
+        assert_eq!(prepare_fts_query("hello world"), "\"hello\"* \"world\"*");
+        assert_eq!(
+            prepare_fts_query("  hello   world  "),
+            "\"hello\"* \"world\"*"
+        );
+        // Quotes are escaped
+        assert_eq!(prepare_fts_query("he\"llo"), "\"he\"\"llo\"*");
+    }
+
+    #[tokio::test]
+    async fn test_search_performance_1000_entries() {
+        let (db, _dir) = create_test_db();
+
+        // Insert 1000 entries
+        for i in 0..1000 {
+            let entry = HistoryEntry {
+                id: 0,
+                text: format!(
+                    "This is transcription number {} with some random words like hello world meeting notes {}",
+                    i,
+                    if i % 10 == 0 { "special marker" } else { "regular text" }
+                ),
+                timestamp: format!("2024-01-01T{:02}:{:02}:00Z", i / 60 % 24, i % 60),
+                duration_ms: 1000 + (i as u64 * 10),
+                model_id: "base".to_string(),
+                language: Some("en".to_string()),
+                gpu_used: i % 2 == 0,
+                audio_path: None,
+                pinned: false,
+                original_text: None,
+                metadata: Default::default(),
             };
             db.insert_history(&entry).await.unwrap();
         }
 
-        assert_eq!(db.count().await.unwrap(), 5);
+        // Verify we have 1000 entries
+        assert_eq!(db.count().await.unwrap(), 1000);
+
+        // Measure search performance
+        let start = std::time::Instant::now();
+
+        // Prefix search
+        let results = db.search_history("hel").await.unwrap();
+        let prefix_duration = start.elapsed();
+        assert!(!results.is_empty(), "Prefix search should find results");
+
+        // Multi-word search
+        let start = std::time::Instant::now();
+        let results = db.search_history("hello wor").await.unwrap();
+        let multi_word_duration = start.elapsed();
+        assert!(!results.is_empty(), "Multi-word search should find results");
+
+        // LIKE fallback search (substring in middle)
+        let start = std::time::Instant::now();
+        let results = db.search_history("special mark").await.unwrap();
+        let like_duration = start.elapsed();
+        assert_eq!(
+            results.len(),
+            100,
+            "Should find 100 entries with 'special marker'"
+        );
+
+        // Assert all searches complete in under 50ms
+        assert!(
+            prefix_duration.as_millis() < 50,
+            "Prefix search took {}ms, expected <50ms",
+            prefix_duration.as_millis()
+        );
+        assert!(
+            multi_word_duration.as_millis() < 50,
+            "Multi-word search took {}ms, expected <50ms",
+            multi_word_duration.as_millis()
+        );
+        assert!(
+            like_duration.as_millis() < 50,
+            "LIKE fallback search took {}ms, expected <50ms",
+            like_duration.as_millis()
+        );
+    }
+
+    fn audio_entry(dir: &TempDir, name: &str, timestamp: String) -> HistoryEntry {
+        let path = dir.path().join(format!("{}.flac", name));
+        std::fs::write(&path, b"fLaC").unwrap();
+        HistoryEntry {
+            id: 0,
+            text: name.to_string(),
+            timestamp,
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: Some(path.to_string_lossy().into_owned()),
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audio_path_roundtrip() {
+        let (db, dir) = create_test_db();
+
+        let entry = audio_entry(&dir, "clip", "2024-01-01T00:00:00Z".to_string());
+        let id = db.insert_history(&entry).await.unwrap();
+
+        let stored = db.get_entry(id).await.unwrap().unwrap();
+        assert_eq!(stored.audio_path, entry.audio_path);
+        assert!(db.get_entry(id + 1).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_delete_entry_removes_audio_file() {
+        let (db, dir) = create_test_db();
+
+        let entry = audio_entry(&dir, "clip", "2024-01-01T00:00:00Z".to_string());
+        let path = entry.audio_path.clone().unwrap();
+        let id = db.insert_history(&entry).await.unwrap();
+
+        db.delete_entry(id).await.unwrap();
+        assert!(!std::path::Path::new(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_remove_entry_audio_keeps_text() {
+        let (db, dir) = create_test_db();
+
+        let entry = audio_entry(&dir, "clip", "2024-01-01T00:00:00Z".to_string());
+        let path = entry.audio_path.clone().unwrap();
+        let id = db.insert_history(&entry).await.unwrap();
+
+        db.remove_entry_audio(id).await.unwrap();
+
+        let stored = db.get_entry(id).await.unwrap().unwrap();
+        assert_eq!(stored.text, "clip");
+        assert!(stored.audio_path.is_none());
+        assert!(!std::path::Path::new(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_prune_audio_archive_by_count() {
+        let (db, dir) = create_test_db();
+
+        let mut paths = Vec::new();
+        for i in 0..5 {
+            let entry = audio_entry(
+                &dir,
+                &format!("clip{}", i),
+                format!("2099-01-0{}T00:00:00Z", i + 1),
+            );
+            paths.push(entry.audio_path.clone().unwrap());
+            db.insert_history(&entry).await.unwrap();
+        }
+
+        let removed = db.prune_audio_archive(2, 0).await.unwrap();
+        assert_eq!(removed, 3);
+
+        // The two newest recordings are kept, all text entries remain
+        assert!(std::path::Path::new(&paths[4]).exists());
+        assert!(std::path::Path::new(&paths[3]).exists());
+        assert!(!std::path::Path::new(&paths[0]).exists());
+        assert_eq!(db.count().await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_prune_audio_archive_by_age() {
+        let (db, dir) = create_test_db();
+
+        let old = audio_entry(&dir, "old", (Utc::now() - Duration::days(40)).to_rfc3339());
+        let recent = audio_entry(&dir, "recent", Utc::now().to_rfc3339());
+        let old_id = db.insert_history(&old).await.unwrap();
+        let recent_id = db.insert_history(&recent).await.unwrap();
+
+        let removed = db.prune_audio_archive(0, 30).await.unwrap();
+        assert_eq!(removed, 1);
+        assert!(db
+            .get_entry(old_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .audio_path
+            .is_none());
+        assert!(db
+            .get_entry(recent_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .audio_path
+            .is_some());
+    }
+
+    fn revision(history_id: i64, text: &str, model_id: &str) -> HistoryRevision {
+        HistoryRevision {
+            id: 0,
+            history_id,
+            text: text.to_string(),
+            model_id: model_id.to_string(),
+            language: None,
+            gpu_used: false,
+            options: Default::default(),
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_get_revisions() {
+        let (db, dir) = create_test_db();
+
+        let entry = audio_entry(&dir, "original text", "2024-01-01T00:00:00Z".to_string());
+        let id = db.insert_history(&entry).await.unwrap();
+
+        db.insert_revision(&revision(id, "better text", "large-v3"))
+            .await
+            .unwrap();
+
+        let revisions = db.get_revisions(id).await.unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].text, "better text");
+        assert_eq!(revisions[0].model_id, "large-v3");
+        assert!(revisions[0].options.use_prompt);
+
+        // Original text is untouched until a revision is applied
+        let stored = db.get_entry(id).await.unwrap().unwrap();
+        assert_eq!(stored.text, "original text");
+    }
+
+    #[tokio::test]
+    async fn test_apply_revision_swaps_text() {
+        let (db, dir) = create_test_db();
+
+        let entry = audio_entry(&dir, "helo wrld", "2024-01-01T00:00:00Z".to_string());
+        let id = db.insert_history(&entry).await.unwrap();
+        let revision_id = db
+            .insert_revision(&revision(id, "hello world", "large-v3"))
+            .await
+            .unwrap();
+
+        let updated = db.apply_revision(id, revision_id).await.unwrap();
+        assert_eq!(updated.text, "hello world");
+        assert_eq!(updated.model_id, "large-v3");
+        assert_eq!(updated.audio_path, entry.audio_path);
+
+        // Previous text is kept as a revision
+        let revisions = db.get_revisions(id).await.unwrap();
+        assert_eq!(revisions[0].text, "helo wrld");
+        assert_eq!(revisions[0].model_id, "base");
+
+        // Search index follows the new text
+        let results = db.search_history("hello").await.unwrap();
+        assert_eq!(results.len(), 1);
+        let results = db.search_history("wrld").await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_revision_of_other_entry_fails() {
+        let (db, dir) = create_test_db();
+
+        let first = db
+            .insert_history(&audio_entry(
+                &dir,
+                "first",
+                "2024-01-01T00:00:00Z".to_string(),
+            ))
+            .await
+            .unwrap();
+        let second = db
+            .insert_history(&audio_entry(
+                &dir,
+                "second",
+                "2024-01-02T00:00:00Z".to_string(),
+            ))
+            .await
+            .unwrap();
+        let revision_id = db
+            .insert_revision(&revision(first, "first again", "small"))
+            .await
+            .unwrap();
+
+        assert!(db.apply_revision(second, revision_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_entry_removes_revisions() {
+        let (db, dir) = create_test_db();
+
+        let id = db
+            .insert_history(&audio_entry(
+                &dir,
+                "text",
+                "2024-01-01T00:00:00Z".to_string(),
+            ))
+            .await
+            .unwrap();
+        db.insert_revision(&revision(id, "other", "small"))
+            .await
+            .unwrap();
+
+        db.delete_entry(id).await.unwrap();
+        assert!(db.get_revisions(id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_recent_with_audio() {
+        let (db, dir) = create_test_db();
+
+        let mut without_audio = audio_entry(&dir, "no audio", "2024-01-03T00:00:00Z".to_string());
+        without_audio.audio_path = None;
+        db.insert_history(&without_audio).await.unwrap();
+        for i in 0..3 {
+            let entry = audio_entry(
+                &dir,
+                &format!("clip{}", i),
+                format!("2024-01-0{}T00:00:00Z", i + 1),
+            );
+            db.insert_history(&entry).await.unwrap();
+        }
+
+        let entries = db.get_recent_with_audio(2).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].text, "clip2");
+        assert!(entries.iter().all(|e| e.audio_path.is_some()));
+    }
+
+    fn metadata_entry(text: &str, timestamp: &str, metadata: HistoryMetadata) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            text: text.to_string(),
+            timestamp: timestamp.to_string(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata,
+        }
+    }
+
+    async fn insert_metadata_entries(db: &Database) {
+        let entries = [
+            metadata_entry(
+                "hotkey into firefox",
+                "2024-01-01T00:00:00Z",
+                HistoryMetadata {
+                    source: Some(HistorySource::Hotkey),
+                    source_app: Some("firefox".to_string()),
+                    source_window: Some("Inbox - Mozilla Firefox".to_string()),
+                    transcription_time_ms: Some(300),
+                    injection_time_ms: Some(40),
+                    voice_commands: vec![CommandAction::InsertText(".".to_string())],
+                    ..Default::default()
+                },
+            ),
+            metadata_entry(
+                "streaming into code",
+                "2024-01-02T00:00:00Z",
+                HistoryMetadata {
+                    source: Some(HistorySource::Hotkey),
+                    source_app: Some("code".to_string()),
+                    streaming_mode: Some(StreamingMode::Speed),
+                    transcription_time_ms: Some(1200),
+                    ..Default::default()
+                },
+            ),
+            metadata_entry(
+                "from the tray",
+                "2024-01-03T00:00:00Z",
+                HistoryMetadata {
+                    source: Some(HistorySource::Tray),
+                    transcription_time_ms: Some(800),
+                    ..Default::default()
+                },
+            ),
+            metadata_entry(
+                "legacy entry",
+                "2024-01-04T00:00:00Z",
+                HistoryMetadata::default(),
+            ),
+        ];
+        for entry in &entries {
+            db.insert_history(entry).await.unwrap();
+        }
+    }
+
+    fn texts(entries: &[HistoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.text.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_metadata_roundtrip() {
+        let (db, _dir) = create_test_db();
+        let metadata = HistoryMetadata {
+            source: Some(HistorySource::Hotkey),
+            source_app: Some("firefox".to_string()),
+            source_window: Some("Inbox".to_string()),
+            streaming_mode: Some(StreamingMode::Accuracy),
+            transcription_time_ms: Some(512),
+            injection_time_ms: Some(64),
+            voice_commands: vec![CommandAction::Undo, CommandAction::DeleteCharacters(3)],
+            translated: true,
+        };
+        let id = db
+            .insert_history(&metadata_entry(
+                "text",
+                "2024-01-01T00:00:00Z",
+                metadata.clone(),
+            ))
+            .await
+            .unwrap();
+
+        let entry = db.get_entry(id).await.unwrap().unwrap();
+        assert_eq!(entry.metadata, metadata);
+
+        let legacy = db
+            .insert_history(&metadata_entry(
+                "old",
+                "2024-01-02T00:00:00Z",
+                Default::default(),
+            ))
+            .await
+            .unwrap();
+        let entry = db.get_entry(legacy).await.unwrap().unwrap();
+        assert_eq!(entry.metadata, HistoryMetadata::default());
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_source_and_app() {
+        let (db, _dir) = create_test_db();
+        insert_metadata_entries(&db).await;
+
+        let filter = HistoryFilter {
+            source: Some(HistorySource::Hotkey),
+            ..Default::default()
+        };
+        let entries = db.get_history_filtered(10, 0, &filter).await.unwrap();
+        assert_eq!(
+            texts(&entries),
+            vec!["streaming into code", "hotkey into firefox"]
+        );
+
+        let filter = HistoryFilter {
+            source_app: Some("FireFox".to_string()),
+            ..Default::default()
+        };
+        let entries = db.get_history_filtered(10, 0, &filter).await.unwrap();
+        assert_eq!(texts(&entries), vec!["hotkey into firefox"]);
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_streaming_and_commands() {
+        let (db, _dir) = create_test_db();
+        insert_metadata_entries(&db).await;
+
+        let streaming = HistoryFilter {
+            streaming: Some(true),
+            ..Default::default()
+        };
+        let entries = db.get_history_filtered(10, 0, &streaming).await.unwrap();
+        assert_eq!(texts(&entries), vec!["streaming into code"]);
+
+        let speed = HistoryFilter {
+            streaming_mode: Some(StreamingMode::Speed),
+            ..Default::default()
+        };
+        assert_eq!(
+            db.get_history_filtered(10, 0, &speed).await.unwrap().len(),
+            1
+        );
+
+        let with_commands = HistoryFilter {
+            has_voice_commands: Some(true),
+            ..Default::default()
+        };
+        let entries = db
+            .get_history_filtered(10, 0, &with_commands)
+            .await
+            .unwrap();
+        assert_eq!(texts(&entries), vec!["hotkey into firefox"]);
+
+        let batch_without_commands = HistoryFilter {
+            streaming: Some(false),
+            has_voice_commands: Some(false),
+            ..Default::default()
+        };
+        let entries = db
+            .get_history_filtered(10, 0, &batch_without_commands)
+            .await
+            .unwrap();
+        assert_eq!(texts(&entries), vec!["legacy entry", "from the tray"]);
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_latency() {
+        let (db, _dir) = create_test_db();
+        insert_metadata_entries(&db).await;
+
+        let slow = HistoryFilter {
+            min_transcription_time_ms: Some(800),
+            ..Default::default()
+        };
+        let entries = db.get_history_filtered(10, 0, &slow).await.unwrap();
+        assert_eq!(
+            texts(&entries),
+            vec!["from the tray", "streaming into code"]
+        );
+
+        let slow_injection = HistoryFilter {
+            min_injection_time_ms: Some(10),
+            ..Default::default()
+        };
+        let entries = db
+            .get_history_filtered(10, 0, &slow_injection)
+            .await
+            .unwrap();
+        assert_eq!(texts(&entries), vec!["hotkey into firefox"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_with_filter() {
+        let (db, _dir) = create_test_db();
+        insert_metadata_entries(&db).await;
+
+        let filter = HistoryFilter {
+            source_app: Some("code".to_string()),
+            ..Default::default()
+        };
+        // FTS path
+        let entries = db.search_history_filtered("into", &filter).await.unwrap();
+        assert_eq!(texts(&entries), vec!["streaming into code"]);
+        // LIKE fallback path
+        let entries = db.search_history_filtered("ream", &filter).await.unwrap();
+        assert_eq!(texts(&entries), vec!["streaming into code"]);
+        // Empty query lists filtered entries
+        let entries = db.search_history_filtered("", &filter).await.unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_source_apps() {
+        let (db, _dir) = create_test_db();
+        insert_metadata_entries(&db).await;
+
+        let apps = db.get_source_apps().await.unwrap();
+        assert_eq!(apps, vec!["code".to_string(), "firefox".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_date_range() {
+        let (db, _dir) = create_test_db();
+        insert_metadata_entries(&db).await;
 
-        db.clear_all().await.unwrap();
+        let range = HistoryFilter {
+            from: Some("2024-01-02".to_string()),
+            to: Some("2024-01-03".to_string()),
+            ..Default::default()
+        };
+        let entries = db.get_history_filtered(10, 0, &range).await.unwrap();
+        assert_eq!(
+            texts(&entries),
+            vec!["from the tray", "streaming into code"]
+        );
 
-        assert_eq!(db.count().await.unwrap(), 0);
+        let before = HistoryFilter {
+            to: Some("2024-01-01T12:00:00+00:00".to_string()),
+            ..Default::default()
+        };
+        let entries = db.get_history_filtered(10, 0, &before).await.unwrap();
+        assert_eq!(texts(&entries), vec!["hotkey into firefox"]);
     }
 
     #[tokio::test]
-    async fn test_prune_history() {
+    async fn test_find_entries_is_unlimited_and_chronological() {
         let (db, _dir) = create_test_db();
-
-        // Insert 10 entries
-        for i in 0..10 {
-            let entry = HistoryEntry {
-                id: 0,
-                text: format!("Entry {}", i),
-                timestamp: format!("2024-01-{:02}T00:00:00Z", i + 1),
-                duration_ms: 1000,
-                model_id: "base".to_string(),
-                language: None,
-                gpu_used: false,
-            };
+        for i in 0..120 {
+            let entry = metadata_entry(
+                &format!("note number {}", i),
+                &format!("2024-01-01T00:{:02}:{:02}Z", i / 60, i % 60),
+                HistoryMetadata::default(),
+            );
             db.insert_history(&entry).await.unwrap();
         }
 
-        // Prune to keep only 5
-        let pruned = db.prune_history(5).await.unwrap();
-        assert_eq!(pruned, 5);
-
-        // Should have 5 entries left
-        assert_eq!(db.count().await.unwrap(), 5);
+        let all = db
+            .find_entries("", &HistoryFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 120);
+        assert_eq!(all[0].text, "note number 0");
+
+        let matches = db
+            .find_entries("note", &HistoryFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 120);
+        assert_eq!(matches[119].text, "note number 119");
     }
 
     #[tokio::test]
-    async fn test_search_history() {
+    async fn test_import_entry_skips_duplicates() {
         let (db, _dir) = create_test_db();
+        let entry = metadata_entry(
+            "imported",
+            "2024-01-01T00:00:00Z",
+            HistoryMetadata::default(),
+        );
+        let revisions = vec![revision(99, "other take", "small")];
 
-        let entry1 = HistoryEntry {
-            id: 0,
-            text: "Hello world".to_string(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(),
-            duration_ms: 1000,
-            model_id: "base".to_string(),
-            language: None,
-            gpu_used: true,
-        };
-        db.insert_history(&entry1).await.unwrap();
+        let id = db.import_entry(&entry, &revisions).await.unwrap().unwrap();
+        assert!(db.import_entry(&entry, &revisions).await.unwrap().is_none());
 
-        let entry2 = HistoryEntry {
-            id: 0,
-            text: "Goodbye world".to_string(),
-            timestamp: "2024-01-02T00:00:00Z".to_string(),
-            duration_ms: 1000,
-            model_id: "base".to_string(),
-            language: None,
-            gpu_used: false,
-        };
-        db.insert_history(&entry2).await.unwrap();
+        assert_eq!(db.count().await.unwrap(), 1);
+        let stored = db.get_revisions(id).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].history_id, id);
+        assert_eq!(db.search_history("imported").await.unwrap().len(), 1);
+    }
 
-        let results = db.search_history("Hello").await.unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].text, "Hello world");
-        assert!(results[0].gpu_used);
+    async fn insert_texts(db: &Database, texts: &[&str]) {
+        for (i, text) in texts.iter().enumerate() {
+            let timestamp = format!("2024-02-{:02}T00:00:00Z", i + 1);
+            db.insert_history(&metadata_entry(
+                text,
+                &timestamp,
+                HistoryMetadata::default(),
+            ))
+            .await
+            .unwrap();
+        }
+    }
+
+    fn result_texts(page: &HistorySearchPage) -> Vec<&str> {
+        page.results.iter().map(|r| r.entry.text.as_str()).collect()
     }
 
     #[tokio::test]
-    async fn test_prefix_search() {
+    async fn test_search_ranks_by_relevance() {
         let (db, _dir) = create_test_db();
+        insert_texts(
+            &db,
+            &[
+                "budget budget budget review",
+                "a long note that mentions the budget once among many other words",
+                "unrelated",
+            ],
+        )
+        .await;
 
-        let entry = HistoryEntry {
-            id: 0,
-            text: "hello world".to_string(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(),
-            duration_ms: 1000,
-            model_id: "base".to_string(),
-            language: None,
-            gpu_used: false,
-        };
-        db.insert_history(&entry).await.unwrap();
+        let page = db
+            .search("budget", &HistoryFilter::default(), 10, 0)
+            .await
+            .unwrap();
 
-        // Prefix search should find "hello world"
-        let results = db.search_history("hel").await.unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].text, "hello world");
+        assert_eq!(page.total, 2);
+        assert_eq!(page.results[0].entry.text, "budget budget budget review");
+        let scores: Vec<f64> = page.results.iter().map(|r| r.score.unwrap()).collect();
+        assert!(scores[0] > scores[1]);
     }
 
     #[tokio::test]
-    async fn test_multi_word_prefix_search() {
+    async fn test_search_highlights_and_snippets() {
         let (db, _dir) = create_test_db();
+        insert_texts(&db, &["Café meeting notes"]).await;
+
+        let page = db
+            .search("meet", &HistoryFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        let result = &page.results[0];
+
+        // UTF-16 offsets: "Café " is five units
+        assert_eq!(result.highlights, vec![[5, 12]]);
+        assert_eq!(result.snippet, "Café meeting notes");
+        assert_eq!(result.snippet_highlights, vec![[5, 12]]);
+
+        // Substring fallback highlights too
+        let page = db
+            .search("eeting", &HistoryFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        assert!(page.results[0].score.is_none());
+        assert_eq!(page.results[0].highlights, vec![[6, 12]]);
+    }
 
-        let entry1 = HistoryEntry {
-            id: 0,
-            text: "hello world".to_string(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(),
-            duration_ms: 1000,
-            model_id: "base".to_string(),
-            language: None,
-            gpu_used: false,
-        };
-        db.insert_history(&entry1).await.unwrap();
-
-        let entry2 = HistoryEntry {
-            id: 0,
-            text: "hello there".to_string(),
-            timestamp: "2024-01-02T00:00:00Z".to_string(),
-            duration_ms: 1000,
-            model_id: "base".to_string(),
-            language: None,
-            gpu_used: false,
-        };
-        db.insert_history(&entry2).await.unwrap();
-
-        // Multi-word prefix should narrow results
-        let results = db.search_history("hel wor").await.unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].text, "hello world");
+    #[tokio::test]
+    async fn test_search_pagination() {
+        let (db, _dir) = create_test_db();
+        let texts: Vec<String> = (0..25).map(|i| format!("note {}", i)).collect();
+        insert_texts(&db, &texts.iter().map(String::as_str).collect::<Vec<_>>()).await;
+
+        let first = db
+            .search("note", &HistoryFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        let last = db
+            .search("note", &HistoryFilter::default(), 10, 20)
+            .await
+            .unwrap();
+        let beyond = db
+            .search("note", &HistoryFilter::default(), 10, 30)
+            .await
+            .unwrap();
+
+        assert_eq!(first.total, 25);
+        assert_eq!(first.results.len(), 10);
+        assert_eq!(last.results.len(), 5);
+        assert!(beyond.results.is_empty());
+        assert_eq!(beyond.total, 25);
     }
 
     #[tokio::test]
-    async fn test_like_fallback() {
+    async fn test_search_phrase_and_not_queries() {
         let (db, _dir) = create_test_db();
+        insert_texts(
+            &db,
+            &[
+                "send the report tomorrow",
+                "report send status",
+                "draft report",
+            ],
+        )
+        .await;
+        let filter = HistoryFilter::default();
 
-        let entry = HistoryEntry {
-            id: 0,
-            text: "meeting notes".to_string(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(),
-            duration_ms: 1000,
-            model_id: "base".to_string(),
-            language: None,
-            gpu_used: false,
-        };
-        db.insert_history(&entry).await.unwrap();
+        let page = db
+            .search("\"send the report\"", &filter, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(result_texts(&page), vec!["send the report tomorrow"]);
 
-        // Substring in middle - FTS won't match prefix, LIKE will fallback
-        let results = db.search_history("ting not").await.unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].text, "meeting notes");
+        let page = db.search("report -draft", &filter, 10, 0).await.unwrap();
+        assert_eq!(page.total, 2);
+        assert!(!result_texts(&page).contains(&"draft report"));
+
+        let page = db.search("report NOT send", &filter, 10, 0).await.unwrap();
+        assert_eq!(result_texts(&page), vec!["draft report"]);
+
+        // Only exclusions: everything else, newest first
+        let page = db.search("-draft", &filter, 10, 0).await.unwrap();
+        assert_eq!(
+            result_texts(&page),
+            vec!["report send status", "send the report tomorrow"]
+        );
     }
 
     #[tokio::test]
-    async fn test_case_insensitive_search() {
+    async fn test_filter_by_model_and_language() {
         let (db, _dir) = create_test_db();
-
-        let entry = HistoryEntry {
-            id: 0,
-            text: "Hello World".to_string(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(),
-            duration_ms: 1000,
-            model_id: "base".to_string(),
-            language: None,
-            gpu_used: false,
+        let mut small = metadata_entry("small english", "2024-01-01T00:00:00Z", Default::default());
+        small.model_id = "small".to_string();
+        small.language = Some("en".to_string());
+        let mut base = metadata_entry("base german", "2024-01-02T00:00:00Z", Default::default());
+        base.language = Some("de".to_string());
+        db.insert_history(&small).await.unwrap();
+        db.insert_history(&base).await.unwrap();
+
+        let by_model = HistoryFilter {
+            model_id: Some("small".to_string()),
+            ..Default::default()
         };
-        db.insert_history(&entry).await.unwrap();
-
-        // Lowercase search should find uppercase text
-        let results = db.search_history("hello").await.unwrap();
-        assert_eq!(results.len(), 1);
+        let entries = db.get_history_filtered(10, 0, &by_model).await.unwrap();
+        assert_eq!(texts(&entries), vec!["small english"]);
 
-        // Uppercase search should find mixed case text
-        let results = db.search_history("HELLO").await.unwrap();
-        assert_eq!(results.len(), 1);
+        let by_language = HistoryFilter {
+            language: Some("DE".to_string()),
+            ..Default::default()
+        };
+        let page = db.search("german", &by_language, 10, 0).await.unwrap();
+        assert_eq!(result_texts(&page), vec!["base german"]);
+        let page = db.search("english", &by_language, 10, 0).await.unwrap();
+        assert_eq!(page.total, 0);
     }
 
     #[tokio::test]
-    async fn test_empty_search_returns_all() {
+    async fn test_fts_index_follows_deletes_and_prune() {
         let (db, _dir) = create_test_db();
+        insert_texts(&db, &["alpha one", "alpha two", "alpha three"]).await;
 
-        for i in 0..3 {
-            let entry = HistoryEntry {
-                id: 0,
-                text: format!("Entry {}", i),
-                timestamp: format!("2024-01-0{}T00:00:00Z", i + 1),
-                duration_ms: 1000,
-                model_id: "base".to_string(),
-                language: None,
-                gpu_used: false,
-            };
-            db.insert_history(&entry).await.unwrap();
-        }
+        db.prune_history(2).await.unwrap();
+        assert_eq!(db.search_history("alpha").await.unwrap().len(), 2);
 
-        // Empty search should return all entries
-        let results = db.search_history("").await.unwrap();
-        assert_eq!(results.len(), 3);
+        let id = db.get_history(1, 0).await.unwrap()[0].id;
+        db.delete_entry(id).await.unwrap();
+        assert_eq!(db.search_history("alpha").await.unwrap().len(), 1);
 
-        // Whitespace-only search should return all entries
-        let results = db.search_history("   ").await.unwrap();
-        assert_eq!(results.len(), 3);
+        db.clear_all().await.unwrap();
+        let page = db
+            .search("alpha", &HistoryFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 0);
     }
 
-    #[test]
-    fn test_prepare_fts_query() {
-        assert_eq!(prepare_fts_query("hello"), "\"hello\"*");
-        assert_eq!(prepare_fts_query("hello world"), "\"hello\"* \"world\"*");
-        assert_eq!(
-            prepare_fts_query("  hello   world  "),
-            "\"hello\"* \"world\"*"
-        );
-        // Quotes are escaped
-        assert_eq!(prepare_fts_query("he\"llo"), "\"he\"\"llo\"*");
+    #[tokio::test]
+    async fn test_pinned_entries_survive_pruning() {
+        let (db, _dir) = create_test_db();
+        insert_texts(&db, &["oldest", "old", "new", "newest"]).await;
+        let oldest = db.get_history(10, 0).await.unwrap()[3].id;
+        db.set_pinned(oldest, true).await.unwrap();
+
+        assert_eq!(db.prune_history(1).await.unwrap(), 2);
+        let remaining = db.get_history(10, 0).await.unwrap();
+        assert_eq!(texts(&remaining), vec!["newest", "oldest"]);
+        assert!(remaining[1].pinned);
+
+        let pinned = HistoryFilter {
+            pinned: Some(true),
+            ..Default::default()
+        };
+        let entries = db.get_history_filtered(10, 0, &pinned).await.unwrap();
+        assert_eq!(texts(&entries), vec!["oldest"]);
+
+        assert!(db.set_pinned(9999, true).await.is_err());
     }
 
     #[tokio::test]
-    async fn test_search_performance_1000_entries() {
+    async fn test_prune_older_than() {
         let (db, _dir) = create_test_db();
+        let recent = Utc::now().to_rfc3339();
+        db.insert_history(&metadata_entry("recent", &recent, Default::default()))
+            .await
+            .unwrap();
+        let old = db
+            .insert_history(&metadata_entry(
+                "old pinned",
+                "2020-01-01T00:00:00Z",
+                Default::default(),
+            ))
+            .await
+            .unwrap();
+        db.set_pinned(old, true).await.unwrap();
+        db.insert_history(&metadata_entry(
+            "old",
+            "2020-01-02T00:00:00Z",
+            Default::default(),
+        ))
+        .await
+        .unwrap();
+
+        assert_eq!(db.prune_older_than(30).await.unwrap(), 1);
+        assert_eq!(db.count().await.unwrap(), 2);
+        assert!(db.search_history("old").await.unwrap()[0].pinned);
+    }
 
-        // Insert 1000 entries
-        for i in 0..1000 {
-            let entry = HistoryEntry {
-                id: 0,
-                text: format!(
-                    "This is transcription number {} with some random words like hello world meeting notes {}",
-                    i,
-                    if i % 10 == 0 { "special marker" } else { "regular text" }
-                ),
-                timestamp: format!("2024-01-01T{:02}:{:02}:00Z", i / 60 % 24, i % 60),
-                duration_ms: 1000 + (i as u64 * 10),
-                model_id: "base".to_string(),
-                language: Some("en".to_string()),
-                gpu_used: i % 2 == 0,
-            };
-            db.insert_history(&entry).await.unwrap();
-        }
+    #[tokio::test]
+    async fn test_set_encryption_keeps_history_searchable() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        let db = Database::open(&path).unwrap();
+        db.insert_history(&metadata_entry(
+            "confidential meeting notes",
+            "2024-01-01T00:00:00Z",
+            Default::default(),
+        ))
+        .await
+        .unwrap();
+
+        let key = DatabaseKey::generate().unwrap();
+        db.set_encryption(Some(&key)).await.unwrap();
+        assert!(db.is_encrypted());
+        assert!(encryption::is_encrypted(&path).unwrap());
+        assert_eq!(db.search_history("confid").await.unwrap().len(), 1);
+        drop(db);
+
+        assert!(Database::open(&path).is_err());
+        let db = Database::open_with_key(&path, Some(&key)).unwrap();
+        assert_eq!(db.count().await.unwrap(), 1);
+
+        db.set_encryption(None).await.unwrap();
+        assert!(!db.is_encrypted());
+        drop(db);
+        let db = Database::open(&path).unwrap();
+        assert_eq!(db.search_history("meeting").await.unwrap().len(), 1);
+    }
 
-        // Verify we have 1000 entries
-        assert_eq!(db.count().await.unwrap(), 1000);
+    #[tokio::test]
+    async fn test_set_encryption_removes_archived_audio() {
+        let (db, dir) = create_test_db();
+        let entry = audio_entry(&dir, "clip", "2024-01-01T00:00:00Z".to_string());
+        let path = entry.audio_path.clone().unwrap();
+        let id = db.insert_history(&entry).await.unwrap();
 
-        // Measure search performance
-        let start = std::time::Instant::now();
+        db.set_encryption(Some(&DatabaseKey::generate().unwrap()))
+            .await
+            .unwrap();
+        assert!(!std::path::Path::new(&path).exists());
+        let stored = db.get_entry(id).await.unwrap().unwrap();
+        assert_eq!(stored.text, "clip");
+        assert!(stored.audio_path.is_none());
+    }
 
-        // Prefix search
-        let results = db.search_history("hel").await.unwrap();
-        let prefix_duration = start.elapsed();
-        assert!(!results.is_empty(), "Prefix search should find results");
+    #[tokio::test]
+    async fn test_open_at_trusts_the_file_over_the_settings() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        let enabled = |key_source| HistoryEncryptionConfig {
+            enabled: true,
+            key_source,
+        };
+        let disabled = HistoryEncryptionConfig::default();
+        let no_key = || Ok(None);
+
+        // Decrypted, but the setting still says encrypted
+        Database::open(&path)
+            .unwrap()
+            .insert_history(&metadata_entry(
+                "kept",
+                "2024-01-01T00:00:00Z",
+                Default::default(),
+            ))
+            .await
+            .unwrap();
+        let db = open_at(&path, &enabled(KeySource::Passphrase), no_key)
+            .unwrap()
+            .unwrap();
+        assert!(!db.is_encrypted());
+        assert_eq!(db.count().await.unwrap(), 1);
+
+        // Encrypted, but the setting still says unencrypted
+        let key = DatabaseKey::generate().unwrap();
+        db.set_encryption(Some(&key)).await.unwrap();
+        drop(db);
+        let db = open_at(&path, &disabled, || Ok(Some(key.clone())))
+            .unwrap()
+            .unwrap();
+        assert_eq!(db.count().await.unwrap(), 1);
+        drop(db);
+
+        let passphrase = DatabaseKey::Passphrase("open sesame".into());
+        Database::open_with_key(&path, Some(&key))
+            .unwrap()
+            .set_encryption(Some(&passphrase))
+            .await
+            .unwrap();
+        assert!(open_at(&path, &disabled, no_key).unwrap().is_none());
+        assert!(open_at(&path, &disabled, || Ok(Some(key.clone())))
+            .unwrap()
+            .is_none());
+        assert!(open_at(&path, &enabled(KeySource::Keyring), no_key).is_err());
+    }
 
-        // Multi-word search
-        let start = std::time::Instant::now();
-        let results = db.search_history("hello wor").await.unwrap();
-        let multi_word_duration = start.elapsed();
-        assert!(!results.is_empty(), "Multi-word search should find results");
+    #[tokio::test]
+    async fn test_open_encrypted_migrates_existing_database() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        let db = Database::open(&path).unwrap();
+        db.insert_history(&metadata_entry(
+            "carried over",
+            "2024-01-01T00:00:00Z",
+            Default::default(),
+        ))
+        .await
+        .unwrap();
+        drop(db);
+
+        let key = DatabaseKey::Passphrase("open sesame".into());
+        let db = Database::open_encrypted(&path, &key).unwrap();
+        assert!(encryption::is_encrypted(&path).unwrap());
+        assert_eq!(db.get_history(10, 0).await.unwrap()[0].text, "carried over");
+        drop(db);
+
+        let wrong = DatabaseKey::Passphrase("wrong".into());
+        assert!(Database::open_encrypted(&path, &wrong).is_err());
+    }
 
-        // LIKE fallback search (substring in middle)
-        let start = std::time::Instant::now();
-        let results = db.search_history("special mark").await.unwrap();
-        let like_duration = start.elapsed();
+    #[tokio::test]
+    async fn test_update_entry_text_keeps_original() {
+        let (db, _dir) = create_test_db();
+        let id = db
+            .insert_history(&metadata_entry(
+                "deploy it to Cooper Netties",
+                "2024-01-01T00:00:00Z",
+                Default::default(),
+            ))
+            .await
+            .unwrap();
+
+        let edited = db
+            .update_entry_text(id, "Deploy it to Kubernetes.")
+            .await
+            .unwrap();
+        assert_eq!(edited.text, "Deploy it to Kubernetes.");
         assert_eq!(
-            results.len(),
-            100,
-            "Should find 100 entries with 'special marker'"
+            edited.original_text.as_deref(),
+            Some("deploy it to Cooper Netties")
         );
 
-        // Assert all searches complete in under 50ms
-        assert!(
-            prefix_duration.as_millis() < 50,
-            "Prefix search took {}ms, expected <50ms",
-            prefix_duration.as_millis()
-        );
-        assert!(
-            multi_word_duration.as_millis() < 50,
-            "Multi-word search took {}ms, expected <50ms",
-            multi_word_duration.as_millis()
+        // A second edit is still compared with the first transcription
+        db.update_entry_text(id, "deploy it to Kubernetes")
+            .await
+            .unwrap();
+        let stored = db.get_entry(id).await.unwrap().unwrap();
+        assert_eq!(
+            stored.original_text.as_deref(),
+            Some("deploy it to Cooper Netties")
         );
-        assert!(
-            like_duration.as_millis() < 50,
-            "LIKE fallback search took {}ms, expected <50ms",
-            like_duration.as_millis()
+        assert_eq!(
+            db.get_corrections(id).await.unwrap(),
+            vec![TextCorrection {
+                original: "Cooper Netties".to_string(),
+                corrected: "Kubernetes".to_string(),
+            }]
         );
+        assert_eq!(db.search_history("kubernetes").await.unwrap().len(), 1);
+
+        // Editing back to the original clears the edit
+        let reverted = db
+            .update_entry_text(id, "deploy it to Cooper Netties")
+            .await
+            .unwrap();
+        assert!(reverted.original_text.is_none());
+        assert!(db.get_corrections(id).await.unwrap().is_empty());
+
+        assert!(db.update_entry_text(id, "  ").await.is_err());
+        assert!(db.update_entry_text(id + 1, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_correction_summary_aggregates_entries() {
+        let (db, _dir) = create_test_db();
+        for (text, edited) in [
+            ("Cooper Netties is down", "Kubernetes is down"),
+            (
+                "restart Cooper Netties and Cooper Netties again",
+                "restart Kubernetes and Kubernetes again",
+            ),
+            ("call Jon", "call John"),
+        ] {
+            let id = db
+                .insert_history(&metadata_entry(
+                    text,
+                    "2024-01-01T00:00:00Z",
+                    Default::default(),
+                ))
+                .await
+                .unwrap();
+            db.update_entry_text(id, edited).await.unwrap();
+        }
+
+        let summary = db.correction_summary(1, 10).await.unwrap();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].original, "Cooper Netties");
+        assert_eq!(summary[0].corrected, "Kubernetes");
+        assert_eq!(summary[0].count, 3);
+        assert_eq!(summary[0].entries, 2);
+        assert_eq!(db.correction_summary(2, 10).await.unwrap().len(), 1);
+
+        // Deleting an entry removes its corrections
+        let first = db.search_history("down").await.unwrap()[0].id;
+        db.delete_entry(first).await.unwrap();
+        assert_eq!(db.correction_summary(1, 10).await.unwrap()[0].count, 2);
     }
 }
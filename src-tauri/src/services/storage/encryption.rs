@@ -0,0 +1,347 @@
+//! History database encryption
+//!
+//! The history database can be encrypted at rest with SQLCipher. The key is
+//! either a random 256-bit key stored in the OS keyring, which unlocks the
+//! database automatically, or a passphrase the user enters once per session
+//! (SQLCipher derives the key from it with PBKDF2).
+//!
+//! Switching encryption on or off exports the whole database to a new file
+//! with the new key and swaps it in, so existing history is carried over.
+
+use crate::error::AppError;
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub use ez_flow_history::schema::is_encrypted;
+
+/// Keyring service holding the database key
+const KEYRING_SERVICE: &str = "com.ezflow.EZFlow";
+
+/// Keyring account holding the database key
+const KEYRING_USER: &str = "history-db";
+
+/// Where the database key comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// Random key stored in the OS keyring
+    #[default]
+    Keyring,
+    /// Key derived from a passphrase entered each session
+    Passphrase,
+}
+
+/// History encryption settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryEncryptionConfig {
+    /// Whether the history database is encrypted
+    pub enabled: bool,
+    /// Where the key comes from
+    pub key_source: KeySource,
+}
+
+/// Key for an encrypted history database
+#[derive(Clone, PartialEq, Eq)]
+pub enum DatabaseKey {
+    /// Raw 256-bit key
+    Raw([u8; 32]),
+    /// Passphrase the key is derived from
+    Passphrase(String),
+}
+
+impl DatabaseKey {
+    /// Generate a random raw key
+    pub fn generate() -> Result<Self, AppError> {
+        let mut key = [0u8; 32];
+        getrandom::getrandom(&mut key)
+            .map_err(|e| AppError::Config(format!("Failed to generate database key: {}", e)))?;
+        Ok(Self::Raw(key))
+    }
+
+    /// Value for `PRAGMA key` and `ATTACH ... KEY`; SQLCipher treats
+    /// `x'<hex>'` as a raw key and anything else as a passphrase
+    fn sql_value(&self) -> String {
+        match self {
+            Self::Raw(key) => format!("x'{}'", hex::encode(key)),
+            Self::Passphrase(passphrase) => passphrase.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Raw(_) => f.write_str("DatabaseKey::Raw(..)"),
+            Self::Passphrase(_) => f.write_str("DatabaseKey::Passphrase(..)"),
+        }
+    }
+}
+
+/// Unlock a freshly opened connection, failing if the key is wrong
+pub fn apply_key(conn: &Connection, key: &DatabaseKey) -> Result<(), AppError> {
+    conn.pragma_update(None, "key", key.sql_value())
+        .map_err(|e| AppError::Config(format!("Failed to set database key: {}", e)))?;
+
+    // The key is only checked when the first page is read
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| match e.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::NotADatabase) => AppError::Config(
+                "Could not unlock the history database: the key or passphrase is incorrect".into(),
+            ),
+            _ => AppError::Config(format!("Failed to open database: {}", e)),
+        })
+}
+
+/// Write a copy of the database open on `conn` to `target`, encrypted with
+/// `key` or unencrypted when `key` is `None`. Any existing file at `target`
+/// is replaced.
+pub fn export(conn: &Connection, target: &Path, key: Option<&DatabaseKey>) -> Result<(), AppError> {
+    remove_if_exists(target)?;
+
+    let target_str = target.to_string_lossy();
+    let key = key.map(DatabaseKey::sql_value).unwrap_or_default();
+    conn.execute("ATTACH DATABASE ?1 AS rekey KEY ?2", [&*target_str, &key])
+        .map_err(|e| AppError::Config(format!("Failed to create database copy: {}", e)))?;
+
+    // sqlcipher_export copies the schema and rows but not the schema version
+    let result = conn
+        .query_row("SELECT sqlcipher_export('rekey')", [], |_| Ok(()))
+        .and_then(|_| {
+            let version: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+            conn.pragma_update(
+                Some(DatabaseName::Attached("rekey")),
+                "user_version",
+                version,
+            )
+        });
+    let detached = conn.execute_batch("DETACH DATABASE rekey");
+
+    if let Err(e) = result.and(detached) {
+        let _ = std::fs::remove_file(target);
+        return Err(AppError::Config(format!(
+            "Failed to copy history database: {}",
+            e
+        )));
+    }
+    Ok(())
+}
+
+/// Path the re-keyed copy of `db_path` is written to before replacing it
+pub fn rekey_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_else(|| "history.db".into());
+    name.push(".rekey");
+    db_path.with_file_name(name)
+}
+
+/// Where the original database is kept while its rekeyed copy replaces it
+pub fn previous_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_else(|| "history.db".into());
+    name.push(".previous");
+    db_path.with_file_name(name)
+}
+
+/// Delete the unencrypted backups migrations left next to `db_path`
+pub fn remove_plaintext_backups(db_path: &Path) -> Result<usize, AppError> {
+    let (Some(dir), Some(name)) = (db_path.parent(), db_path.file_name()) else {
+        return Ok(0);
+    };
+    let prefix = format!("{}.v", name.to_string_lossy());
+
+    let mut removed = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if file_name.starts_with(&prefix) && file_name.ends_with(".bak") && !is_encrypted(&path)? {
+            std::fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), AppError> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn keyring_entry() -> Result<keyring::Entry, AppError> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| AppError::Config(format!("Failed to access the system keyring: {}", e)))
+}
+
+/// Read the database key from the OS keyring
+pub fn load_keyring_key() -> Result<Option<DatabaseKey>, AppError> {
+    let secret = match keyring_entry()?.get_password() {
+        Ok(secret) => secret,
+        Err(keyring::Error::NoEntry) => return Ok(None),
+        Err(e) => {
+            return Err(AppError::Config(format!(
+                "Failed to read the history key from the system keyring: {}",
+                e
+            )))
+        }
+    };
+
+    let key = hex::decode(secret.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| {
+            AppError::Config("The history key in the system keyring is invalid".into())
+        })?;
+    Ok(Some(DatabaseKey::Raw(key)))
+}
+
+/// Store a raw database key in the OS keyring
+pub fn store_keyring_key(key: &DatabaseKey) -> Result<(), AppError> {
+    let DatabaseKey::Raw(raw) = key else {
+        return Err(AppError::Config(
+            "Only generated keys can be stored in the keyring".into(),
+        ));
+    };
+    keyring_entry()?
+        .set_password(&hex::encode(raw))
+        .map_err(|e| {
+            AppError::Config(format!(
+                "Failed to save the history key to the system keyring: {}",
+                e
+            ))
+        })
+}
+
+/// Remove the database key from the OS keyring
+pub fn delete_keyring_key() -> Result<(), AppError> {
+    match keyring_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(AppError::Config(format!(
+            "Failed to remove the history key from the system keyring: {}",
+            e
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn plain_db(path: &Path) -> Connection {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE notes (text TEXT);
+             INSERT INTO notes VALUES ('top secret');
+             PRAGMA user_version = 7;",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn read_note(conn: &Connection) -> String {
+        conn.query_row("SELECT text FROM notes", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_config_defaults_to_disabled_keyring() {
+        let config: HistoryEncryptionConfig = serde_json::from_str("{}").unwrap();
+        assert!(!config.enabled);
+        assert_eq!(config.key_source, KeySource::Keyring);
+    }
+
+    #[test]
+    fn test_is_encrypted() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        assert!(!is_encrypted(&path).unwrap());
+
+        plain_db(&path);
+        assert!(!is_encrypted(&path).unwrap());
+
+        std::fs::write(&path, [0x5a; 64]).unwrap();
+        assert!(is_encrypted(&path).unwrap());
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let plain_path = dir.path().join("plain.db");
+        let encrypted_path = dir.path().join("encrypted.db");
+        let restored_path = dir.path().join("restored.db");
+        let conn = plain_db(&plain_path);
+        let key = DatabaseKey::generate().unwrap();
+
+        export(&conn, &encrypted_path, Some(&key)).unwrap();
+        assert!(is_encrypted(&encrypted_path).unwrap());
+        let bytes = std::fs::read(&encrypted_path).unwrap();
+        assert!(!bytes.windows(10).any(|w| w == b"top secret"));
+
+        let encrypted = Connection::open(&encrypted_path).unwrap();
+        apply_key(&encrypted, &key).unwrap();
+        assert_eq!(read_note(&encrypted), "top secret");
+
+        export(&encrypted, &restored_path, None).unwrap();
+        assert!(!is_encrypted(&restored_path).unwrap());
+        let restored = Connection::open(&restored_path).unwrap();
+        assert_eq!(read_note(&restored), "top secret");
+        let version: u32 = restored
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 7);
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let conn = plain_db(&dir.path().join("plain.db"));
+        let path = dir.path().join("encrypted.db");
+        let key = DatabaseKey::Passphrase("correct horse".into());
+        export(&conn, &path, Some(&key)).unwrap();
+
+        let wrong = Connection::open(&path).unwrap();
+        let err = apply_key(&wrong, &DatabaseKey::Passphrase("battery staple".into())).unwrap_err();
+        assert!(err.to_string().contains("incorrect"));
+
+        let right = Connection::open(&path).unwrap();
+        apply_key(&right, &key).unwrap();
+        assert_eq!(read_note(&right), "top secret");
+    }
+
+    #[test]
+    fn test_remove_plaintext_backups() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("history.db");
+        let conn = plain_db(&db_path);
+        conn.execute_batch(&format!(
+            "VACUUM INTO '{}'",
+            dir.path().join("history.db.v5.bak").display()
+        ))
+        .unwrap();
+        let key = DatabaseKey::generate().unwrap();
+        export(&conn, &dir.path().join("history.db.v6.bak"), Some(&key)).unwrap();
+        std::fs::write(dir.path().join("other.db.v5.bak"), b"unrelated").unwrap();
+
+        assert_eq!(remove_plaintext_backups(&db_path).unwrap(), 1);
+        assert!(!dir.path().join("history.db.v5.bak").exists());
+        assert!(dir.path().join("history.db.v6.bak").exists());
+        assert!(dir.path().join("other.db.v5.bak").exists());
+    }
+
+    #[test]
+    fn test_debug_hides_key() {
+        let key = DatabaseKey::Passphrase("hunter2".into());
+        assert!(!format!("{:?}", key).contains("hunter2"));
+    }
+}
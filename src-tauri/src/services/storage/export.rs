@@ -0,0 +1,175 @@
+//! History export and import formats
+//!
+//! JSON exports carry every field (including revisions) and can be imported
+//! back; CSV is meant for spreadsheets and Markdown for reading as a journal.
+//! The formats are shared with the CLI (`ez_flow_history::export`); this
+//! module maps the app's entries onto them.
+
+use crate::error::AppError;
+use crate::models::{HistoryEntry, HistoryRevision};
+use ez_flow_history::export::{self as formats, HistoryRecord};
+use serde::{Deserialize, Serialize};
+
+/// Export file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryExportFormat {
+    /// Lossless JSON that `import_history` can read back
+    Json,
+    /// Comma-separated values
+    Csv,
+    /// Markdown journal grouped by day
+    Markdown,
+}
+
+/// A history entry with its revisions, as stored in a JSON export
+pub type ExportedEntry = formats::ExportedEntry<HistoryEntry, HistoryRevision>;
+
+/// JSON export file
+pub type HistoryExport = formats::HistoryExport<ExportedEntry>;
+
+/// Render entries as CSV (RFC 4180 quoting)
+pub fn to_csv(entries: &[HistoryEntry]) -> Result<String, AppError> {
+    Ok(formats::to_csv(&records(entries)?))
+}
+
+/// Render entries as a Markdown journal, one section per local day
+pub fn to_markdown(entries: &[HistoryEntry]) -> Result<String, AppError> {
+    Ok(formats::to_markdown(&records(entries)?))
+}
+
+/// Entries as the shared export writers take them
+fn records(entries: &[HistoryEntry]) -> Result<Vec<HistoryRecord>, AppError> {
+    entries
+        .iter()
+        .map(|entry| {
+            let meta = &entry.metadata;
+            Ok(HistoryRecord {
+                id: entry.id,
+                text: entry.text.clone(),
+                timestamp: entry.timestamp.clone(),
+                duration_ms: entry.duration_ms,
+                model_id: entry.model_id.clone(),
+                language: entry.language.clone(),
+                gpu_used: entry.gpu_used,
+                audio_path: entry.audio_path.clone(),
+                pinned: entry.pinned,
+                original_text: entry.original_text.clone(),
+                source: enum_name(&meta.source),
+                source_app: meta.source_app.clone(),
+                source_window: meta.source_window.clone(),
+                streaming_mode: enum_name(&meta.streaming_mode),
+                transcription_time_ms: meta.transcription_time_ms,
+                injection_time_ms: meta.injection_time_ms,
+                voice_commands: serde_json::to_value(&meta.voice_commands)?,
+                translated: meta.translated,
+            })
+        })
+        .collect()
+}
+
+/// Serde name of a unit enum
+fn enum_name<T: Serialize>(value: &Option<T>) -> Option<String> {
+    value
+        .as_ref()
+        .and_then(|v| serde_json::to_value(v).ok())
+        .and_then(|v| v.as_str().map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HistoryMetadata, HistorySource};
+    use crate::services::voice_commands::CommandAction;
+
+    fn entry(id: i64, text: &str, timestamp: &str) -> HistoryEntry {
+        HistoryEntry {
+            id,
+            text: text.to_string(),
+            timestamp: timestamp.to_string(),
+            duration_ms: 1200,
+            model_id: "base".to_string(),
+            language: Some("en".to_string()),
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: HistoryMetadata {
+                source: Some(HistorySource::Hotkey),
+                source_app: Some("firefox".to_string()),
+                transcription_time_ms: Some(250),
+                voice_commands: vec![CommandAction::InsertText(".".to_string())],
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let revision = HistoryRevision {
+            id: 3,
+            history_id: 1,
+            text: "alternative".to_string(),
+            model_id: "ggml-small".to_string(),
+            language: None,
+            gpu_used: true,
+            options: Default::default(),
+            created_at: "2024-01-02T00:00:00+00:00".to_string(),
+        };
+        let export = HistoryExport::new(vec![ExportedEntry {
+            entry: entry(1, "Hello, world.", "2024-01-01T12:00:00+00:00"),
+            revisions: vec![revision],
+        }]);
+
+        let json = export.to_json().unwrap();
+        let parsed = HistoryExport::from_json(&json).unwrap();
+
+        assert_eq!(parsed.version, formats::EXPORT_VERSION);
+        let imported = &parsed.entries[0];
+        assert_eq!(imported.entry.text, "Hello, world.");
+        assert_eq!(imported.entry.metadata, export.entries[0].entry.metadata);
+        assert_eq!(imported.revisions.len(), 1);
+        assert_eq!(imported.revisions[0].text, "alternative");
+    }
+
+    #[test]
+    fn test_json_rejects_newer_version() {
+        let json = r#"{"version": 99, "exported_at": "2024-01-01T00:00:00Z", "entries": []}"#;
+        let err = HistoryExport::from_json(json).unwrap_err().to_string();
+        assert!(err.contains("Unsupported history export version"));
+    }
+
+    #[test]
+    fn test_json_rejects_invalid_file() {
+        assert!(HistoryExport::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let csv = to_csv(&[entry(7, "Hi, \"you\"\nthere", "2024-01-01T12:00:00+00:00")]).unwrap();
+        let mut lines = csv.split("\r\n");
+
+        assert_eq!(lines.next().unwrap(), formats::CSV_HEADER.join(","));
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("7,2024-01-01T12:00:00+00:00,\"Hi, \"\"you\"\"\nthere\","));
+        assert!(csv.contains(",hotkey,firefox,,,250,,"));
+        assert!(csv.contains("\"[{\"\"type\"\":\"\"insert_text\"\",\"\"value\"\":\"\".\"\"}]\""));
+    }
+
+    #[test]
+    fn test_markdown_groups_by_day() {
+        let entries = [
+            entry(1, "First note.", "2024-03-01T12:00:00+00:00"),
+            entry(2, "Second note.", "2024-03-01T12:30:00+00:00"),
+            entry(3, "Next day.", "2024-03-02T12:00:00+00:00"),
+        ];
+
+        let markdown = to_markdown(&entries).unwrap();
+
+        assert!(markdown.starts_with("# EZ Flow History\n"));
+        assert_eq!(markdown.matches("\n## ").count(), 2);
+        assert_eq!(markdown.matches("\n### ").count(), 3);
+        assert!(markdown.contains(" · firefox\n\nFirst note.\n"));
+        assert!(markdown.find("First note.").unwrap() < markdown.find("Next day.").unwrap());
+    }
+}
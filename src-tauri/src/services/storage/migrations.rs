@@ -0,0 +1,562 @@
+//! History database schema migrations
+//!
+//! The schema version is tracked in SQLite's `user_version` pragma. Each
+//! migration runs in its own transaction and bumps the version when it
+//! commits, so a failure leaves the database at the last good version.
+//! Before upgrading an existing database a copy is written next to it, and a
+//! database created by a newer build is refused rather than modified.
+
+use crate::error::AppError;
+use rusqlite::{Connection, Transaction};
+use std::path::{Path, PathBuf};
+
+/// A single schema migration
+pub struct Migration {
+    /// Schema version after this migration is applied
+    pub version: u32,
+    /// Short description for logs
+    pub description: &'static str,
+    /// Apply the migration
+    pub up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// All migrations, in order. Never edit or reorder an existing entry; append
+/// a new one instead.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create history table and full-text index",
+        up: create_history,
+    },
+    Migration {
+        version: 2,
+        description: "add gpu_used column",
+        up: add_gpu_used,
+    },
+    Migration {
+        version: 3,
+        description: "add audio_path column",
+        up: add_audio_path,
+    },
+    Migration {
+        version: 4,
+        description: "create history_revisions table",
+        up: create_revisions,
+    },
+    Migration {
+        version: 5,
+        description: "add dictation metadata columns",
+        up: add_dictation_metadata,
+    },
+    Migration {
+        version: 6,
+        description: "keep the full-text index in sync with triggers",
+        up: add_fts_triggers,
+    },
+    Migration {
+        version: 7,
+        description: "add pinned column",
+        up: add_pinned,
+    },
+    Migration {
+        version: 8,
+        description: "track edited text and corrections",
+        up: add_corrections,
+    },
+    Migration {
+        version: 9,
+        description: "add translated column",
+        up: add_translated,
+    },
+];
+
+/// Schema version this build expects
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Bring the database up to the latest schema version.
+///
+/// `db_path` is the on-disk location of the database; when given, existing
+/// databases are backed up before any migration runs.
+pub fn migrate(conn: &Connection, db_path: Option<&Path>) -> Result<(), AppError> {
+    run_migrations(conn, MIGRATIONS, db_path)
+}
+
+fn run_migrations(
+    conn: &Connection,
+    migrations: &[Migration],
+    db_path: Option<&Path>,
+) -> Result<(), AppError> {
+    let current = schema_version(conn)?;
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+
+    if current > latest {
+        return Err(AppError::Config(format!(
+            "History database uses schema version {} but this version of EZ Flow only \
+             supports up to version {}. Update EZ Flow to open it; the database was left \
+             unchanged.",
+            current, latest
+        )));
+    }
+    if current == latest {
+        return Ok(());
+    }
+
+    if let Some(path) = db_path {
+        if has_user_tables(conn)? {
+            let backup = backup_path(path, current);
+            backup_database(conn, &backup)?;
+            tracing::info!(
+                "Backed up history database (schema v{}) to {:?}",
+                current,
+                backup
+            );
+        }
+    }
+
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        tracing::info!(
+            "Migrating history database to v{}: {}",
+            migration.version,
+            migration.description
+        );
+
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::Config(format!("Failed to start database migration: {}", e)))?;
+        (migration.up)(&tx)
+            .and_then(|_| tx.pragma_update(None, "user_version", migration.version))
+            .and_then(|_| tx.commit())
+            .map_err(|e| {
+                AppError::Config(format!(
+                    "Database migration to version {} ({}) failed: {}",
+                    migration.version, migration.description, e
+                ))
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Read the schema version stored in the database
+pub fn schema_version(conn: &Connection) -> Result<u32, AppError> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| AppError::Config(format!("Failed to read database version: {}", e)))
+}
+
+/// Backup file written before migrating from `version`
+fn backup_path(db_path: &Path, version: u32) -> PathBuf {
+    let mut name = db_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_else(|| "history.db".into());
+    name.push(format!(".v{}.bak", version));
+    db_path.with_file_name(name)
+}
+
+fn backup_database(conn: &Connection, backup: &Path) -> Result<(), AppError> {
+    // VACUUM INTO refuses to overwrite, so replace any stale backup
+    if backup.exists() {
+        std::fs::remove_file(backup)?;
+    }
+    conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()])
+        .map_err(|e| AppError::Config(format!("Failed to back up database: {}", e)))?;
+    Ok(())
+}
+
+fn has_user_tables(conn: &Connection) -> Result<bool, AppError> {
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Config(format!("Failed to inspect database: {}", e)))?;
+    Ok(count > 0)
+}
+
+/// Databases created before versioning already have some of these columns,
+/// so column additions check first.
+fn add_column_if_missing(
+    tx: &Transaction,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    let exists: bool = tx.query_row(
+        &format!(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name = ?1",
+            table
+        ),
+        [column],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        tx.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))?;
+    }
+    Ok(())
+}
+
+fn create_history(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            text TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            model_id TEXT NOT NULL,
+            language TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_history_timestamp ON history(timestamp DESC);
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+            text,
+            content='history',
+            content_rowid='id'
+        );
+        "#,
+    )
+}
+
+fn add_gpu_used(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "history", "gpu_used", "INTEGER DEFAULT 0")
+}
+
+fn add_audio_path(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "history", "audio_path", "TEXT")
+}
+
+fn create_revisions(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS history_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            history_id INTEGER NOT NULL REFERENCES history(id),
+            text TEXT NOT NULL,
+            model_id TEXT NOT NULL,
+            language TEXT,
+            gpu_used INTEGER DEFAULT 0,
+            options TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_history_revisions_history_id
+            ON history_revisions(history_id);
+        "#,
+    )
+}
+
+fn add_dictation_metadata(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "history", "source", "TEXT")?;
+    add_column_if_missing(tx, "history", "source_app", "TEXT")?;
+    add_column_if_missing(tx, "history", "source_window", "TEXT")?;
+    add_column_if_missing(tx, "history", "streaming_mode", "TEXT")?;
+    add_column_if_missing(tx, "history", "transcription_ms", "INTEGER")?;
+    add_column_if_missing(tx, "history", "injection_ms", "INTEGER")?;
+    add_column_if_missing(tx, "history", "voice_commands", "TEXT")?;
+    tx.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_history_source ON history(source);
+        CREATE INDEX IF NOT EXISTS idx_history_source_app ON history(source_app);
+        "#,
+    )
+}
+
+fn add_fts_triggers(tx: &Transaction) -> rusqlite::Result<()> {
+    // External-content FTS5 tables remove rows with the special 'delete'
+    // command, which needs the old text
+    tx.execute_batch(
+        r#"
+        CREATE TRIGGER IF NOT EXISTS history_fts_insert AFTER INSERT ON history BEGIN
+            INSERT INTO history_fts (rowid, text) VALUES (new.id, new.text);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS history_fts_delete AFTER DELETE ON history BEGIN
+            INSERT INTO history_fts (history_fts, rowid, text)
+                VALUES ('delete', old.id, old.text);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS history_fts_update AFTER UPDATE OF text ON history BEGIN
+            INSERT INTO history_fts (history_fts, rowid, text)
+                VALUES ('delete', old.id, old.text);
+            INSERT INTO history_fts (rowid, text) VALUES (new.id, new.text);
+        END;
+
+        INSERT INTO history_fts (history_fts) VALUES ('rebuild');
+        "#,
+    )
+}
+
+fn add_pinned(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "history", "pinned", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn add_translated(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "history", "translated", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn add_corrections(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "history", "original_text", "TEXT")?;
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS history_corrections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            history_id INTEGER NOT NULL REFERENCES history(id),
+            original TEXT NOT NULL,
+            corrected TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_history_corrections_history_id
+            ON history_corrections(history_id);
+        CREATE INDEX IF NOT EXISTS idx_history_corrections_pair
+            ON history_corrections(original, corrected);
+        "#,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .filter_map(Result::ok)
+            .collect()
+    }
+
+    fn table_exists(conn: &Connection, table: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    /// Schema written by releases before versioning (user_version 0)
+    fn legacy_db(path: &Path, with_gpu_used: bool) -> Connection {
+        let conn = Connection::open(path).unwrap();
+        let gpu_column = if with_gpu_used {
+            "gpu_used INTEGER DEFAULT 0,"
+        } else {
+            ""
+        };
+        conn.execute_batch(&format!(
+            r#"
+            CREATE TABLE history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                text TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                model_id TEXT NOT NULL,
+                language TEXT,
+                {}
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX idx_history_timestamp ON history(timestamp DESC);
+            CREATE VIRTUAL TABLE history_fts USING fts5(
+                text,
+                content='history',
+                content_rowid='id'
+            );
+            INSERT INTO history (text, timestamp, duration_ms, model_id, language)
+                VALUES ('legacy entry', '2024-01-01T00:00:00Z', 1000, 'base', 'en');
+            INSERT INTO history_fts (rowid, text) VALUES (1, 'legacy entry');
+            "#,
+            gpu_column
+        ))
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as u32 + 1);
+        }
+        assert_eq!(latest_version(), MIGRATIONS.len() as u32);
+    }
+
+    #[test]
+    fn test_fresh_database_migrates_to_latest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        let conn = Connection::open(&path).unwrap();
+
+        migrate(&conn, Some(&path)).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), latest_version());
+        let history = columns(&conn, "history");
+        assert!(history.contains(&"gpu_used".to_string()));
+        assert!(history.contains(&"audio_path".to_string()));
+        assert!(history.contains(&"source_app".to_string()));
+        assert!(history.contains(&"voice_commands".to_string()));
+        assert!(history.contains(&"pinned".to_string()));
+        assert!(history.contains(&"original_text".to_string()));
+        assert!(table_exists(&conn, "history_corrections"));
+        assert!(table_exists(&conn, "history_revisions"));
+
+        // Nothing to back up for a new database
+        assert!(!backup_path(&path, 0).exists());
+    }
+
+    #[test]
+    fn test_migrate_is_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        migrate(&conn, None).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn test_legacy_database_keeps_data() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        let conn = legacy_db(&path, true);
+
+        migrate(&conn, Some(&path)).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), latest_version());
+        assert!(columns(&conn, "history").contains(&"audio_path".to_string()));
+        let text: String = conn
+            .query_row("SELECT text FROM history WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(text, "legacy entry");
+        let fts_hits: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM history_fts WHERE history_fts MATCH 'legacy'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(fts_hits, 1);
+    }
+
+    #[test]
+    fn test_fts_index_follows_history_changes() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+
+        let hits = |term: &str| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM history_fts WHERE history_fts MATCH ?1",
+                [term],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+
+        conn.execute(
+            "INSERT INTO history (text, timestamp, duration_ms, model_id)
+             VALUES ('first draft', '2024-01-01T00:00:00Z', 1000, 'base')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(hits("draft"), 1);
+
+        conn.execute("UPDATE history SET text = 'final copy' WHERE id = 1", [])
+            .unwrap();
+        assert_eq!(hits("draft"), 0);
+        assert_eq!(hits("final"), 1);
+
+        conn.execute("DELETE FROM history WHERE id = 1", [])
+            .unwrap();
+        assert_eq!(hits("final"), 0);
+    }
+
+    #[test]
+    fn test_legacy_database_without_gpu_column() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        let conn = legacy_db(&path, false);
+
+        migrate(&conn, Some(&path)).unwrap();
+
+        let gpu_used: bool = conn
+            .query_row("SELECT gpu_used FROM history WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert!(!gpu_used);
+    }
+
+    #[test]
+    fn test_backup_written_before_migrating() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        let conn = legacy_db(&path, true);
+
+        migrate(&conn, Some(&path)).unwrap();
+
+        let backup = backup_path(&path, 0);
+        assert!(backup.exists());
+        let backup_conn = Connection::open(&backup).unwrap();
+        assert_eq!(schema_version(&backup_conn).unwrap(), 0);
+        assert!(!columns(&backup_conn, "history").contains(&"audio_path".to_string()));
+        let count: i64 = backup_conn
+            .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        let conn = Connection::open(&path).unwrap();
+        migrate(&conn, None).unwrap();
+        let future = latest_version() + 1;
+        conn.pragma_update(None, "user_version", future).unwrap();
+
+        let err = migrate(&conn, Some(&path)).unwrap_err().to_string();
+
+        assert!(err.contains("Update EZ Flow"));
+        assert!(err.contains(&future.to_string()));
+        assert_eq!(schema_version(&conn).unwrap(), future);
+        assert!(!backup_path(&path, future).exists());
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        fn broken(tx: &Transaction) -> rusqlite::Result<()> {
+            tx.execute_batch("CREATE TABLE half_done (id INTEGER); SELECT * FROM missing_table;")
+        }
+        let migrations = [
+            Migration {
+                version: 1,
+                description: "create history table and full-text index",
+                up: create_history,
+            },
+            Migration {
+                version: 2,
+                description: "broken",
+                up: broken,
+            },
+        ];
+        let conn = Connection::open_in_memory().unwrap();
+
+        let err = run_migrations(&conn, &migrations, None)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("version 2"));
+        assert_eq!(schema_version(&conn).unwrap(), 1);
+        assert!(table_exists(&conn, "history"));
+        assert!(!table_exists(&conn, "half_done"));
+    }
+}
@@ -2,8 +2,19 @@
 //!
 //! Handles settings and other persistent data.
 
+pub mod archive;
+pub mod corrections;
 pub mod database;
+pub mod encryption;
+pub mod export;
+pub mod migrations;
+pub mod retention;
 pub mod settings;
 
+pub use ez_flow_history::search;
+
+pub use archive::{AudioArchive, AudioArchiveConfig};
 pub use database::{Database, DatabaseState};
+pub use encryption::HistoryEncryptionConfig;
+pub use retention::HistoryRetentionConfig;
 pub use settings::{get_settings_path, load_settings, save_settings, SettingsError, SettingsState};
@@ -0,0 +1,205 @@
+//! History retention
+//!
+//! Removes old history entries according to the user's retention settings.
+//! Limits are applied on startup and then periodically in the background;
+//! pinned entries are never removed.
+
+use super::{Database, DatabaseState, SettingsState};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often the background task applies the retention limits
+const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Compact the database after removing at least this many entries
+const VACUUM_THRESHOLD: usize = 500;
+
+/// History retention settings (0 = unlimited for each limit)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryRetentionConfig {
+    /// Maximum number of unpinned entries to keep
+    pub max_entries: u32,
+    /// Delete unpinned entries older than this many days
+    pub max_age_days: u32,
+    /// Maximum size of the history database in megabytes
+    pub max_db_size_mb: u32,
+}
+
+impl HistoryRetentionConfig {
+    /// Whether any limit is set
+    pub fn is_limited(&self) -> bool {
+        self.max_entries > 0 || self.max_age_days > 0 || self.max_db_size_mb > 0
+    }
+}
+
+/// Result of applying the retention limits
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RetentionReport {
+    /// Entries removed
+    pub removed: usize,
+    /// Whether the database file was compacted afterwards
+    pub vacuumed: bool,
+}
+
+/// Apply the retention limits to the database
+///
+/// The database is compacted after large deletions and whenever the size
+/// limit removed entries, since deleted rows only free pages inside the file.
+pub async fn apply_retention(
+    db: &Database,
+    config: &HistoryRetentionConfig,
+) -> Result<RetentionReport, AppError> {
+    let mut report = RetentionReport::default();
+
+    if config.max_age_days > 0 {
+        report.removed += db.prune_older_than(config.max_age_days).await?;
+    }
+    if config.max_entries > 0 {
+        report.removed += db.prune_history(config.max_entries as usize).await?;
+    }
+
+    let mut removed_for_size = 0;
+    if config.max_db_size_mb > 0 {
+        let max_bytes = config.max_db_size_mb as u64 * 1024 * 1024;
+        removed_for_size = db.prune_to_size(max_bytes).await?;
+        report.removed += removed_for_size;
+    }
+
+    if report.removed >= VACUUM_THRESHOLD || removed_for_size > 0 {
+        db.vacuum().await?;
+        report.vacuumed = true;
+    }
+
+    if report.removed > 0 {
+        tracing::info!("History retention removed {} entries", report.removed);
+    }
+
+    Ok(report)
+}
+
+/// Apply the retention limits now and then every hour, reading the current
+/// settings each time
+pub fn spawn_retention_task(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let config = app.state::<SettingsState>().get().await.history_retention;
+            if config.is_limited() {
+                let db_state = app.state::<DatabaseState>();
+                match db_state.get() {
+                    Some(db) => {
+                        if let Err(e) = apply_retention(db, &config).await {
+                            tracing::warn!("Failed to apply history retention: {}", e);
+                        }
+                    }
+                    None => {
+                        tracing::debug!("Database unavailable; skipping history retention");
+                    }
+                }
+            }
+
+            tokio::time::sleep(RETENTION_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HistoryEntry;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn entry(text: &str, days_ago: i64) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            text: text.to_string(),
+            timestamp: (Utc::now() - chrono::Duration::days(days_ago)).to_rfc3339(),
+            duration_ms: 1000,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+            audio_path: None,
+            pinned: false,
+            original_text: None,
+            metadata: Default::default(),
+        }
+    }
+
+    fn test_db() -> (Database, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let db = Database::open(&dir.path().join("history.db")).unwrap();
+        (db, dir)
+    }
+
+    #[test]
+    fn test_default_is_unlimited() {
+        let config = HistoryRetentionConfig::default();
+        assert!(!config.is_limited());
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_keeps_everything() {
+        let (db, _dir) = test_db();
+        for i in 0..5 {
+            db.insert_history(&entry("note", i * 100)).await.unwrap();
+        }
+
+        let report = apply_retention(&db, &HistoryRetentionConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(report, RetentionReport::default());
+        assert_eq!(db.count().await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_age_and_count_limits_spare_pinned() {
+        let (db, _dir) = test_db();
+        let pinned = db.insert_history(&entry("keep me", 400)).await.unwrap();
+        db.set_pinned(pinned, true).await.unwrap();
+        db.insert_history(&entry("expired", 60)).await.unwrap();
+        for i in 0..3 {
+            db.insert_history(&entry("recent", i)).await.unwrap();
+        }
+
+        let config = HistoryRetentionConfig {
+            max_entries: 2,
+            max_age_days: 30,
+            ..Default::default()
+        };
+        let report = apply_retention(&db, &config).await.unwrap();
+
+        assert_eq!(report.removed, 2);
+        assert!(!report.vacuumed);
+        let remaining = db.get_history(10, 0).await.unwrap();
+        assert_eq!(remaining.len(), 3);
+        assert!(remaining.iter().any(|e| e.id == pinned && e.pinned));
+    }
+
+    #[tokio::test]
+    async fn test_size_limit_prunes_and_vacuums() {
+        let (db, _dir) = test_db();
+        let text = "lorem ipsum dolor sit amet ".repeat(400);
+        for i in 0..200 {
+            db.insert_history(&entry(&text, i)).await.unwrap();
+        }
+        let before = db.file_size().await.unwrap();
+        assert!(before > 1024 * 1024);
+
+        let config = HistoryRetentionConfig {
+            max_db_size_mb: 1,
+            ..Default::default()
+        };
+        let report = apply_retention(&db, &config).await.unwrap();
+
+        assert!(report.removed > 0);
+        assert!(report.vacuumed);
+        assert!(db.file_size().await.unwrap() <= 1024 * 1024);
+        // The newest entries are the ones kept
+        let newest = db.get_history(1, 0).await.unwrap();
+        assert!(newest[0].timestamp > (Utc::now() - chrono::Duration::hours(1)).to_rfc3339());
+    }
+}
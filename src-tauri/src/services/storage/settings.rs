@@ -136,6 +136,80 @@ impl SettingsState {
         }
     }
 
+    /// Get audio preprocessing configuration synchronously (non-blocking)
+    /// Returns the configured DSP chain settings
+    pub fn get_audio_processing_sync(&self) -> crate::services::audio::dsp::DspConfig {
+        match self.settings.try_read() {
+            Ok(guard) => guard.audio_processing.clone(),
+            Err(_) => {
+                tracing::warn!("Could not acquire settings lock, using default audio_processing");
+                crate::services::audio::dsp::DspConfig::default()
+            }
+        }
+    }
+
+    /// Get audio archive configuration synchronously (non-blocking)
+    /// Returns the recording archive and retention settings in effect
+    pub fn get_audio_archive_sync(&self) -> crate::services::storage::AudioArchiveConfig {
+        match self.settings.try_read() {
+            Ok(guard) => guard.effective_audio_archive(),
+            Err(_) => {
+                tracing::warn!("Could not acquire settings lock, using default audio_archive");
+                crate::services::storage::AudioArchiveConfig::default()
+            }
+        }
+    }
+
+    /// Get text replacement configuration synchronously (non-blocking)
+    /// Returns the find/replace and snippet rules
+    pub fn get_text_replacements_sync(
+        &self,
+    ) -> crate::services::text_replacements::ReplacementConfig {
+        match self.settings.try_read() {
+            Ok(guard) => guard.text_replacements.clone(),
+            Err(_) => {
+                tracing::warn!("Could not acquire settings lock, using default text_replacements");
+                crate::services::text_replacements::ReplacementConfig::default()
+            }
+        }
+    }
+
+    /// Get whether text normalization is enabled synchronously (non-blocking)
+    /// Returns true if spoken numbers should be written in digits
+    pub fn get_text_normalization_sync(&self) -> bool {
+        match self.settings.try_read() {
+            Ok(guard) => guard.text_normalization,
+            Err(_) => {
+                tracing::warn!("Could not acquire settings lock, using default text_normalization");
+                true // Default to enabled
+            }
+        }
+    }
+
+    /// Get the hotkey bindings synchronously (non-blocking)
+    /// Returns the hotkeys bound besides the main one
+    pub fn get_hotkey_bindings_sync(&self) -> Vec<crate::services::hotkey::HotkeyBinding> {
+        match self.settings.try_read() {
+            Ok(guard) => guard.hotkey_bindings.clone(),
+            Err(_) => {
+                tracing::warn!("Could not acquire settings lock, using no hotkey_bindings");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Get the dictation profiles synchronously (non-blocking)
+    /// Returns the profiles hotkey bindings refer to
+    pub fn get_dictation_profiles_sync(&self) -> Vec<crate::services::hotkey::DictationProfile> {
+        match self.settings.try_read() {
+            Ok(guard) => guard.dictation_profiles.clone(),
+            Err(_) => {
+                tracing::warn!("Could not acquire settings lock, using no dictation_profiles");
+                Vec::new()
+            }
+        }
+    }
+
     /// Update settings and save to disk
     pub async fn update(&self, settings: Settings) -> Result<(), SettingsError> {
         save_settings(&settings)?;
@@ -4,7 +4,8 @@
 //! emitting partial results as audio chunks become available.
 
 use crate::models::settings::StreamingMode;
-use crate::services::audio::AudioChunk;
+use crate::services::audio::processing::{resample_for_whisper, AudioBuffer};
+use crate::services::audio::{AudioChunk, RecordedAudio};
 use crate::services::transcription::{ChunkTranscriptionResult, TranscriptionError};
 use crate::services::transcription::engine::SharedWhisperEngine;
 use serde::Serialize;
@@ -29,6 +30,9 @@ const MAX_EMIT_RATE_PER_SEC: u32 = 3;
 /// Minimum interval between emissions in milliseconds
 const MIN_EMIT_INTERVAL_MS: u64 = 333; // ~3 per second
 
+/// Seconds of trailing audio re-transcribed in balanced mode
+const BALANCED_TAIL_SECS: usize = 5;
+
 /// Partial transcription event payload
 #[derive(Debug, Clone, Serialize)]
 pub struct PartialTranscriptionEvent {
@@ -327,43 +331,20 @@ impl StreamingTranscriptionService {
             }
             StreamingMode::Balanced => {
                 // Re-transcribe last 5 seconds for cleanup
-                let last_5_secs_samples = 5 * 16000;
-                if full_audio.len() > last_5_secs_samples {
+                let tail_samples = BALANCED_TAIL_SECS * 16000;
+                if full_audio.len() > tail_samples {
                     tracing::info!("Balanced mode: re-transcribing last 5 seconds");
 
-                    let tail = &full_audio[full_audio.len() - last_5_secs_samples..];
+                    let tail = &full_audio[full_audio.len() - tail_samples..];
                     let tail_result = engine
                         .transcribe_with_auto_load_and_prompt(tail.to_vec(), model_id, initial_prompt)
                         .await?;
 
                     // Replace last portion with reconciled text
-                    let word_count = streaming_text.split_whitespace().count();
-                    let tail_word_count = tail_result.text.split_whitespace().count();
-
-                    if word_count > tail_word_count {
-                        // Keep earlier words, replace tail
-                        let words: Vec<&str> = streaming_text.split_whitespace().collect();
-                        let keep_count = word_count.saturating_sub(tail_word_count + 2);
-                        let mut final_text = words[..keep_count.min(words.len())].join(" ");
-                        if !final_text.is_empty() {
-                            final_text.push(' ');
-                        }
-                        final_text.push_str(&tail_result.text);
-
-                        self.emit_final(app, &final_text, full_audio.len() as f32 / 16000.0, true)
-                            .await;
-                        Ok(final_text)
-                    } else {
-                        // Use tail result
-                        self.emit_final(
-                            app,
-                            &tail_result.text,
-                            full_audio.len() as f32 / 16000.0,
-                            true,
-                        )
+                    let final_text = merge_tail(&streaming_text, &tail_result.text);
+                    self.emit_final(app, &final_text, full_audio.len() as f32 / 16000.0, true)
                         .await;
-                        Ok(tail_result.text)
-                    }
+                    Ok(final_text)
                 } else {
                     // Audio too short, use streaming result
                     self.emit_final(app, &streaming_text, full_audio.len() as f32 / 16000.0, false)
@@ -387,6 +368,92 @@ impl StreamingTranscriptionService {
     }
 }
 
+impl StreamingTranscriptionService {
+    /// Perform final reconciliation for a finished recording
+    ///
+    /// Short recordings are reconciled from memory as in [`Self::reconcile`].
+    /// Recordings spilled to disk are never loaded whole: balanced mode reads
+    /// only the tail from disk and accuracy mode re-transcribes in windows.
+    pub async fn reconcile_recording<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        engine: &SharedWhisperEngine,
+        recording: &RecordedAudio,
+        model_id: &str,
+        initial_prompt: Option<&str>,
+    ) -> Result<String, TranscriptionError> {
+        if !recording.is_spilled() {
+            let full_audio = recording
+                .to_whisper_samples()
+                .map_err(|e| TranscriptionError::AudioError(e.to_string()))?;
+            return self
+                .reconcile(app, engine, &full_audio, model_id, initial_prompt)
+                .await;
+        }
+
+        let mode = *self.mode.read().await;
+        let streaming_text = self.accumulated_text.read().await.clone();
+        let duration_secs = recording.duration_secs();
+
+        match mode {
+            StreamingMode::Speed => {
+                tracing::info!("Speed mode: using streaming result directly");
+                self.emit_final(app, &streaming_text, duration_secs, false).await;
+                Ok(streaming_text)
+            }
+            StreamingMode::Balanced => {
+                tracing::info!("Balanced mode: re-transcribing last 5 seconds of long recording");
+
+                let tail_len = BALANCED_TAIL_SECS * recording.sample_rate() as usize;
+                let tail_start = recording.len().saturating_sub(tail_len as u64);
+                let tail = recording
+                    .read_range(tail_start, tail_len)
+                    .and_then(|samples| {
+                        resample_for_whisper(AudioBuffer::new(samples, recording.sample_rate()))
+                    })
+                    .map_err(|e| TranscriptionError::AudioError(e.to_string()))?;
+                let tail_result = engine
+                    .transcribe_with_auto_load_and_prompt(tail, model_id, initial_prompt)
+                    .await?;
+
+                let final_text = merge_tail(&streaming_text, &tail_result.text);
+                self.emit_final(app, &final_text, duration_secs, true).await;
+                Ok(final_text)
+            }
+            StreamingMode::Accuracy => {
+                tracing::info!("Accuracy mode: re-transcribing long recording in windows");
+
+                let result = engine
+                    .transcribe_recording_with_auto_load(recording, model_id, initial_prompt)
+                    .await?;
+                self.emit_final(app, &result.text, duration_secs, true).await;
+                Ok(result.text)
+            }
+        }
+    }
+}
+
+/// Replace the end of the streaming text with a re-transcribed tail
+///
+/// Drops as many trailing streaming words as the tail contains (plus a small
+/// margin for words split at the chunk boundary) and appends the tail text.
+fn merge_tail(streaming_text: &str, tail_text: &str) -> String {
+    let words: Vec<&str> = streaming_text.split_whitespace().collect();
+    let tail_word_count = tail_text.split_whitespace().count();
+
+    if words.len() <= tail_word_count {
+        return tail_text.to_string();
+    }
+
+    let keep_count = words.len().saturating_sub(tail_word_count + 2);
+    let mut final_text = words[..keep_count].join(" ");
+    if !final_text.is_empty() {
+        final_text.push(' ');
+    }
+    final_text.push_str(tail_text);
+    final_text
+}
+
 impl Default for StreamingTranscriptionService {
     fn default() -> Self {
         Self::new()
@@ -497,6 +564,18 @@ mod tests {
         assert_eq!(service.get_accumulated_text().await, "Hello world");
     }
 
+    #[test]
+    fn test_merge_tail_replaces_last_words() {
+        let merged = merge_tail("one two three four five six seven", "sex seven");
+        assert_eq!(merged, "one two three sex seven");
+    }
+
+    #[test]
+    fn test_merge_tail_uses_tail_when_longer() {
+        let merged = merge_tail("hello", "hello world");
+        assert_eq!(merged, "hello world");
+    }
+
     #[test]
     fn test_shared_service_clone() {
         let service = SharedStreamingService::new();
@@ -0,0 +1,889 @@
+//! English rules
+//!
+//! Standalone one-word numbers below ten ("one", "first") stay spelled out,
+//! as in most style guides; they are only rewritten as part of a time,
+//! date, amount, measurement or digit sequence.
+
+use super::{joined, render, tokenize, Match, Word};
+
+const UNITS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const SCALES: [(&str, u64); 4] = [
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+    ("billion", 1_000_000_000),
+    ("trillion", 1_000_000_000_000),
+];
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Units written as symbols after a number: the spoken words and the symbol,
+/// longest phrases first
+const MEASUREMENTS: &[(&[&str], &str)] = &[
+    (&["miles", "per", "hour"], " mph"),
+    (&["kilometers", "per", "hour"], " km/h"),
+    (&["kilometres", "per", "hour"], " km/h"),
+    (&["degrees", "celsius"], "°C"),
+    (&["degrees", "fahrenheit"], "°F"),
+    (&["degrees"], "°"),
+    (&["degree"], "°"),
+    (&["kilometers"], " km"),
+    (&["kilometres"], " km"),
+    (&["kilometer"], " km"),
+    (&["meters"], " m"),
+    (&["metres"], " m"),
+    (&["meter"], " m"),
+    (&["centimeters"], " cm"),
+    (&["centimetres"], " cm"),
+    (&["millimeters"], " mm"),
+    (&["millimetres"], " mm"),
+    (&["kilograms"], " kg"),
+    (&["kilogram"], " kg"),
+    (&["grams"], " g"),
+    (&["milligrams"], " mg"),
+    (&["liters"], " L"),
+    (&["litres"], " L"),
+    (&["milliliters"], " mL"),
+    (&["millilitres"], " mL"),
+    (&["kilobytes"], " KB"),
+    (&["megabytes"], " MB"),
+    (&["gigabytes"], " GB"),
+    (&["terabytes"], " TB"),
+    (&["megahertz"], " MHz"),
+    (&["gigahertz"], " GHz"),
+];
+
+/// Rewrite spoken numbers in an English transcript
+pub(super) fn normalize(text: &str) -> String {
+    let (words, tail) = tokenize(text, |part| number_word(&part.to_lowercase()).is_some());
+    render(&words, tail, match_at)
+}
+
+/// The written form of the run of words starting at `i`, if any
+fn match_at(words: &[Word], i: usize) -> Option<Match> {
+    if let Some(matched) = time(words, i).or_else(|| date(words, i)) {
+        return Some(matched);
+    }
+    if let Some((len, year)) = year_pair(words, i).filter(|(_, y)| (1900..2100).contains(y)) {
+        return Some(Match::new(len, year.to_string()));
+    }
+    if let Some(matched) = decade(words, i).or_else(|| digit_sequence(words, i)) {
+        return Some(matched);
+    }
+
+    let number = parse_number(words, i)?;
+    if let Some(matched) = amount(words, i, &number) {
+        return Some(matched);
+    }
+    if !number.spelled || number.is_small() || next_to_number(words, i, number.len) {
+        return None;
+    }
+    Some(Match::new(number.len, number.text))
+}
+
+/// Whether a number word comes right before or after the `len` words at
+/// `i`, as in "fifty fifty". Numbers that don't combine stay spelled out;
+/// a small number before ("one eight hundred") doesn't count.
+fn next_to_number(words: &[Word], i: usize, len: usize) -> bool {
+    let before = joined(words, i)
+        && matches!(
+            number_word(&words[i - 1].key),
+            Some((kind, _, _)) if !matches!(kind, Kind::Zero | Kind::Unit)
+        );
+    let after = joined(words, i + len) && number_word(&words[i + len].key).is_some();
+    before || after
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Zero,
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+/// Kind and value of a number word, and whether it is an ordinal
+fn number_word(key: &str) -> Option<(Kind, u64, bool)> {
+    if let Some((kind, value)) = cardinal_word(key) {
+        return Some((kind, value, false));
+    }
+    let base = match key {
+        "first" => "one".to_string(),
+        "second" => "two".to_string(),
+        "third" => "three".to_string(),
+        "fifth" => "five".to_string(),
+        "eighth" => "eight".to_string(),
+        "ninth" => "nine".to_string(),
+        "twelfth" => "twelve".to_string(),
+        _ => match key.strip_suffix("ieth") {
+            Some(tens) => format!("{}y", tens),
+            None => key.strip_suffix("th")?.to_string(),
+        },
+    };
+    cardinal_word(&base)
+        .filter(|(kind, _)| *kind != Kind::Zero)
+        .map(|(kind, value)| (kind, value, true))
+}
+
+fn cardinal_word(key: &str) -> Option<(Kind, u64)> {
+    if key == "zero" {
+        return Some((Kind::Zero, 0));
+    }
+    if key == "hundred" {
+        return Some((Kind::Hundred, 100));
+    }
+    if let Some(i) = UNITS.iter().position(|w| *w == key) {
+        return Some((Kind::Unit, i as u64 + 1));
+    }
+    if let Some(i) = TEENS.iter().position(|w| *w == key) {
+        return Some((Kind::Teen, i as u64 + 10));
+    }
+    if let Some(i) = TENS.iter().position(|w| *w == key) {
+        return Some((Kind::Tens, (i as u64 + 2) * 10));
+    }
+    SCALES
+        .iter()
+        .find(|(word, _)| *word == key)
+        .map(|(_, value)| (Kind::Scale, *value))
+}
+
+/// A single spoken digit, with "oh" for zero
+fn digit(key: &str) -> Option<char> {
+    match key {
+        "zero" | "oh" | "o" => Some('0'),
+        _ => UNITS
+            .iter()
+            .position(|w| *w == key)
+            .and_then(|i| char::from_digit(i as u32 + 1, 10)),
+    }
+}
+
+/// A number read from one or more words
+#[derive(Debug)]
+struct Number {
+    /// Written form ("23", "1.5 million", "21st")
+    text: String,
+    /// Whole-number value; `None` for decimals
+    value: Option<u64>,
+    /// Number of words read
+    len: usize,
+    ordinal: bool,
+    /// Spoken as words rather than written in digits
+    spelled: bool,
+}
+
+impl Number {
+    /// A single word for a number below ten
+    fn is_small(&self) -> bool {
+        self.len == 1 && self.value.is_some_and(|v| v < 10)
+    }
+
+    /// Whole number without an ordinal suffix
+    fn cardinal_value(&self) -> Option<u64> {
+        self.value.filter(|_| !self.ordinal)
+    }
+}
+
+/// Read a number starting at `i`: digits as written, or number words with
+/// an optional "point" and digits and a trailing scale ("one point five
+/// million")
+fn parse_number(words: &[Word], i: usize) -> Option<Number> {
+    let core = words[i].core;
+    let mut number = if is_written_number(core) {
+        Number {
+            text: core.to_string(),
+            value: core.replace(',', "").parse().ok(),
+            len: 1,
+            ordinal: false,
+            spelled: false,
+        }
+    } else {
+        let number = parse_cardinal(words, i)?;
+        if number.ordinal {
+            return Some(number);
+        }
+        number
+    };
+
+    // Decimal digits
+    let point = i + number.len;
+    if number.spelled && joined(words, point) && words[point].key == "point" {
+        let mut digits = String::new();
+        let mut j = point + 1;
+        while joined(words, j) {
+            match digit(&words[j].key) {
+                Some(d) => digits.push(d),
+                None => break,
+            }
+            j += 1;
+        }
+        if !digits.is_empty() {
+            number.text = format!("{}.{}", number.text, digits);
+            number.value = None;
+            number.len = j - i;
+        }
+    }
+
+    // "1.5 million" keeps the scale as a word
+    let scale = i + number.len;
+    if number.text.contains('.')
+        && joined(words, scale)
+        && matches!(
+            words[scale].key.as_str(),
+            "million" | "billion" | "trillion"
+        )
+    {
+        number.text = format!("{} {}", number.text, words[scale].key);
+        number.len += 1;
+    }
+
+    Some(number)
+}
+
+fn is_written_number(core: &str) -> bool {
+    core.starts_with(|c: char| c.is_ascii_digit())
+        && core
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+}
+
+/// Read number words into a whole number ("two hundred and five",
+/// "twenty first")
+fn parse_cardinal(words: &[Word], i: usize) -> Option<Number> {
+    let mut total = 0;
+    let mut current = 0;
+    let mut prev: Option<Kind> = None;
+    let mut last_scale = u64::MAX;
+    let mut ordinal = false;
+    let mut end = i;
+    let mut j = i;
+
+    while j < words.len() && (j == i || joined(words, j)) {
+        let key = words[j].key.as_str();
+        // "and" only between a hundred or thousand and what follows it
+        if key == "and" && matches!(prev, Some(Kind::Hundred | Kind::Scale)) && end == j {
+            j += 1;
+            continue;
+        }
+        let Some((kind, value, is_ordinal)) = number_word(key) else {
+            break;
+        };
+        let fits = match kind {
+            Kind::Zero => prev.is_none(),
+            Kind::Unit => matches!(prev, None | Some(Kind::Tens | Kind::Hundred | Kind::Scale)),
+            Kind::Teen | Kind::Tens => {
+                matches!(prev, None | Some(Kind::Hundred | Kind::Scale))
+            }
+            Kind::Hundred => {
+                matches!(prev, Some(Kind::Unit | Kind::Teen | Kind::Tens)) && current < 100
+            }
+            Kind::Scale => {
+                prev.is_some() && prev != Some(Kind::Zero) && current > 0 && value < last_scale
+            }
+        };
+        if !fits {
+            break;
+        }
+
+        match kind {
+            Kind::Hundred => current *= 100,
+            Kind::Scale => {
+                total += current * value;
+                current = 0;
+                last_scale = value;
+            }
+            _ => current += value,
+        }
+        prev = Some(kind);
+        j += 1;
+        end = j;
+        if is_ordinal {
+            ordinal = true;
+            break;
+        }
+        if kind == Kind::Zero {
+            break;
+        }
+    }
+
+    if end == i {
+        return None;
+    }
+    let value = total + current;
+    let mut text = group_thousands(value);
+    if ordinal {
+        text.push_str(ordinal_suffix(value));
+    }
+    Some(Number {
+        text,
+        value: Some(value),
+        len: end - i,
+        ordinal,
+        spelled: true,
+    })
+}
+
+/// Digits, with thousands separators from 10,000 up
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    if value < 10_000 {
+        return digits;
+    }
+    let head = match digits.len() % 3 {
+        0 => 3,
+        n => n,
+    };
+    let mut grouped = digits[..head].to_string();
+    for group in digits.as_bytes()[head..].chunks(3) {
+        grouped.push(',');
+        grouped.push_str(std::str::from_utf8(group).unwrap_or_default());
+    }
+    grouped
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 100, value % 10) {
+        (11..=13, _) => "th",
+        (_, 1) => "st",
+        (_, 2) => "nd",
+        (_, 3) => "rd",
+        _ => "th",
+    }
+}
+
+/// A two-digit number spoken as one or two words ("nineteen", "eighty
+/// four")
+fn two_digit(words: &[Word], i: usize) -> Option<(usize, u64)> {
+    match number_word(&words[i].key)? {
+        (Kind::Teen, value, false) => Some((1, value)),
+        (Kind::Tens, tens, false) => match words.get(i + 1) {
+            Some(next) if joined(words, i + 1) => match number_word(&next.key) {
+                Some((Kind::Unit, unit, false)) => Some((2, tens + unit)),
+                _ => Some((1, tens)),
+            },
+            _ => Some((1, tens)),
+        },
+        _ => None,
+    }
+}
+
+/// A year read in pairs: "nineteen eighty four", "twenty oh five",
+/// "eighteen hundred"
+fn year_pair(words: &[Word], i: usize) -> Option<(usize, u64)> {
+    let (first_len, century) = two_digit(words, i).filter(|(_, v)| (10..30).contains(v))?;
+    let j = i + first_len;
+    if !joined(words, j) {
+        return None;
+    }
+    match words[j].key.as_str() {
+        "hundred" => Some((first_len + 1, century * 100)),
+        "oh" | "o" => {
+            let unit = words.get(j + 1).filter(|_| joined(words, j + 1))?;
+            match number_word(&unit.key)? {
+                (Kind::Unit, value, false) => Some((first_len + 2, century * 100 + value)),
+                _ => None,
+            }
+        }
+        _ => {
+            let (len, rest) = two_digit(words, j)?;
+            Some((first_len + len, century * 100 + rest))
+        }
+    }
+}
+
+/// A decade or century read in pairs: "nineteen nineties", "eighteen
+/// hundreds"
+fn decade(words: &[Word], i: usize) -> Option<Match> {
+    let (len, century) = two_digit(words, i).filter(|(_, v)| (10..30).contains(v))?;
+    let j = i + len;
+    if !joined(words, j) {
+        return None;
+    }
+    let rest = match words[j].key.as_str() {
+        "hundreds" => 0,
+        key => match number_word(&format!("{}y", key.strip_suffix("ies")?))? {
+            (Kind::Tens, tens, false) => tens,
+            _ => return None,
+        },
+    };
+    Some(Match::new(len + 1, format!("{}s", century * 100 + rest)))
+}
+
+/// A year after a date: digits, pairs or a cardinal ("two thousand five")
+fn year(words: &[Word], i: usize) -> Option<(usize, u64)> {
+    let core = words[i].core;
+    if core.len() == 4 && core.chars().all(|c| c.is_ascii_digit()) {
+        return core.parse().ok().map(|year| (1, year));
+    }
+    year_pair(words, i).or_else(|| {
+        let number = parse_cardinal(words, i)?;
+        number
+            .cardinal_value()
+            .filter(|v| (1000..3000).contains(v))
+            .map(|year| (number.len, year))
+    })
+}
+
+/// Whether the words before `i` announce a time: "at", "by", "until",
+/// "it's", "from", or "to" after "from"
+fn time_cue(words: &[Word], i: usize) -> bool {
+    if !joined(words, i) {
+        return false;
+    }
+    match words[i - 1].key.as_str() {
+        "at" | "by" | "until" | "till" | "it's" | "from" => true,
+        "to" => (0..i - 1)
+            .rev()
+            .take_while(|&j| joined(words, j + 1))
+            .any(|j| words[j].key == "from"),
+        _ => false,
+    }
+}
+
+/// "five p m", "ten thirty a.m.", "six oh five PM", "seven o'clock", and
+/// without a marker "six oh five" or, after a cue, "at ten thirty"
+fn time(words: &[Word], i: usize) -> Option<Match> {
+    let word = &words[i];
+    let (hour, mut minutes) = match word.core.split_once(':') {
+        Some((hour, minutes)) if minutes.len() == 2 => {
+            (hour.parse().ok()?, Some(minutes.parse::<u64>().ok()?))
+        }
+        Some(_) => return None,
+        None if word.core.chars().all(|c| c.is_ascii_digit()) => (word.core.parse().ok()?, None),
+        None => match number_word(&word.key)? {
+            (Kind::Unit | Kind::Teen, hour, false) => (hour, None),
+            _ => return None,
+        },
+    };
+    if !(1..=12).contains(&hour) || minutes.is_some_and(|m| m > 59) {
+        return None;
+    }
+
+    let mut j = i + 1;
+    let mut oclock = false;
+    let mut oh = false;
+    let spoken = minutes.is_none() && !word.core.starts_with(|c: char| c.is_ascii_digit());
+    if minutes.is_none() && joined(words, j) {
+        match words[j].key.as_str() {
+            "o'clock" => {
+                oclock = true;
+                j += 1;
+            }
+            "oh" | "o" => {
+                if let Some((Kind::Unit, value, false)) = words
+                    .get(j + 1)
+                    .filter(|_| joined(words, j + 1))
+                    .and_then(|w| number_word(&w.key))
+                {
+                    minutes = Some(value);
+                    oh = true;
+                    j += 2;
+                }
+            }
+            _ => {
+                if let Some(number) = parse_cardinal(words, j) {
+                    if let Some(value) = number.cardinal_value().filter(|v| (10..60).contains(v)) {
+                        minutes = Some(value);
+                        j += number.len;
+                    }
+                }
+            }
+        }
+    }
+
+    let marker = if joined(words, j) {
+        match words[j].key.as_str() {
+            "am" => Some(("AM", 1)),
+            "pm" => Some(("PM", 1)),
+            half @ ("a" | "p") if joined(words, j + 1) && words[j + 1].key == "m" => {
+                Some((if half == "a" { "AM" } else { "PM" }, 2))
+            }
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let clock = match minutes {
+        Some(minutes) => format!("{}:{:02}", hour, minutes),
+        None => hour.to_string(),
+    };
+    match marker {
+        Some((marker, len)) => {
+            let end = j + len;
+            let last = &words[end - 1];
+            let mut matched = Match::new(end - i, format!("{} {}", clock, marker));
+            // The dot of "p.m." isn't a full stop unless the text ends there
+            if last.core.contains('.') && last.trail.starts_with('.') && end < words.len() {
+                matched.skip_trail = 1;
+            }
+            Some(matched)
+        }
+        None if oclock => Some(Match::new(j - i, format!("{} o'clock", clock))),
+        // Two numbers alone are as often a count ("nine twenty dollar
+        // bills"), and more digits make a code ("nine oh two one oh")
+        None if spoken
+            && minutes.is_some()
+            && (oh || time_cue(words, i))
+            && !(joined(words, j) && number_word(&words[j].key).is_some()) =>
+        {
+            Some(Match::new(j - i, clock))
+        }
+        None => None,
+    }
+}
+
+/// A month name; Whisper capitalizes months, which keeps "may" and
+/// "march" the verbs out
+fn month(word: &Word) -> Option<&'static str> {
+    if !word.core.starts_with(char::is_uppercase) {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .find(|month| month.eq_ignore_ascii_case(word.core))
+        .copied()
+}
+
+/// "March fifth, twenty twenty four" or "the fifth of March"
+fn date(words: &[Word], i: usize) -> Option<Match> {
+    if let Some(month) = month(&words[i]) {
+        let j = i + 1;
+        if !joined(words, j) {
+            return None;
+        }
+        let day = parse_number(words, j)?;
+        let value = day.value.filter(|v| (1..=31).contains(v))?;
+        // "May one day" is not a date
+        if month == "May" && day.spelled && !day.ordinal {
+            return None;
+        }
+
+        let mut text = format!("{} {}", month, value);
+        let mut end = j + day.len;
+        let year_follows = joined(words, end)
+            || (end < words.len()
+                && words[end - 1].trail == ","
+                && words[end].sep == " "
+                && words[end].lead.is_empty());
+        if year_follows {
+            if let Some((len, year)) = year(words, end) {
+                text = format!("{}, {}", text, year);
+                end += len;
+            }
+        }
+        return Some(Match::new(end - i, text));
+    }
+
+    let day = parse_cardinal(words, i).filter(|day| day.ordinal)?;
+    day.value.filter(|v| (1..=31).contains(v))?;
+    let of = i + day.len;
+    if !joined(words, of) || words[of].key != "of" || !joined(words, of + 1) {
+        return None;
+    }
+    let month = month(&words[of + 1])?;
+    Some(Match::new(
+        day.len + 2,
+        format!("{} of {}", day.text, month),
+    ))
+}
+
+/// Four or more spoken digits: phone numbers, codes, PINs
+fn digit_sequence(words: &[Word], i: usize) -> Option<Match> {
+    // A leading "oh" is more likely the interjection
+    if words[i].key == "oh" || words[i].key == "o" {
+        return None;
+    }
+    let mut digits = String::new();
+    let mut j = i;
+    while j < words.len() && (j == i || joined(words, j)) {
+        match digit(&words[j].key) {
+            Some(d) => digits.push(d),
+            None => break,
+        }
+        j += 1;
+    }
+    if digits.len() < 4 {
+        return None;
+    }
+
+    let text = match digits.len() {
+        7 => format!("{}-{}", &digits[..3], &digits[3..]),
+        10 => format!("{}-{}-{}", &digits[..3], &digits[3..6], &digits[6..]),
+        11 if digits.starts_with('1') => {
+            format!("1-{}-{}-{}", &digits[1..4], &digits[4..7], &digits[7..])
+        }
+        _ => digits,
+    };
+    Some(Match::new(j - i, text))
+}
+
+/// A number followed by a currency, "percent" or a unit
+fn amount(words: &[Word], i: usize, number: &Number) -> Option<Match> {
+    if number.ordinal {
+        return None;
+    }
+    let j = i + number.len;
+    if !joined(words, j) {
+        return None;
+    }
+
+    let symbol = match words[j].key.as_str() {
+        "dollar" | "dollars" => Some("$"),
+        "euro" | "euros" => Some("€"),
+        "yen" => Some("¥"),
+        _ => None,
+    };
+    if let Some(symbol) = symbol {
+        return Some(currency(words, i, number, symbol));
+    }
+
+    match words[j].key.as_str() {
+        "cent" | "cents" => {
+            let cents = number.cardinal_value().filter(|v| *v < 100)?;
+            return Some(Match::new(number.len + 1, format!("{}¢", cents)));
+        }
+        "percent" => return Some(Match::new(number.len + 1, format!("{}%", number.text))),
+        "per" if joined(words, j + 1) && words[j + 1].key == "cent" => {
+            return Some(Match::new(number.len + 2, format!("{}%", number.text)));
+        }
+        _ => {}
+    }
+
+    let (unit, symbol) = MEASUREMENTS.iter().find(|(unit, _)| {
+        unit.iter()
+            .enumerate()
+            .all(|(k, part)| joined(words, j + k) && words[j + k].key == *part)
+    })?;
+    Some(Match::new(
+        number.len + unit.len(),
+        format!("{}{}", number.text, symbol),
+    ))
+}
+
+/// "five dollars", "five dollars and twenty cents", "one dollar fifty"
+fn currency(words: &[Word], i: usize, number: &Number, symbol: &str) -> Match {
+    let mut matched = Match::new(number.len + 1, format!("{}{}", symbol, number.text));
+
+    let mut start = i + matched.len;
+    let and = joined(words, start) && words[start].key == "and";
+    if and {
+        start += 1;
+    }
+    if number.value.is_none() || !joined(words, start) {
+        return matched;
+    }
+    let Some(cents) = parse_number(words, start) else {
+        return matched;
+    };
+    let unit = start + cents.len;
+    let is_cents = joined(words, unit) && matches!(words[unit].key.as_str(), "cent" | "cents");
+    // Without "and" or "cents" only a spoken two-digit number counts
+    let bare = !and && cents.spelled && symbol != "¥";
+    if let Some(value) = cents
+        .cardinal_value()
+        .filter(|v| *v < 100 && (is_cents || (bare && *v >= 10)))
+    {
+        matched.text = format!("{}.{:02}", matched.text, value);
+        matched.len = if is_cents { unit + 1 } else { unit } - i;
+    }
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(cases: &[(&str, &str)]) {
+        for (spoken, expected) in cases {
+            assert_eq!(normalize(spoken), *expected, "{:?}", spoken);
+        }
+    }
+
+    #[test]
+    fn test_cardinals() {
+        check(&[
+            ("twenty three people", "23 people"),
+            ("twenty-three people", "23 people"),
+            ("ten minutes", "10 minutes"),
+            ("one hundred", "100"),
+            ("two hundred and five", "205"),
+            ("nineteen hundred", "1900"),
+            ("three thousand four hundred", "3400"),
+            ("forty two thousand", "42,000"),
+            ("one million two hundred thousand", "1,200,000"),
+            ("three point one four", "3.14"),
+            ("zero point five", "0.5"),
+            ("one point five million", "1.5 million"),
+            ("Twenty people came.", "20 people came."),
+        ]);
+    }
+
+    #[test]
+    fn test_small_numbers_stay_words() {
+        check(&[
+            ("one of them", "one of them"),
+            ("I have two cats", "I have two cats"),
+            ("the first time", "the first time"),
+            ("one two", "one two"),
+            ("zero", "zero"),
+            ("rock and roll", "rock and roll"),
+            ("a hundred and", "a hundred and"),
+        ]);
+    }
+
+    #[test]
+    fn test_ordinals() {
+        check(&[
+            ("the twenty first century", "the 21st century"),
+            ("his thirteenth birthday", "his 13th birthday"),
+            ("the one hundredth time", "the 100th time"),
+            ("the forty second street", "the 42nd street"),
+            ("the second place", "the second place"),
+        ]);
+    }
+
+    #[test]
+    fn test_currency_and_percentages() {
+        check(&[
+            ("five dollars", "$5"),
+            ("it costs one dollar.", "it costs $1."),
+            ("five dollars and twenty five cents", "$5.25"),
+            ("ten dollars and five cents", "$10.05"),
+            ("fifty cents", "50¢"),
+            ("twenty euros", "€20"),
+            ("one point five million dollars", "$1.5 million"),
+            ("one dollar fifty", "$1.50"),
+            ("two euros twenty five please", "€2.25 please"),
+            ("five dollars twenty cents", "$5.20"),
+            ("fifty fifty", "fifty fifty"),
+            ("five dollars and a coffee", "$5 and a coffee"),
+            ("twenty percent", "20%"),
+            ("five per cent", "5%"),
+            ("3 percent", "3%"),
+        ]);
+    }
+
+    #[test]
+    fn test_times() {
+        check(&[
+            ("at five p m", "at 5 PM"),
+            ("at five p.m. tomorrow", "at 5 PM tomorrow"),
+            ("meet at five p.m.", "meet at 5 PM."),
+            ("ten thirty a m", "10:30 AM"),
+            ("six oh five PM", "6:05 PM"),
+            ("eleven forty five pm", "11:45 PM"),
+            ("seven o'clock", "7 o'clock"),
+            ("5 pm", "5 PM"),
+            ("5:30 a.m., then", "5:30 AM, then"),
+            ("thirteen p m", "13 p m"),
+            ("meet at ten thirty tomorrow", "meet at 10:30 tomorrow"),
+            ("it's seven thirty", "it's 7:30"),
+            ("at ten fifteen", "at 10:15"),
+            ("by eleven forty five", "by 11:45"),
+            ("from nine thirty to ten fifteen", "from 9:30 to 10:15"),
+            ("six oh five", "6:05"),
+            // Without a cue two numbers are a count or a range
+            ("ten thirty", "ten thirty"),
+            ("go to ten thirty", "go to ten thirty"),
+            ("I have nine twenty dollar bills", "I have nine $20 bills"),
+            (
+                "we need three fifty foot cables",
+                "we need three 50 foot cables",
+            ),
+            ("the score was eleven twelve", "the score was eleven twelve"),
+            (
+                "pages twelve fifteen and sixteen",
+                "pages twelve fifteen and 16",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_dates() {
+        check(&[
+            ("on March fifth", "on March 5"),
+            ("March fifth, twenty twenty four", "March 5, 2024"),
+            ("June 3 1999", "June 3, 1999"),
+            ("July fourth two thousand and five", "July 4, 2005"),
+            ("the fifth of November", "the 5th of November"),
+            ("May first", "May 1"),
+            ("May one day", "May one day"),
+            ("we march forward", "we march forward"),
+            ("in nineteen eighty four", "in 1984"),
+            ("twenty oh five", "2005"),
+            ("the nineteen nineties", "the 1990s"),
+            ("the eighteen hundreds", "the 1800s"),
+            ("the nineties", "the nineties"),
+        ]);
+    }
+
+    #[test]
+    fn test_digit_sequences() {
+        check(&[
+            ("call five five five one two three four", "call 555-1234"),
+            (
+                "five five five one two three four five six seven",
+                "555-123-4567",
+            ),
+            ("one eight hundred", "one 800"),
+            ("zip code nine oh two one oh", "zip code 90210"),
+            ("one two three", "one two three"),
+            ("oh one two three", "oh one two three"),
+        ]);
+    }
+
+    #[test]
+    fn test_measurements() {
+        check(&[
+            ("five kilometers", "5 km"),
+            ("twenty degrees celsius", "20°C"),
+            ("ninety degrees", "90°"),
+            ("sixty miles per hour", "60 mph"),
+            ("two gigabytes", "2 GB"),
+            ("two point five liters", "2.5 L"),
+            ("five miles", "five miles"),
+        ]);
+    }
+
+    #[test]
+    fn test_punctuation_and_spacing_are_kept() {
+        check(&[
+            ("twenty, thirty", "20, 30"),
+            ("(twenty three)", "(23)"),
+            ("eleven\n\ntwelve", "11\n\n12"),
+            ("  twenty  ", "  20  "),
+            ("five, dollars", "five, dollars"),
+        ]);
+    }
+}
@@ -0,0 +1,249 @@
+//! Inverse text normalization
+//!
+//! Whisper writes numbers inconsistently: the same dictation may come back
+//! as "twenty three" or "23", "five p m" or "5 PM". This stage rewrites
+//! spoken cardinals, ordinals, currencies, percentages, times, dates, phone
+//! numbers and units in written form. Rules are per language; text in a
+//! language without a rule set is left unchanged.
+
+mod en;
+
+/// Languages with a rule set
+pub const SUPPORTED_LANGUAGES: &[&str] = &["en"];
+
+/// Rule set used for a transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    English,
+}
+
+impl Language {
+    /// Rule set for a Whisper language code ("en", "en-US"); auto-detect
+    /// and unknown language fall back to English
+    fn from_code(code: Option<&str>) -> Option<Self> {
+        let code = match code {
+            None => return Some(Self::English),
+            Some(code) if code.is_empty() || code == "auto" => return Some(Self::English),
+            Some(code) => code.split(['-', '_']).next().unwrap_or(code),
+        };
+        match code.to_ascii_lowercase().as_str() {
+            "en" => Some(Self::English),
+            _ => None,
+        }
+    }
+}
+
+/// Rewrites spoken numbers in written form
+pub struct TextNormalizer {
+    language: Option<Language>,
+}
+
+impl TextNormalizer {
+    /// Create a normalizer for transcripts in `language`
+    pub fn new(enabled: bool, language: Option<&str>) -> Self {
+        let rules = if enabled {
+            Language::from_code(language)
+        } else {
+            None
+        };
+        if enabled && rules.is_none() {
+            tracing::debug!("No text normalization rules for language {:?}", language);
+        }
+        Self { language: rules }
+    }
+
+    /// Normalize a transcript
+    pub fn apply(&self, text: &str) -> String {
+        match self.language {
+            Some(Language::English) => en::normalize(text),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// A whitespace-separated token, split into the word and the punctuation
+/// around it
+#[derive(Debug)]
+struct Word<'a> {
+    /// Whitespace (or hyphen) before the token
+    sep: &'a str,
+    /// Punctuation before the word, such as an opening quote
+    lead: &'a str,
+    /// The word itself
+    core: &'a str,
+    /// Punctuation after the word
+    trail: &'a str,
+    /// Lowercase `core` without dots, for matching ("a.m." → "am")
+    key: String,
+}
+
+impl<'a> Word<'a> {
+    fn new(sep: &'a str, lead: &'a str, core: &'a str, trail: &'a str) -> Self {
+        let key = core.to_lowercase().replace('.', "").replace('’', "'");
+        Self {
+            sep,
+            lead,
+            core,
+            trail,
+            key,
+        }
+    }
+}
+
+/// Whether `words[index]` continues the phrase before it: only spaces or a
+/// hyphen between them and no punctuation in the way
+fn joined(words: &[Word], index: usize) -> bool {
+    index > 0
+        && index < words.len()
+        && !words[index].sep.is_empty()
+        && words[index].sep.chars().all(|c| c == ' ' || c == '-')
+        && words[index - 1].trail.is_empty()
+        && words[index].lead.is_empty()
+}
+
+/// Split text into words, keeping the whitespace between them. Hyphenated
+/// tokens are split when `splits` accepts every part ("twenty-three").
+/// Returns the words and any trailing whitespace.
+fn tokenize<'a>(text: &'a str, splits: impl Fn(&str) -> bool) -> (Vec<Word<'a>>, &'a str) {
+    let mut words = Vec::new();
+    let mut rest = text;
+    loop {
+        let Some(start) = rest.find(|c: char| !c.is_whitespace()) else {
+            return (words, rest);
+        };
+        let sep = &rest[..start];
+        let after = &rest[start..];
+        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+        let token = &after[..end];
+        rest = &after[end..];
+
+        let Some(core_start) = token.find(char::is_alphanumeric) else {
+            words.push(Word::new(sep, token, "", ""));
+            continue;
+        };
+        let core_end = token
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_alphanumeric())
+            .map_or(token.len(), |(i, c)| i + c.len_utf8());
+        let (lead, core, trail) = (
+            &token[..core_start],
+            &token[core_start..core_end],
+            &token[core_end..],
+        );
+
+        let parts: Vec<&str> = core.split('-').collect();
+        if parts.len() > 1 && parts.iter().all(|part| !part.is_empty() && splits(part)) {
+            let last = parts.len() - 1;
+            for (i, part) in parts.into_iter().enumerate() {
+                words.push(Word::new(
+                    if i == 0 { sep } else { "-" },
+                    if i == 0 { lead } else { "" },
+                    part,
+                    if i == last { trail } else { "" },
+                ));
+            }
+        } else {
+            words.push(Word::new(sep, lead, core, trail));
+        }
+    }
+}
+
+/// A run of words rewritten in written form
+struct Match {
+    /// Number of words replaced
+    len: usize,
+    /// Written form
+    text: String,
+    /// Bytes of the last word's trailing punctuation that belong to the
+    /// match (the final dot of "p.m.")
+    skip_trail: usize,
+}
+
+impl Match {
+    fn new(len: usize, text: String) -> Self {
+        Self {
+            len,
+            text,
+            skip_trail: 0,
+        }
+    }
+}
+
+/// Rebuild the text, replacing the runs `matcher` recognizes
+fn render(words: &[Word], tail: &str, matcher: impl Fn(&[Word], usize) -> Option<Match>) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < words.len() {
+        let word = &words[i];
+        out.push_str(word.sep);
+        out.push_str(word.lead);
+        match matcher(words, i) {
+            Some(matched) => {
+                out.push_str(&matched.text);
+                let last = &words[i + matched.len - 1];
+                out.push_str(&last.trail[matched.skip_trail..]);
+                i += matched.len;
+            }
+            None => {
+                out.push_str(word.core);
+                out.push_str(word.trail);
+                i += 1;
+            }
+        }
+    }
+    out.push_str(tail);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_selection() {
+        let cases = [
+            (None, Some(Language::English)),
+            (Some("auto"), Some(Language::English)),
+            (Some("en"), Some(Language::English)),
+            (Some("en-GB"), Some(Language::English)),
+            (Some("de"), None),
+        ];
+        for (code, expected) in cases {
+            assert_eq!(Language::from_code(code), expected, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_or_disabled_leaves_text() {
+        let text = "twenty three apples";
+        assert_eq!(TextNormalizer::new(true, Some("fr")).apply(text), text);
+        assert_eq!(TextNormalizer::new(false, Some("en")).apply(text), text);
+        assert_eq!(TextNormalizer::new(true, None).apply(text), "23 apples");
+    }
+
+    #[test]
+    fn test_tokenize_keeps_spacing_and_punctuation() {
+        let (words, tail) = tokenize("  \"Hello,\n\nworld!\" ", |_| false);
+        let parts: Vec<_> = words
+            .iter()
+            .map(|w| (w.sep, w.lead, w.core, w.trail))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![("  ", "\"", "Hello", ","), ("\n\n", "", "world", "!\"")]
+        );
+        assert_eq!(tail, " ");
+        assert_eq!(render(&words, tail, |_, _| None), "  \"Hello,\n\nworld!\" ");
+    }
+
+    #[test]
+    fn test_tokenize_splits_hyphenated_words() {
+        let (words, _) = tokenize("twenty-three well-known", |part| {
+            matches!(part, "twenty" | "three")
+        });
+        let cores: Vec<_> = words.iter().map(|w| w.core).collect();
+        assert_eq!(cores, vec!["twenty", "three", "well-known"]);
+        assert!(joined(&words, 1));
+    }
+}
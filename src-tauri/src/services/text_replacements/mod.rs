@@ -0,0 +1,352 @@
+//! Text replacement and snippet expansion
+//!
+//! User-defined rules rewrite transcripts after voice commands are parsed and
+//! before the text is injected. Literal rules fix recurring misrecognitions
+//! ("e z flow" → "EZ Flow"), regex rules handle patterns, and snippets expand
+//! a spoken trigger phrase ("insert signature") into a block of text.
+
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// What a rule's `find` text is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// Words matched literally; any whitespace matches between them
+    #[default]
+    Literal,
+    /// Regular expression; the replacement may use `$1`/`${name}` groups
+    Regex,
+    /// Spoken trigger phrase; trailing punctuation Whisper adds to the
+    /// phrase is replaced along with it
+    Snippet,
+}
+
+/// How a rule treats letter case
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseMode {
+    /// Match any case and insert the replacement as written
+    #[default]
+    Insensitive,
+    /// Match only the exact case
+    Sensitive,
+    /// Match any case and carry the match's case over to the replacement
+    /// (all caps stays all caps, a capitalized match capitalizes the
+    /// replacement)
+    Preserve,
+}
+
+/// A find/replace rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplacementRule {
+    /// Text, pattern or trigger phrase to find
+    pub find: String,
+    /// Text to insert instead (may span several lines)
+    pub replace: String,
+    /// How `find` is interpreted
+    pub kind: RuleKind,
+    /// How letter case is handled
+    pub case: CaseMode,
+    /// Only match whole words (literal rules and snippets)
+    pub whole_word: bool,
+    /// Whether the rule is applied
+    pub enabled: bool,
+}
+
+impl Default for ReplacementRule {
+    fn default() -> Self {
+        Self {
+            find: String::new(),
+            replace: String::new(),
+            kind: RuleKind::default(),
+            case: CaseMode::default(),
+            whole_word: true,
+            enabled: true,
+        }
+    }
+}
+
+impl ReplacementRule {
+    /// Compile the rule's pattern
+    fn compile(&self) -> Result<Regex, String> {
+        let find = self.find.trim();
+        if find.is_empty() {
+            return Err("Replacement rule has nothing to find".to_string());
+        }
+
+        let pattern = match self.kind {
+            RuleKind::Regex => find.to_string(),
+            RuleKind::Literal | RuleKind::Snippet => {
+                let words = find
+                    .split_whitespace()
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join(r"\s+");
+                let starts_word = find.starts_with(|c: char| c.is_alphanumeric());
+                let ends_word = find.ends_with(|c: char| c.is_alphanumeric());
+                let mut pattern = String::new();
+                if self.whole_word && starts_word {
+                    pattern.push_str(r"\b");
+                }
+                pattern.push_str(&words);
+                if self.whole_word && ends_word {
+                    pattern.push_str(r"\b");
+                }
+                if self.kind == RuleKind::Snippet {
+                    pattern.push_str(r"[.,!?]?");
+                }
+                pattern
+            }
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(self.case != CaseMode::Sensitive)
+            .build()
+            .map_err(|e| format!("Invalid pattern '{}': {}", self.find, e))
+    }
+}
+
+/// Text replacement settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplacementConfig {
+    /// Whether replacement rules are applied
+    pub enabled: bool,
+    /// Rules, applied in order
+    pub rules: Vec<ReplacementRule>,
+}
+
+impl Default for ReplacementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Check that a rule can be used
+pub fn validate_rule(rule: &ReplacementRule) -> Result<(), String> {
+    rule.compile().map(|_| ())
+}
+
+struct CompiledRule {
+    regex: Regex,
+    rule: ReplacementRule,
+}
+
+/// Applies a set of replacement rules to transcripts
+pub struct TextReplacer {
+    rules: Vec<CompiledRule>,
+}
+
+impl TextReplacer {
+    /// Compile the enabled rules; invalid rules are skipped with a warning
+    pub fn new(config: &ReplacementConfig) -> Self {
+        let rules = if config.enabled {
+            config
+                .rules
+                .iter()
+                .filter(|rule| rule.enabled)
+                .filter_map(|rule| match rule.compile() {
+                    Ok(regex) => Some(CompiledRule {
+                        regex,
+                        rule: rule.clone(),
+                    }),
+                    Err(e) => {
+                        tracing::warn!("Skipping replacement rule: {}", e);
+                        None
+                    }
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self { rules }
+    }
+
+    /// Whether there are no rules to apply
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Apply every rule in order
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for compiled in &self.rules {
+            if let Cow::Owned(replaced) = replace_all(compiled, &text) {
+                text = replaced;
+            }
+        }
+        text
+    }
+}
+
+fn replace_all<'t>(compiled: &CompiledRule, text: &'t str) -> Cow<'t, str> {
+    let rule = &compiled.rule;
+    match (rule.kind, rule.case) {
+        (RuleKind::Regex, CaseMode::Preserve) => {
+            compiled.regex.replace_all(text, |caps: &regex::Captures| {
+                let mut expanded = String::new();
+                caps.expand(&rule.replace, &mut expanded);
+                match_case(&caps[0], &expanded)
+            })
+        }
+        (RuleKind::Regex, _) => compiled.regex.replace_all(text, rule.replace.as_str()),
+        (RuleKind::Literal, CaseMode::Preserve) => {
+            compiled.regex.replace_all(text, |caps: &regex::Captures| {
+                match_case(&caps[0], &rule.replace)
+            })
+        }
+        _ => compiled
+            .regex
+            .replace_all(text, NoExpand(rule.replace.as_str())),
+    }
+}
+
+/// `replacement` with the case of `matched` carried over
+fn match_case(matched: &str, replacement: &str) -> String {
+    let mut letters = matched.chars().filter(|c| c.is_alphabetic()).peekable();
+    let Some(first) = letters.peek().copied() else {
+        return replacement.to_string();
+    };
+
+    let has_several = matched.chars().filter(|c| c.is_alphabetic()).count() > 1;
+    if has_several && letters.all(|c| !c.is_lowercase()) {
+        return replacement.to_uppercase();
+    }
+    if first.is_uppercase() {
+        let mut chars = replacement.chars();
+        return match chars.next() {
+            Some(c) => c.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        };
+    }
+    replacement.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: RuleKind, find: &str, replace: &str) -> ReplacementRule {
+        ReplacementRule {
+            find: find.to_string(),
+            replace: replace.to_string(),
+            kind,
+            ..Default::default()
+        }
+    }
+
+    fn apply(rules: Vec<ReplacementRule>, text: &str) -> String {
+        TextReplacer::new(&ReplacementConfig {
+            enabled: true,
+            rules,
+        })
+        .apply(text)
+    }
+
+    #[test]
+    fn test_literal_matches_words_and_any_spacing() {
+        let rules = vec![rule(RuleKind::Literal, "e z flow", "EZ Flow")];
+        assert_eq!(
+            apply(rules.clone(), "I use E Z  flow daily"),
+            "I use EZ Flow daily"
+        );
+        // Whole words only
+        assert_eq!(apply(rules, "see z flower"), "see z flower");
+    }
+
+    #[test]
+    fn test_literal_replacement_is_not_expanded() {
+        let rules = vec![rule(RuleKind::Literal, "price", "$1 each")];
+        assert_eq!(apply(rules, "the price"), "the $1 each");
+    }
+
+    #[test]
+    fn test_case_sensitive_rule() {
+        let mut sensitive = rule(RuleKind::Literal, "Go", "Golang");
+        sensitive.case = CaseMode::Sensitive;
+        assert_eq!(
+            apply(vec![sensitive], "Go is fun, let's go"),
+            "Golang is fun, let's go"
+        );
+    }
+
+    #[test]
+    fn test_preserve_case() {
+        let mut preserve = rule(RuleKind::Literal, "asap", "as soon as possible");
+        preserve.case = CaseMode::Preserve;
+        assert_eq!(
+            apply(vec![preserve.clone()], "Asap please, ASAP! asap."),
+            "As soon as possible please, AS SOON AS POSSIBLE! as soon as possible."
+        );
+    }
+
+    #[test]
+    fn test_regex_with_groups() {
+        let rules = vec![rule(RuleKind::Regex, r"(\d+) percent", "$1%")];
+        assert_eq!(apply(rules, "up 20 percent"), "up 20%");
+    }
+
+    #[test]
+    fn test_snippet_absorbs_trailing_punctuation() {
+        let rules = vec![rule(
+            RuleKind::Snippet,
+            "insert signature",
+            "Best regards,\nJane Doe",
+        )];
+        assert_eq!(
+            apply(rules, "Thanks for the update. Insert signature."),
+            "Thanks for the update. Best regards,\nJane Doe"
+        );
+    }
+
+    #[test]
+    fn test_rules_apply_in_order() {
+        let rules = vec![
+            rule(RuleKind::Literal, "k eights", "k8s"),
+            rule(RuleKind::Literal, "k8s", "Kubernetes"),
+        ];
+        assert_eq!(apply(rules, "deploy to k eights"), "deploy to Kubernetes");
+    }
+
+    #[test]
+    fn test_disabled_and_invalid_rules_are_skipped() {
+        let mut disabled = rule(RuleKind::Literal, "hello", "bye");
+        disabled.enabled = false;
+        let invalid = rule(RuleKind::Regex, "(unclosed", "x");
+        assert!(validate_rule(&invalid).is_err());
+        assert!(validate_rule(&rule(RuleKind::Literal, "  ", "x")).is_err());
+
+        let replacer = TextReplacer::new(&ReplacementConfig {
+            enabled: true,
+            rules: vec![disabled, invalid],
+        });
+        assert!(replacer.is_empty());
+        assert_eq!(replacer.apply("hello"), "hello");
+    }
+
+    #[test]
+    fn test_disabled_config_applies_nothing() {
+        let config = ReplacementConfig {
+            enabled: false,
+            rules: vec![rule(RuleKind::Literal, "hello", "bye")],
+        };
+        assert_eq!(TextReplacer::new(&config).apply("hello"), "hello");
+    }
+
+    #[test]
+    fn test_rule_defaults() {
+        let parsed: ReplacementRule =
+            serde_json::from_str(r#"{"find": "e z flow", "replace": "EZ Flow"}"#).unwrap();
+        assert_eq!(parsed.kind, RuleKind::Literal);
+        assert_eq!(parsed.case, CaseMode::Insensitive);
+        assert!(parsed.whole_word);
+        assert!(parsed.enabled);
+    }
+}
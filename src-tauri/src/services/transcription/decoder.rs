@@ -4,169 +4,214 @@
 
 use super::TranscriptionError;
 use crate::services::audio::processing::{resample_for_whisper, AudioBuffer};
+use crate::services::audio::{AudioError, RecordedAudio, RecordingSpool};
 use std::path::Path;
 use symphonia::core::audio::SampleBuffer;
-use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
-use symphonia::core::formats::FormatOptions;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::formats::{FormatOptions, FormatReader};
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 
 /// Decode an audio file to f32 samples at 16kHz mono
 pub fn decode_audio_file(path: &Path) -> Result<Vec<f32>, TranscriptionError> {
-    if !path.exists() {
-        return Err(TranscriptionError::InvalidAudioFile(format!(
-            "File not found: {}",
-            path.display()
-        )));
+    let mut stream = DecodedStream::open(path)?;
+
+    let mut samples: Vec<f32> = Vec::new();
+    while let Some(packet) = stream.next_mono()? {
+        samples.extend(packet);
     }
 
-    let file = std::fs::File::open(path)
-        .map_err(|e| TranscriptionError::InvalidAudioFile(format!("Failed to open file: {}", e)))?;
+    if samples.is_empty() {
+        return Err(TranscriptionError::InvalidAudioFile(
+            "No audio samples decoded".to_string(),
+        ));
+    }
 
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    tracing::debug!("Mono samples: {}", samples.len());
 
-    // Create format hint from file extension
-    let mut hint = Hint::new();
-    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-        hint.with_extension(ext);
-    }
+    // Resample to 16kHz if needed
+    let buffer = AudioBuffer::new(samples, stream.sample_rate);
+    let resampled = resample_for_whisper(buffer)
+        .map_err(|e| TranscriptionError::AudioError(format!("Resampling failed: {}", e)))?;
 
-    // Probe the format
-    let probed = symphonia::default::get_probe()
-        .format(
-            &hint,
-            mss,
-            &FormatOptions::default(),
-            &MetadataOptions::default(),
-        )
-        .map_err(|e| {
-            TranscriptionError::InvalidAudioFile(format!("Failed to probe format: {}", e))
-        })?;
+    tracing::info!(
+        "Audio decoded: {} samples at 16kHz ({:.2}s)",
+        resampled.len(),
+        resampled.len() as f32 / 16000.0
+    );
 
-    let mut format = probed.format;
+    Ok(resampled)
+}
 
-    // Find the first audio track
-    let track = format
-        .tracks()
-        .iter()
-        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-        .ok_or_else(|| TranscriptionError::InvalidAudioFile("No audio track found".to_string()))?;
+/// Decode an audio file to a mono recording at its own sample rate. Long
+/// files are spooled to disk, so they can be transcribed in windows.
+pub fn decode_audio_recording(path: &Path) -> Result<RecordedAudio, TranscriptionError> {
+    let mut stream = DecodedStream::open(path)?;
+    let audio_error = |e: AudioError| TranscriptionError::AudioError(e.to_string());
 
-    let track_id = track.id;
-    let codec_params = track.codec_params.clone();
+    let mut spool = RecordingSpool::with_defaults(stream.sample_rate);
+    while let Some(packet) = stream.next_mono()? {
+        spool.push(&packet).map_err(audio_error)?;
+    }
+    let recording = spool.finish().map_err(audio_error)?;
 
-    let sample_rate = codec_params
-        .sample_rate
-        .ok_or_else(|| TranscriptionError::InvalidAudioFile("Unknown sample rate".to_string()))?;
+    if recording.is_empty() {
+        return Err(TranscriptionError::InvalidAudioFile(
+            "No audio samples decoded".to_string(),
+        ));
+    }
+    tracing::info!("Audio decoded: {:.2}s", recording.duration_secs());
+    Ok(recording)
+}
 
-    let channels = codec_params.channels.map(|c| c.count()).unwrap_or(1);
+/// The first audio track of a file, decoded packet by packet
+struct DecodedStream {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    sample_rate: u32,
+    channels: usize,
+    sample_buf: Option<SampleBuffer<f32>>,
+}
 
-    tracing::debug!("Decoding audio: {} Hz, {} channels", sample_rate, channels);
+impl DecodedStream {
+    fn open(path: &Path) -> Result<Self, TranscriptionError> {
+        if !path.exists() {
+            return Err(TranscriptionError::InvalidAudioFile(format!(
+                "File not found: {}",
+                path.display()
+            )));
+        }
 
-    // Create decoder
-    let mut decoder = symphonia::default::get_codecs()
-        .make(&codec_params, &DecoderOptions::default())
-        .map_err(|e| {
-            TranscriptionError::InvalidAudioFile(format!("Failed to create decoder: {}", e))
+        let file = std::fs::File::open(path).map_err(|e| {
+            TranscriptionError::InvalidAudioFile(format!("Failed to open file: {}", e))
         })?;
 
-    // Decode all packets
-    let mut samples: Vec<f32> = Vec::new();
-    let mut sample_buf: Option<SampleBuffer<f32>> = None;
-
-    loop {
-        let packet = match format.next_packet() {
-            Ok(p) => p,
-            Err(symphonia::core::errors::Error::IoError(ref e))
-                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
-            {
-                break;
-            }
-            Err(symphonia::core::errors::Error::ResetRequired) => {
-                // Reset the decoder
-                decoder.reset();
-                continue;
-            }
-            Err(e) => {
-                tracing::warn!("Packet read error: {}", e);
-                break;
-            }
-        };
+        let mss = MediaSourceStream::new(Box::new(file), Default::default());
 
-        // Skip packets from other tracks
-        if packet.track_id() != track_id {
-            continue;
+        // Create format hint from file extension
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(ext);
         }
 
-        // Decode the packet
-        let decoded = match decoder.decode(&packet) {
-            Ok(d) => d,
-            Err(symphonia::core::errors::Error::DecodeError(e)) => {
-                tracing::warn!("Decode error: {}", e);
-                continue;
-            }
-            Err(e) => {
-                return Err(TranscriptionError::InvalidAudioFile(format!(
-                    "Failed to decode: {}",
-                    e
-                )));
-            }
-        };
+        // Probe the format
+        let probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                mss,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(|e| {
+                TranscriptionError::InvalidAudioFile(format!("Failed to probe format: {}", e))
+            })?;
 
-        // Get or create sample buffer
-        if sample_buf.is_none() {
-            let spec = *decoded.spec();
-            let duration = decoded.capacity() as u64;
-            sample_buf = Some(SampleBuffer::new(duration, spec));
-        }
+        let format = probed.format;
 
-        if let Some(ref mut buf) = sample_buf {
-            buf.copy_interleaved_ref(decoded);
-            samples.extend_from_slice(buf.samples());
-        }
-    }
+        // Find the first audio track
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or_else(|| {
+                TranscriptionError::InvalidAudioFile("No audio track found".to_string())
+            })?;
 
-    if samples.is_empty() {
-        return Err(TranscriptionError::InvalidAudioFile(
-            "No audio samples decoded".to_string(),
-        ));
-    }
+        let track_id = track.id;
+        let codec_params = track.codec_params.clone();
 
-    tracing::debug!("Decoded {} raw samples", samples.len());
+        let sample_rate = codec_params.sample_rate.ok_or_else(|| {
+            TranscriptionError::InvalidAudioFile("Unknown sample rate".to_string())
+        })?;
 
-    // Convert stereo to mono if needed
-    let mono_samples = if channels > 1 {
-        tracing::debug!("Converting {} channels to mono", channels);
-        // Interleaved samples: chunk by channels and average
-        samples
-            .chunks(channels)
-            .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
-            .collect()
-    } else {
-        samples
-    };
+        let channels = codec_params.channels.map(|c| c.count()).unwrap_or(1);
 
-    tracing::debug!("Mono samples: {}", mono_samples.len());
+        tracing::debug!("Decoding audio: {} Hz, {} channels", sample_rate, channels);
 
-    // Resample to 16kHz if needed
-    let buffer = AudioBuffer::new(mono_samples, sample_rate);
-    let resampled = resample_for_whisper(buffer)
-        .map_err(|e| TranscriptionError::AudioError(format!("Resampling failed: {}", e)))?;
+        // Create decoder
+        let decoder = symphonia::default::get_codecs()
+            .make(&codec_params, &DecoderOptions::default())
+            .map_err(|e| {
+                TranscriptionError::InvalidAudioFile(format!("Failed to create decoder: {}", e))
+            })?;
 
-    tracing::info!(
-        "Audio decoded: {} samples at 16kHz ({:.2}s)",
-        resampled.len(),
-        resampled.len() as f32 / 16000.0
-    );
+        Ok(Self {
+            format,
+            decoder,
+            track_id,
+            sample_rate,
+            channels,
+            sample_buf: None,
+        })
+    }
 
-    Ok(resampled)
+    /// Decode the next packet of the track, mixed down to mono
+    fn next_mono(&mut self) -> Result<Option<Vec<f32>>, TranscriptionError> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(p) => p,
+                Err(symphonia::core::errors::Error::IoError(ref e))
+                    if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(None);
+                }
+                Err(symphonia::core::errors::Error::ResetRequired) => {
+                    // Reset the decoder
+                    self.decoder.reset();
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Packet read error: {}", e);
+                    return Ok(None);
+                }
+            };
+
+            // Skip packets from other tracks
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            // Decode the packet
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(d) => d,
+                Err(symphonia::core::errors::Error::DecodeError(e)) => {
+                    tracing::warn!("Decode error: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    return Err(TranscriptionError::InvalidAudioFile(format!(
+                        "Failed to decode: {}",
+                        e
+                    )));
+                }
+            };
+
+            // Get or create sample buffer
+            let buf = self.sample_buf.get_or_insert_with(|| {
+                SampleBuffer::new(decoded.capacity() as u64, *decoded.spec())
+            });
+            buf.copy_interleaved_ref(decoded);
+
+            // Interleaved samples: chunk by channels and average
+            let samples = if self.channels > 1 {
+                buf.samples()
+                    .chunks(self.channels)
+                    .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
+                    .collect()
+            } else {
+                buf.samples().to_vec()
+            };
+            return Ok(Some(samples));
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::services::audio::spool::DEFAULT_SPILL_THRESHOLD_SECS;
 
     #[test]
     fn test_decode_nonexistent_file() {
@@ -189,4 +234,42 @@ mod tests {
 
         std::fs::remove_file(test_file).ok();
     }
+
+    fn write_wav(path: &Path, channels: u16, sample_rate: u32, frames: usize) {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for i in 0..frames * channels as usize {
+            writer.write_sample((i % 100) as i16 * 100).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn test_decode_audio_recording_mixes_to_mono() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("short.wav");
+        write_wav(&path, 2, 8000, 8000);
+
+        let recording = decode_audio_recording(&path).unwrap();
+        assert!(!recording.is_spilled());
+        assert_eq!(recording.sample_rate(), 8000);
+        assert_eq!(recording.len(), 8000);
+    }
+
+    #[test]
+    fn test_decode_audio_recording_spools_long_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("long.wav");
+        let frames = 8000 * (DEFAULT_SPILL_THRESHOLD_SECS as usize + 5);
+        write_wav(&path, 1, 8000, frames);
+
+        let recording = decode_audio_recording(&path).unwrap();
+        assert!(recording.is_spilled());
+        assert_eq!(recording.len(), frames as u64);
+    }
 }
@@ -4,9 +4,14 @@
 
 use super::gpu::{detect_gpu_backend, GpuBackend};
 use super::{ModelError, TranscriptionError, TranscriptionResult};
-use crate::services::audio::AudioChunk;
+use crate::services::audio::dsp::{self, DspConfig};
+use crate::services::audio::spool::{TRANSCRIPTION_WINDOW_OVERLAP_SECS, TRANSCRIPTION_WINDOW_SECS};
+use crate::services::audio::{resample_for_whisper, AudioBuffer, AudioChunk, RecordedAudio};
+use std::borrow::Cow;
+use std::future::Future;
 use std::path::Path;
 use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::Mutex;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
@@ -25,6 +30,15 @@ pub struct ChunkTranscriptionResult {
     pub timestamp_ms: u64,
 }
 
+/// Language and task for decoding speech
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Spoken language code (None = auto-detect)
+    pub language: Option<String>,
+    /// Translate the speech to English instead of transcribing it
+    pub translate: bool,
+}
+
 /// Whisper transcription engine
 pub struct WhisperEngine {
     ctx: Option<WhisperContext>,
@@ -33,6 +47,10 @@ pub struct WhisperEngine {
     gpu_backend: GpuBackend,
     /// Whether GPU is currently being used for inference
     using_gpu: bool,
+    /// Audio preprocessing applied before inference
+    preprocessing: DspConfig,
+    /// Language and task for full transcriptions
+    decoding: DecodeOptions,
 }
 
 impl WhisperEngine {
@@ -44,14 +62,52 @@ impl WhisperEngine {
             model_id: String::new(),
             gpu_backend,
             using_gpu: false,
+            preprocessing: DspConfig::default(),
+            decoding: DecodeOptions::default(),
         }
     }
 
+    /// Set the audio preprocessing configuration
+    pub fn set_preprocessing(&mut self, config: DspConfig) {
+        self.preprocessing = config;
+    }
+
+    /// Get the audio preprocessing configuration
+    pub fn preprocessing(&self) -> &DspConfig {
+        &self.preprocessing
+    }
+
+    /// Set the language and task for full transcriptions
+    pub fn set_decoding(&mut self, options: DecodeOptions) {
+        self.decoding = options;
+    }
+
+    /// Get the language and task for full transcriptions
+    pub fn decoding(&self) -> &DecodeOptions {
+        &self.decoding
+    }
+
+    /// Run the preprocessing chain over 16kHz samples, borrowing when disabled
+    fn preprocess<'a>(&self, audio: &'a [f32]) -> Cow<'a, [f32]> {
+        if !self.preprocessing.enabled {
+            return Cow::Borrowed(audio);
+        }
+        let mut processed = audio.to_vec();
+        dsp::preprocess(&mut processed, &self.preprocessing, 16000);
+        Cow::Owned(processed)
+    }
+
     /// Check if a model is loaded
     pub fn is_loaded(&self) -> bool {
         self.ctx.is_some()
     }
 
+    /// Check if the given model (by ID such as "base") is the one loaded
+    pub fn has_model(&self, model_id: &str) -> bool {
+        self.is_loaded()
+            && (self.model_id == model_id || self.model_id == format!("ggml-{}", model_id))
+    }
+
     /// Get the currently loaded model ID
     pub fn model_id(&self) -> &str {
         &self.model_id
@@ -171,7 +227,8 @@ impl WhisperEngine {
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
-        params.set_language(Some("auto"));
+        params.set_language(Some(self.decoding.language.as_deref().unwrap_or("auto")));
+        params.set_translate(self.decoding.translate);
 
         // Set initial prompt if provided
         if let Some(prompt) = initial_prompt {
@@ -179,13 +236,15 @@ impl WhisperEngine {
             tracing::debug!("Set initial prompt for transcription");
         }
 
+        let audio = self.preprocess(audio);
+
         // Create state and run inference
         let mut state = ctx
             .create_state()
             .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))?;
 
         state
-            .full(params, audio)
+            .full(params, &audio)
             .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))?;
 
         let inference_duration = start_time.elapsed();
@@ -308,13 +367,15 @@ impl WhisperEngine {
             }
         }
 
+        let samples = self.preprocess(&chunk.samples);
+
         // Create state and run inference
         let mut state = ctx
             .create_state()
             .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))?;
 
         state
-            .full(params, &chunk.samples)
+            .full(params, &samples)
             .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))?;
 
         let inference_duration = start_time.elapsed();
@@ -465,6 +526,191 @@ pub fn build_initial_prompt(
     }
 }
 
+/// Build the prompt for a transcription window from the user prompt and the
+/// tail of the text transcribed so far
+fn window_prompt(initial_prompt: Option<&str>, previous_text: Option<&str>) -> Option<String> {
+    // Whisper prompts are limited to ~224 tokens; keep the carried-over text short
+    const MAX_CONTEXT_CHARS: usize = 200;
+
+    let context = previous_text.filter(|t| !t.is_empty()).map(|text| {
+        let skip = text.chars().count().saturating_sub(MAX_CONTEXT_CHARS);
+        text.chars().skip(skip).collect::<String>()
+    });
+
+    match (initial_prompt.filter(|p| !p.is_empty()), context) {
+        (Some(prompt), Some(context)) => Some(format!("{}. {}", prompt, context)),
+        (Some(prompt), None) => Some(prompt.to_string()),
+        (None, Some(context)) => Some(context),
+        (None, None) => None,
+    }
+}
+
+/// Join the texts of two overlapping windows, dropping the words the second
+/// one repeats. The seam is the longest run of at least two words shared by
+/// the end of `previous` and the start of `next`; without one the texts are
+/// concatenated.
+fn join_window_text(previous: &str, next: &str) -> String {
+    // Words considered on each side, comfortably more than the overlap holds
+    const SEAM_WORDS: usize = 20;
+    const MIN_RUN: usize = 2;
+
+    let prev_words = word_spans(previous);
+    let next_words = word_spans(next);
+    let prev_start = prev_words.len().saturating_sub(SEAM_WORDS);
+    let normalized = |text: &str| -> String {
+        text.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let prev_tail: Vec<String> = prev_words[prev_start..]
+        .iter()
+        .map(|&(start, end)| normalized(&previous[start..end]))
+        .collect();
+    let next_head: Vec<String> = next_words
+        .iter()
+        .take(SEAM_WORDS)
+        .map(|&(start, end)| normalized(&next[start..end]))
+        .collect();
+
+    // (index in prev_tail, index in next_head, length) of the longest run
+    let mut best = (0, 0, 0);
+    for i in 0..prev_tail.len() {
+        for j in 0..next_head.len() {
+            let len = prev_tail[i..]
+                .iter()
+                .zip(&next_head[j..])
+                .take_while(|(a, b)| !a.is_empty() && a == b)
+                .count();
+            if len > best.2 {
+                best = (i, j, len);
+            }
+        }
+    }
+
+    let (i, j, len) = best;
+    if len < MIN_RUN {
+        return match (previous.is_empty(), next.is_empty()) {
+            (_, true) => previous.to_string(),
+            (true, false) => next.to_string(),
+            (false, false) => format!("{} {}", previous, next),
+        };
+    }
+
+    let kept = &previous[..prev_words[prev_start + i + len - 1].1];
+    match next_words.get(j + len) {
+        Some(&(start, _)) => format!("{} {}", kept, &next[start..]),
+        None => kept.to_string(),
+    }
+}
+
+/// Byte ranges of the whitespace-separated words of a text
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    text.split_whitespace()
+        .map(|word| {
+            let start = word.as_ptr() as usize - text.as_ptr() as usize;
+            (start, start + word.len())
+        })
+        .collect()
+}
+
+/// Text of the windows of a recording transcribed so far
+#[derive(Debug, Default)]
+struct WindowedText {
+    /// Number of windows done, including skipped slivers
+    windows: usize,
+    combined: Option<TranscriptionResult>,
+}
+
+impl WindowedText {
+    /// Prompt for the next window, ending with the text so far
+    fn prompt(&self, initial_prompt: Option<&str>) -> Option<String> {
+        window_prompt(initial_prompt, self.combined.as_ref().map(|r| r.text.as_str()))
+    }
+
+    /// Add the transcription of the next window
+    fn push(&mut self, result: TranscriptionResult) {
+        tracing::debug!("Transcribed window {}: {} chars", self.windows, result.text.len());
+        self.windows += 1;
+        self.combined = Some(match self.combined.take() {
+            None => result,
+            Some(mut acc) => {
+                acc.text = join_window_text(&acc.text, &result.text);
+                acc.duration_ms += result.duration_ms;
+                acc.language = acc.language.or(result.language);
+                acc
+            }
+        });
+    }
+
+    fn finish(self) -> Result<TranscriptionResult, TranscriptionError> {
+        self.combined
+            .ok_or_else(|| TranscriptionError::InvalidAudioFile("Empty audio buffer".to_string()))
+    }
+}
+
+/// Model, prompt, language and task a recording is transcribed with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptionJob {
+    pub model_id: String,
+    pub initial_prompt: Option<String>,
+    /// Language and task for exactly this model (None = the loaded model
+    /// with the configured decoding)
+    pub decoding: Option<DecodeOptions>,
+}
+
+/// The first windows of a long recording, transcribed while it was recorded
+#[derive(Debug)]
+pub struct PartialTranscription {
+    job: TranscriptionJob,
+    text: WindowedText,
+}
+
+/// Transcribe a recording with `transcribe`, in one pass when it is in
+/// memory and in overlapping windows when it was spilled to disk. Windows
+/// already in `text` are not transcribed again.
+async fn transcribe_recording<F, Fut>(
+    recording: &RecordedAudio,
+    initial_prompt: Option<&str>,
+    mut text: WindowedText,
+    mut transcribe: F,
+) -> Result<TranscriptionResult, TranscriptionError>
+where
+    F: FnMut(Vec<f32>, Option<String>) -> Fut,
+    Fut: Future<Output = Result<TranscriptionResult, TranscriptionError>>,
+{
+    if !recording.is_spilled() {
+        let samples = recording
+            .to_whisper_samples()
+            .map_err(|e| TranscriptionError::AudioError(e.to_string()))?;
+        return transcribe(samples, initial_prompt.map(str::to_string)).await;
+    }
+
+    tracing::info!(
+        "Transcribing long recording ({:.1}s) in {}s windows, {} done while recording",
+        recording.duration_secs(),
+        TRANSCRIPTION_WINDOW_SECS,
+        text.windows
+    );
+
+    let windows = recording
+        .overlapping_whisper_windows(TRANSCRIPTION_WINDOW_SECS, TRANSCRIPTION_WINDOW_OVERLAP_SECS)
+        .skip_windows(text.windows);
+    for window in windows {
+        let samples = window.map_err(|e| TranscriptionError::AudioError(e.to_string()))?;
+        // Whisper rejects very short buffers; skip a trailing sliver of audio
+        if samples.len() < 1600 {
+            text.windows += 1;
+            continue;
+        }
+
+        let prompt = text.prompt(initial_prompt);
+        text.push(transcribe(samples, prompt).await?);
+    }
+
+    text.finish()
+}
+
 impl Default for WhisperEngine {
     fn default() -> Self {
         Self::new()
@@ -474,12 +720,16 @@ impl Default for WhisperEngine {
 /// Thread-safe wrapper for the Whisper engine
 pub struct SharedWhisperEngine {
     inner: Arc<Mutex<WhisperEngine>>,
+    /// Engine for a model other than the loaded one, kept between
+    /// [`SharedWhisperEngine::transcribe_with_model`] calls
+    side: Arc<Mutex<Option<WhisperEngine>>>,
 }
 
 impl SharedWhisperEngine {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(WhisperEngine::new())),
+            side: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -492,6 +742,7 @@ impl SharedWhisperEngine {
     pub async fn unload_model(&self) {
         let mut engine = self.inner.lock().await;
         engine.unload_model();
+        *self.side.lock().await = None;
     }
 
     pub async fn is_loaded(&self) -> bool {
@@ -504,6 +755,12 @@ impl SharedWhisperEngine {
         engine.model_id().to_string()
     }
 
+    /// Update the audio preprocessing configuration
+    pub async fn set_preprocessing(&self, config: DspConfig) {
+        let mut engine = self.inner.lock().await;
+        engine.set_preprocessing(config);
+    }
+
     pub async fn transcribe(
         &self,
         audio: Vec<f32>,
@@ -575,6 +832,191 @@ impl SharedWhisperEngine {
         engine.transcribe_with_prompt(&audio, initial_prompt)
     }
 
+    /// Transcribe with a specific model without replacing the loaded one
+    ///
+    /// Uses the shared engine when it already has `model_id` loaded. Otherwise
+    /// a side engine runs the call on a blocking thread, so re-transcribing
+    /// with a larger model doesn't change the dictation model. The side engine
+    /// keeps its model until another one is requested, so a batch of calls
+    /// with the same model loads it once. `preprocessing` overrides the
+    /// configured DSP chain and `decoding` the language and task for this call.
+    pub async fn transcribe_with_model(
+        &self,
+        audio: Vec<f32>,
+        model_id: &str,
+        initial_prompt: Option<&str>,
+        preprocessing: Option<DspConfig>,
+        decoding: Option<DecodeOptions>,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let mut engine = self.inner.lock().await;
+
+        if engine.has_model(model_id) {
+            let configured = engine.preprocessing().clone();
+            let configured_decoding = engine.decoding().clone();
+            if let Some(config) = preprocessing {
+                engine.set_preprocessing(config);
+            }
+            if let Some(options) = decoding {
+                engine.set_decoding(options);
+            }
+            let result = engine.transcribe_with_prompt(&audio, initial_prompt);
+            engine.set_preprocessing(configured);
+            engine.set_decoding(configured_decoding);
+            return result;
+        }
+
+        let preprocessing = preprocessing.unwrap_or_else(|| engine.preprocessing().clone());
+        let decoding = decoding.unwrap_or_else(|| engine.decoding().clone());
+        drop(engine);
+
+        let mut side = self.side.lock().await;
+        let cached = side.take().filter(|engine| engine.has_model(model_id));
+        let model_path = super::get_model_path(model_id);
+        if cached.is_none() && !model_path.exists() {
+            return Err(ModelError::NotFound(model_path).into());
+        }
+
+        let initial_prompt = initial_prompt.map(str::to_string);
+        let (engine, result) = tokio::task::spawn_blocking(move || {
+            let mut engine = match cached {
+                Some(engine) => engine,
+                None => {
+                    let mut engine = WhisperEngine::new();
+                    engine.load_model(&model_path)?;
+                    engine
+                }
+            };
+            engine.set_preprocessing(preprocessing);
+            engine.set_decoding(decoding);
+            let result = engine.transcribe_with_prompt(&audio, initial_prompt.as_deref());
+            Ok::<_, TranscriptionError>((engine, result))
+        })
+        .await
+        .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))??;
+
+        *side = Some(engine);
+        result
+    }
+
+    /// Transcribe a finished recording with automatic model loading
+    ///
+    /// In-memory recordings are transcribed in a single pass. Recordings that
+    /// were spilled to disk are read and transcribed in overlapping windows so
+    /// that long sessions never have to be loaded into memory at once; the
+    /// tail of each window's text is carried into the next window's prompt
+    /// and the words repeated at the seam are dropped.
+    pub async fn transcribe_recording_with_auto_load(
+        &self,
+        recording: &RecordedAudio,
+        model_id: &str,
+        initial_prompt: Option<&str>,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let text = WindowedText::default();
+        transcribe_recording(recording, initial_prompt, text, |samples, prompt| async move {
+            self.transcribe_with_auto_load_and_prompt(samples, model_id, prompt.as_deref())
+                .await
+        })
+        .await
+    }
+
+    /// Transcribe a finished recording with a specific model, in windows
+    /// like [`SharedWhisperEngine::transcribe_recording_with_auto_load`].
+    /// `preprocessing` and `decoding` override the settings as in
+    /// [`SharedWhisperEngine::transcribe_with_model`].
+    pub async fn transcribe_recording_with_model(
+        &self,
+        recording: &RecordedAudio,
+        model_id: &str,
+        initial_prompt: Option<&str>,
+        preprocessing: Option<DspConfig>,
+        decoding: Option<DecodeOptions>,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let (preprocessing, decoding) = (&preprocessing, &decoding);
+        let text = WindowedText::default();
+        transcribe_recording(recording, initial_prompt, text, |samples, prompt| async move {
+            self.transcribe_with_model(
+                samples,
+                model_id,
+                prompt.as_deref(),
+                preprocessing.clone(),
+                decoding.clone(),
+            )
+            .await
+        })
+        .await
+    }
+
+    /// Transcribe audio for `job`
+    async fn transcribe_job(
+        &self,
+        audio: Vec<f32>,
+        job: &TranscriptionJob,
+        initial_prompt: Option<&str>,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        match &job.decoding {
+            Some(decoding) => {
+                self.transcribe_with_model(
+                    audio,
+                    &job.model_id,
+                    initial_prompt,
+                    None,
+                    Some(decoding.clone()),
+                )
+                .await
+            }
+            None => {
+                self.transcribe_with_auto_load_and_prompt(audio, &job.model_id, initial_prompt)
+                    .await
+            }
+        }
+    }
+
+    /// Transcribe the windows of a recording in progress as they arrive
+    /// from [`RecordingSpool::watch_windows`], until the recording stops.
+    /// [`SharedWhisperEngine::transcribe_recording_job`] then only has to
+    /// transcribe the rest.
+    ///
+    /// [`RecordingSpool::watch_windows`]: crate::services::audio::RecordingSpool::watch_windows
+    pub async fn transcribe_windows(
+        &self,
+        mut windows: UnboundedReceiver<AudioBuffer>,
+        job: TranscriptionJob,
+    ) -> Result<PartialTranscription, TranscriptionError> {
+        let mut text = WindowedText::default();
+        while let Some(window) = windows.recv().await {
+            let samples = resample_for_whisper(window)
+                .map_err(|e| TranscriptionError::AudioError(e.to_string()))?;
+            let prompt = text.prompt(job.initial_prompt.as_deref());
+            text.push(self.transcribe_job(samples, &job, prompt.as_deref()).await?);
+        }
+        Ok(PartialTranscription { job, text })
+    }
+
+    /// Transcribe a finished recording for `job` like
+    /// [`SharedWhisperEngine::transcribe_recording_with_auto_load`],
+    /// continuing after the windows of `partial` if it was transcribed for
+    /// the same job
+    pub async fn transcribe_recording_job(
+        &self,
+        recording: &RecordedAudio,
+        job: &TranscriptionJob,
+        partial: Option<PartialTranscription>,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let text = match partial {
+            Some(partial) if partial.job == *job => partial.text,
+            Some(_) => {
+                tracing::info!("Settings changed while recording, transcribing from the start");
+                WindowedText::default()
+            }
+            None => WindowedText::default(),
+        };
+        let initial_prompt = job.initial_prompt.as_deref();
+        transcribe_recording(recording, initial_prompt, text, |samples, prompt| async move {
+            self.transcribe_job(samples, job, prompt.as_deref()).await
+        })
+        .await
+    }
+
     /// Transcribe a single audio chunk for streaming
     ///
     /// This method is optimized for incremental transcription during recording.
@@ -638,6 +1080,7 @@ impl Clone for SharedWhisperEngine {
     fn clone(&self) -> Self {
         Self {
             inner: Arc::clone(&self.inner),
+            side: Arc::clone(&self.side),
         }
     }
 }
@@ -696,6 +1139,19 @@ mod tests {
         assert!(matches!(result, Err(TranscriptionError::ModelNotLoaded)));
     }
 
+    #[tokio::test]
+    async fn test_transcribe_with_model_missing_model() {
+        let engine = SharedWhisperEngine::new();
+        let result = engine
+            .transcribe_with_model(vec![0.0; 16000], "nonexistent_model", None, None, None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(TranscriptionError::ModelError(ModelError::NotFound(_)))
+        ));
+        assert!(engine.side.lock().await.is_none());
+    }
+
     #[tokio::test]
     async fn test_shared_engine_transcribe_without_model() {
         let engine = SharedWhisperEngine::new();
@@ -755,6 +1211,93 @@ mod tests {
         assert_eq!(result, Some("Legal proceedings transcription".to_string()));
     }
 
+    #[test]
+    fn test_window_prompt_first_window() {
+        assert_eq!(window_prompt(None, None), None);
+        assert_eq!(
+            window_prompt(Some("Kubernetes"), None),
+            Some("Kubernetes".to_string())
+        );
+    }
+
+    #[test]
+    fn test_window_prompt_carries_previous_text() {
+        let result = window_prompt(Some("Kubernetes"), Some("we deployed the cluster"));
+        assert_eq!(result, Some("Kubernetes. we deployed the cluster".to_string()));
+    }
+
+    #[test]
+    fn test_window_prompt_truncates_long_context() {
+        let previous = "word ".repeat(100);
+        let result = window_prompt(None, Some(&previous)).unwrap();
+        assert_eq!(result.chars().count(), 200);
+        assert!(previous.ends_with(&result));
+    }
+
+    #[test]
+    fn test_join_window_text_drops_repeated_seam() {
+        // The first window ends mid-word, the second hears it whole
+        assert_eq!(
+            join_window_text(
+                "we deployed the cluster to",
+                "the cluster today and it worked"
+            ),
+            "we deployed the cluster today and it worked"
+        );
+        // Case and punctuation don't hide the repeat
+        assert_eq!(
+            join_window_text(
+                "Then we left the office.",
+                "We left the office, and went home."
+            ),
+            "Then we left the office. and went home."
+        );
+        assert_eq!(
+            join_window_text("it all went fine", "went fine"),
+            "it all went fine"
+        );
+    }
+
+    #[test]
+    fn test_windowed_text_continues_prompt() {
+        let result = |text: &str| TranscriptionResult {
+            text: text.to_string(),
+            duration_ms: 100,
+            model_id: "base".to_string(),
+            language: None,
+            gpu_used: false,
+        };
+        let mut text = WindowedText::default();
+        assert_eq!(text.prompt(Some("Vocab")).as_deref(), Some("Vocab"));
+
+        text.push(result("we went to the market"));
+        assert_eq!(
+            text.prompt(Some("Vocab")).as_deref(),
+            Some("Vocab. we went to the market")
+        );
+        text.push(result("the market and bought bread"));
+        assert_eq!(text.windows, 2);
+
+        let combined = text.finish().unwrap();
+        assert_eq!(combined.text, "we went to the market and bought bread");
+        assert_eq!(combined.duration_ms, 200);
+    }
+
+    #[test]
+    fn test_join_window_text_without_seam() {
+        assert_eq!(
+            join_window_text("hello there", "general kenobi"),
+            "hello there general kenobi"
+        );
+        // A single shared word is too weak a match
+        assert_eq!(
+            join_window_text("I said yes", "yes we can"),
+            "I said yes yes we can"
+        );
+        assert_eq!(join_window_text("", "start"), "start");
+        assert_eq!(join_window_text("end", ""), "end");
+    }
+
     #[test]
     fn test_estimate_chunk_confidence_empty_text() {
         let confidence = estimate_chunk_confidence("", 2.0, 0);
@@ -8,8 +8,11 @@ pub mod gpu;
 pub mod languages;
 pub mod models;
 
-pub use decoder::decode_audio_file;
-pub use engine::{build_initial_prompt, ChunkTranscriptionResult, WhisperEngine};
+pub use decoder::{decode_audio_file, decode_audio_recording};
+pub use engine::{
+    build_initial_prompt, ChunkTranscriptionResult, DecodeOptions, PartialTranscription,
+    TranscriptionJob, WhisperEngine,
+};
 pub use gpu::{detect_gpu_backend, is_gpu_available, GpuBackend, GpuInfo};
 pub use languages::{get_language_by_code, get_languages, is_valid_language_code, Language};
 pub use models::{
@@ -4,13 +4,13 @@
 
 use crate::commands::audio::{AudioCommand, AudioResponse, AudioState};
 use crate::commands::TranscriptionState;
-use crate::models::HistoryEntry;
-use crate::services::audio::processing::resample_for_whisper;
-use crate::services::storage::{DatabaseState, SettingsState};
+use crate::models::{HistoryEntry, HistoryMetadata, HistorySource};
+use crate::services::storage::{archive, DatabaseState, SettingsState};
 use chrono::Utc;
 use std::sync::Mutex;
+use std::time::Instant;
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
     tray::{TrayIcon, TrayIconBuilder},
     AppHandle, Emitter, Manager,
 };
@@ -23,6 +23,7 @@ pub mod menu_ids {
     pub const STOP_RECORDING: &str = "stop_recording";
     pub const TRANSCRIBE_FILE: &str = "transcribe_file";
     pub const HISTORY: &str = "history";
+    pub const INCOGNITO: &str = "incognito";
     pub const SETTINGS: &str = "settings";
     pub const ABOUT: &str = "about";
     pub const QUIT: &str = "quit";
@@ -32,6 +33,7 @@ pub mod menu_ids {
 pub struct TrayMenuState {
     pub start_recording: MenuItem<tauri::Wry>,
     pub stop_recording: MenuItem<tauri::Wry>,
+    pub incognito: CheckMenuItem<tauri::Wry>,
     pub tray_icon: Mutex<Option<TrayIcon>>,
 }
 
@@ -63,6 +65,14 @@ pub fn setup_tray(app: &AppHandle<tauri::Wry>) -> Result<(), Box<dyn std::error:
     )?;
 
     let history = MenuItem::with_id(app, menu_ids::HISTORY, "History...", true, None::<&str>)?;
+    let incognito = CheckMenuItem::with_id(
+        app,
+        menu_ids::INCOGNITO,
+        "Incognito (Don't Save History)",
+        true,
+        false,
+        None::<&str>,
+    )?;
 
     let settings = MenuItem::with_id(app, menu_ids::SETTINGS, "Settings...", true, None::<&str>)?;
 
@@ -79,6 +89,7 @@ pub fn setup_tray(app: &AppHandle<tauri::Wry>) -> Result<(), Box<dyn std::error:
             &transcribe_file,
             &PredefinedMenuItem::separator(app)?,
             &history,
+            &incognito,
             &settings,
             &PredefinedMenuItem::separator(app)?,
             &about,
@@ -105,6 +116,7 @@ pub fn setup_tray(app: &AppHandle<tauri::Wry>) -> Result<(), Box<dyn std::error:
     let menu_state = TrayMenuState {
         start_recording: start_recording.clone(),
         stop_recording: stop_recording.clone(),
+        incognito: incognito.clone(),
         tray_icon: Mutex::new(Some(tray)),
     };
     app.manage(menu_state);
@@ -134,6 +146,10 @@ fn handle_menu_event(app: &AppHandle<tauri::Wry>, menu_id: &str) {
             tracing::info!("History requested from tray menu");
             show_window(app, "history");
         }
+        menu_ids::INCOGNITO => {
+            let enabled = !app.state::<DatabaseState>().is_incognito();
+            set_incognito(app, enabled);
+        }
         menu_ids::SETTINGS => {
             tracing::info!("Settings requested from tray menu");
             show_window(app, "settings");
@@ -152,6 +168,21 @@ fn handle_menu_event(app: &AppHandle<tauri::Wry>, menu_id: &str) {
     }
 }
 
+/// Turn incognito mode on or off, keeping the tray checkbox in sync and
+/// notifying the frontend
+pub fn set_incognito(app: &AppHandle<tauri::Wry>, enabled: bool) {
+    app.state::<DatabaseState>().set_incognito(enabled);
+
+    if let Some(menu_state) = app.try_state::<TrayMenuState>() {
+        if let Err(e) = menu_state.incognito.set_checked(enabled) {
+            tracing::error!("Failed to update incognito menu state: {}", e);
+        }
+    }
+    if let Err(e) = app.emit("history://incognito-changed", enabled) {
+        tracing::error!("Failed to emit history://incognito-changed: {}", e);
+    }
+}
+
 /// Update tray menu items and icon for recording state
 pub fn update_tray_for_recording(app: &AppHandle<tauri::Wry>, is_recording: bool) {
     // Get menu items from state and update their enabled state
@@ -236,9 +267,9 @@ fn stop_recording_from_tray(app: &AppHandle<tauri::Wry>) {
     update_tray_for_recording(app, false);
 
     // Stop recording
-    let buffer = match audio_state.send_command(AudioCommand::Stop) {
-        Ok(AudioResponse::Buffer(Ok(buf))) => buf,
-        Ok(AudioResponse::Buffer(Err(e))) => {
+    let recording = match audio_state.send_command(AudioCommand::Stop) {
+        Ok(AudioResponse::Recording(Ok(recording))) => recording,
+        Ok(AudioResponse::Recording(Err(e))) => {
             tracing::error!("Failed to stop recording: {}", e);
             let _ = app_handle.emit("tray://transcription-error", e);
             return;
@@ -254,38 +285,35 @@ fn stop_recording_from_tray(app: &AppHandle<tauri::Wry>) {
         }
     };
 
-    if buffer.samples.is_empty() {
+    if recording.is_empty() {
         tracing::warn!("No audio recorded");
         let _ = app_handle.emit("tray://transcription-error", "No audio recorded");
         return;
     }
 
-    // Resample to 16kHz for Whisper
-    let samples = match resample_for_whisper(buffer) {
-        Ok(s) => s,
-        Err(e) => {
-            tracing::error!("Failed to resample audio: {}", e);
-            let _ = app_handle.emit("tray://transcription-error", e.to_string());
-            return;
-        }
-    };
-
     // Clone the engine for the async task
     let engine = transcription_state.engine.clone();
     // Get model_id synchronously before spawning
     let model_id = settings_state.get_model_id_sync();
+    let archive_config = settings_state.get_audio_archive_sync();
 
     // Spawn async task for transcription with auto-load
     tauri::async_runtime::spawn(async move {
         tracing::info!("Starting transcription...");
+        let transcribe_start = Instant::now();
 
-        match engine.transcribe_with_auto_load(samples, &model_id).await {
+        match engine
+            .transcribe_recording_with_auto_load(&recording, &model_id, None)
+            .await
+        {
             Ok(result) => {
+                let transcription_time_ms = transcribe_start.elapsed().as_millis() as u64;
                 tracing::info!("Transcription complete: {} chars", result.text.len());
 
                 // Save to history
                 let database_state = app_handle.state::<DatabaseState>();
-                if let Some(db) = database_state.get() {
+                if let Some(db) = database_state.history_db() {
+                    let audio_path = archive::archive_recording(&archive_config, recording).await;
                     let entry = HistoryEntry {
                         id: 0,
                         text: result.text.clone(),
@@ -294,11 +322,25 @@ fn stop_recording_from_tray(app: &AppHandle<tauri::Wry>) {
                         model_id: result.model_id.clone(),
                         language: result.language.clone(),
                         gpu_used: result.gpu_used,
+                        audio_path,
+                        pinned: false,
+                        original_text: None,
+                        metadata: HistoryMetadata {
+                            source: Some(HistorySource::Tray),
+                            transcription_time_ms: Some(transcription_time_ms),
+                            ..Default::default()
+                        },
                     };
                     if let Err(e) = db.insert_history(&entry).await {
                         tracing::error!("Failed to save transcription to history: {}", e);
+                        if let Some(path) = &entry.audio_path {
+                            archive::remove_archived_audio(path);
+                        }
                     } else {
                         tracing::debug!("Saved transcription to history");
+                        if entry.audio_path.is_some() {
+                            archive::enforce_retention(db, &archive_config).await;
+                        }
                         // Emit event to refresh history UI
                         match app_handle.emit("history://new-entry", ()) {
                             Ok(_) => tracing::debug!("Emitted history://new-entry event"),
@@ -364,8 +406,11 @@ fn transcribe_file_from_tray(app: &AppHandle<tauri::Wry>) {
                     Ok(samples) => {
                         tracing::info!("Audio file decoded, {} samples", samples.len());
 
+                        let transcribe_start = Instant::now();
                         match engine.transcribe_with_auto_load(samples, &model_id).await {
                             Ok(result) => {
+                                let transcription_time_ms =
+                                    transcribe_start.elapsed().as_millis() as u64;
                                 tracing::info!(
                                     "File transcription complete: {} chars",
                                     result.text.len()
@@ -373,7 +418,7 @@ fn transcribe_file_from_tray(app: &AppHandle<tauri::Wry>) {
 
                                 // Save to history
                                 let database_state = app_for_emit.state::<DatabaseState>();
-                                if let Some(db) = database_state.get() {
+                                if let Some(db) = database_state.history_db() {
                                     let entry = HistoryEntry {
                                         id: 0,
                                         text: result.text.clone(),
@@ -382,6 +427,14 @@ fn transcribe_file_from_tray(app: &AppHandle<tauri::Wry>) {
                                         model_id: result.model_id.clone(),
                                         language: result.language.clone(),
                                         gpu_used: result.gpu_used,
+                                        audio_path: None,
+                                        pinned: false,
+                                        original_text: None,
+                                        metadata: HistoryMetadata {
+                                            source: Some(HistorySource::File),
+                                            transcription_time_ms: Some(transcription_time_ms),
+                                            ..Default::default()
+                                        },
                                     };
                                     if let Err(e) = db.insert_history(&entry).await {
                                         tracing::error!(
@@ -464,6 +517,7 @@ mod tests {
         assert_eq!(menu_ids::STOP_RECORDING, "stop_recording");
         assert_eq!(menu_ids::TRANSCRIBE_FILE, "transcribe_file");
         assert_eq!(menu_ids::HISTORY, "history");
+        assert_eq!(menu_ids::INCOGNITO, "incognito");
         assert_eq!(menu_ids::SETTINGS, "settings");
         assert_eq!(menu_ids::ABOUT, "about");
         assert_eq!(menu_ids::QUIT, "quit");
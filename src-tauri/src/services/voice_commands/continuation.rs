@@ -0,0 +1,166 @@
+//! Joining a dictation onto text typed by the previous one
+//!
+//! Whisper transcribes every push-to-talk recording as if it were a new
+//! sentence: capitalized and without a leading space. When the cursor is
+//! still after our previous dictation, the start of the new text is adjusted
+//! to continue it.
+
+/// Characters after which the next word follows without a space
+const OPENERS: &[char] = &['(', '[', '{', '“', '‘', '/', '-'];
+
+/// Punctuation that attaches to the previous word
+const ATTACHING: &[char] = &['.', ',', '!', '?', ';', ':', ')', ']', '}', '%', '…'];
+
+/// Closing characters that may follow the end of a sentence
+const CLOSERS: &[char] = &['"', '”', '’', '\'', ')', ']'];
+
+/// Common words whose capital mid-sentence can only be Whisper's. Words
+/// that double as names ("May", "Will") are left out.
+const FUNCTION_WORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "because",
+    "been", "before", "but", "by", "could", "did", "do", "does", "for", "from", "had", "has",
+    "have", "he", "her", "here", "him", "his", "how", "if", "in", "into", "is", "it", "its",
+    "just", "me", "might", "must", "my", "no", "nor", "not", "of", "on", "or", "our", "out",
+    "over", "she", "should", "so", "some", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "those", "to", "too", "up", "us", "very", "was", "we", "were", "what",
+    "when", "where", "which", "while", "who", "why", "with", "would", "yet", "you", "your",
+];
+
+/// Adjust `text` to follow `preceding`, the text just before the cursor.
+/// `capitalize` asks for a capital even mid-sentence (after "new
+/// paragraph").
+pub fn continue_after(preceding: &str, capitalize: bool, text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+
+    let mut result = String::with_capacity(text.len() + 1);
+    let needs_space = !preceding.is_empty()
+        && !preceding.ends_with(char::is_whitespace)
+        && !preceding.ends_with(OPENERS)
+        && !text.starts_with(char::is_whitespace)
+        && !text.starts_with(ATTACHING);
+    if needs_space {
+        result.push(' ');
+    }
+
+    let sentence_start = capitalize || ends_sentence(preceding);
+    let Some(first) = first_word(text) else {
+        result.push_str(text);
+        return result;
+    };
+    let (before, rest) = text.split_at(first.start);
+    result.push_str(before);
+    if sentence_start {
+        result.push_str(&capitalize_first(rest));
+    } else if is_sentence_case(&rest[..first.len]) {
+        result.push_str(&lowercase_first(rest));
+    } else {
+        result.push_str(rest);
+    }
+    result
+}
+
+/// Whether the cursor is at the start of a sentence after `preceding`
+fn ends_sentence(preceding: &str) -> bool {
+    let trimmed = preceding.trim_end().trim_end_matches(CLOSERS);
+    trimmed.is_empty() || trimmed.ends_with(['.', '!', '?'])
+}
+
+struct FirstWord {
+    /// Byte offset of the word
+    start: usize,
+    /// Length of the word's leading run of letters
+    len: usize,
+}
+
+/// The first word of `text` when it begins the text, after whitespace and
+/// opening punctuation only
+fn first_word(text: &str) -> Option<FirstWord> {
+    let start = text.find(|c: char| !c.is_whitespace() && !OPENERS.contains(&c) && c != '"')?;
+    let rest = &text[start..];
+    if !rest.starts_with(char::is_alphabetic) {
+        return None;
+    }
+    let len = rest.find(|c: char| !c.is_alphabetic()).unwrap_or(rest.len());
+    Some(FirstWord { start, len })
+}
+
+/// "The" but not "John", "I", "NASA" or "iPhone": a capital Whisper likely
+/// added
+fn is_sentence_case(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().is_some_and(char::is_uppercase)
+        && word.chars().count() > 1
+        && chars.all(char::is_lowercase)
+        && FUNCTION_WORDS.contains(&word.to_lowercase().as_str())
+}
+
+fn capitalize_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+    }
+}
+
+fn lowercase_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(c) => c.to_lowercase().chain(chars).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continue_after() {
+        let cases = [
+            // Nothing before the cursor
+            ("", false, "Hello there.", "Hello there."),
+            // Continuing a sentence
+            ("I went", false, "To the shop.", " to the shop."),
+            ("Dear Bob,", false, "We got it.", " we got it."),
+            // Starting a new one
+            ("That was fun.", false, "the next day", " The next day"),
+            ("Really?", false, "yes.", " Yes."),
+            ("He said \"stop.\"", false, "we did.", " We did."),
+            // Capitals that aren't sentence case stay
+            ("and then", false, "I left.", " I left."),
+            ("we use", false, "NASA data.", " NASA data."),
+            ("buy an", false, "iPhone", " iPhone"),
+            // Proper nouns keep their capital
+            ("I met", false, "John.", " John."),
+            ("talk to", false, "Sarah", " Sarah"),
+            ("back in", false, "May we moved.", " May we moved."),
+            ("we write", false, "Python daily.", " Python daily."),
+            // Punctuation attaches to the previous word
+            ("Hello", false, ".", "."),
+            ("Hello", false, ", world", ", world"),
+            // No space after whitespace or an opening bracket
+            ("Hello ", false, "There", "there"),
+            ("Notes (", false, "As below)", "as below)"),
+            ("Line one\n", false, "And line two", "and line two"),
+            // "new paragraph" at the end of the previous dictation
+            ("Regards\n\n", true, "bob", "Bob"),
+            // Opening quotes are skipped to find the word
+            ("She said", false, "\"This is it\"", " \"this is it\""),
+            ("The end.", false, "(the beginning)", " (The beginning)"),
+            // Nothing to capitalize
+            ("Total", false, "42 items", " 42 items"),
+            ("", true, "", ""),
+        ];
+        for (preceding, capitalize, text, expected) in cases {
+            assert_eq!(
+                continue_after(preceding, capitalize, text),
+                expected,
+                "{:?} + {:?}",
+                preceding,
+                text
+            );
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Text positions used by the editing commands
+//!
+//! Shared by the parser, which applies edits to the text of the current
+//! dictation, and the injection history, which works out how much already
+//! injected text an edit removes.
+
+/// Characters that belong to a word
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '\'' | '’' | '-' | '_')
+}
+
+/// Characters that end a sentence
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '\n')
+}
+
+/// Byte index where the last word of `text` starts, including the
+/// whitespace before it and punctuation after it
+pub fn last_word_start(text: &str) -> usize {
+    let rest = text.trim_end();
+    let rest = rest.trim_end_matches(|c: char| !is_word_char(c) && !c.is_whitespace());
+    let rest = rest.trim_end_matches(is_word_char);
+    rest.trim_end().len()
+}
+
+/// Byte index where the last sentence of `text` starts: just after the
+/// previous sentence's closing punctuation, or 0
+pub fn last_sentence_start(text: &str) -> usize {
+    let rest = text.trim_end();
+    let rest = rest.trim_end_matches(is_sentence_end).trim_end();
+    // Sentence ends are all single-byte characters
+    rest.rfind(is_sentence_end).map_or(0, |i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn without_last_word(text: &str) -> &str {
+        &text[..last_word_start(text)]
+    }
+
+    fn without_last_sentence(text: &str) -> &str {
+        &text[..last_sentence_start(text)]
+    }
+
+    #[test]
+    fn test_last_word_start() {
+        let cases = [
+            ("send it now", "send it"),
+            ("send it now.  ", "send it"),
+            ("don't re-run", "don't"),
+            ("one", ""),
+            ("", ""),
+            ("hello, world!", "hello,"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(without_last_word(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_last_sentence_start() {
+        let cases = [
+            ("First. Second one", "First."),
+            ("First. Second one. ", "First."),
+            ("Is it? Yes! No.", "Is it? Yes!"),
+            ("Line one\nline two", "Line one\n"),
+            ("only one sentence.", ""),
+            ("", ""),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(without_last_sentence(text), expected, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Identifier formatting used by the "camel case" style commands
+//!
+//! Dictated words are collected until the next command and joined into a
+//! single identifier, so "snake case user id" becomes `user_id`.
+
+use serde::{Deserialize, Serialize};
+
+/// How dictated words are joined into an identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentifierCase {
+    /// `userName`
+    Camel,
+    /// `UserName`
+    Pascal,
+    /// `user_name`
+    Snake,
+    /// `user-name`
+    Kebab,
+}
+
+impl IdentifierCase {
+    /// Join lowercase words into an identifier
+    pub fn join(self, words: &[String]) -> String {
+        match self {
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| if i == 0 { word.clone() } else { title(word) })
+                .collect(),
+            Self::Pascal => words.iter().map(|word| title(word)).collect(),
+            Self::Snake => words.join("_"),
+            Self::Kebab => words.join("-"),
+        }
+    }
+}
+
+/// Split a dictated word into lowercase identifier parts, dropping
+/// apostrophes and other punctuation ("user's" → "users", "re-run" →
+/// "re", "run")
+pub fn identifier_parts(word: &str) -> impl Iterator<Item = String> + '_ {
+    word.split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '’')
+        .map(|part| part.replace(['\'', '’'], "").to_lowercase())
+        .filter(|part| !part.is_empty())
+}
+
+fn title(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join() {
+        let words: Vec<String> = ["get", "user", "id"].map(String::from).to_vec();
+        let cases = [
+            (IdentifierCase::Camel, "getUserId"),
+            (IdentifierCase::Pascal, "GetUserId"),
+            (IdentifierCase::Snake, "get_user_id"),
+            (IdentifierCase::Kebab, "get-user-id"),
+        ];
+        for (case, expected) in cases {
+            assert_eq!(case.join(&words), expected, "{:?}", case);
+        }
+        assert_eq!(IdentifierCase::Camel.join(&[]), "");
+    }
+
+    #[test]
+    fn test_identifier_parts() {
+        let cases: [(&str, &[&str]); 5] = [
+            ("User", &["user"]),
+            ("user's", &["users"]),
+            ("re-run", &["re", "run"]),
+            ("HTTP2", &["http2"]),
+            ("--", &[]),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(
+                identifier_parts(word).collect::<Vec<_>>(),
+                expected,
+                "{:?}",
+                word
+            );
+        }
+    }
+}
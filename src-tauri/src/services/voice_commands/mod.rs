@@ -2,7 +2,22 @@
 //!
 //! This module provides functionality for parsing voice commands from transcribed text
 //! and converting them into actions like punctuation insertion, formatting, and editing.
-
+//! Spoken phrases are looked up in a [`CommandRegistry`] built from the configuration.
+
+mod continuation;
+mod editing;
+mod formatting;
+mod registry;
+
+pub use continuation::continue_after;
+pub use editing::{last_sentence_start, last_word_start};
+pub use formatting::IdentifierCase;
+pub use registry::{
+    ends_sentence, normalize_phrase, normalize_word, CommandConflict, CommandRegistry,
+    CommandSource, ConflictKind, CustomCommand, RegisteredCommand, SUPPORTED_LANGUAGES,
+};
+
+use crate::services::platform::Key;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for the voice command parser
@@ -14,6 +29,16 @@ pub struct CommandConfig {
     pub require_prefix: bool,
     /// The prefix to use when require_prefix is true
     pub prefix: String,
+    /// Languages whose built-in command phrases are recognized
+    #[serde(default = "default_languages")]
+    pub languages: Vec<String>,
+    /// User-defined commands; these override built-in phrases
+    #[serde(default)]
+    pub custom_commands: Vec<CustomCommand>,
+}
+
+fn default_languages() -> Vec<String> {
+    vec!["en".to_string()]
 }
 
 impl Default for CommandConfig {
@@ -22,12 +47,15 @@ impl Default for CommandConfig {
             enabled: true,
             require_prefix: false,
             prefix: "command".to_string(),
+            languages: default_languages(),
+            custom_commands: Vec::new(),
         }
     }
 }
 
 /// Actions that can be performed by voice commands
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum CommandAction {
     /// Insert text (punctuation, newlines, etc.)
     InsertText(String),
@@ -37,6 +65,47 @@ pub enum CommandAction {
     Undo,
     /// Capitalize the next word
     CapitalizeNext,
+    /// Remove the previous dictated utterance
+    ScratchThat,
+    /// Delete the word before the cursor
+    DeleteLastWord,
+    /// Delete the sentence before the cursor
+    DeleteLastSentence,
+    /// Select all text in the focused field
+    SelectAll,
+    /// Press a single key
+    PressKey(Key),
+    /// Move the cursor to the end of the line
+    GoToLineEnd,
+    /// Type the following words in capitals until `EndAllCaps`
+    StartAllCaps,
+    /// Stop typing in capitals
+    EndAllCaps,
+    /// Join the following words into an identifier until the next command
+    StartIdentifier(IdentifierCase),
+    /// End an identifier without any other command
+    EndIdentifier,
+    /// Opening quote or bracket; the next word follows without a space
+    Open(String),
+    /// Closing quote or bracket; attaches to the previous word
+    Close(String),
+    /// Start a bulleted list item on a new line
+    BulletPoint,
+    /// Start a numbered list at item 1 on a new line
+    NumberedList,
+    /// Start the next item of the current list
+    NextItem,
+    /// Join the next word to the previous one without a space
+    NoSpace,
+}
+
+/// One step of delivering a parsed dictation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseStep {
+    /// Text to inject
+    Text(String),
+    /// Action to perform with the text injector
+    Action(CommandAction),
 }
 
 /// Result of parsing a command
@@ -48,354 +117,326 @@ pub struct ParseResult {
     pub actions: Vec<CommandAction>,
     /// Whether capitalization should be applied to the next character
     pub capitalize_next: bool,
+    /// Text and injector actions in the order they are delivered. Edits
+    /// that could be applied to `text` itself are not repeated here.
+    pub steps: Vec<ParseStep>,
 }
 
 /// Voice command parser
 pub struct CommandParser {
     config: CommandConfig,
+    registry: CommandRegistry,
 }
 
 impl CommandParser {
     /// Create a new command parser with the given configuration
     pub fn new(config: CommandConfig) -> Self {
-        Self { config }
+        let registry = CommandRegistry::from_config(&config);
+        for conflict in registry.conflicts() {
+            tracing::warn!(
+                "Voice command conflict for '{}': {:?}",
+                conflict.phrase,
+                conflict.kind
+            );
+        }
+        Self { config, registry }
+    }
+
+    /// The registry the parser looks phrases up in
+    pub fn registry(&self) -> &CommandRegistry {
+        &self.registry
     }
 
     /// Parse transcribed text and extract/process commands
+    ///
+    /// At each word the longest registered phrase wins, so "question mark"
+    /// is one command rather than "question" followed by the word "mark".
     pub fn parse(&self, text: &str) -> ParseResult {
         if !self.config.enabled {
-            return ParseResult {
+            let output = Output {
                 text: text.to_string(),
-                actions: vec![],
-                capitalize_next: false,
+                ..Default::default()
             };
+            return output.into_result();
         }
 
-        let mut result_text = String::new();
-        let mut actions = Vec::new();
-        let mut capitalize_next = false;
-        let mut skip_space = false;
-
-        // Split text into words while preserving spaces
         let words: Vec<&str> = text.split_whitespace().collect();
+        let normalized: Vec<String> = words.iter().map(|w| normalize_word(w)).collect();
+        let prefix: Vec<String> = self
+            .config
+            .prefix
+            .split_whitespace()
+            .map(normalize_word)
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        let mut output = Output::default();
         let mut i = 0;
 
         while i < words.len() {
-            let word = words[i];
-            let word_lower = word.to_lowercase();
-
-            // Check for prefix if required
-            if self.config.require_prefix {
-                let prefix_lower = self.config.prefix.to_lowercase();
-                if word_lower == prefix_lower && i + 1 < words.len() {
-                    // Check if next word is a command
-                    let next_word = words[i + 1];
-                    if let Some((action, replacement)) = self.parse_command(next_word) {
-                        // Process the command
-                        if !result_text.is_empty() && !skip_space && !replacement.is_empty() {
-                            // Don't add space before punctuation
-                            if !is_punctuation(&replacement) {
-                                result_text.push(' ');
-                            }
-                        }
-
-                        // Handle capitalization from previous command
-                        let mut to_append = replacement.clone();
-                        if capitalize_next && !to_append.is_empty() {
-                            to_append = capitalize_first(&to_append);
-                            capitalize_next = false;
-                        }
-
-                        result_text.push_str(&to_append);
-                        actions.push(action.clone());
-
-                        // Check if this action requires capitalizing the next word
-                        if matches!(action, CommandAction::InsertText(ref s) if s == "\n\n") {
-                            capitalize_next = true;
-                        }
-
-                        skip_space = replacement.is_empty() || is_punctuation(&replacement);
-                        i += 2;
-                        continue;
-                    }
-                }
+            // With a prefix required, commands only start right after it
+            let command_start = if !self.config.require_prefix {
+                Some(i)
+            } else if !prefix.is_empty() && normalized[i..].starts_with(&prefix) {
+                Some(i + prefix.len())
             } else {
-                // No prefix required - check if word itself is a command
-                if let Some((action, replacement)) = self.parse_command(word) {
-                    // Process the command
-                    if !result_text.is_empty() && !skip_space && !replacement.is_empty() {
-                        // Don't add space before punctuation
-                        if !is_punctuation(&replacement) {
-                            result_text.push(' ');
-                        }
-                    }
-
-                    // Handle capitalization from previous command
-                    let mut to_append = replacement.clone();
-                    if capitalize_next && !to_append.is_empty() {
-                        to_append = capitalize_first(&to_append);
-                        capitalize_next = false;
-                    }
-
-                    result_text.push_str(&to_append);
-                    actions.push(action.clone());
-
-                    // Check if this action requires capitalizing the next word
-                    if matches!(action, CommandAction::InsertText(ref s) if s == "\n\n") {
-                        capitalize_next = true;
-                    }
-
-                    skip_space = replacement.is_empty() || is_punctuation(&replacement);
+                None
+            };
+
+            let matched = command_start
+                .and_then(|start| {
+                    self.registry
+                        .longest_match(&normalized[start..])
+                        .map(|(len, command)| (start + len, command))
+                })
+                // A lone built-in word ending a sentence is dictated text, as
+                // in "Let me ask a question.", unless the prefix announced it
+                .filter(|&(end, command)| {
+                    let lone_built_in = end == i + 1 && command.source == CommandSource::BuiltIn;
+                    !lone_built_in || !ends_sentence(words[i])
+                });
+
+            match matched {
+                Some((end, command)) => {
+                    output.push_action(&command.action);
+                    i = end;
+                }
+                None => {
+                    output.push_word(words[i]);
                     i += 1;
-                    continue;
                 }
             }
-
-            // Not a command - add word normally
-            if !result_text.is_empty() && !skip_space {
-                result_text.push(' ');
-            }
-
-            // Handle capitalization from previous command
-            let mut to_append = word.to_string();
-            if capitalize_next {
-                to_append = capitalize_first(&to_append);
-                capitalize_next = false;
-            }
-
-            result_text.push_str(&to_append);
-            skip_space = false;
-            i += 1;
         }
 
-        ParseResult {
-            text: result_text,
-            actions,
-            capitalize_next,
-        }
+        output.into_result()
     }
+}
 
-    /// Parse a single word as a command
-    /// Returns (action, text_replacement) if it's a command
-    fn parse_command(&self, word: &str) -> Option<(CommandAction, String)> {
-        let word_lower = word.to_lowercase();
+/// The kind of list being dictated
+#[derive(Debug, Clone, Copy)]
+enum List {
+    Bulleted,
+    /// Numbered, with the number of the current item
+    Numbered(usize),
+}
 
-        // Punctuation commands (Story 7.2)
-        match word_lower.as_str() {
-            "period" | "full stop" => {
-                Some((CommandAction::InsertText(".".to_string()), ".".to_string()))
-            }
-            "comma" => Some((CommandAction::InsertText(",".to_string()), ",".to_string())),
-            "question mark" | "question" => {
-                Some((CommandAction::InsertText("?".to_string()), "?".to_string()))
-            }
-            "exclamation point" | "exclamation mark" | "exclamation" => {
-                Some((CommandAction::InsertText("!".to_string()), "!".to_string()))
-            }
-            "colon" => Some((CommandAction::InsertText(":".to_string()), ":".to_string())),
-            "semicolon" | "semi-colon" => {
-                Some((CommandAction::InsertText(";".to_string()), ";".to_string()))
-            }
+/// Text and actions built up while parsing
+#[derive(Default)]
+struct Output {
+    text: String,
+    actions: Vec<CommandAction>,
+    capitalize_next: bool,
+    /// The next word follows without a space
+    skip_space: bool,
+    /// Actions for the injector, with the length `text` had at the time
+    injector_actions: Vec<(usize, CommandAction)>,
+    /// Text before this point was delivered before an injector action and
+    /// can no longer be edited in place
+    floor: usize,
+    /// `skip_space` as it was right after the last injector action
+    floor_skip_space: bool,
+    /// Words are typed in capitals
+    all_caps: bool,
+    /// Words are being collected into an identifier
+    identifier: Option<IdentifierCase>,
+    /// Lowercase words of the identifier so far
+    identifier_words: Vec<String>,
+    /// The list new items continue
+    list: Option<List>,
+}
 
-            // Formatting commands (Story 7.3)
-            "new line" | "newline" => Some((
-                CommandAction::InsertText("\n".to_string()),
-                "\n".to_string(),
-            )),
-            "new paragraph" | "paragraph" => Some((
-                CommandAction::InsertText("\n\n".to_string()),
-                "\n\n".to_string(),
-            )),
-
-            // Editing commands (Story 7.4)
-            "delete that" | "backspace" => {
-                Some((CommandAction::DeleteCharacters(1), String::new()))
-            }
-            "undo" => Some((CommandAction::Undo, String::new())),
+impl Output {
+    /// Append a dictated word
+    fn push_word(&mut self, word: &str) {
+        if self.identifier.is_none() {
+            self.append_word(word, true);
+            return;
+        }
 
-            _ => None,
+        let core = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+        self.identifier_words
+            .extend(formatting::identifier_parts(core));
+        // Punctuation Whisper put after the word ends the identifier
+        let trailing = &word[core.len()..];
+        if !trailing.is_empty() {
+            self.flush_identifier();
+            self.text.push_str(trailing);
+            self.skip_space = false;
         }
     }
 
-    /// Parse multi-word commands (for commands like "full stop", "question mark", etc.)
-    /// This is used when we need to check two consecutive words
-    pub fn parse_multi_word_command(
-        &self,
-        word1: &str,
-        word2: &str,
-    ) -> Option<(CommandAction, String)> {
-        let combined = format!("{} {}", word1.to_lowercase(), word2.to_lowercase());
-
-        match combined.as_str() {
-            "full stop" => Some((CommandAction::InsertText(".".to_string()), ".".to_string())),
-            "question mark" => Some((CommandAction::InsertText("?".to_string()), "?".to_string())),
-            "exclamation point" | "exclamation mark" => {
-                Some((CommandAction::InsertText("!".to_string()), "!".to_string()))
-            }
-            "semi colon" | "semi-colon" => {
-                Some((CommandAction::InsertText(";".to_string()), ";".to_string()))
-            }
-            "new line" => Some((
-                CommandAction::InsertText("\n".to_string()),
-                "\n".to_string(),
-            )),
-            "new paragraph" => Some((
-                CommandAction::InsertText("\n\n".to_string()),
-                "\n\n".to_string(),
-            )),
-            "delete that" => Some((CommandAction::DeleteCharacters(1), String::new())),
-            _ => None,
+    /// Append a word to the text, separated by a space unless suppressed
+    fn append_word(&mut self, word: &str, capitalize: bool) {
+        if !self.text.is_empty() && !self.skip_space {
+            self.text.push(' ');
         }
+        if self.all_caps {
+            self.text.push_str(&word.to_uppercase());
+        } else if capitalize && self.capitalize_next {
+            self.text.push_str(&capitalize_first(word));
+        } else {
+            self.text.push_str(word);
+        }
+        self.capitalize_next = false;
+        self.skip_space = false;
     }
 
-    /// Advanced parse that handles multi-word commands
-    pub fn parse_advanced(&self, text: &str) -> ParseResult {
-        if !self.config.enabled {
-            return ParseResult {
-                text: text.to_string(),
-                actions: vec![],
-                capitalize_next: false,
-            };
+    /// Write out the identifier being collected and leave identifier mode
+    fn flush_identifier(&mut self) {
+        let Some(case) = self.identifier.take() else {
+            return;
+        };
+        let words = std::mem::take(&mut self.identifier_words);
+        if !words.is_empty() {
+            self.append_word(&case.join(&words), false);
         }
+    }
 
-        let mut result_text = String::new();
-        let mut actions = Vec::new();
-        let mut capitalize_next = false;
-        let mut skip_space = false;
-
-        let words: Vec<&str> = text.split_whitespace().collect();
-        let mut i = 0;
+    /// Start a list item on a line of its own
+    fn push_list_item(&mut self, marker: &str) {
+        let trimmed = self.text.trim_end_matches([' ', '\t']).len();
+        if trimmed >= self.floor {
+            self.text.truncate(trimmed);
+        }
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+        self.text.push_str(marker);
+        self.skip_space = true;
+        self.capitalize_next = true;
+    }
 
-        while i < words.len() {
-            let word = words[i];
-            let word_lower = word.to_lowercase();
-
-            // Check for prefix if required
-            let (command_start, prefix_consumed) = if self.config.require_prefix {
-                let prefix_lower = self.config.prefix.to_lowercase();
-                if word_lower == prefix_lower && i + 1 < words.len() {
-                    (i + 1, true)
+    /// Record a command's action and apply its effect on the text
+    fn push_action(&mut self, action: &CommandAction) {
+        self.actions.push(action.clone());
+        // Any command ends an identifier
+        self.flush_identifier();
+
+        match action {
+            CommandAction::InsertText(insert) if !insert.is_empty() => {
+                // Punctuation and line breaks attach to the previous word
+                let attaches = insert.starts_with(['.', ',', '?', '!', ':', ';', '\n']);
+                if !self.text.is_empty() && !self.skip_space && !attaches {
+                    self.text.push(' ');
+                }
+                if self.capitalize_next && insert.starts_with(char::is_alphanumeric) {
+                    self.text.push_str(&capitalize_first(insert));
+                    self.capitalize_next = false;
                 } else {
-                    // Not a prefix, treat as regular word
-                    if !result_text.is_empty() && !skip_space {
-                        result_text.push(' ');
-                    }
-                    let mut to_append = word.to_string();
-                    if capitalize_next {
-                        to_append = capitalize_first(&to_append);
-                        capitalize_next = false;
-                    }
-                    result_text.push_str(&to_append);
-                    skip_space = false;
-                    i += 1;
-                    continue;
+                    self.text.push_str(insert);
                 }
-            } else {
-                (i, false)
-            };
 
-            // Try to parse multi-word command first
-            if command_start + 1 < words.len() {
-                if let Some((action, replacement)) =
-                    self.parse_multi_word_command(words[command_start], words[command_start + 1])
-                {
-                    if !result_text.is_empty() && !skip_space && !replacement.is_empty() {
-                        if !is_punctuation(&replacement) {
-                            result_text.push(' ');
-                        }
-                    }
-
-                    let mut to_append = replacement.clone();
-                    if capitalize_next && !to_append.is_empty() {
-                        to_append = capitalize_first(&to_append);
-                        capitalize_next = false;
-                    }
-
-                    result_text.push_str(&to_append);
-                    actions.push(action.clone());
-
-                    if matches!(action, CommandAction::InsertText(ref s) if s == "\n\n") {
-                        capitalize_next = true;
-                    }
-
-                    skip_space = replacement.is_empty() || is_punctuation(&replacement);
-                    i = command_start + 2;
-                    continue;
+                // A new paragraph starts a new sentence
+                let paragraph = insert.ends_with("\n\n");
+                if paragraph {
+                    self.capitalize_next = true;
                 }
+                self.skip_space = paragraph || insert.ends_with([' ', '\t']);
             }
-
-            // Try single-word command
-            if let Some((action, replacement)) = self.parse_command(words[command_start]) {
-                if !result_text.is_empty() && !skip_space && !replacement.is_empty() {
-                    if !is_punctuation(&replacement) {
-                        result_text.push(' ');
-                    }
+            CommandAction::InsertText(_) => {}
+            CommandAction::CapitalizeNext => self.capitalize_next = true,
+
+            CommandAction::StartAllCaps => self.all_caps = true,
+            CommandAction::EndAllCaps => self.all_caps = false,
+            CommandAction::StartIdentifier(case) => self.identifier = Some(*case),
+            CommandAction::EndIdentifier => {}
+            CommandAction::Open(open) => {
+                if !self.text.is_empty() && !self.skip_space {
+                    self.text.push(' ');
                 }
-
-                let mut to_append = replacement.clone();
-                if capitalize_next && !to_append.is_empty() {
-                    to_append = capitalize_first(&to_append);
-                    capitalize_next = false;
+                self.text.push_str(open);
+                self.skip_space = true;
+            }
+            CommandAction::Close(close) => {
+                self.text.push_str(close);
+                self.skip_space = false;
+            }
+            CommandAction::BulletPoint => {
+                self.list = Some(List::Bulleted);
+                self.push_list_item("- ");
+            }
+            CommandAction::NumberedList => {
+                self.list = Some(List::Numbered(1));
+                self.push_list_item("1. ");
+            }
+            CommandAction::NextItem => match self.list {
+                Some(List::Numbered(n)) => {
+                    self.list = Some(List::Numbered(n + 1));
+                    self.push_list_item(&format!("{}. ", n + 1));
                 }
-
-                result_text.push_str(&to_append);
-                actions.push(action.clone());
-
-                if matches!(action, CommandAction::InsertText(ref s) if s == "\n\n") {
-                    capitalize_next = true;
+                Some(List::Bulleted) | None => {
+                    self.list = Some(List::Bulleted);
+                    self.push_list_item("- ");
                 }
+            },
+            CommandAction::NoSpace => self.skip_space = true,
 
-                skip_space = replacement.is_empty() || is_punctuation(&replacement);
-                i = command_start + 1;
-                continue;
+            // Edits within this dictation are applied to the text; past its
+            // start they are left to the injector
+            CommandAction::ScratchThat if self.text.len() > self.floor => {
+                self.truncate(self.floor);
             }
-
-            // Not a command
-            if prefix_consumed {
-                // The prefix was consumed but no command found, output prefix as text
-                if !result_text.is_empty() && !skip_space {
-                    result_text.push(' ');
-                }
-                let mut to_append = word.to_string();
-                if capitalize_next {
-                    to_append = capitalize_first(&to_append);
-                    capitalize_next = false;
+            CommandAction::DeleteLastWord if self.text.len() > self.floor => {
+                self.truncate(self.floor + last_word_start(&self.text[self.floor..]));
+            }
+            CommandAction::DeleteLastSentence if self.text.len() > self.floor => {
+                self.truncate(self.floor + last_sentence_start(&self.text[self.floor..]));
+                if self.text.trim_end().ends_with(['.', '!', '?']) {
+                    self.capitalize_next = true;
                 }
-                result_text.push_str(&to_append);
             }
 
-            if !result_text.is_empty() && !skip_space && !prefix_consumed {
-                result_text.push(' ');
-            } else if !prefix_consumed {
-                // Nothing in result_text yet
+            CommandAction::SelectAll | CommandAction::PressKey(_) | CommandAction::GoToLineEnd => {
+                // Typing resumes at a new position, without a leading space
+                self.push_injector_action(action, true);
             }
+            _ => self.push_injector_action(action, self.skip_space),
+        }
+    }
 
-            if !prefix_consumed {
-                let mut to_append = word.to_string();
-                if capitalize_next {
-                    to_append = capitalize_first(&to_append);
-                    capitalize_next = false;
-                }
-                result_text.push_str(&to_append);
-            }
+    fn push_injector_action(&mut self, action: &CommandAction, skip_space: bool) {
+        self.injector_actions
+            .push((self.text.len(), action.clone()));
+        self.floor = self.text.len();
+        self.skip_space = skip_space;
+        self.floor_skip_space = skip_space;
+    }
+
+    /// Remove the end of the text back to byte `len`
+    fn truncate(&mut self, len: usize) {
+        self.text.truncate(len);
+        self.skip_space = if len == self.floor {
+            self.floor_skip_space
+        } else {
+            self.text.ends_with(char::is_whitespace)
+        };
+    }
 
-            skip_space = false;
-            i += 1;
+    fn into_result(mut self) -> ParseResult {
+        self.flush_identifier();
+
+        let mut steps = Vec::new();
+        let mut delivered = 0;
+        for (position, action) in self.injector_actions {
+            if position > delivered {
+                steps.push(ParseStep::Text(self.text[delivered..position].to_string()));
+                delivered = position;
+            }
+            steps.push(ParseStep::Action(action));
+        }
+        if self.text.len() > delivered {
+            steps.push(ParseStep::Text(self.text[delivered..].to_string()));
         }
 
         ParseResult {
-            text: result_text,
-            actions,
-            capitalize_next,
+            text: self.text,
+            actions: self.actions,
+            capitalize_next: self.capitalize_next,
+            steps,
         }
     }
 }
 
-/// Check if a string is punctuation
-fn is_punctuation(s: &str) -> bool {
-    matches!(s, "." | "," | "?" | "!" | ":" | ";")
-}
-
 /// Capitalize the first character of a string
 fn capitalize_first(s: &str) -> String {
     let mut chars = s.chars();
@@ -415,6 +456,7 @@ mod tests {
             enabled: true,
             require_prefix: false,
             prefix: "command".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
@@ -451,6 +493,7 @@ mod tests {
             enabled: true,
             require_prefix: false,
             prefix: "command".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
@@ -471,6 +514,7 @@ mod tests {
             enabled: true,
             require_prefix: false,
             prefix: "command".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
@@ -493,6 +537,7 @@ mod tests {
             enabled: true,
             require_prefix: true,
             prefix: "command".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
@@ -515,6 +560,7 @@ mod tests {
             enabled: false,
             require_prefix: false,
             prefix: "command".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
@@ -530,6 +576,7 @@ mod tests {
             enabled: true,
             require_prefix: false,
             prefix: "command".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
@@ -554,27 +601,28 @@ mod tests {
             enabled: true,
             require_prefix: false,
             prefix: "command".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
         // Test "full stop"
-        let result = parser.parse_advanced("hello full stop world");
+        let result = parser.parse("hello full stop world");
         assert_eq!(result.text, "hello. world");
 
         // Test "question mark"
-        let result = parser.parse_advanced("hello question mark");
+        let result = parser.parse("hello question mark");
         assert_eq!(result.text, "hello?");
 
         // Test "new line"
-        let result = parser.parse_advanced("hello new line world");
+        let result = parser.parse("hello new line world");
         assert_eq!(result.text, "hello\n world");
 
         // Test "new paragraph"
-        let result = parser.parse_advanced("hello new paragraph world");
+        let result = parser.parse("hello new paragraph world");
         assert_eq!(result.text, "hello\n\nWorld");
 
         // Test "delete that"
-        let result = parser.parse_advanced("hello delete that");
+        let result = parser.parse("hello delete that");
         assert!(result
             .actions
             .iter()
@@ -587,6 +635,7 @@ mod tests {
             enabled: true,
             require_prefix: false,
             prefix: "command".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
@@ -622,6 +671,7 @@ mod tests {
             enabled: true,
             require_prefix: true,
             prefix: "insert".to_string(),
+            ..Default::default()
         };
         let parser = CommandParser::new(config);
 
@@ -633,4 +683,365 @@ mod tests {
         let result = parser.parse("hello command period world");
         assert_eq!(result.text, "hello command period world");
     }
+
+    #[test]
+    fn test_longest_match_wins() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        let result = parser.parse("is it done question mark");
+        assert_eq!(result.text, "is it done?");
+        assert_eq!(result.actions.len(), 1);
+
+        // "semi colon" as two words and with a prefix
+        let result = parser.parse("first semi colon second");
+        assert_eq!(result.text, "first; second");
+    }
+
+    #[test]
+    fn test_whisper_punctuation_on_command_words() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        // Multi-word phrases match with the punctuation stripped
+        let result = parser.parse("first line. New line. Second line");
+        assert_eq!(result.text, "first line.\n Second line");
+        let result = parser.parse("Hello Question mark. World");
+        assert_eq!(result.text, "Hello? World");
+    }
+
+    #[test]
+    fn test_single_word_commands_ending_a_sentence() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        // A sentence ending on a command word is dictated text
+        let result = parser.parse("Let me ask a question.");
+        assert_eq!(result.text, "Let me ask a question.");
+        assert!(result.actions.is_empty());
+        let result = parser.parse("It was a long period. Then paragraph.");
+        assert_eq!(result.text, "It was a long period. Then paragraph.");
+        let result = parser.parse("Hello Period. World");
+        assert_eq!(result.text, "Hello Period. World");
+
+        // Without sentence punctuation they are still commands
+        let result = parser.parse("hello question");
+        assert_eq!(result.text, "hello?");
+        let result = parser.parse("hello period, world");
+        assert_eq!(result.text, "hello. world");
+
+        // After the prefix they are always commands
+        let parser = CommandParser::new(CommandConfig {
+            require_prefix: true,
+            ..Default::default()
+        });
+        let result = parser.parse("Let me ask a command question.");
+        assert_eq!(result.text, "Let me ask a?");
+    }
+
+    #[test]
+    fn test_custom_commands() {
+        let config = CommandConfig {
+            custom_commands: vec![
+                CustomCommand {
+                    phrase: "sign off".to_string(),
+                    action: CommandAction::InsertText("Best regards".to_string()),
+                },
+                CustomCommand {
+                    phrase: "cap".to_string(),
+                    action: CommandAction::CapitalizeNext,
+                },
+                CustomCommand {
+                    phrase: "period".to_string(),
+                    action: CommandAction::InsertText("...".to_string()),
+                },
+            ],
+            ..Default::default()
+        };
+        let parser = CommandParser::new(config);
+
+        let result = parser.parse("thanks comma sign off");
+        assert_eq!(result.text, "thanks, Best regards");
+
+        let result = parser.parse("ask cap john period");
+        assert_eq!(result.text, "ask John...");
+        assert_eq!(
+            result.actions,
+            vec![
+                CommandAction::CapitalizeNext,
+                CommandAction::InsertText("...".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multi_word_prefix_and_command() {
+        let config = CommandConfig {
+            require_prefix: true,
+            prefix: "hey flow".to_string(),
+            ..Default::default()
+        };
+        let parser = CommandParser::new(config);
+
+        let result = parser.parse("done hey flow new paragraph next");
+        assert_eq!(result.text, "done\n\nNext");
+
+        // A prefix without a command is kept as text
+        let result = parser.parse("hey flow there");
+        assert_eq!(result.text, "hey flow there");
+    }
+
+    #[test]
+    fn test_other_languages() {
+        let config = CommandConfig {
+            languages: vec!["de".to_string()],
+            ..Default::default()
+        };
+        let parser = CommandParser::new(config);
+
+        let result = parser.parse("Hallo Komma wie geht's Fragezeichen");
+        assert_eq!(result.text, "Hallo, wie geht's?");
+
+        // English phrases are not recognized unless enabled
+        let result = parser.parse("hallo period");
+        assert_eq!(result.text, "hallo period");
+    }
+
+    #[test]
+    fn test_editing_commands_keep_spacing() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        let result = parser.parse("hello undo world");
+        assert_eq!(result.text, "hello world");
+        assert_eq!(result.actions, vec![CommandAction::Undo]);
+    }
+
+    #[test]
+    fn test_config_backward_compatibility() {
+        let json = r#"{"enabled": true, "require_prefix": false, "prefix": "command"}"#;
+        let config: CommandConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.languages, vec!["en".to_string()]);
+        assert!(config.custom_commands.is_empty());
+    }
+
+    #[test]
+    fn test_editing_commands_within_dictation() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        let cases = [
+            ("send it now delete last word", "send it"),
+            ("send it now delete last word later", "send it later"),
+            (
+                "First point period second point delete last sentence try again",
+                "First point. Try again",
+            ),
+            ("never mind scratch that hello there", "hello there"),
+        ];
+        for (spoken, expected) in cases {
+            let result = parser.parse(spoken);
+            assert_eq!(result.text, expected, "{:?}", spoken);
+            assert_eq!(
+                result.steps,
+                vec![ParseStep::Text(expected.to_string())],
+                "{:?}",
+                spoken
+            );
+        }
+    }
+
+    #[test]
+    fn test_editing_commands_past_dictation_go_to_injector() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        let result = parser.parse("scratch that");
+        assert_eq!(result.text, "");
+        assert_eq!(
+            result.steps,
+            vec![ParseStep::Action(CommandAction::ScratchThat)]
+        );
+
+        let result = parser.parse("delete last word okay");
+        assert_eq!(
+            result.steps,
+            vec![
+                ParseStep::Action(CommandAction::DeleteLastWord),
+                ParseStep::Text("okay".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_commands_are_ordered_with_text() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        let result = parser.parse("looks good press enter thanks press tab");
+        assert_eq!(result.text, "looks goodthanks");
+        assert_eq!(
+            result.steps,
+            vec![
+                ParseStep::Text("looks good".to_string()),
+                ParseStep::Action(CommandAction::PressKey(Key::Enter)),
+                ParseStep::Text("thanks".to_string()),
+                ParseStep::Action(CommandAction::PressKey(Key::Tab)),
+            ]
+        );
+
+        // Edits stop at text already delivered before a key
+        let result = parser.parse("select all replaced text scratch that new text");
+        assert_eq!(
+            result.steps,
+            vec![
+                ParseStep::Action(CommandAction::SelectAll),
+                ParseStep::Text("new text".to_string()),
+            ]
+        );
+
+        let result = parser.parse("go to end of line and more");
+        assert_eq!(
+            result.steps,
+            vec![
+                ParseStep::Action(CommandAction::GoToLineEnd),
+                ParseStep::Text("and more".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_press_key_serialization() {
+        let action = CommandAction::PressKey(Key::Escape);
+        let json = serde_json::to_string(&action).unwrap();
+        assert_eq!(json, r#"{"type":"press_key","value":"escape"}"#);
+        assert_eq!(
+            serde_json::from_str::<CommandAction>(&json).unwrap(),
+            action
+        );
+    }
+
+    #[test]
+    fn test_formatting_commands() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        let cases = [
+            // Capitals
+            (
+                "all caps warning end caps do not touch",
+                "WARNING do not touch",
+            ),
+            ("this is all caps very important", "this is VERY IMPORTANT"),
+            ("all caps stop period end caps go", "STOP. go"),
+            // Quotes and brackets
+            ("he said open quote hello close quote", "he said \"hello\""),
+            (
+                "open quote quoted text end quote period",
+                "\"quoted text\".",
+            ),
+            (
+                "call it open paren maybe close paren today",
+                "call it (maybe) today",
+            ),
+            ("open bracket one close bracket", "[one]"),
+            ("open brace key close brace", "{key}"),
+            // No space
+            ("pre no space process", "preprocess"),
+            ("version no space two", "versiontwo"),
+            ("e no space mail me", "email me"),
+            // Lists
+            (
+                "groceries colon bullet point milk bullet point eggs",
+                "groceries:\n- Milk\n- Eggs",
+            ),
+            ("bullet point milk next item eggs", "- Milk\n- Eggs"),
+            ("next item first", "- First"),
+            (
+                "steps numbered list build next item test next item ship",
+                "steps\n1. Build\n2. Test\n3. Ship",
+            ),
+            (
+                "numbered list a next item b numbered list c",
+                "1. A\n2. B\n1. C",
+            ),
+            ("done new line bullet point next", "done\n- Next"),
+        ];
+        for (spoken, expected) in cases {
+            assert_eq!(parser.parse(spoken).text, expected, "{:?}", spoken);
+        }
+    }
+
+    #[test]
+    fn test_identifier_commands() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        let cases = [
+            ("camel case get user name", "getUserName"),
+            ("pascal case user service", "UserService"),
+            ("snake case max retry count", "max_retry_count"),
+            ("kebab case main nav bar", "main-nav-bar"),
+            // Words are lowercased and punctuation inside words dropped
+            ("camel case Get User's Name", "getUsersName"),
+            ("snake case re-run all", "re_run_all"),
+            // Any command ends the identifier
+            ("set snake case user id period", "set user_id."),
+            (
+                "snake case user id end case equals five",
+                "user_id equals five",
+            ),
+            (
+                "call camel case fetch data no space open paren close paren",
+                "call fetchData()",
+            ),
+            ("snake case first new line second", "first\n second"),
+            // Punctuation Whisper attached to a word ends it too
+            (
+                "rename it to camel case user id. Done",
+                "rename it to userId. Done",
+            ),
+            // Identifiers combine with other formatting
+            ("all caps snake case max size end caps", "MAX_SIZE"),
+            ("open quote camel case user id close quote", "\"userId\""),
+            ("prefix no space snake case one two", "prefixone_two"),
+            // The identifier is typed as written at a sentence start
+            ("paragraph camel case my var", "\n\nmyVar"),
+            // Nothing to join
+            ("camel case", ""),
+            ("camel case period", "."),
+        ];
+        for (spoken, expected) in cases {
+            assert_eq!(parser.parse(spoken).text, expected, "{:?}", spoken);
+        }
+    }
+
+    #[test]
+    fn test_formatting_commands_stay_in_text() {
+        let parser = CommandParser::new(CommandConfig::default());
+
+        let result = parser.parse("bullet point one scratch that two");
+        assert_eq!(result.text, "two");
+        assert_eq!(result.steps, vec![ParseStep::Text("two".to_string())]);
+
+        let result = parser.parse("camel case user id press tab snake case next field");
+        assert_eq!(
+            result.steps,
+            vec![
+                ParseStep::Text("userId".to_string()),
+                ParseStep::Action(CommandAction::PressKey(Key::Tab)),
+                ParseStep::Text("next_field".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_formatting_actions_serialization() {
+        let cases = [
+            (
+                CommandAction::StartIdentifier(IdentifierCase::Snake),
+                r#"{"type":"start_identifier","value":"snake"}"#,
+            ),
+            (
+                CommandAction::Open("(".to_string()),
+                r#"{"type":"open","value":"("}"#,
+            ),
+            (CommandAction::NoSpace, r#"{"type":"no_space"}"#),
+        ];
+        for (action, json) in cases {
+            assert_eq!(serde_json::to_string(&action).unwrap(), json);
+            assert_eq!(serde_json::from_str::<CommandAction>(json).unwrap(), action);
+        }
+    }
 }
@@ -0,0 +1,458 @@
+//! Registry of spoken command phrases
+//!
+//! Maps phrases of any number of words to [`CommandAction`]s. Built-in
+//! phrases come in per-language tables; user-defined commands from
+//! [`CommandConfig`] are added on top and override built-ins with the same
+//! phrase. Problems found while building the registry are reported as
+//! [`CommandConflict`]s instead of failing, so one bad entry doesn't disable
+//! every command.
+
+use super::{CommandAction, CommandConfig, IdentifierCase};
+use crate::services::platform::Key;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Languages with built-in command phrases
+pub const SUPPORTED_LANGUAGES: &[&str] = &["en", "de", "es", "fr"];
+
+/// A user-defined voice command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomCommand {
+    /// Spoken phrase, one or more words
+    pub phrase: String,
+    /// Action performed when the phrase is spoken
+    pub action: CommandAction,
+}
+
+/// Where a registered command comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandSource {
+    /// Built-in phrase for the given language
+    BuiltIn,
+    /// Defined by the user in `CommandConfig::custom_commands`
+    User,
+}
+
+/// A phrase in the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredCommand {
+    /// Normalized phrase (lowercase words separated by single spaces)
+    pub phrase: String,
+    /// Action performed when the phrase is spoken
+    pub action: CommandAction,
+    /// Where the command comes from
+    pub source: CommandSource,
+    /// Language of a built-in phrase
+    pub language: Option<String>,
+}
+
+/// Why a command could not be registered as given
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// The phrase has no words; the command is ignored
+    EmptyPhrase,
+    /// The phrase is already registered; the first entry wins
+    Duplicate,
+    /// A user command replaces a built-in phrase
+    OverridesBuiltIn,
+    /// The phrase is the command prefix, so it can never be spoken as a
+    /// command; the command is ignored
+    MatchesPrefix,
+    /// The language has no built-in phrases
+    UnsupportedLanguage,
+}
+
+/// A problem found while building the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandConflict {
+    /// Phrase (or language code) the problem is about
+    pub phrase: String,
+    /// What is wrong
+    pub kind: ConflictKind,
+}
+
+/// Lookup table from spoken phrases to actions
+#[derive(Debug, Clone, Default)]
+pub struct CommandRegistry {
+    commands: HashMap<String, RegisteredCommand>,
+    max_words: usize,
+    conflicts: Vec<CommandConflict>,
+}
+
+impl CommandRegistry {
+    /// Build the registry for a configuration
+    pub fn from_config(config: &CommandConfig) -> Self {
+        let mut registry = Self::default();
+        let prefix = normalize_phrase(&config.prefix);
+
+        for language in &config.languages {
+            let language = language.to_lowercase();
+            let Some(table) = built_in_commands(&language) else {
+                registry.conflicts.push(CommandConflict {
+                    phrase: language,
+                    kind: ConflictKind::UnsupportedLanguage,
+                });
+                continue;
+            };
+            for (phrase, action) in table {
+                registry.insert(RegisteredCommand {
+                    phrase: normalize_phrase(phrase),
+                    action,
+                    source: CommandSource::BuiltIn,
+                    language: Some(language.clone()),
+                });
+            }
+        }
+
+        for custom in &config.custom_commands {
+            let phrase = normalize_phrase(&custom.phrase);
+            if phrase.is_empty() {
+                registry.conflicts.push(CommandConflict {
+                    phrase: custom.phrase.clone(),
+                    kind: ConflictKind::EmptyPhrase,
+                });
+                continue;
+            }
+            if config.require_prefix && phrase == prefix {
+                registry.conflicts.push(CommandConflict {
+                    phrase,
+                    kind: ConflictKind::MatchesPrefix,
+                });
+                continue;
+            }
+            registry.insert(RegisteredCommand {
+                phrase,
+                action: custom.action.clone(),
+                source: CommandSource::User,
+                language: None,
+            });
+        }
+
+        registry
+    }
+
+    /// Add a command, recording a conflict if the phrase is taken
+    fn insert(&mut self, command: RegisteredCommand) {
+        if let Some(existing) = self.commands.get(&command.phrase) {
+            let overrides =
+                existing.source == CommandSource::BuiltIn && command.source == CommandSource::User;
+            if overrides {
+                self.conflicts.push(CommandConflict {
+                    phrase: command.phrase.clone(),
+                    kind: ConflictKind::OverridesBuiltIn,
+                });
+            } else {
+                // The same phrase meaning the same thing in two languages is fine
+                if existing.action != command.action {
+                    self.conflicts.push(CommandConflict {
+                        phrase: command.phrase,
+                        kind: ConflictKind::Duplicate,
+                    });
+                }
+                return;
+            }
+        }
+
+        self.max_words = self.max_words.max(command.phrase.split(' ').count());
+        self.commands.insert(command.phrase.clone(), command);
+    }
+
+    /// The longest command at the start of `words` (already normalized with
+    /// [`normalize_word`]), with the number of words it spans
+    pub fn longest_match(&self, words: &[String]) -> Option<(usize, &RegisteredCommand)> {
+        let longest = self.max_words.min(words.len());
+        (1..=longest).rev().find_map(|len| {
+            let phrase = words[..len].join(" ");
+            self.commands.get(&phrase).map(|command| (len, command))
+        })
+    }
+
+    /// Problems found while building the registry
+    pub fn conflicts(&self) -> &[CommandConflict] {
+        &self.conflicts
+    }
+
+    /// All registered commands, sorted by phrase
+    pub fn commands(&self) -> Vec<RegisteredCommand> {
+        let mut commands: Vec<RegisteredCommand> = self.commands.values().cloned().collect();
+        commands.sort_by(|a, b| a.phrase.cmp(&b.phrase));
+        commands
+    }
+}
+
+/// Lowercase a spoken word and strip the punctuation Whisper attaches to it
+pub fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Whether Whisper ended a sentence on this word, as in "question." from
+/// "Let me ask a question."
+pub fn ends_sentence(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', ')'])
+        .ends_with(['.', '?', '!'])
+}
+
+/// Normalize every word of a phrase and join them with single spaces
+pub fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .split_whitespace()
+        .map(normalize_word)
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn insert(text: &str) -> CommandAction {
+    CommandAction::InsertText(text.to_string())
+}
+
+fn open(text: &str) -> CommandAction {
+    CommandAction::Open(text.to_string())
+}
+
+fn close(text: &str) -> CommandAction {
+    CommandAction::Close(text.to_string())
+}
+
+fn identifier(case: IdentifierCase) -> CommandAction {
+    CommandAction::StartIdentifier(case)
+}
+
+/// Built-in phrases for a language
+fn built_in_commands(language: &str) -> Option<Vec<(&'static str, CommandAction)>> {
+    let table = match language {
+        "en" => vec![
+            // Punctuation
+            ("period", insert(".")),
+            ("full stop", insert(".")),
+            ("comma", insert(",")),
+            ("question mark", insert("?")),
+            ("question", insert("?")),
+            ("exclamation point", insert("!")),
+            ("exclamation mark", insert("!")),
+            ("exclamation", insert("!")),
+            ("colon", insert(":")),
+            ("semicolon", insert(";")),
+            ("semi-colon", insert(";")),
+            ("semi colon", insert(";")),
+            // Formatting
+            ("new line", insert("\n")),
+            ("newline", insert("\n")),
+            ("new paragraph", insert("\n\n")),
+            ("paragraph", insert("\n\n")),
+            ("all caps", CommandAction::StartAllCaps),
+            ("end caps", CommandAction::EndAllCaps),
+            ("no space", CommandAction::NoSpace),
+            ("open quote", open("\"")),
+            ("close quote", close("\"")),
+            ("end quote", close("\"")),
+            ("open paren", open("(")),
+            ("close paren", close(")")),
+            ("open bracket", open("[")),
+            ("close bracket", close("]")),
+            ("open brace", open("{")),
+            ("close brace", close("}")),
+            ("bullet point", CommandAction::BulletPoint),
+            ("numbered list", CommandAction::NumberedList),
+            ("next item", CommandAction::NextItem),
+            // Identifiers
+            ("camel case", identifier(IdentifierCase::Camel)),
+            ("pascal case", identifier(IdentifierCase::Pascal)),
+            ("snake case", identifier(IdentifierCase::Snake)),
+            ("kebab case", identifier(IdentifierCase::Kebab)),
+            ("end case", CommandAction::EndIdentifier),
+            // Editing
+            ("delete that", CommandAction::DeleteCharacters(1)),
+            ("backspace", CommandAction::DeleteCharacters(1)),
+            ("undo", CommandAction::Undo),
+            ("scratch that", CommandAction::ScratchThat),
+            ("delete last word", CommandAction::DeleteLastWord),
+            ("delete last sentence", CommandAction::DeleteLastSentence),
+            ("select all", CommandAction::SelectAll),
+            ("press enter", CommandAction::PressKey(Key::Enter)),
+            ("press return", CommandAction::PressKey(Key::Enter)),
+            ("press tab", CommandAction::PressKey(Key::Tab)),
+            ("press escape", CommandAction::PressKey(Key::Escape)),
+            ("go to end of line", CommandAction::GoToLineEnd),
+        ],
+        "de" => vec![
+            ("punkt", insert(".")),
+            ("komma", insert(",")),
+            ("fragezeichen", insert("?")),
+            ("ausrufezeichen", insert("!")),
+            ("doppelpunkt", insert(":")),
+            ("semikolon", insert(";")),
+            ("neue zeile", insert("\n")),
+            ("neuer absatz", insert("\n\n")),
+            ("rückgängig", CommandAction::Undo),
+        ],
+        "es" => vec![
+            ("punto", insert(".")),
+            ("coma", insert(",")),
+            ("signo de interrogación", insert("?")),
+            ("signo de exclamación", insert("!")),
+            ("dos puntos", insert(":")),
+            ("punto y coma", insert(";")),
+            ("nueva línea", insert("\n")),
+            ("nuevo párrafo", insert("\n\n")),
+            ("deshacer", CommandAction::Undo),
+        ],
+        "fr" => vec![
+            ("point", insert(".")),
+            ("virgule", insert(",")),
+            ("point d'interrogation", insert("?")),
+            ("point d'exclamation", insert("!")),
+            ("deux points", insert(":")),
+            ("point-virgule", insert(";")),
+            ("nouvelle ligne", insert("\n")),
+            ("nouveau paragraphe", insert("\n\n")),
+            ("annuler", CommandAction::Undo),
+        ],
+        _ => return None,
+    };
+    Some(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        text.split_whitespace().map(normalize_word).collect()
+    }
+
+    fn custom(phrase: &str, action: CommandAction) -> CustomCommand {
+        CustomCommand {
+            phrase: phrase.to_string(),
+            action,
+        }
+    }
+
+    #[test]
+    fn test_longest_match_prefers_more_words() {
+        let registry = CommandRegistry::from_config(&CommandConfig::default());
+
+        let (len, command) = registry
+            .longest_match(&words("question mark please"))
+            .unwrap();
+        assert_eq!(len, 2);
+        assert_eq!(command.action, insert("?"));
+
+        let (len, _) = registry
+            .longest_match(&words("question everything"))
+            .unwrap();
+        assert_eq!(len, 1);
+
+        assert!(registry.longest_match(&words("hello world")).is_none());
+        assert!(registry.longest_match(&[]).is_none());
+    }
+
+    #[test]
+    fn test_normalize_strips_whisper_punctuation() {
+        assert_eq!(normalize_word("Period."), "period");
+        assert_eq!(normalize_word("semi-colon,"), "semi-colon");
+        assert_eq!(
+            normalize_phrase("  Insert   Signature! "),
+            "insert signature"
+        );
+    }
+
+    #[test]
+    fn test_ends_sentence() {
+        assert!(ends_sentence("question."));
+        assert!(ends_sentence("Really?\""));
+        assert!(!ends_sentence("period"));
+        assert!(!ends_sentence("comma,"));
+    }
+
+    #[test]
+    fn test_user_commands_of_any_length() {
+        let config = CommandConfig {
+            custom_commands: vec![custom(
+                "insert my email address",
+                insert("jane@example.com"),
+            )],
+            ..Default::default()
+        };
+        let registry = CommandRegistry::from_config(&config);
+
+        let (len, command) = registry
+            .longest_match(&words("Insert my email address, thanks"))
+            .unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(command.source, CommandSource::User);
+        assert!(registry.conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_multiple_languages() {
+        let config = CommandConfig {
+            languages: vec!["en".to_string(), "es".to_string()],
+            ..Default::default()
+        };
+        let registry = CommandRegistry::from_config(&config);
+
+        let (len, command) = registry.longest_match(&words("punto y coma")).unwrap();
+        assert_eq!(len, 3);
+        assert_eq!(command.action, insert(";"));
+        assert_eq!(command.language.as_deref(), Some("es"));
+        assert!(registry.longest_match(&words("period")).is_some());
+        assert!(registry.longest_match(&words("virgule")).is_none());
+    }
+
+    #[test]
+    fn test_conflicts_are_reported() {
+        let config = CommandConfig {
+            require_prefix: true,
+            languages: vec!["en".to_string(), "xx".to_string()],
+            custom_commands: vec![
+                custom("Period", insert("。")),
+                custom("sign off", insert("Cheers")),
+                custom("sign  off", insert("Bye")),
+                custom("command", CommandAction::Undo),
+                custom(" ... ", CommandAction::Undo),
+            ],
+            ..Default::default()
+        };
+        let registry = CommandRegistry::from_config(&config);
+
+        let kinds: Vec<(&str, ConflictKind)> = registry
+            .conflicts()
+            .iter()
+            .map(|c| (c.phrase.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("xx", ConflictKind::UnsupportedLanguage),
+                ("period", ConflictKind::OverridesBuiltIn),
+                ("sign off", ConflictKind::Duplicate),
+                ("command", ConflictKind::MatchesPrefix),
+                (" ... ", ConflictKind::EmptyPhrase),
+            ]
+        );
+
+        // The user override and the first duplicate win
+        let (_, period) = registry.longest_match(&words("period")).unwrap();
+        assert_eq!(period.action, insert("。"));
+        let (_, sign_off) = registry.longest_match(&words("sign off")).unwrap();
+        assert_eq!(sign_off.action, insert("Cheers"));
+        assert!(registry.longest_match(&words("command")).is_none());
+    }
+
+    #[test]
+    fn test_built_in_tables_have_no_duplicates() {
+        for language in SUPPORTED_LANGUAGES {
+            let config = CommandConfig {
+                languages: vec![language.to_string()],
+                ..Default::default()
+            };
+            let registry = CommandRegistry::from_config(&config);
+            assert!(registry.conflicts().is_empty(), "{}", language);
+            assert!(!registry.commands().is_empty(), "{}", language);
+        }
+    }
+}
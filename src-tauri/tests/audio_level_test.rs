@@ -58,8 +58,8 @@ fn test_real_audio_capture_levels() {
 
     // Stop recording
     match service.stop() {
-        Ok(buffer) => {
-            println!("✓ Recording stopped: {} samples", buffer.samples.len());
+        Ok(recording) => {
+            println!("✓ Recording stopped: {} samples", recording.len());
         }
         Err(e) => {
             println!("✗ Failed to stop recording: {}", e);
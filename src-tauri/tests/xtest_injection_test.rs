@@ -0,0 +1,212 @@
+//! Integration test for native X11 text injection through XTEST
+//! Run under a headless X server: xvfb-run -a cargo test --test xtest_injection_test
+
+#![cfg(target_os = "linux")]
+
+use ez_flow_lib::services::platform::{
+    ClipboardConfig, InjectionMethod, LinuxTextInjector, TextInjector,
+};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    ConnectionExt, CreateWindowAux, EventMask, InputFocus, KeyButMask, WindowClass,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+/// Held while a test has the input focus, so keys typed by one test can't
+/// land in another's window
+static DISPLAY: Mutex<()> = Mutex::new(());
+
+/// A focused window that records the keys typed into it
+struct TestWindow {
+    _display: MutexGuard<'static, ()>,
+    conn: RustConnection,
+    min_keycode: u8,
+    keysyms_per_keycode: usize,
+    keysyms: Vec<u32>,
+}
+
+impl TestWindow {
+    /// Open a window on `$DISPLAY`, or None when there is no X server
+    fn open() -> Option<Self> {
+        if std::env::var("WAYLAND_DISPLAY").is_ok() || std::env::var("DISPLAY").is_err() {
+            println!("No X11 display, skipping (run under xvfb-run)");
+            return None;
+        }
+        // A failed test poisons the lock but leaves the display usable
+        let display = DISPLAY.lock().unwrap_or_else(|e| e.into_inner());
+        let (conn, screen_num) = match x11rb::connect(None) {
+            Ok(connection) => connection,
+            Err(e) => {
+                println!("Could not connect to X server, skipping: {}", e);
+                return None;
+            }
+        };
+
+        let screen = &conn.setup().roots[screen_num];
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            0,
+            window,
+            screen.root,
+            0,
+            0,
+            200,
+            100,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            0,
+            &CreateWindowAux::new().event_mask(EventMask::KEY_PRESS | EventMask::STRUCTURE_NOTIFY),
+        )
+        .unwrap();
+        conn.map_window(window).unwrap();
+        conn.flush().unwrap();
+        loop {
+            if let Event::MapNotify(_) = conn.wait_for_event().unwrap() {
+                break;
+            }
+        }
+        conn.set_input_focus(InputFocus::PARENT, window, x11rb::CURRENT_TIME)
+            .unwrap();
+        conn.sync().unwrap();
+
+        let (min_keycode, keysyms_per_keycode, keysyms) = Self::mapping(&conn);
+        Some(Self {
+            _display: display,
+            conn,
+            min_keycode,
+            keysyms_per_keycode,
+            keysyms,
+        })
+    }
+
+    fn mapping(conn: &RustConnection) -> (u8, usize, Vec<u32>) {
+        let setup = conn.setup();
+        let count = setup.max_keycode - setup.min_keycode + 1;
+        let reply = conn
+            .get_keyboard_mapping(setup.min_keycode, count)
+            .unwrap()
+            .reply()
+            .unwrap();
+        (
+            setup.min_keycode,
+            reply.keysyms_per_keycode as usize,
+            reply.keysyms,
+        )
+    }
+
+    /// The keysyms of the key presses received until typing stops
+    fn typed_keysyms(&self) -> Vec<u32> {
+        let mut typed = Vec::new();
+        let mut last_event = Instant::now();
+        while last_event.elapsed() < Duration::from_millis(500) {
+            match self.conn.poll_for_event().unwrap() {
+                Some(Event::KeyPress(press)) => {
+                    let shifted = press.state.contains(KeyButMask::SHIFT);
+                    let index = (press.detail - self.min_keycode) as usize
+                        * self.keysyms_per_keycode
+                        + shifted as usize;
+                    typed.push(self.keysyms[index]);
+                    last_event = Instant::now();
+                }
+                Some(_) => {}
+                None => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        typed
+    }
+}
+
+fn typing_injector() -> LinuxTextInjector {
+    let mut injector = LinuxTextInjector::new().unwrap();
+    injector.set_method(InjectionMethod::Type);
+    // Leave the clipboard alone, the test server has no clipboard manager
+    injector.set_clipboard_config(ClipboardConfig {
+        keep_transcript: false,
+        ..Default::default()
+    });
+    injector
+}
+
+#[test]
+fn test_xtest_types_text_into_focused_window() {
+    let Some(window) = TestWindow::open() else {
+        return;
+    };
+
+    let text = "Hello, World! 42";
+    typing_injector().inject_text(text).unwrap();
+
+    let typed: String = window
+        .typed_keysyms()
+        .into_iter()
+        .filter(|&keysym| (0x20..0x7f).contains(&keysym))
+        .map(|keysym| keysym as u8 as char)
+        .collect();
+    assert_eq!(typed, text);
+}
+
+#[test]
+fn test_xtest_types_characters_missing_from_layout() {
+    let Some(window) = TestWindow::open() else {
+        return;
+    };
+    let before = TestWindow::mapping(&window.conn);
+
+    typing_injector().inject_text("€→").unwrap();
+
+    // Both characters are typed on the spare keycode, which is cleared again
+    let presses = window.typed_keysyms();
+    assert_eq!(presses.len(), 2);
+    assert_eq!(TestWindow::mapping(&window.conn), before);
+}
+
+#[test]
+fn test_xtest_follows_keyboard_mapping_changes() {
+    let Some(mut window) = TestWindow::open() else {
+        return;
+    };
+    let injector = typing_injector();
+    injector.inject_text("a").unwrap();
+    window.typed_keysyms();
+
+    // Swap the keysyms of the A and B keys, like switching layouts
+    let before = TestWindow::mapping(&window.conn);
+    let per = window.keysyms_per_keycode;
+    let keycode_of = |keysym: u32| {
+        let index = window
+            .keysyms
+            .chunks(per)
+            .position(|syms| syms[0] == keysym);
+        window.min_keycode + index.unwrap() as u8
+    };
+    let (a, b) = (keycode_of(0x61), keycode_of(0x62));
+    let syms_of = |keycode: u8| {
+        let start = (keycode - window.min_keycode) as usize * per;
+        window.keysyms[start..start + per].to_vec()
+    };
+    let (a_syms, b_syms) = (syms_of(a), syms_of(b));
+    let remap = |keycode: u8, syms: &[u32]| {
+        window
+            .conn
+            .change_keyboard_mapping(1, keycode, per as u8, syms)
+            .unwrap()
+            .check()
+            .unwrap();
+    };
+    remap(a, &b_syms);
+    remap(b, &a_syms);
+    window.keysyms = TestWindow::mapping(&window.conn).2;
+
+    let result = injector.inject_text("ab");
+    let typed = window.typed_keysyms();
+    remap(a, &a_syms);
+    remap(b, &b_syms);
+    assert_eq!(TestWindow::mapping(&window.conn), before);
+
+    result.unwrap();
+    assert_eq!(typed, vec![0x61, 0x62]);
+}
@@ -234,6 +234,58 @@ describe('history service', () => {
 			expect(result).toBe(0);
 		});
 	});
+
+	describe('update_history_entry command', () => {
+		test('should return the entry with its original text', async () => {
+			mockInvoke.mockResolvedValueOnce({
+				id: 3,
+				text: 'Deploy to Kubernetes',
+				original_text: 'Deploy to Cooper Netties'
+			});
+
+			const { invoke } = await import('@tauri-apps/api/core');
+			const result = await invoke('update_history_entry', { id: 3, text: 'Deploy to Kubernetes' });
+
+			expect(mockInvoke).toHaveBeenCalledWith('update_history_entry', {
+				id: 3,
+				text: 'Deploy to Kubernetes'
+			});
+			expect(result).toMatchObject({ original_text: 'Deploy to Cooper Netties' });
+		});
+	});
+
+	describe('unlock_history command', () => {
+		test('should pass the passphrase', async () => {
+			mockInvoke.mockResolvedValueOnce(undefined);
+
+			const { invoke } = await import('@tauri-apps/api/core');
+			await invoke('unlock_history', { passphrase: 'open sesame' });
+
+			expect(mockInvoke).toHaveBeenCalledWith('unlock_history', { passphrase: 'open sesame' });
+		});
+
+		test('should reject an incorrect passphrase', async () => {
+			mockInvoke.mockRejectedValueOnce(
+				new Error('Could not unlock the history database: the key or passphrase is incorrect')
+			);
+
+			const { invoke } = await import('@tauri-apps/api/core');
+
+			await expect(invoke('unlock_history', { passphrase: 'wrong' })).rejects.toThrow('incorrect');
+		});
+	});
+
+	describe('save_history in incognito mode', () => {
+		test('should report that history is not being saved', async () => {
+			mockInvoke.mockRejectedValueOnce(
+				new Error('Incognito mode is on; history is not being saved')
+			);
+
+			const { invoke } = await import('@tauri-apps/api/core');
+
+			await expect(invoke('save_history', { result: {} })).rejects.toThrow('Incognito');
+		});
+	});
 });
 
 describe('history entry model', () => {
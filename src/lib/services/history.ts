@@ -3,6 +3,14 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import type { HistoryKeySource, VoiceCommandAction } from '../stores/settings';
+
+export type { VoiceCommandAction };
+
+/**
+ * What started a transcription
+ */
+export type HistorySource = 'hotkey' | 'tray' | 'app' | 'file' | 'cli';
 
 /**
  * History entry interface
@@ -14,20 +22,168 @@ export interface HistoryEntry {
 	duration_ms: number;
 	model_id: string;
 	language: string | null;
+	gpu_used?: boolean;
+	audio_path?: string | null;
+	/** Pinned entries are never removed by retention */
+	pinned?: boolean;
+	/** The transcription as first recorded, if the text has been edited */
+	original_text?: string | null;
+	source?: HistorySource | null;
+	source_app?: string | null;
+	source_window?: string | null;
+	streaming_mode?: 'speed' | 'balanced' | 'accuracy' | null;
+	transcription_time_ms?: number | null;
+	injection_time_ms?: number | null;
+	voice_commands?: VoiceCommandAction[];
+}
+
+/**
+ * Filters for listing and searching history
+ */
+export interface HistoryFilter {
+	source?: HistorySource;
+	source_app?: string;
+	/** true = streaming transcriptions only, false = batch only */
+	streaming?: boolean;
+	streaming_mode?: 'speed' | 'balanced' | 'accuracy';
+	has_voice_commands?: boolean;
+	min_transcription_time_ms?: number;
+	min_injection_time_ms?: number;
+	/** Start of the date range: RFC 3339 timestamp or YYYY-MM-DD */
+	from?: string;
+	/** End of the date range (inclusive for YYYY-MM-DD) */
+	to?: string;
+	model_id?: string;
+	language?: string;
+	pinned?: boolean;
+}
+
+/**
+ * Result of applying the history retention limits
+ */
+export interface RetentionReport {
+	removed: number;
+	vacuumed: boolean;
+}
+
+/**
+ * Words replaced when editing a transcription
+ */
+export interface TextCorrection {
+	original: string;
+	corrected: string;
+}
+
+/**
+ * A correction aggregated over all edited entries
+ */
+export interface CorrectionSummary extends TextCorrection {
+	count: number;
+	/** Number of entries the correction was made in */
+	entries: number;
+	last_corrected: string;
+}
+
+/**
+ * Encryption and incognito state of the history
+ */
+export interface HistoryPrivacyStatus {
+	encrypted: boolean;
+	key_source: HistoryKeySource;
+	/** Waiting for the passphrase of an encrypted database */
+	locked: boolean;
+	/** New transcriptions are not saved for this session */
+	incognito: boolean;
+}
+
+/**
+ * A search hit; highlight ranges are [start, end) offsets for String.slice
+ */
+export interface HistorySearchResult extends HistoryEntry {
+	/** BM25 relevance (higher is better); null for substring matches */
+	score: number | null;
+	snippet: string;
+	snippet_highlights: [number, number][];
+	highlights: [number, number][];
+}
+
+/**
+ * One page of search results
+ */
+export interface HistorySearchPage {
+	results: HistorySearchResult[];
+	total: number;
+}
+
+/**
+ * History export file format
+ */
+export type HistoryExportFormat = 'json' | 'csv' | 'markdown';
+
+/**
+ * Result of a history import
+ */
+export interface HistoryImportSummary {
+	imported: number;
+	skipped: number;
 }
 
 /**
  * Get paginated history entries
  */
-export async function getHistory(limit: number = 100, offset: number = 0): Promise<HistoryEntry[]> {
-	return invoke<HistoryEntry[]>('get_history', { limit, offset });
+export async function getHistory(
+	limit: number = 100,
+	offset: number = 0,
+	filter?: HistoryFilter
+): Promise<HistoryEntry[]> {
+	return invoke<HistoryEntry[]>('get_history', { limit, offset, filter });
 }
 
 /**
  * Search history entries
  */
-export async function searchHistory(query: string): Promise<HistoryEntry[]> {
-	return invoke<HistoryEntry[]>('search_history', { query });
+export async function searchHistory(query: string, filter?: HistoryFilter): Promise<HistoryEntry[]> {
+	return invoke<HistoryEntry[]>('search_history', { query, filter });
+}
+
+/**
+ * Search history with ranking, highlights and pagination.
+ *
+ * Supports "quoted phrases" and excluding words with -word or NOT word.
+ */
+export async function searchHistoryPage(
+	query: string,
+	limit: number = 50,
+	offset: number = 0,
+	filter?: HistoryFilter
+): Promise<HistorySearchPage> {
+	return invoke<HistorySearchPage>('search_history_page', { query, filter, limit, offset });
+}
+
+/**
+ * Get the applications dictated into (most recent first)
+ */
+export async function getHistorySourceApps(): Promise<string[]> {
+	return invoke<string[]>('get_history_source_apps');
+}
+
+/**
+ * Export history entries to a file; returns the number of exported entries
+ */
+export async function exportHistory(
+	path: string,
+	format: HistoryExportFormat,
+	query?: string,
+	filter?: HistoryFilter
+): Promise<number> {
+	return invoke<number>('export_history', { path, format, query, filter });
+}
+
+/**
+ * Import history entries from a JSON export
+ */
+export async function importHistory(path: string): Promise<HistoryImportSummary> {
+	return invoke<HistoryImportSummary>('import_history', { path });
 }
 
 /**
@@ -44,6 +200,83 @@ export async function clearHistory(): Promise<void> {
 	return invoke('clear_history');
 }
 
+/**
+ * Pin or unpin a history entry
+ */
+export async function setHistoryPinned(id: number, pinned: boolean): Promise<void> {
+	return invoke('set_history_pinned', { id, pinned });
+}
+
+/**
+ * Replace the text of a history entry, keeping the original transcription
+ */
+export async function updateHistoryEntry(id: number, text: string): Promise<HistoryEntry> {
+	return invoke<HistoryEntry>('update_history_entry', { id, text });
+}
+
+/**
+ * Get the corrections made by editing a history entry
+ */
+export async function getHistoryCorrections(id: number): Promise<TextCorrection[]> {
+	return invoke<TextCorrection[]>('get_history_corrections', { id });
+}
+
+/**
+ * Get corrections aggregated over all edited entries, most frequent first
+ */
+export async function getCorrectionSummary(
+	minCount?: number,
+	limit?: number
+): Promise<CorrectionSummary[]> {
+	return invoke<CorrectionSummary[]>('get_correction_summary', { minCount, limit });
+}
+
+/**
+ * Apply the history retention limits from settings now
+ */
+export async function applyHistoryRetention(): Promise<RetentionReport> {
+	return invoke<RetentionReport>('apply_history_retention');
+}
+
+/**
+ * Get the encryption and incognito state of the history
+ */
+export async function getHistoryPrivacyStatus(): Promise<HistoryPrivacyStatus> {
+	return invoke<HistoryPrivacyStatus>('get_history_privacy_status');
+}
+
+/**
+ * Encrypt the history database, or change its key. Unencrypted archived
+ * recordings and migration backups are deleted.
+ */
+export async function enableHistoryEncryption(
+	keySource: HistoryKeySource,
+	passphrase?: string
+): Promise<void> {
+	return invoke('enable_history_encryption', { keySource, passphrase });
+}
+
+/**
+ * Decrypt the history database
+ */
+export async function disableHistoryEncryption(): Promise<void> {
+	return invoke('disable_history_encryption');
+}
+
+/**
+ * Unlock a passphrase-encrypted history database for this session
+ */
+export async function unlockHistory(passphrase: string): Promise<void> {
+	return invoke('unlock_history', { passphrase });
+}
+
+/**
+ * Turn incognito mode (history not saved until restart) on or off
+ */
+export async function setIncognito(enabled: boolean): Promise<void> {
+	return invoke('set_incognito', { enabled });
+}
+
 /**
  * Get history entry count
  */
@@ -4,6 +4,13 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { HotkeyBinding } from '../stores/settings';
+
+/**
+ * Where global hotkeys come from: the global-shortcut plugin, or on
+ * Wayland the GlobalShortcuts portal or keyboards read through evdev
+ */
+export type HotkeyBackend = 'global_shortcut' | 'portal' | 'evdev';
 
 /**
  * Format a hotkey string for display based on platform
@@ -60,6 +67,21 @@ export async function clearHotkey(): Promise<void> {
 	return invoke('clear_hotkey');
 }
 
+/**
+ * Register further hotkey bindings, replacing the current ones.
+ * Rejects if bindings conflict with each other or the main hotkey.
+ */
+export async function setHotkeyBindings(bindings: HotkeyBinding[]): Promise<void> {
+	return invoke('set_hotkey_bindings', { bindings });
+}
+
+/**
+ * Get the currently registered hotkey bindings
+ */
+export async function getHotkeyBindings(): Promise<HotkeyBinding[]> {
+	return invoke<HotkeyBinding[]>('get_hotkey_bindings');
+}
+
 /**
  * Check if a hotkey is available (not conflicting)
  */
@@ -81,6 +103,13 @@ export async function getHotkeyError(): Promise<string | null> {
 	return invoke<string | null>('get_hotkey_error');
 }
 
+/**
+ * Get the backend global hotkeys are registered with
+ */
+export async function getHotkeyBackend(): Promise<HotkeyBackend> {
+	return invoke<HotkeyBackend>('get_hotkey_backend');
+}
+
 /**
  * Listen for hotkey recording started events
  */
@@ -4,7 +4,10 @@ import {
 	isModifierKey,
 	validateHotkey,
 	buildHotkeyString,
-	parseHotkeyString
+	parseHotkeyString,
+	isInputTrigger,
+	loneModifierTrigger,
+	mouseButtonTrigger
 } from './keybind';
 
 // Mock KeyboardEvent for testing
@@ -82,6 +85,40 @@ describe('normalizeKeyboardEventKey', () => {
 		);
 		expect(normalizeKeyboardEventKey(createMockKeyboardEvent('Delete', 'Delete'))).toBe('Delete');
 	});
+
+	test('normalizes CapsLock and Fn', () => {
+		expect(normalizeKeyboardEventKey(createMockKeyboardEvent('CapsLock', 'CapsLock'))).toBe(
+			'CapsLock'
+		);
+		expect(normalizeKeyboardEventKey(createMockKeyboardEvent('Fn', ''))).toBe('Fn');
+	});
+});
+
+describe('input triggers', () => {
+	test('identifies input triggers', () => {
+		expect(isInputTrigger('RightCtrl')).toBe(true);
+		expect(isInputTrigger('CapsLock')).toBe(true);
+		expect(isInputTrigger('MouseBack')).toBe(true);
+		expect(isInputTrigger('Ctrl')).toBe(false);
+		expect(isInputTrigger('Space')).toBe(false);
+	});
+
+	test('names lone modifiers by side', () => {
+		expect(loneModifierTrigger(createMockKeyboardEvent('Control', 'ControlRight'))).toBe(
+			'RightCtrl'
+		);
+		expect(loneModifierTrigger(createMockKeyboardEvent('Shift', 'ShiftLeft'))).toBe('LeftShift');
+		expect(loneModifierTrigger(createMockKeyboardEvent('Meta', 'MetaLeft'))).toBe('LeftSuper');
+		expect(loneModifierTrigger(createMockKeyboardEvent('a', 'KeyA'))).toBeNull();
+	});
+
+	test('names mouse buttons', () => {
+		expect(mouseButtonTrigger(1)).toBe('MouseMiddle');
+		expect(mouseButtonTrigger(3)).toBe('MouseBack');
+		expect(mouseButtonTrigger(4)).toBe('MouseForward');
+		expect(mouseButtonTrigger(0)).toBeNull();
+		expect(mouseButtonTrigger(2)).toBeNull();
+	});
 });
 
 describe('isModifierKey', () => {
@@ -119,6 +156,21 @@ describe('validateHotkey', () => {
 		expect(validateHotkey('Shift+F1')).toBeNull();
 	});
 
+	test('accepts input triggers without modifiers', () => {
+		expect(validateHotkey('RightCtrl')).toBeNull();
+		expect(validateHotkey('CapsLock')).toBeNull();
+		expect(validateHotkey('MouseForward')).toBeNull();
+		expect(validateHotkey('Shift+MouseBack')).toBeNull();
+	});
+
+	test('rejects input triggers where they are not supported', () => {
+		expect(validateHotkey('CapsLock', false)).toBe(
+			'Lone modifiers, Fn, CapsLock and mouse buttons only work as hotkeys on Linux'
+		);
+		expect(validateHotkey('Shift+MouseBack', false)).not.toBeNull();
+		expect(validateHotkey('Ctrl+Shift+Space', false)).toBeNull();
+	});
+
 	test('rejects empty hotkey', () => {
 		expect(validateHotkey('')).toBe('Hotkey cannot be empty');
 		expect(validateHotkey('   ')).toBe('Hotkey cannot be empty');
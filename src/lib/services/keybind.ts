@@ -29,6 +29,8 @@ export function normalizeKeyboardEventKey(e: KeyboardEvent): string | null {
 	if (key === 'ArrowDown') return 'Down';
 	if (key === 'ArrowLeft') return 'Left';
 	if (key === 'ArrowRight') return 'Right';
+	if (key === 'CapsLock') return 'CapsLock';
+	if (key === 'Fn') return 'Fn';
 
 	// Function keys
 	if (code.startsWith('F') && /^F\d+$/.test(code)) {
@@ -55,11 +57,75 @@ export function isModifierKey(key: string): boolean {
 	return ['Ctrl', 'Cmd', 'Alt', 'Shift'].includes(key);
 }
 
+/**
+ * Keys and mouse buttons that can be held on their own for push-to-talk.
+ * They are read from the input devices, which only works on Linux.
+ * CapsLock and mouse buttons are kept from other apps while bound.
+ */
+export const INPUT_TRIGGERS = [
+	'LeftCtrl',
+	'RightCtrl',
+	'LeftShift',
+	'RightShift',
+	'LeftAlt',
+	'RightAlt',
+	'LeftSuper',
+	'RightSuper',
+	'CapsLock',
+	'Fn',
+	'MouseMiddle',
+	'MouseBack',
+	'MouseForward'
+];
+
+/**
+ * Check if a key is an input trigger
+ */
+export function isInputTrigger(key: string): boolean {
+	return INPUT_TRIGGERS.includes(key);
+}
+
+/**
+ * Input trigger for a modifier key event, e.g. ControlRight -> RightCtrl
+ * Returns null for other keys
+ */
+export function loneModifierTrigger(e: KeyboardEvent): string | null {
+	const match = /^(Control|Shift|Alt|Meta)(Left|Right)$/.exec(e.code);
+	if (!match) return null;
+	const names: Record<string, string> = { Control: 'Ctrl', Shift: 'Shift', Alt: 'Alt', Meta: 'Super' };
+	return `${match[2]}${names[match[1]]}`;
+}
+
+/**
+ * Input trigger for a mouse button (MouseEvent.button)
+ * Returns null for the left and right buttons
+ */
+export function mouseButtonTrigger(button: number): string | null {
+	switch (button) {
+		case 1:
+			return 'MouseMiddle';
+		case 3:
+			return 'MouseBack';
+		case 4:
+			return 'MouseForward';
+		default:
+			return null;
+	}
+}
+
+/**
+ * Whether input triggers can be bound on this platform (Linux only)
+ */
+export function inputTriggersSupported(): boolean {
+	return typeof navigator !== 'undefined' && navigator.platform.startsWith('Linux');
+}
+
 /**
  * Validate a hotkey string
- * Returns an error message if invalid, null if valid
+ * Returns an error message if invalid, null if valid.
+ * Pass `inputTriggers = false` where input triggers can't be bound.
  */
-export function validateHotkey(hotkey: string): string | null {
+export function validateHotkey(hotkey: string, inputTriggers = true): string | null {
 	if (!hotkey || hotkey.trim() === '') {
 		return 'Hotkey cannot be empty';
 	}
@@ -71,6 +137,7 @@ export function validateHotkey(hotkey: string): string | null {
 
 	const hasModifier = parts.some(p => isModifierKey(p));
 	const isFunctionKey = parts.some(p => /^F\d+$/.test(p));
+	const isTrigger = parts.some(p => isInputTrigger(p));
 	const nonModifiers = parts.filter(p => !isModifierKey(p));
 
 	// Must have at least one non-modifier key
@@ -78,11 +145,15 @@ export function validateHotkey(hotkey: string): string | null {
 		return 'Hotkey must include a non-modifier key';
 	}
 
-	// Must have a modifier unless it's a function key
-	if (!hasModifier && !isFunctionKey) {
+	// Must have a modifier unless it's a function key or input trigger
+	if (!hasModifier && !isFunctionKey && !isTrigger) {
 		return 'Hotkey must include a modifier key (Ctrl, Alt, Shift) or be a function key';
 	}
 
+	if (isTrigger && !inputTriggers) {
+		return 'Lone modifiers, Fn, CapsLock and mouse buttons only work as hotkeys on Linux';
+	}
+
 	// Should not have more than one non-modifier key
 	if (nonModifiers.length > 1) {
 		return 'Hotkey can only have one main key';
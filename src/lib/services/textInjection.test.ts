@@ -13,6 +13,7 @@ mock.module('@tauri-apps/api/core', () => ({
  * This service handles:
  * - Injecting text at cursor position via Tauri backend
  * - Setting injection delay between keystrokes
+ * - Choosing between paste and typing
  * - Getting platform-specific permission instructions
  * - Combined inject with delay functionality
  */
@@ -27,6 +28,7 @@ describe('textInjection service', () => {
 
 		expect(typeof module.injectText).toBe('function');
 		expect(typeof module.setInjectionDelay).toBe('function');
+		expect(typeof module.setInjectionMethod).toBe('function');
 		expect(typeof module.getInjectionPermissionInstructions).toBe('function');
 		expect(typeof module.injectTextWithDelay).toBe('function');
 	});
@@ -161,6 +163,17 @@ describe('textInjection service', () => {
 		});
 	});
 
+	describe('setInjectionMethod behavior', () => {
+		test('should call set_injection_method with the method', async () => {
+			mockInvoke.mockResolvedValueOnce(undefined);
+
+			const { setInjectionMethod } = await import('./textInjection');
+			await setInjectionMethod('type');
+
+			expect(mockInvoke).toHaveBeenCalledWith('set_injection_method', { method: 'type' });
+		});
+	});
+
 	describe('injectTextWithDelay integration', () => {
 		test('should inject text without setting delay when delayMs is undefined', async () => {
 			mockInvoke.mockResolvedValue(undefined);
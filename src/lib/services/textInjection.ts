@@ -3,6 +3,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import type { InjectionMethod } from '../stores/settings';
 
 /**
  * Inject text at the current cursor position
@@ -20,6 +21,13 @@ export async function setInjectionDelay(delayMs: number): Promise<void> {
 	return invoke('set_injection_delay', { delayMs: Math.min(Math.max(0, delayMs), 50) });
 }
 
+/**
+ * Choose whether text is pasted via the clipboard or typed as keystrokes
+ */
+export async function setInjectionMethod(method: InjectionMethod): Promise<void> {
+	return invoke('set_injection_method', { method });
+}
+
 /**
  * Get platform-specific permission instructions if needed
  * Returns null if no special permissions are required
@@ -0,0 +1,75 @@
+import { test, expect, describe, beforeEach, mock } from 'bun:test';
+import type { ReplacementRule } from '../stores/settings';
+
+// Mock Tauri invoke API
+const mockInvoke = mock(() => Promise.resolve(undefined as unknown));
+
+mock.module('@tauri-apps/api/core', () => ({
+	invoke: mockInvoke
+}));
+
+/**
+ * Tests for textReplacements service
+ *
+ * This service handles:
+ * - Exporting and importing replacement rules
+ * - Validating a rule
+ * - Previewing rules against sample text
+ */
+
+const rule: ReplacementRule = {
+	find: 'e z flow',
+	replace: 'EZ Flow',
+	kind: 'literal',
+	case: 'insensitive',
+	whole_word: true,
+	enabled: true
+};
+
+describe('textReplacements service', () => {
+	beforeEach(() => {
+		mockInvoke.mockClear();
+	});
+
+	test('exportReplacements passes path and rules', async () => {
+		const { exportReplacements } = await import('./textReplacements');
+		await exportReplacements('/tmp/rules.json', [rule]);
+
+		expect(mockInvoke).toHaveBeenCalledWith('export_replacements', {
+			path: '/tmp/rules.json',
+			rules: [rule]
+		});
+	});
+
+	test('importReplacements returns the imported rules', async () => {
+		mockInvoke.mockResolvedValueOnce([rule]);
+
+		const { importReplacements } = await import('./textReplacements');
+		const rules = await importReplacements('/tmp/rules.json');
+
+		expect(mockInvoke).toHaveBeenCalledWith('import_replacements', { path: '/tmp/rules.json' });
+		expect(rules).toEqual([rule]);
+	});
+
+	test('validateReplacementRule rejects invalid rules', async () => {
+		mockInvoke.mockRejectedValueOnce("Invalid pattern '(': unclosed group");
+
+		const { validateReplacementRule } = await import('./textReplacements');
+		await expect(validateReplacementRule({ ...rule, kind: 'regex', find: '(' })).rejects.toContain(
+			'Invalid pattern'
+		);
+	});
+
+	test('previewReplacements returns the rewritten text', async () => {
+		mockInvoke.mockResolvedValueOnce('I use EZ Flow');
+
+		const { previewReplacements } = await import('./textReplacements');
+		const text = await previewReplacements('I use e z flow', [rule]);
+
+		expect(mockInvoke).toHaveBeenCalledWith('preview_replacements', {
+			text: 'I use e z flow',
+			rules: [rule]
+		});
+		expect(text).toBe('I use EZ Flow');
+	});
+});
@@ -0,0 +1,34 @@
+/**
+ * Text replacement service for find/replace rules and spoken snippets
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { ReplacementRule } from '../stores/settings';
+
+/**
+ * Export replacement rules to a JSON file
+ */
+export async function exportReplacements(path: string, rules: ReplacementRule[]): Promise<void> {
+	return invoke('export_replacements', { path, rules });
+}
+
+/**
+ * Import replacement rules from a JSON file
+ */
+export async function importReplacements(path: string): Promise<ReplacementRule[]> {
+	return invoke<ReplacementRule[]>('import_replacements', { path });
+}
+
+/**
+ * Check that a rule can be used; rejects with the reason if not
+ */
+export async function validateReplacementRule(rule: ReplacementRule): Promise<void> {
+	return invoke('validate_replacement_rule', { rule });
+}
+
+/**
+ * Apply rules to sample text to try them out
+ */
+export async function previewReplacements(text: string, rules: ReplacementRule[]): Promise<string> {
+	return invoke<string>('preview_replacements', { text, rules });
+}
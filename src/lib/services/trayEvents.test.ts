@@ -190,7 +190,9 @@ describe('trayEvents service', () => {
 
 			await stopRecording();
 
-			expect(mockInvoke).toHaveBeenCalledWith('stop_recording_and_transcribe');
+			expect(mockInvoke).toHaveBeenCalledWith('stop_recording_and_transcribe', {
+				source: 'tray'
+			});
 		});
 
 		test('should copy transcription to clipboard', async () => {
@@ -668,7 +670,9 @@ describe('trayEvents service', () => {
 			await stopRecording();
 
 			expect(mockInvoke).toHaveBeenCalledWith('hide_recording_indicator');
-			expect(mockInvoke).toHaveBeenCalledWith('stop_recording_and_transcribe');
+			expect(mockInvoke).toHaveBeenCalledWith('stop_recording_and_transcribe', {
+				source: 'tray'
+			});
 			expect(mockWriteText).toHaveBeenCalledWith(transcription);
 		});
 	});
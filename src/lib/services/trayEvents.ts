@@ -40,7 +40,9 @@ export async function stopRecording() {
 
   try {
     await invoke('hide_recording_indicator');
-    const result = await invoke<{ text: string }>('stop_recording_and_transcribe');
+    const result = await invoke<{ text: string }>('stop_recording_and_transcribe', {
+      source: 'tray'
+    });
     isRecording = false;
 
     if (result && result.text) {
@@ -0,0 +1,47 @@
+import { test, expect, describe, beforeEach, mock } from 'bun:test';
+import type { VoiceCommandSettings } from '../stores/settings';
+
+// Mock Tauri invoke API
+const mockInvoke = mock(() => Promise.resolve(undefined as unknown));
+
+mock.module('@tauri-apps/api/core', () => ({
+	invoke: mockInvoke
+}));
+
+/**
+ * Tests for voiceCommands service
+ */
+
+describe('voiceCommands service', () => {
+	beforeEach(() => {
+		mockInvoke.mockClear();
+	});
+
+	test('getVoiceCommands passes the configuration and returns the list', async () => {
+		const config: VoiceCommandSettings = {
+			enabled: true,
+			require_prefix: false,
+			prefix: 'command',
+			languages: ['en'],
+			custom_commands: [{ phrase: 'comma', action: { type: 'insert_text', value: '、' } }]
+		};
+		const list = {
+			commands: [
+				{
+					phrase: 'comma',
+					action: { type: 'insert_text', value: '、' },
+					source: 'user',
+					language: null
+				}
+			],
+			conflicts: [{ phrase: 'comma', kind: 'overrides_built_in' }]
+		};
+		mockInvoke.mockResolvedValueOnce(list);
+
+		const { getVoiceCommands } = await import('./voiceCommands');
+		const result = await getVoiceCommands(config);
+
+		expect(mockInvoke).toHaveBeenCalledWith('get_voice_commands', { config });
+		expect(result).toEqual(list);
+	});
+});
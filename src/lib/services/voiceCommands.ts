@@ -0,0 +1,54 @@
+/**
+ * Voice command service for inspecting the command registry
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { VoiceCommandAction, VoiceCommandSettings } from '../stores/settings';
+
+/**
+ * Where a registered command comes from
+ */
+export type VoiceCommandSource = 'built_in' | 'user';
+
+/**
+ * Why a command could not be registered as given
+ */
+export type VoiceCommandConflictKind =
+	| 'empty_phrase'
+	| 'duplicate'
+	| 'overrides_built_in'
+	| 'matches_prefix'
+	| 'unsupported_language';
+
+/**
+ * Phrase recognized as a voice command
+ */
+export interface RegisteredVoiceCommand {
+	phrase: string;
+	action: VoiceCommandAction;
+	source: VoiceCommandSource;
+	language: string | null;
+}
+
+/**
+ * Problem found while building the registry
+ */
+export interface VoiceCommandConflict {
+	phrase: string;
+	kind: VoiceCommandConflictKind;
+}
+
+/**
+ * Registered commands and conflicts for a configuration
+ */
+export interface VoiceCommandList {
+	commands: RegisteredVoiceCommand[];
+	conflicts: VoiceCommandConflict[];
+}
+
+/**
+ * List the commands recognized with a (possibly unsaved) configuration
+ */
+export async function getVoiceCommands(config: VoiceCommandSettings): Promise<VoiceCommandList> {
+	return invoke<VoiceCommandList>('get_voice_commands', { config });
+}
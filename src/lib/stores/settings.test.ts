@@ -115,10 +115,31 @@ describe('Default settings', () => {
 		expect(defaultSettings.auto_copy).toBe(true);
 	});
 
+	test('should restore the clipboard 200ms after pasting by default', () => {
+		expect(defaultSettings.clipboard_restore_delay_ms).toBe(200);
+	});
+
 	test('should have injection_delay_ms as 0 by default', () => {
 		expect(defaultSettings.injection_delay_ms).toBe(0);
 	});
 
+	test('should paste text by default', () => {
+		expect(defaultSettings.injection_method).toBe('paste');
+	});
+
+	test('should have no app profiles by default', () => {
+		expect(defaultSettings.app_profiles).toEqual([]);
+	});
+
+	test('should have no extra hotkey bindings or dictation profiles by default', () => {
+		expect(defaultSettings.hotkey_bindings).toEqual([]);
+		expect(defaultSettings.dictation_profiles).toEqual([]);
+	});
+
+	test('should inject transcripts by default', () => {
+		expect(defaultSettings.dictation_sink).toEqual({ type: 'inject' });
+	});
+
 	test('should have onboarding flags as false by default', () => {
 		expect(defaultSettings.onboarding_completed).toBe(false);
 		expect(defaultSettings.onboarding_skipped).toBe(false);
@@ -178,6 +199,11 @@ describe('Default settings', () => {
 	test('should have correct streaming_mode default', () => {
 		expect(defaultSettings.streaming_mode).toBe('balanced');
 	});
+
+	test('should have audio processing disabled by default', () => {
+		expect(defaultSettings.audio_processing.enabled).toBe(false);
+		expect(defaultSettings.audio_processing.high_pass_enabled).toBe(true);
+	});
 });
 
 describe('Settings interface completeness', () => {
@@ -210,6 +236,8 @@ describe('Settings interface completeness', () => {
 		expect(settings).toHaveProperty('voice_commands');
 		expect(settings).toHaveProperty('streaming_enabled');
 		expect(settings).toHaveProperty('streaming_mode');
+		expect(settings).toHaveProperty('audio_processing');
+		expect(settings).toHaveProperty('audio_archive');
 	});
 
 	test('should be assignable with partial overrides', () => {
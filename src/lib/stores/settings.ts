@@ -15,11 +15,57 @@ export type RecordingMode = 'push_to_talk' | 'toggle';
  */
 export type StreamingMode = 'speed' | 'balanced' | 'accuracy';
 
+/**
+ * How text is delivered to the focused application
+ */
+export type InjectionMethod = 'paste' | 'type';
+
+/**
+ * Key combination that pastes into an application
+ */
+export type PasteShortcut = 'standard' | 'ctrl_shift_v' | 'shift_insert';
+
 /**
  * Indicator position options
  */
 export type IndicatorPosition = 'cursor' | 'top_right' | 'bottom_right' | 'hidden';
 
+/**
+ * Voice command action
+ */
+export interface VoiceCommandAction {
+	type:
+		| 'insert_text'
+		| 'delete_characters'
+		| 'undo'
+		| 'capitalize_next'
+		| 'scratch_that'
+		| 'delete_last_word'
+		| 'delete_last_sentence'
+		| 'select_all'
+		| 'press_key'
+		| 'go_to_line_end'
+		| 'start_all_caps'
+		| 'end_all_caps'
+		| 'start_identifier'
+		| 'end_identifier'
+		| 'open'
+		| 'close'
+		| 'bullet_point'
+		| 'numbered_list'
+		| 'next_item'
+		| 'no_space';
+	value?: string | number;
+}
+
+/**
+ * User-defined voice command
+ */
+export interface CustomVoiceCommand {
+	phrase: string;
+	action: VoiceCommandAction;
+}
+
 /**
  * Voice command settings
  */
@@ -27,6 +73,139 @@ export interface VoiceCommandSettings {
 	enabled: boolean;
 	require_prefix: boolean;
 	prefix: string;
+	languages: string[];
+	custom_commands: CustomVoiceCommand[];
+}
+
+/**
+ * How a replacement rule's find text is interpreted
+ */
+export type ReplacementRuleKind = 'literal' | 'regex' | 'snippet';
+
+/**
+ * How a replacement rule treats letter case
+ */
+export type ReplacementCaseMode = 'insensitive' | 'sensitive' | 'preserve';
+
+/**
+ * Find/replace rule or spoken snippet
+ */
+export interface ReplacementRule {
+	find: string;
+	replace: string;
+	kind: ReplacementRuleKind;
+	case: ReplacementCaseMode;
+	whole_word: boolean;
+	enabled: boolean;
+}
+
+/**
+ * Text replacement settings
+ */
+export interface TextReplacementSettings {
+	enabled: boolean;
+	rules: ReplacementRule[];
+}
+
+/**
+ * Injection overrides for a set of applications. `apps` holds window
+ * classes or process names (Linux), bundle ids or app names (macOS) or
+ * executable names (Windows); unset overrides use the global settings.
+ */
+export interface AppProfile {
+	name: string;
+	apps: string[];
+	enabled: boolean;
+	injection_method: InjectionMethod | null;
+	injection_delay_ms: number | null;
+	clipboard_restore_delay_ms: number | null;
+	paste_shortcut: PasteShortcut;
+	strip_trailing_newline: boolean;
+	voice_commands: VoiceCommandSettings | null;
+	text_replacements: TextReplacementSettings | null;
+}
+
+/**
+ * Where finished transcripts go. `file` paths expand `~/` and `{date}`;
+ * `pipe` is a FIFO or Unix socket (a named pipe on Windows).
+ */
+export type DictationSink =
+	| { type: 'inject' }
+	| { type: 'clipboard' }
+	| { type: 'file'; path: string }
+	| { type: 'pipe'; path: string }
+	| { type: 'command'; command: string; args: string[] };
+
+/**
+ * What a hotkey binding does with its recording
+ */
+export type HotkeyAction = 'dictate' | 'clipboard' | 'translate' | 'command';
+
+/**
+ * Transcription overrides that hotkey bindings refer to by name
+ */
+export interface DictationProfile {
+	name: string;
+	model_id: string | null;
+	language: string | null;
+	context_prompt: string | null;
+	sink: DictationSink | null;
+}
+
+/**
+ * A hotkey bound to an action besides the main dictation hotkey
+ */
+export interface HotkeyBinding {
+	name: string;
+	hotkey: string;
+	action: HotkeyAction;
+	profile: string | null;
+	enabled: boolean;
+}
+
+/**
+ * Audio preprocessing settings
+ */
+export interface AudioProcessingSettings {
+	enabled: boolean;
+	high_pass_enabled: boolean;
+	high_pass_cutoff_hz: number;
+	noise_suppression_enabled: boolean;
+	noise_reduction_db: number;
+	agc_enabled: boolean;
+	agc_target_dbfs: number;
+	agc_max_gain_db: number;
+}
+
+/**
+ * Recording archive settings
+ */
+export interface AudioArchiveSettings {
+	enabled: boolean;
+	max_entries: number;
+	max_age_days: number;
+}
+
+/**
+ * History retention limits (0 = unlimited)
+ */
+export interface HistoryRetentionSettings {
+	max_entries: number;
+	max_age_days: number;
+	max_db_size_mb: number;
+}
+
+/**
+ * Where the history encryption key comes from
+ */
+export type HistoryKeySource = 'keyring' | 'passphrase';
+
+/**
+ * History database encryption
+ */
+export interface HistoryEncryptionSettings {
+	enabled: boolean;
+	key_source: HistoryKeySource;
 }
 
 /**
@@ -34,6 +213,8 @@ export interface VoiceCommandSettings {
  */
 export interface Settings {
 	hotkey: string;
+	hotkey_bindings: HotkeyBinding[];
+	dictation_profiles: DictationProfile[];
 	recording_mode: RecordingMode;
 	model_id: string;
 	language: string | null;
@@ -41,7 +222,11 @@ export interface Settings {
 	indicator_position: IndicatorPosition;
 	auto_paste: boolean;
 	auto_copy: boolean;
+	clipboard_restore_delay_ms: number;
 	injection_delay_ms: number;
+	injection_method: InjectionMethod;
+	app_profiles: AppProfile[];
+	dictation_sink: DictationSink;
 	onboarding_completed: boolean;
 	onboarding_skipped: boolean;
 	use_gpu: boolean;
@@ -56,8 +241,14 @@ export interface Settings {
 	preview_position_x: number | null;
 	preview_position_y: number | null;
 	voice_commands: VoiceCommandSettings;
+	text_replacements: TextReplacementSettings;
+	text_normalization: boolean;
 	streaming_enabled: boolean;
 	streaming_mode: StreamingMode;
+	audio_processing: AudioProcessingSettings;
+	audio_archive: AudioArchiveSettings;
+	history_retention: HistoryRetentionSettings;
+	history_encryption: HistoryEncryptionSettings;
 }
 
 /**
@@ -65,6 +256,8 @@ export interface Settings {
  */
 export const defaultSettings: Settings = {
 	hotkey: 'Ctrl+Shift+Space',
+	hotkey_bindings: [],
+	dictation_profiles: [],
 	recording_mode: 'push_to_talk',
 	model_id: 'base',
 	language: null,
@@ -72,7 +265,11 @@ export const defaultSettings: Settings = {
 	indicator_position: 'top_right',
 	auto_paste: true,
 	auto_copy: true,
+	clipboard_restore_delay_ms: 200,
 	injection_delay_ms: 0,
+	injection_method: 'paste',
+	app_profiles: [],
+	dictation_sink: { type: 'inject' },
 	onboarding_completed: false,
 	onboarding_skipped: false,
 	use_gpu: true,
@@ -89,10 +286,41 @@ export const defaultSettings: Settings = {
 	voice_commands: {
 		enabled: true,
 		require_prefix: false,
-		prefix: 'command'
+		prefix: 'command',
+		languages: ['en'],
+		custom_commands: []
 	},
+	text_replacements: {
+		enabled: true,
+		rules: []
+	},
+	text_normalization: true,
 	streaming_enabled: true,
-	streaming_mode: 'balanced'
+	streaming_mode: 'balanced',
+	audio_processing: {
+		enabled: false,
+		high_pass_enabled: true,
+		high_pass_cutoff_hz: 80,
+		noise_suppression_enabled: true,
+		noise_reduction_db: 12,
+		agc_enabled: true,
+		agc_target_dbfs: -20,
+		agc_max_gain_db: 20
+	},
+	audio_archive: {
+		enabled: false,
+		max_entries: 500,
+		max_age_days: 90
+	},
+	history_retention: {
+		max_entries: 0,
+		max_age_days: 0,
+		max_db_size_mb: 0
+	},
+	history_encryption: {
+		enabled: false,
+		key_source: 'keyring'
+	}
 };
 
 /**